@@ -1,26 +1,29 @@
-use std::borrow::Borrow;
+use std::{borrow::Borrow, path::{Path, PathBuf}};
 
 //#![cfg(any(target_arch = "wasm32", doc))]
 use airbus_systems::{
     simulator::{
         from_bool, to_bool, Simulation, SimulatorApuReadState, SimulatorElectricalReadState,
-        SimulatorFireReadState, SimulatorPneumaticReadState, SimulatorReadState,
-        SimulatorReadWriter, SimulatorWriteState,
+        SimulatorFireReadState, SimulatorHydraulicReadState, SimulatorLandingGearReadState,
+        SimulatorPneumaticReadState, SimulatorReadState, SimulatorReadWriter, SimulatorWriteState,
     },
-    A320,
+    HydraulicConfig, HydraulicConfigError, PtuState, A320,
 };
 use msfs::{
     legacy::{AircraftVariable, NamedVariable},
     MSFSEvent,
 };
 use uom::si::{
-    electric_current::ampere, electric_potential::volt, f64::*, frequency::hertz, length::foot,
-    mass::pound, ratio::percent, thermodynamic_temperature::degree_celsius, velocity::knot,
+    angle::degree, angular_velocity::revolution_per_minute, electric_current::ampere,
+    electric_potential::volt, f64::*, frequency::hertz, length::foot, mass::pound, power::watt,
+    pressure::{inch_of_mercury, psi}, ratio::percent, ratio::ratio, thermodynamic_temperature::degree_celsius,
+    velocity::foot_per_second, velocity::knot, volume::gallon,
+    volume_rate::gallon_per_minute,
 };
 
 #[msfs::gauge(name=systems)]
 async fn systems(mut gauge: msfs::Gauge) -> Result<(), Box<dyn std::error::Error>> {
-    let mut simulation = Simulation::new(A320::new(), A320SimulatorReadWriter::new()?);
+    let mut simulation = Simulation::new(aircraft()?, A320SimulatorReadWriter::new()?);
 
     while let Some(event) = gauge.next_event().await {
         match event {
@@ -34,8 +37,29 @@ async fn systems(mut gauge: msfs::Gauge) -> Result<(), Box<dyn std::error::Error
     Ok(())
 }
 
+// The simulator maps the package's work folder to this path for WASM modules.
+const WORK_FOLDER: &str = "/work";
+const HYDRAULIC_CONFIG_FILE: &str = "hydraulic.toml";
+
+// Defaults to the work folder. Building with A320_HYDRAULIC_CONFIG set reads another file instead,
+// relative to the work folder unless the path is absolute.
+fn hydraulic_config_path() -> PathBuf {
+    Path::new(WORK_FOLDER).join(option_env!("A320_HYDRAULIC_CONFIG").unwrap_or(HYDRAULIC_CONFIG_FILE))
+}
+
+// The hydraulic config is optional, but when present it must be valid.
+fn aircraft() -> Result<A320, HydraulicConfigError> {
+    match HydraulicConfig::from_file(hydraulic_config_path()) {
+        Ok(config) => Ok(A320::new_with_hydraulic_config(&config)),
+        Err(HydraulicConfigError::Io(_)) => Ok(A320::new()),
+        Err(error) => Err(error),
+    }
+}
+
 struct A320SimulatorReadWriter {
+    ambient_pressure: AircraftVariable,
     ambient_temperature: AircraftVariable,
+    annunciator_light_test: NamedVariable,
     apu_available: NamedVariable,
     apu_bleed_air_valve_open: NamedVariable,
     apu_bleed_pb_fault: NamedVariable,
@@ -44,6 +68,10 @@ struct A320SimulatorReadWriter {
     apu_egt_caution: NamedVariable,
     apu_egt_warning: NamedVariable,
     apu_fire_button_released: NamedVariable,
+    engine_1_fire_button_released: NamedVariable,
+    engine_2_fire_button_released: NamedVariable,
+    engine_1_fire_button_pushed_in: NamedVariable,
+    engine_2_fire_button_pushed_in: NamedVariable,
     apu_air_intake_flap_is_ecam_open: NamedVariable,
     apu_flap_open_percentage: NamedVariable,
     apu_gen_frequency: NamedVariable,
@@ -151,15 +179,130 @@ struct A320SimulatorReadWriter {
     elec_tr_ess_potential_within_normal_range: NamedVariable,
     engine_1_n2: AircraftVariable,
     engine_2_n2: AircraftVariable,
+    engine_1_master_on: AircraftVariable,
+    engine_2_master_on: AircraftVariable,
+    gear_handle_position: AircraftVariable,
+    flight_phase: NamedVariable,
+    ground_speed: AircraftVariable,
+    hyd_anti_skid_and_nws_switch_on: AircraftVariable,
+    hyd_autobrake_decel: NamedVariable,
+    hyd_autobrake_lo_armed: NamedVariable,
+    hyd_autobrake_lo_pb_pressed: NamedVariable,
+    hyd_autobrake_max_armed: NamedVariable,
+    hyd_autobrake_max_pb_pressed: NamedVariable,
+    hyd_autobrake_med_armed: NamedVariable,
+    hyd_autobrake_med_pb_pressed: NamedVariable,
+    hyd_blue_electric_pump_pb_auto: NamedVariable,
+    hyd_blue_electric_pump_pb_fault_light: NamedVariable,
+    hyd_blue_electric_pump_pb_off_light: NamedVariable,
+    hyd_blue_electric_pump_power: NamedVariable,
+    hyd_blue_ground_cart_pressure: NamedVariable,
+    hyd_blue_pump_override_pb_on: NamedVariable,
+    hyd_brake_accumulator_pressure: NamedVariable,
+    hyd_brake_fans_running: NamedVariable,
+    hyd_fwd_cargo_door_selector_operated: NamedVariable,
+    hyd_aft_cargo_door_selector_operated: NamedVariable,
+    hyd_dump_state_requested: NamedVariable,
+    hyd_engine_1_pump_pb_auto: NamedVariable,
+    hyd_engine_1_pump_pb_fault_light: NamedVariable,
+    hyd_engine_1_pump_pb_off_light: NamedVariable,
+    hyd_engine_2_pump_pb_auto: NamedVariable,
+    hyd_engine_2_pump_pb_fault_light: NamedVariable,
+    hyd_engine_2_pump_pb_off_light: NamedVariable,
+    hyd_blue_electric_pump_low_pressure_caution: NamedVariable,
+    hyd_engine_1_pump_low_pressure_caution: NamedVariable,
+    hyd_engine_2_pump_low_pressure_caution: NamedVariable,
+    hyd_engine_1_pump_overheat_caution: NamedVariable,
+    hyd_engine_2_pump_overheat_caution: NamedVariable,
+    hyd_blue_system_low_pressure_caution: NamedVariable,
+    hyd_green_system_low_pressure_caution: NamedVariable,
+    hyd_yellow_system_low_pressure_caution: NamedVariable,
+    hyd_brake_1_temperature: NamedVariable,
+    hyd_brake_2_temperature: NamedVariable,
+    hyd_brake_3_temperature: NamedVariable,
+    hyd_brake_4_temperature: NamedVariable,
+    hyd_bscu_channel_1_fault: NamedVariable,
+    hyd_bscu_channel_2_fault: NamedVariable,
+    hyd_engine_driven_pump_1_shaft_power: NamedVariable,
+    hyd_engine_driven_pump_2_shaft_power: NamedVariable,
+    hyd_nose_gear_downlocked: NamedVariable,
+    hyd_nose_gear_uplocked: NamedVariable,
+    hyd_left_gear_downlocked: NamedVariable,
+    hyd_left_gear_uplocked: NamedVariable,
+    hyd_right_gear_downlocked: NamedVariable,
+    hyd_right_gear_uplocked: NamedVariable,
+    lgciu_nose_gear_disagree: NamedVariable,
+    lgciu_left_gear_disagree: NamedVariable,
+    lgciu_right_gear_disagree: NamedVariable,
+    hyd_ground_spoilers_deployed: AircraftVariable,
+    hyd_left_alternate_brake_pressure: NamedVariable,
+    hyd_left_brake_pedal_position: AircraftVariable,
+    hyd_left_normal_brake_pressure: NamedVariable,
+    hyd_nws_tow_pin_inserted: NamedVariable,
+    hyd_parking_brake_applied: AircraftVariable,
+    hyd_pushback_attached: AircraftVariable,
+    hyd_right_alternate_brake_pressure: NamedVariable,
+    hyd_right_brake_pedal_position: AircraftVariable,
+    hyd_right_normal_brake_pressure: NamedVariable,
+    hyd_yellow_electric_pump_pb_on: NamedVariable,
+    hyd_yellow_electric_pump_power: NamedVariable,
+    hyd_left_aileron_position: NamedVariable,
+    hyd_right_aileron_position: NamedVariable,
+    hyd_left_elevator_position: NamedVariable,
+    hyd_right_elevator_position: NamedVariable,
+    hyd_left_spoiler_1_position: NamedVariable,
+    hyd_left_spoiler_2_position: NamedVariable,
+    hyd_left_spoiler_3_position: NamedVariable,
+    hyd_left_spoiler_4_position: NamedVariable,
+    hyd_left_spoiler_5_position: NamedVariable,
+    hyd_right_spoiler_1_position: NamedVariable,
+    hyd_right_spoiler_2_position: NamedVariable,
+    hyd_right_spoiler_3_position: NamedVariable,
+    hyd_right_spoiler_4_position: NamedVariable,
+    hyd_right_spoiler_5_position: NamedVariable,
+    hyd_spoiler_availability: NamedVariable,
+    hyd_triple_indicator_accumulator_pressure: NamedVariable,
+    hyd_triple_indicator_left_brake_pressure: NamedVariable,
+    hyd_triple_indicator_right_brake_pressure: NamedVariable,
+    hyd_nose_wheel_steering_angle_limit: NamedVariable,
+    hyd_nose_wheel_steering_available: NamedVariable,
+    hyd_ptu_barking_intensity: NamedVariable,
+    hyd_ptu_pb_auto: NamedVariable,
+    hyd_ptu_pb_fault_light: NamedVariable,
+    hyd_ptu_pb_off_light: NamedVariable,
+    hyd_ptu_self_test_in_progress: NamedVariable,
+    hyd_ptu_direction: NamedVariable,
+    hyd_ptu_flow: NamedVariable,
+    hyd_ptu_shaft_speed: NamedVariable,
+    hyd_rat_in_transit: NamedVariable,
+    hyd_rat_stow_position: NamedVariable,
+    hyd_rat_stow_requested: NamedVariable,
+    hyd_rat_time_to_deploy: NamedVariable,
+    hyd_rudder_travel_limit: NamedVariable,
+    hyd_blue_pressure: NamedVariable,
+    hyd_blue_reservoir_volume: NamedVariable,
+    hyd_green_pressure: NamedVariable,
+    hyd_green_reservoir_volume: NamedVariable,
+    hyd_yellow_pressure: NamedVariable,
+    hyd_yellow_reservoir_volume: NamedVariable,
     indicated_airspeed: AircraftVariable,
     indicated_altitude: AircraftVariable,
     left_inner_tank_fuel_quantity: AircraftVariable,
+    left_wheel_rpm: AircraftVariable,
+    left_wing_flex: AircraftVariable,
+    longitudinal_velocity: AircraftVariable,
+    on_ground: AircraftVariable,
+    right_wheel_rpm: AircraftVariable,
+    right_wing_flex: AircraftVariable,
     unlimited_fuel: AircraftVariable,
+    vertical_velocity: AircraftVariable,
 }
 impl A320SimulatorReadWriter {
     fn new() -> Result<Self, Box<dyn std::error::Error>> {
         Ok(A320SimulatorReadWriter {
+            ambient_pressure: AircraftVariable::from("AMBIENT PRESSURE", "inHg", 0)?,
             ambient_temperature: AircraftVariable::from("AMBIENT TEMPERATURE", "celsius", 0)?,
+            annunciator_light_test: NamedVariable::from("A32NX_OVHD_INTLT_ANN"),
             apu_available: NamedVariable::from("A32NX_APU_AVAILABLE"),
             apu_bleed_air_valve_open: NamedVariable::from("A32NX_APU_BLEED_AIR_VALVE_OPEN"),
             apu_bleed_pb_fault: NamedVariable::from("A32NX_APU_BLEED_PB_FAULT"),
@@ -168,6 +311,10 @@ impl A320SimulatorReadWriter {
             apu_egt_caution: NamedVariable::from("A32NX_APU_EGT_CAUTION"),
             apu_egt_warning: NamedVariable::from("A32NX_APU_EGT_WARNING"),
             apu_fire_button_released: NamedVariable::from("A32NX_FIRE_BUTTON_APU"),
+            engine_1_fire_button_released: NamedVariable::from("A32NX_FIRE_BUTTON_ENG1"),
+            engine_2_fire_button_released: NamedVariable::from("A32NX_FIRE_BUTTON_ENG2"),
+            engine_1_fire_button_pushed_in: NamedVariable::from("A32NX_FIRE_BUTTON_ENG1_PUSHED_IN"),
+            engine_2_fire_button_pushed_in: NamedVariable::from("A32NX_FIRE_BUTTON_ENG2_PUSHED_IN"),
             apu_air_intake_flap_is_ecam_open: NamedVariable::from("A32NX_APU_FLAP_ECAM_OPEN"),
             apu_flap_open_percentage: NamedVariable::from("A32NX_APU_FLAP_OPEN_PERCENTAGE"),
             apu_gen_frequency: NamedVariable::from("A32NX_APU_GEN_FREQ"),
@@ -375,6 +522,186 @@ impl A320SimulatorReadWriter {
             ),
             engine_1_n2: AircraftVariable::from("ENG N2 RPM", "Percent", 1)?,
             engine_2_n2: AircraftVariable::from("ENG N2 RPM", "Percent", 2)?,
+            engine_1_master_on: AircraftVariable::from("GENERAL ENG STARTER", "Bool", 1)?,
+            engine_2_master_on: AircraftVariable::from("GENERAL ENG STARTER", "Bool", 2)?,
+            gear_handle_position: AircraftVariable::from("GEAR HANDLE POSITION", "Bool", 0)?,
+            flight_phase: NamedVariable::from("A32NX_FWC_FLIGHT_PHASE"),
+            ground_speed: AircraftVariable::from("GROUND VELOCITY", "Knots", 0)?,
+            hyd_anti_skid_and_nws_switch_on: AircraftVariable::from(
+                "ANTISKID BRAKES ACTIVE",
+                "Bool",
+                0,
+            )?,
+            hyd_autobrake_decel: NamedVariable::from("A32NX_AUTOBRAKES_DECEL_LIGHT"),
+            hyd_autobrake_lo_armed: NamedVariable::from("A32NX_AUTOBRAKES_LO_ARMED"),
+            hyd_autobrake_lo_pb_pressed: NamedVariable::from("A32NX_AUTOBRAKES_LO_PB_PRESSED"),
+            hyd_autobrake_max_armed: NamedVariable::from("A32NX_AUTOBRAKES_MAX_ARMED"),
+            hyd_autobrake_max_pb_pressed: NamedVariable::from("A32NX_AUTOBRAKES_MAX_PB_PRESSED"),
+            hyd_autobrake_med_armed: NamedVariable::from("A32NX_AUTOBRAKES_MED_ARMED"),
+            hyd_autobrake_med_pb_pressed: NamedVariable::from("A32NX_AUTOBRAKES_MED_PB_PRESSED"),
+            hyd_blue_electric_pump_pb_auto: NamedVariable::from("A32NX_OVHD_HYD_EPUMPB_PB_IS_AUTO"),
+            hyd_blue_electric_pump_pb_fault_light: NamedVariable::from(
+                "A32NX_OVHD_HYD_EPUMPB_PB_FAULT_LIGHT",
+            ),
+            hyd_blue_electric_pump_pb_off_light: NamedVariable::from(
+                "A32NX_OVHD_HYD_EPUMPB_PB_OFF_LIGHT",
+            ),
+            hyd_blue_electric_pump_power: NamedVariable::from("A32NX_HYD_BLUE_EPUMP_POWER"),
+            hyd_blue_ground_cart_pressure: NamedVariable::from("A32NX_HYD_BLUE_GROUND_CART_PRESSURE"),
+            hyd_blue_pump_override_pb_on: NamedVariable::from(
+                "A32NX_OVHD_HYD_BLUEPUMP_OVRD_PB_IS_ON",
+            ),
+            hyd_brake_accumulator_pressure: NamedVariable::from(
+                "A32NX_HYD_BRAKE_ALTN_ACC_PRESS",
+            ),
+            hyd_brake_fans_running: NamedVariable::from("A32NX_BRAKE_FAN"),
+            hyd_fwd_cargo_door_selector_operated: NamedVariable::from(
+                "A32NX_HYD_FWD_CARGO_DOOR_SELECTOR_OPERATED",
+            ),
+            hyd_aft_cargo_door_selector_operated: NamedVariable::from(
+                "A32NX_HYD_AFT_CARGO_DOOR_SELECTOR_OPERATED",
+            ),
+            hyd_dump_state_requested: NamedVariable::from("A32NX_HYD_DUMP_STATE"),
+            hyd_engine_1_pump_pb_auto: NamedVariable::from("A32NX_OVHD_HYD_ENG_1_PUMP_PB_IS_AUTO"),
+            hyd_engine_1_pump_pb_fault_light: NamedVariable::from(
+                "A32NX_OVHD_HYD_ENG_1_PUMP_PB_FAULT_LIGHT",
+            ),
+            hyd_engine_1_pump_pb_off_light: NamedVariable::from(
+                "A32NX_OVHD_HYD_ENG_1_PUMP_PB_OFF_LIGHT",
+            ),
+            hyd_engine_2_pump_pb_auto: NamedVariable::from("A32NX_OVHD_HYD_ENG_2_PUMP_PB_IS_AUTO"),
+            hyd_engine_2_pump_pb_fault_light: NamedVariable::from(
+                "A32NX_OVHD_HYD_ENG_2_PUMP_PB_FAULT_LIGHT",
+            ),
+            hyd_engine_2_pump_pb_off_light: NamedVariable::from(
+                "A32NX_OVHD_HYD_ENG_2_PUMP_PB_OFF_LIGHT",
+            ),
+            hyd_blue_electric_pump_low_pressure_caution: NamedVariable::from(
+                "A32NX_HYD_B_ELEC_PUMP_LO_PR_CAUTION",
+            ),
+            hyd_engine_1_pump_low_pressure_caution: NamedVariable::from(
+                "A32NX_HYD_G_ENG_1_PUMP_LO_PR_CAUTION",
+            ),
+            hyd_engine_2_pump_low_pressure_caution: NamedVariable::from(
+                "A32NX_HYD_Y_ENG_2_PUMP_LO_PR_CAUTION",
+            ),
+            hyd_engine_1_pump_overheat_caution: NamedVariable::from(
+                "A32NX_HYD_G_ENG_1_PUMP_OVHT_CAUTION",
+            ),
+            hyd_engine_2_pump_overheat_caution: NamedVariable::from(
+                "A32NX_HYD_Y_ENG_2_PUMP_OVHT_CAUTION",
+            ),
+            hyd_blue_system_low_pressure_caution: NamedVariable::from(
+                "A32NX_HYD_B_SYS_LO_PR_CAUTION",
+            ),
+            hyd_green_system_low_pressure_caution: NamedVariable::from(
+                "A32NX_HYD_G_SYS_LO_PR_CAUTION",
+            ),
+            hyd_yellow_system_low_pressure_caution: NamedVariable::from(
+                "A32NX_HYD_Y_SYS_LO_PR_CAUTION",
+            ),
+            hyd_brake_1_temperature: NamedVariable::from("A32NX_BRAKE_TEMPERATURE_1"),
+            hyd_brake_2_temperature: NamedVariable::from("A32NX_BRAKE_TEMPERATURE_2"),
+            hyd_brake_3_temperature: NamedVariable::from("A32NX_BRAKE_TEMPERATURE_3"),
+            hyd_brake_4_temperature: NamedVariable::from("A32NX_BRAKE_TEMPERATURE_4"),
+            hyd_bscu_channel_1_fault: NamedVariable::from("A32NX_HYD_BSCU_CHANNEL_1_FAULT"),
+            hyd_bscu_channel_2_fault: NamedVariable::from("A32NX_HYD_BSCU_CHANNEL_2_FAULT"),
+            hyd_engine_driven_pump_1_shaft_power: NamedVariable::from(
+                "A32NX_HYD_ENG_1_PUMP_SHAFT_POWER",
+            ),
+            hyd_engine_driven_pump_2_shaft_power: NamedVariable::from(
+                "A32NX_HYD_ENG_2_PUMP_SHAFT_POWER",
+            ),
+            hyd_nose_gear_downlocked: NamedVariable::from("A32NX_HYD_NOSE_GEAR_DOWNLOCKED"),
+            hyd_nose_gear_uplocked: NamedVariable::from("A32NX_HYD_NOSE_GEAR_UPLOCKED"),
+            hyd_left_gear_downlocked: NamedVariable::from("A32NX_HYD_LEFT_GEAR_DOWNLOCKED"),
+            hyd_left_gear_uplocked: NamedVariable::from("A32NX_HYD_LEFT_GEAR_UPLOCKED"),
+            hyd_right_gear_downlocked: NamedVariable::from("A32NX_HYD_RIGHT_GEAR_DOWNLOCKED"),
+            hyd_right_gear_uplocked: NamedVariable::from("A32NX_HYD_RIGHT_GEAR_UPLOCKED"),
+            lgciu_nose_gear_disagree: NamedVariable::from("A32NX_LGCIU_NOSE_GEAR_DISAGREE"),
+            lgciu_left_gear_disagree: NamedVariable::from("A32NX_LGCIU_LEFT_GEAR_DISAGREE"),
+            lgciu_right_gear_disagree: NamedVariable::from("A32NX_LGCIU_RIGHT_GEAR_DISAGREE"),
+            hyd_ground_spoilers_deployed: AircraftVariable::from(
+                "SPOILERS HANDLE POSITION",
+                "Percent",
+                0,
+            )?,
+            hyd_left_alternate_brake_pressure: NamedVariable::from(
+                "A32NX_HYD_BRAKE_ALTN_LEFT_PRESS",
+            ),
+            hyd_left_brake_pedal_position: AircraftVariable::from(
+                "BRAKE LEFT POSITION",
+                "Percent",
+                0,
+            )?,
+            hyd_left_normal_brake_pressure: NamedVariable::from(
+                "A32NX_HYD_BRAKE_NORM_LEFT_PRESS",
+            ),
+            hyd_nws_tow_pin_inserted: NamedVariable::from("A32NX_HYD_NWS_TOW_PIN_INSERTED"),
+            hyd_parking_brake_applied: AircraftVariable::from(
+                "BRAKE PARKING POSITION",
+                "Bool",
+                0,
+            )?,
+            hyd_pushback_attached: AircraftVariable::from("PUSHBACK ATTACHED", "Bool", 0)?,
+            hyd_right_alternate_brake_pressure: NamedVariable::from(
+                "A32NX_HYD_BRAKE_ALTN_RIGHT_PRESS",
+            ),
+            hyd_right_brake_pedal_position: AircraftVariable::from(
+                "BRAKE RIGHT POSITION",
+                "Percent",
+                0,
+            )?,
+            hyd_right_normal_brake_pressure: NamedVariable::from(
+                "A32NX_HYD_BRAKE_NORM_RIGHT_PRESS",
+            ),
+            hyd_yellow_electric_pump_pb_on: NamedVariable::from("A32NX_OVHD_HYD_EPUMPY_PB_IS_ON"),
+            hyd_yellow_electric_pump_power: NamedVariable::from("A32NX_HYD_YELLOW_EPUMP_POWER"),
+            hyd_left_aileron_position: NamedVariable::from("A32NX_HYD_LEFT_AILERON_POSITION"),
+            hyd_right_aileron_position: NamedVariable::from("A32NX_HYD_RIGHT_AILERON_POSITION"),
+            hyd_left_elevator_position: NamedVariable::from("A32NX_HYD_LEFT_ELEVATOR_POSITION"),
+            hyd_right_elevator_position: NamedVariable::from("A32NX_HYD_RIGHT_ELEVATOR_POSITION"),
+            hyd_left_spoiler_1_position: NamedVariable::from("A32NX_HYD_LEFT_SPOILER_1_POSITION"),
+            hyd_left_spoiler_2_position: NamedVariable::from("A32NX_HYD_LEFT_SPOILER_2_POSITION"),
+            hyd_left_spoiler_3_position: NamedVariable::from("A32NX_HYD_LEFT_SPOILER_3_POSITION"),
+            hyd_left_spoiler_4_position: NamedVariable::from("A32NX_HYD_LEFT_SPOILER_4_POSITION"),
+            hyd_left_spoiler_5_position: NamedVariable::from("A32NX_HYD_LEFT_SPOILER_5_POSITION"),
+            hyd_right_spoiler_1_position: NamedVariable::from("A32NX_HYD_RIGHT_SPOILER_1_POSITION"),
+            hyd_right_spoiler_2_position: NamedVariable::from("A32NX_HYD_RIGHT_SPOILER_2_POSITION"),
+            hyd_right_spoiler_3_position: NamedVariable::from("A32NX_HYD_RIGHT_SPOILER_3_POSITION"),
+            hyd_right_spoiler_4_position: NamedVariable::from("A32NX_HYD_RIGHT_SPOILER_4_POSITION"),
+            hyd_right_spoiler_5_position: NamedVariable::from("A32NX_HYD_RIGHT_SPOILER_5_POSITION"),
+            hyd_spoiler_availability: NamedVariable::from("A32NX_HYD_SPOILER_AVAILABILITY"),
+            hyd_triple_indicator_accumulator_pressure: NamedVariable::from(
+                "A32NX_HYD_TRIPLE_INDICATOR_ACC_PRESS",
+            ),
+            hyd_triple_indicator_left_brake_pressure: NamedVariable::from(
+                "A32NX_HYD_TRIPLE_INDICATOR_LEFT_PRESS",
+            ),
+            hyd_triple_indicator_right_brake_pressure: NamedVariable::from(
+                "A32NX_HYD_TRIPLE_INDICATOR_RIGHT_PRESS",
+            ),
+            hyd_nose_wheel_steering_angle_limit: NamedVariable::from("A32NX_HYD_NWS_ANGLE_LIMIT"),
+            hyd_nose_wheel_steering_available: NamedVariable::from("A32NX_HYD_NWS_AVAILABLE"),
+            hyd_ptu_barking_intensity: NamedVariable::from("A32NX_HYD_PTU_BARKING_INTENSITY"),
+            hyd_ptu_pb_auto: NamedVariable::from("A32NX_OVHD_HYD_PTU_PB_IS_AUTO"),
+            hyd_ptu_pb_fault_light: NamedVariable::from("A32NX_OVHD_HYD_PTU_PB_FAULT_LIGHT"),
+            hyd_ptu_pb_off_light: NamedVariable::from("A32NX_OVHD_HYD_PTU_PB_OFF_LIGHT"),
+            hyd_ptu_self_test_in_progress: NamedVariable::from("A32NX_HYD_PTU_SELF_TEST"),
+            hyd_ptu_direction: NamedVariable::from("A32NX_HYD_PTU_DIRECTION"),
+            hyd_ptu_flow: NamedVariable::from("A32NX_HYD_PTU_FLOW"),
+            hyd_ptu_shaft_speed: NamedVariable::from("A32NX_HYD_PTU_SHAFT_SPEED"),
+            hyd_rat_in_transit: NamedVariable::from("A32NX_HYD_RAT_IN_TRANSIT"),
+            hyd_rat_stow_position: NamedVariable::from("A32NX_HYD_RAT_STOW_POSITION"),
+            hyd_rat_stow_requested: NamedVariable::from("A32NX_HYD_RAT_STOW"),
+            hyd_rat_time_to_deploy: NamedVariable::from("A32NX_HYD_RAT_TIME_TO_DEPLOY"),
+            hyd_rudder_travel_limit: NamedVariable::from("A32NX_HYD_RUDDER_TRAVEL_LIMIT"),
+            hyd_blue_pressure: NamedVariable::from("A32NX_HYD_BLUE_PRESSURE"),
+            hyd_blue_reservoir_volume: NamedVariable::from("A32NX_HYD_BLUE_RESERVOIR"),
+            hyd_green_pressure: NamedVariable::from("A32NX_HYD_GREEN_PRESSURE"),
+            hyd_green_reservoir_volume: NamedVariable::from("A32NX_HYD_GREEN_RESERVOIR"),
+            hyd_yellow_pressure: NamedVariable::from("A32NX_HYD_YELLOW_PRESSURE"),
+            hyd_yellow_reservoir_volume: NamedVariable::from("A32NX_HYD_YELLOW_RESERVOIR"),
             indicated_airspeed: AircraftVariable::from("AIRSPEED INDICATED", "Knots", 0)?,
             indicated_altitude: AircraftVariable::from("INDICATED ALTITUDE", "Feet", 0)?,
             left_inner_tank_fuel_quantity: AircraftVariable::from(
@@ -382,16 +709,26 @@ impl A320SimulatorReadWriter {
                 "Pounds",
                 0,
             )?,
+            left_wheel_rpm: AircraftVariable::from("LEFT WHEEL RPM", "RPM", 0)?,
+            left_wing_flex: AircraftVariable::from("WING FLEX PCT", "Percent over 100", 1)?,
+            longitudinal_velocity: AircraftVariable::from("VELOCITY BODY Z", "Feet per second", 0)?,
+            on_ground: AircraftVariable::from("SIM ON GROUND", "Bool", 0)?,
+            right_wheel_rpm: AircraftVariable::from("RIGHT WHEEL RPM", "RPM", 0)?,
+            right_wing_flex: AircraftVariable::from("WING FLEX PCT", "Percent over 100", 2)?,
             unlimited_fuel: AircraftVariable::from("UNLIMITED FUEL", "Bool", 0)?,
+            vertical_velocity: AircraftVariable::from("VELOCITY BODY Y", "Feet per second", 0)?,
         })
     }
 }
 impl SimulatorReadWriter for A320SimulatorReadWriter {
     fn read(&self) -> SimulatorReadState {
         SimulatorReadState {
+            ambient_pressure: Pressure::new::<inch_of_mercury>(self.ambient_pressure.get()),
             ambient_temperature: ThermodynamicTemperature::new::<degree_celsius>(
                 self.ambient_temperature.get(),
             ),
+            // The annunciator light switch is in TEST position at 0, BRT at 1 and DIM at 2.
+            annunciator_light_test: self.annunciator_light_test.get_value() == 0.,
             apu: SimulatorApuReadState {
                 master_sw_pb_on: to_bool(self.apu_master_sw_pb_on.get_value()),
                 start_pb_on: to_bool(self.apu_start_pb_on.get_value()),
@@ -419,6 +756,57 @@ impl SimulatorReadWriter for A320SimulatorReadWriter {
             },
             fire: SimulatorFireReadState {
                 apu_fire_button_released: to_bool(self.apu_fire_button_released.get_value()),
+                engine_fire_button_released: [
+                    to_bool(self.engine_1_fire_button_released.get_value()),
+                    to_bool(self.engine_2_fire_button_released.get_value()),
+                ],
+                engine_fire_button_pushed_in: [
+                    to_bool(self.engine_1_fire_button_pushed_in.get_value()),
+                    to_bool(self.engine_2_fire_button_pushed_in.get_value()),
+                ],
+            },
+            flight_phase: self.flight_phase.get_value() as u8,
+            ground_speed: Velocity::new::<knot>(self.ground_speed.get()),
+            hydraulic: SimulatorHydraulicReadState {
+                anti_skid_and_nws_switch_on: to_bool(self.hyd_anti_skid_and_nws_switch_on.get()),
+                autobrake_lo_pb_pressed: to_bool(self.hyd_autobrake_lo_pb_pressed.get_value()),
+                autobrake_med_pb_pressed: to_bool(self.hyd_autobrake_med_pb_pressed.get_value()),
+                autobrake_max_pb_pressed: to_bool(self.hyd_autobrake_max_pb_pressed.get_value()),
+                blue_electric_pump_pb_auto: to_bool(
+                    self.hyd_blue_electric_pump_pb_auto.get_value(),
+                ),
+                blue_ground_cart_pressure: Pressure::new::<psi>(
+                    self.hyd_blue_ground_cart_pressure.get_value(),
+                ),
+                blue_pump_override_pb_on: to_bool(self.hyd_blue_pump_override_pb_on.get_value()),
+                brake_fans_running: to_bool(self.hyd_brake_fans_running.get_value()),
+                cargo_door_selector_operated: [
+                    to_bool(self.hyd_fwd_cargo_door_selector_operated.get_value()),
+                    to_bool(self.hyd_aft_cargo_door_selector_operated.get_value()),
+                ],
+                dump_state_requested: to_bool(self.hyd_dump_state_requested.get_value()),
+                engine_pump_pb_auto: [
+                    to_bool(self.hyd_engine_1_pump_pb_auto.get_value()),
+                    to_bool(self.hyd_engine_2_pump_pb_auto.get_value()),
+                ],
+                // The ground spoilers extend to full deflection, moving the handle with them.
+                ground_spoilers_deployed: self.hyd_ground_spoilers_deployed.get() > 90.,
+                left_brake_pedal_position: Ratio::new::<percent>(
+                    self.hyd_left_brake_pedal_position.get(),
+                ),
+                right_brake_pedal_position: Ratio::new::<percent>(
+                    self.hyd_right_brake_pedal_position.get(),
+                ),
+                nose_wheel_steering_tow_pin_inserted: to_bool(
+                    self.hyd_nws_tow_pin_inserted.get_value(),
+                ),
+                parking_brake_applied: to_bool(self.hyd_parking_brake_applied.get()),
+                ptu_pb_auto: to_bool(self.hyd_ptu_pb_auto.get_value()),
+                pushback_tug_attached: to_bool(self.hyd_pushback_attached.get()),
+                rat_stow_requested: to_bool(self.hyd_rat_stow_requested.get_value()),
+                yellow_electric_pump_pb_on: to_bool(
+                    self.hyd_yellow_electric_pump_pb_on.get_value(),
+                ),
             },
             pneumatic: SimulatorPneumaticReadState {
                 apu_bleed_pb_on: to_bool(self.apu_bleed_pb_on.get_value()),
@@ -427,12 +815,35 @@ impl SimulatorReadWriter for A320SimulatorReadWriter {
                 Ratio::new::<percent>(self.engine_1_n2.get()),
                 Ratio::new::<percent>(self.engine_2_n2.get()),
             ],
+            engine_master_on: [
+                to_bool(self.engine_1_master_on.get()),
+                to_bool(self.engine_2_master_on.get()),
+            ],
             indicated_airspeed: Velocity::new::<knot>(self.indicated_airspeed.get()),
             indicated_altitude: Length::new::<foot>(self.indicated_altitude.get()),
+            // The simulator provides a single wheel speed for each main gear.
+            landing_gear: SimulatorLandingGearReadState {
+                gear_lever_up: !to_bool(self.gear_handle_position.get()),
+                wheel_rpm: [
+                    AngularVelocity::new::<revolution_per_minute>(self.left_wheel_rpm.get()),
+                    AngularVelocity::new::<revolution_per_minute>(self.left_wheel_rpm.get()),
+                    AngularVelocity::new::<revolution_per_minute>(self.right_wheel_rpm.get()),
+                    AngularVelocity::new::<revolution_per_minute>(self.right_wheel_rpm.get()),
+                ],
+            },
             left_inner_tank_fuel_quantity: Mass::new::<pound>(
                 self.left_inner_tank_fuel_quantity.get(),
             ),
+            longitudinal_velocity: Velocity::new::<foot_per_second>(
+                self.longitudinal_velocity.get(),
+            ),
+            on_ground: to_bool(self.on_ground.get()),
             unlimited_fuel: to_bool(self.unlimited_fuel.get()),
+            vertical_velocity: Velocity::new::<foot_per_second>(self.vertical_velocity.get()),
+            wing_flex: [
+                Ratio::new::<ratio>(self.left_wing_flex.get()),
+                Ratio::new::<ratio>(self.right_wing_flex.get()),
+            ],
         }
     }
 
@@ -728,5 +1139,168 @@ impl SimulatorReadWriter for A320SimulatorReadWriter {
             .set_value(from_bool(
                 state.electrical.transformer_rectifiers[2].potential_within_normal_range,
             ));
+        self.hyd_brake_accumulator_pressure
+            .set_value(state.hydraulic.brake_accumulator_pressure.get::<psi>());
+        self.hyd_brake_1_temperature
+            .set_value(state.hydraulic.brake_temperatures[0].get::<degree_celsius>());
+        self.hyd_brake_2_temperature
+            .set_value(state.hydraulic.brake_temperatures[1].get::<degree_celsius>());
+        self.hyd_brake_3_temperature
+            .set_value(state.hydraulic.brake_temperatures[2].get::<degree_celsius>());
+        self.hyd_brake_4_temperature
+            .set_value(state.hydraulic.brake_temperatures[3].get::<degree_celsius>());
+        self.hyd_left_alternate_brake_pressure
+            .set_value(state.hydraulic.left_alternate_brake_pressure.get::<psi>());
+        self.hyd_right_alternate_brake_pressure
+            .set_value(state.hydraulic.right_alternate_brake_pressure.get::<psi>());
+        self.hyd_left_normal_brake_pressure
+            .set_value(state.hydraulic.left_normal_brake_pressure.get::<psi>());
+        self.hyd_right_normal_brake_pressure
+            .set_value(state.hydraulic.right_normal_brake_pressure.get::<psi>());
+        self.hyd_autobrake_lo_armed
+            .set_value(from_bool(state.hydraulic.autobrake_lo_armed));
+        self.hyd_autobrake_med_armed
+            .set_value(from_bool(state.hydraulic.autobrake_med_armed));
+        self.hyd_autobrake_max_armed
+            .set_value(from_bool(state.hydraulic.autobrake_max_armed));
+        self.hyd_autobrake_decel
+            .set_value(from_bool(state.hydraulic.autobrake_decel));
+        self.hyd_bscu_channel_1_fault
+            .set_value(from_bool(state.hydraulic.bscu_channel_fault[0]));
+        self.hyd_bscu_channel_2_fault
+            .set_value(from_bool(state.hydraulic.bscu_channel_fault[1]));
+        self.hyd_blue_electric_pump_pb_fault_light
+            .set_value(from_bool(state.hydraulic.blue_electric_pump_pb_lights.fault));
+        self.hyd_blue_electric_pump_pb_off_light
+            .set_value(from_bool(state.hydraulic.blue_electric_pump_pb_lights.off));
+        self.hyd_blue_electric_pump_power
+            .set_value(state.hydraulic.blue_electric_pump_power.get::<watt>());
+        self.hyd_engine_driven_pump_1_shaft_power
+            .set_value(state.hydraulic.engine_driven_pump_shaft_power[0].get::<watt>());
+        self.hyd_engine_driven_pump_2_shaft_power
+            .set_value(state.hydraulic.engine_driven_pump_shaft_power[1].get::<watt>());
+        self.hyd_nose_gear_downlocked
+            .set_value(from_bool(state.hydraulic.gear_downlocked[0]));
+        self.hyd_nose_gear_uplocked
+            .set_value(from_bool(state.hydraulic.gear_uplocked[0]));
+        self.hyd_left_gear_downlocked
+            .set_value(from_bool(state.hydraulic.gear_downlocked[1]));
+        self.hyd_left_gear_uplocked
+            .set_value(from_bool(state.hydraulic.gear_uplocked[1]));
+        self.hyd_right_gear_downlocked
+            .set_value(from_bool(state.hydraulic.gear_downlocked[2]));
+        self.hyd_right_gear_uplocked
+            .set_value(from_bool(state.hydraulic.gear_uplocked[2]));
+        self.lgciu_nose_gear_disagree
+            .set_value(from_bool(state.hydraulic.gear_position_disagree[0]));
+        self.lgciu_left_gear_disagree
+            .set_value(from_bool(state.hydraulic.gear_position_disagree[1]));
+        self.lgciu_right_gear_disagree
+            .set_value(from_bool(state.hydraulic.gear_position_disagree[2]));
+        self.hyd_engine_1_pump_pb_fault_light
+            .set_value(from_bool(state.hydraulic.engine_pump_pb_lights[0].fault));
+        self.hyd_engine_1_pump_pb_off_light
+            .set_value(from_bool(state.hydraulic.engine_pump_pb_lights[0].off));
+        self.hyd_engine_2_pump_pb_fault_light
+            .set_value(from_bool(state.hydraulic.engine_pump_pb_lights[1].fault));
+        self.hyd_engine_2_pump_pb_off_light
+            .set_value(from_bool(state.hydraulic.engine_pump_pb_lights[1].off));
+        self.hyd_yellow_electric_pump_power
+            .set_value(state.hydraulic.yellow_electric_pump_power.get::<watt>());
+        self.hyd_green_system_low_pressure_caution
+            .set_value(from_bool(state.hydraulic.system_low_pressure_caution[0]));
+        self.hyd_blue_system_low_pressure_caution
+            .set_value(from_bool(state.hydraulic.system_low_pressure_caution[1]));
+        self.hyd_yellow_system_low_pressure_caution
+            .set_value(from_bool(state.hydraulic.system_low_pressure_caution[2]));
+        self.hyd_engine_1_pump_low_pressure_caution
+            .set_value(from_bool(state.hydraulic.engine_pump_low_pressure_caution[0]));
+        self.hyd_engine_2_pump_low_pressure_caution
+            .set_value(from_bool(state.hydraulic.engine_pump_low_pressure_caution[1]));
+        self.hyd_engine_1_pump_overheat_caution
+            .set_value(from_bool(state.hydraulic.engine_pump_overheat_caution[0]));
+        self.hyd_engine_2_pump_overheat_caution
+            .set_value(from_bool(state.hydraulic.engine_pump_overheat_caution[1]));
+        self.hyd_blue_electric_pump_low_pressure_caution
+            .set_value(from_bool(state.hydraulic.blue_electric_pump_low_pressure_caution));
+        self.hyd_left_aileron_position
+            .set_value(state.hydraulic.aileron_positions[0].get::<degree>());
+        self.hyd_right_aileron_position
+            .set_value(state.hydraulic.aileron_positions[1].get::<degree>());
+        self.hyd_left_elevator_position
+            .set_value(state.hydraulic.elevator_positions[0].get::<degree>());
+        self.hyd_right_elevator_position
+            .set_value(state.hydraulic.elevator_positions[1].get::<degree>());
+        self.hyd_left_spoiler_1_position
+            .set_value(state.hydraulic.left_spoiler_positions[0].get::<degree>());
+        self.hyd_left_spoiler_2_position
+            .set_value(state.hydraulic.left_spoiler_positions[1].get::<degree>());
+        self.hyd_left_spoiler_3_position
+            .set_value(state.hydraulic.left_spoiler_positions[2].get::<degree>());
+        self.hyd_left_spoiler_4_position
+            .set_value(state.hydraulic.left_spoiler_positions[3].get::<degree>());
+        self.hyd_left_spoiler_5_position
+            .set_value(state.hydraulic.left_spoiler_positions[4].get::<degree>());
+        self.hyd_right_spoiler_1_position
+            .set_value(state.hydraulic.right_spoiler_positions[0].get::<degree>());
+        self.hyd_right_spoiler_2_position
+            .set_value(state.hydraulic.right_spoiler_positions[1].get::<degree>());
+        self.hyd_right_spoiler_3_position
+            .set_value(state.hydraulic.right_spoiler_positions[2].get::<degree>());
+        self.hyd_right_spoiler_4_position
+            .set_value(state.hydraulic.right_spoiler_positions[3].get::<degree>());
+        self.hyd_right_spoiler_5_position
+            .set_value(state.hydraulic.right_spoiler_positions[4].get::<degree>());
+        self.hyd_spoiler_availability
+            .set_value(state.hydraulic.spoiler_availability as f64);
+        self.hyd_triple_indicator_accumulator_pressure
+            .set_value(state.hydraulic.triple_indicator_accumulator_pressure.get::<psi>());
+        self.hyd_triple_indicator_left_brake_pressure
+            .set_value(state.hydraulic.triple_indicator_left_brake_pressure.get::<psi>());
+        self.hyd_triple_indicator_right_brake_pressure
+            .set_value(state.hydraulic.triple_indicator_right_brake_pressure.get::<psi>());
+        self.hyd_nose_wheel_steering_angle_limit
+            .set_value(state.hydraulic.nose_wheel_steering_angle_limit.get::<degree>());
+        self.hyd_nose_wheel_steering_available
+            .set_value(from_bool(state.hydraulic.nose_wheel_steering_available));
+        self.hyd_ptu_barking_intensity
+            .set_value(state.hydraulic.ptu_barking_intensity);
+        self.hyd_ptu_pb_fault_light
+            .set_value(from_bool(state.hydraulic.ptu_pb_lights.fault));
+        self.hyd_ptu_pb_off_light
+            .set_value(from_bool(state.hydraulic.ptu_pb_lights.off));
+        self.hyd_ptu_self_test_in_progress
+            .set_value(from_bool(state.hydraulic.ptu_self_test_in_progress));
+        // Positive when green powers yellow, negative when yellow powers green.
+        self.hyd_ptu_direction
+            .set_value(match state.hydraulic.ptu_state {
+                PtuState::Off => 0.,
+                PtuState::GreenToYellow => 1.,
+                PtuState::YellowToGreen => -1.,
+            });
+        self.hyd_ptu_flow
+            .set_value(state.hydraulic.ptu_flow.get::<gallon_per_minute>());
+        self.hyd_ptu_shaft_speed
+            .set_value(state.hydraulic.ptu_shaft_speed.get::<revolution_per_minute>());
+        self.hyd_rat_in_transit
+            .set_value(from_bool(state.hydraulic.rat_in_transit));
+        self.hyd_rat_stow_position
+            .set_value(state.hydraulic.rat_deployment_progress.get::<percent>());
+        self.hyd_rat_time_to_deploy
+            .set_value(state.hydraulic.rat_time_to_deploy.as_secs_f64());
+        self.hyd_rudder_travel_limit
+            .set_value(state.hydraulic.rudder_travel_limit.get::<degree>());
+        self.hyd_blue_pressure
+            .set_value(state.hydraulic.blue_pressure.get::<psi>());
+        self.hyd_blue_reservoir_volume
+            .set_value(state.hydraulic.blue_reservoir_volume.get::<gallon>());
+        self.hyd_green_pressure
+            .set_value(state.hydraulic.green_pressure.get::<psi>());
+        self.hyd_green_reservoir_volume
+            .set_value(state.hydraulic.green_reservoir_volume.get::<gallon>());
+        self.hyd_yellow_pressure
+            .set_value(state.hydraulic.yellow_pressure.get::<psi>());
+        self.hyd_yellow_reservoir_volume
+            .set_value(state.hydraulic.yellow_reservoir_volume.get::<gallon>());
     }
 }
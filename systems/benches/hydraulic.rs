@@ -0,0 +1,105 @@
+use airbus_systems::{
+    engine::Engine,
+    hydraulic::{interpolation, ElectricPump, HydLoop, IntegrationScheme},
+    simulator::UpdateContext,
+    A320Hydraulic,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::time::Duration;
+use uom::si::{
+    f64::*, length::foot, ratio::percent, thermodynamic_temperature::degree_celsius, velocity::knot,
+};
+
+fn context(delta: Duration) -> UpdateContext {
+    UpdateContext::new(
+        delta,
+        Velocity::new::<knot>(250.),
+        Length::new::<foot>(5000.),
+        ThermodynamicTemperature::new::<degree_celsius>(25.0),
+        Velocity::new::<knot>(0.),
+    )
+}
+
+fn running_engine(number: usize) -> Engine {
+    let mut engine = Engine::new(number);
+    engine.n2 = Ratio::new::<percent>(80.);
+
+    engine
+}
+
+fn interpolation_benchmark(c: &mut Criterion) {
+    let xs = [
+        -100.0, -10.0, 10.0, 240.0, 500.0, 600.0, 1000.0, 1100.0, 1200.0, 5000.0,
+    ];
+    let ys = [
+        -10.0, 10.0, 100.0, 300.0, 1200.0, 1300.0, 1500.0, 1600.0, 1700.0, 1800.0,
+    ];
+
+    c.bench_function("interpolation", |b| {
+        b.iter(|| interpolation(black_box(&xs), black_box(&ys), black_box(750.)))
+    });
+}
+
+fn hyd_loop_update_benchmark(c: &mut Criterion) {
+    let mut hyd_loop = HydLoop::a320_yellow();
+    let mut pump = ElectricPump::new();
+    pump.start();
+    let delta = Duration::from_millis(100);
+    let context = context(delta);
+
+    c.bench_function("HydLoop::update", |b| {
+        b.iter(|| {
+            pump.update(&delta, &context, &hyd_loop);
+            hyd_loop.update(&delta, &context, Some(&pump), None, None, None);
+        })
+    });
+}
+
+// Implicit Euler solves each step by bisection, which the other schemes don't need.
+fn integration_scheme_benchmark(c: &mut Criterion) {
+    let delta = Duration::from_millis(100);
+    let context = context(delta);
+    let mut group = c.benchmark_group("HydLoop::update integration scheme");
+    for scheme in [
+        IntegrationScheme::ExplicitEuler,
+        IntegrationScheme::SemiImplicitEuler,
+        IntegrationScheme::RungeKutta2,
+        IntegrationScheme::ImplicitEuler,
+    ]
+    .iter()
+    {
+        let mut hyd_loop = HydLoop::a320_yellow();
+        hyd_loop.set_integration_scheme(*scheme);
+        let mut pump = ElectricPump::new();
+        pump.start();
+
+        group.bench_function(format!("{:?}", scheme), |b| {
+            b.iter(|| {
+                pump.update(&delta, &context, &hyd_loop);
+                hyd_loop.update(&delta, &context, Some(&pump), None, None, None);
+            })
+        });
+    }
+    group.finish();
+}
+
+// One second of simulation, which the hydraulic system catches up on in ten fixed steps.
+fn a320_hydraulic_update_benchmark(c: &mut Criterion) {
+    let mut hydraulic = A320Hydraulic::new();
+    let engine_1 = running_engine(1);
+    let engine_2 = running_engine(2);
+    let context = context(Duration::from_secs(1));
+
+    c.bench_function("A320Hydraulic::update 10 steps", |b| {
+        b.iter(|| hydraulic.update(&context, &engine_1, &engine_2))
+    });
+}
+
+criterion_group!(
+    benches,
+    interpolation_benchmark,
+    hyd_loop_update_benchmark,
+    integration_scheme_benchmark,
+    a320_hydraulic_update_benchmark
+);
+criterion_main!(benches);
@@ -0,0 +1,230 @@
+//! Runs canned scenarios on the A320 hydraulic system without the simulator,
+//! printing a trace of the loops once per second.
+//!
+//! ```text
+//! cargo run --example hydraulics_cli -- <scenario> [--csv <file>] [--report <file>]
+//! ```
+//!
+//! `--csv` exports the trace, `--report` writes the JSON state report of the
+//! system at the end of the scenario. Run without arguments to list scenarios.
+use airbus_systems::{
+    engine::Engine,
+    hydraulic::LoopId,
+    simulator::{
+        SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorReadState,
+        UpdateContext,
+    },
+    A320Hydraulic, A320HydraulicOverheadPanel,
+};
+use std::{env, fs, process, time::Duration};
+use uom::si::{
+    f64::*, length::foot, pressure::psi, ratio::percent, thermodynamic_temperature::degree_celsius,
+    velocity::knot, volume::gallon,
+};
+
+const FRAME: Duration = Duration::from_millis(50);
+const FRAMES_PER_SECOND: u64 = 20;
+
+// N2 of an engine at idle, reached this long after its master switch is set to on.
+const IDLE_N2_PERCENT: f64 = 60.;
+const ENGINE_START_SECS: f64 = 30.;
+
+const CSV_HEADER: &str =
+    "time_s,green_psi,blue_psi,yellow_psi,green_reservoir_gal,blue_reservoir_gal,yellow_reservoir_gal,ptu,rat_rpm";
+
+/// A named scenario, setting up the inputs of the system at each second.
+struct Scenario {
+    name: &'static str,
+    description: &'static str,
+    duration_secs: u64,
+    in_flight: bool,
+    setup: fn(&mut Rig, u64),
+}
+
+const SCENARIOS: [Scenario; 3] = [
+    Scenario {
+        name: "engine-start",
+        description: "Engine 2 then engine 1 started on the ground, with the PTU self-test",
+        duration_secs: 120,
+        in_flight: false,
+        setup: engine_start,
+    },
+    Scenario {
+        name: "cargo-door",
+        description: "Cargo door operated with the engines off, on the yellow electric pump",
+        duration_secs: 90,
+        in_flight: false,
+        setup: cargo_door,
+    },
+    Scenario {
+        name: "rat-deployment",
+        description: "Dual engine flame out in flight and RAT deployment",
+        duration_secs: 60,
+        in_flight: true,
+        setup: rat_deployment,
+    },
+];
+
+fn engine_start(rig: &mut Rig, second: u64) {
+    rig.state.hydraulic.parking_brake_applied = true;
+    rig.engines[0].master_on = second >= 60;
+    rig.engines[1].master_on = true;
+    rig.engines[1].n2 = started_engine_n2(second);
+    rig.engines[0].n2 = started_engine_n2(second.saturating_sub(60));
+}
+
+fn started_engine_n2(seconds_since_start: u64) -> Ratio {
+    Ratio::new::<percent>(
+        IDLE_N2_PERCENT * (seconds_since_start as f64 / ENGINE_START_SECS).min(1.),
+    )
+}
+
+fn cargo_door(rig: &mut Rig, second: u64) {
+    rig.hydraulic
+        .set_cargo_door_operated((5..35).contains(&second));
+}
+
+fn rat_deployment(rig: &mut Rig, second: u64) {
+    let running = second < 5;
+    for engine in rig.engines.iter_mut() {
+        engine.n2 = Ratio::new::<percent>(if running { 80. } else { 0. });
+        engine.master_on = running;
+    }
+    if second == 5 {
+        rig.hydraulic.deploy_rat();
+    }
+}
+
+/// Reads the state of the simulator into the hydraulic logic, as the
+/// simulation does on each tick.
+struct StateReader<'a> {
+    state: &'a SimulatorReadState,
+}
+impl SimulatorElementVisitor for StateReader<'_> {
+    fn visit(&mut self, visited: &mut Box<&mut dyn SimulatorElement>) {
+        visited.read(self.state);
+    }
+}
+
+struct Rig {
+    hydraulic: A320Hydraulic,
+    overhead: A320HydraulicOverheadPanel,
+    engines: [Engine; 2],
+    state: SimulatorReadState,
+    context: UpdateContext,
+}
+impl Rig {
+    fn new(in_flight: bool) -> Self {
+        let mut state = SimulatorReadState::default();
+        state.on_ground = !in_flight;
+        state.hydraulic.engine_pump_pb_auto = [true, true];
+        state.hydraulic.blue_electric_pump_pb_auto = true;
+        state.hydraulic.ptu_pb_auto = true;
+        let airspeed = Velocity::new::<knot>(if in_flight { 250. } else { 0. });
+
+        Rig {
+            hydraulic: A320Hydraulic::new(),
+            overhead: A320HydraulicOverheadPanel::new(),
+            engines: [Engine::new(1), Engine::new(2)],
+            state,
+            context: UpdateContext::new(
+                FRAME,
+                airspeed,
+                Length::new::<foot>(if in_flight { 10000. } else { 0. }),
+                ThermodynamicTemperature::new::<degree_celsius>(15.),
+                airspeed,
+            ),
+        }
+    }
+
+    fn run_second(&mut self) {
+        self.hydraulic
+            .accept(&mut Box::new(&mut StateReader { state: &self.state }));
+        self.overhead.read(&self.state);
+        self.hydraulic
+            .update_after_hydraulic_overhead(&self.overhead);
+        for _ in 0..FRAMES_PER_SECOND {
+            self.hydraulic
+                .update(&self.context, &self.engines[0], &self.engines[1]);
+        }
+        self.overhead.update_after_hydraulic(&self.hydraulic);
+    }
+
+    fn trace_line(&self, second: u64) -> String {
+        let colors = [LoopId::GREEN, LoopId::BLUE, LoopId::YELLOW];
+        let pressures: Vec<String> = colors
+            .iter()
+            .map(|color| format!("{:.0}", self.hydraulic.loop_pressure(*color).get::<psi>()))
+            .collect();
+        let reservoirs: Vec<String> = colors
+            .iter()
+            .map(|color| {
+                format!(
+                    "{:.2}",
+                    self.hydraulic.reservoir_volume(*color).get::<gallon>()
+                )
+            })
+            .collect();
+
+        format!(
+            "{},{},{},{:?},{:.0}",
+            second,
+            pressures.join(","),
+            reservoirs.join(","),
+            self.hydraulic.ptu_state(),
+            self.hydraulic.rat_rpm()
+        )
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: hydraulics_cli <scenario> [--csv <file>] [--report <file>]");
+    eprintln!();
+    eprintln!("scenarios:");
+    for scenario in SCENARIOS.iter() {
+        eprintln!("  {:<16}{}", scenario.name, scenario.description);
+    }
+    process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let scenario = match args.first() {
+        Some(name) => SCENARIOS
+            .iter()
+            .find(|scenario| scenario.name == name)
+            .unwrap_or_else(|| usage()),
+        None => usage(),
+    };
+    let mut csv_path = None;
+    let mut report_path = None;
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        match (option.as_str(), options.next()) {
+            ("--csv", Some(path)) => csv_path = Some(path.clone()),
+            ("--report", Some(path)) => report_path = Some(path.clone()),
+            _ => usage(),
+        }
+    }
+
+    let mut rig = Rig::new(scenario.in_flight);
+    let mut trace = vec![CSV_HEADER.to_owned()];
+    println!("{}", CSV_HEADER);
+    for second in 0..scenario.duration_secs {
+        (scenario.setup)(&mut rig, second);
+        rig.run_second();
+
+        let line = rig.trace_line(second + 1);
+        println!("{}", line);
+        trace.push(line);
+    }
+
+    if let Some(path) = csv_path {
+        fs::write(&path, trace.join("\n") + "\n")
+            .unwrap_or_else(|error| panic!("Couldn't write the trace to {}: {}", path, error));
+    }
+    if let Some(path) = report_path {
+        fs::write(&path, rig.hydraulic.state_report().to_json())
+            .unwrap_or_else(|error| panic!("Couldn't write the report to {}: {}", path, error));
+    }
+}
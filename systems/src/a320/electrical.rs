@@ -3,8 +3,8 @@ use crate::{
     apu::AuxiliaryPowerUnit,
     electrical::{
         combine_electric_sources, Battery, CombinedElectricSource, Contactor, ElectricSource,
-        ElectricalBus, ElectricalBusStateFactory, ElectricalBusType, EmergencyGenerator,
-        EngineGenerator, ExternalPowerSource, PowerSupply, Powerable, StaticInverter,
+        ElectricalBus, ElectricalBusStateFactory, ElectricalBusType, EmergencyElectricalState,
+        EmergencyGenerator, EngineGenerator, ExternalPowerSource, PowerSupply, Powerable, StaticInverter,
         TransformerRectifier,
     },
     engine::Engine,
@@ -135,6 +135,11 @@ impl SimulatorElementVisitable for A320Electrical {
     }
 }
 impl SimulatorElement for A320Electrical {}
+impl EmergencyElectricalState for A320Electrical {
+    fn is_in_emergency_elec(&self) -> bool {
+        self.alternating_current.ac_bus_1_and_2_unpowered()
+    }
+}
 
 trait AlternatingCurrentState {
     fn ac_bus_1_and_2_unpowered(&self) -> bool;
@@ -199,12 +204,8 @@ impl A320AlternatingCurrentElectrical {
         hydraulic: &A320Hydraulic,
         overhead: &A320ElectricalOverheadPanel,
     ) {
-        self.emergency_gen.update(
-            // ON GROUND BAT ONLY SPEED <= 100 kts scenario. We'll probably need to move this logic into
-            // the ram air turbine, emergency generator and hydraulic implementation.
-            hydraulic.is_blue_pressurised()
-                && context.indicated_airspeed > Velocity::new::<knot>(100.),
-        );
+        self.emergency_gen
+            .update(&hydraulic.emergency_generator_supply(context));
 
         self.main_power_sources
             .update(context, engine1, engine2, apu, ext_pwr, overhead);
@@ -2510,6 +2511,7 @@ mod a320_electrical_circuit_tests {
                 self.airspeed,
                 self.above_ground_level,
                 ThermodynamicTemperature::new::<degree_celsius>(0.),
+                Velocity::new::<knot>(0.),
             );
             self.elec.update(
                 &context,
@@ -2532,6 +2534,7 @@ mod a320_electrical_circuit_tests {
                 self.airspeed,
                 self.above_ground_level,
                 ThermodynamicTemperature::new::<degree_celsius>(0.),
+                Velocity::new::<knot>(0.),
             );
             self.elec.update(
                 &context,
@@ -2548,6 +2551,7 @@ mod a320_electrical_circuit_tests {
                 self.airspeed,
                 self.above_ground_level,
                 ThermodynamicTemperature::new::<degree_celsius>(0.),
+                Velocity::new::<knot>(0.),
             );
             self.elec.update(
                 &context,
@@ -0,0 +1,103 @@
+use crate::{
+    engine::EngineFirePushButtons,
+    overhead::FirePushButton,
+    simulator::{
+        SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorReadState,
+    },
+};
+
+pub struct A320EngineFireOverheadPanel {
+    engine_fire_buttons: [FirePushButton; 2],
+}
+impl A320EngineFireOverheadPanel {
+    pub fn new() -> Self {
+        A320EngineFireOverheadPanel {
+            engine_fire_buttons: [FirePushButton::new(), FirePushButton::new()],
+        }
+    }
+
+    pub fn engine_fire_button_is_released(&self, engine_number: usize) -> bool {
+        self.engine_fire_buttons[engine_number - 1].is_released()
+    }
+
+    /// Pushes the released fire button of the given engine back in.
+    pub fn reset_engine_fire_button(&mut self, engine_number: usize) {
+        self.engine_fire_buttons[engine_number - 1].reset();
+    }
+}
+impl EngineFirePushButtons for A320EngineFireOverheadPanel {
+    fn engine_fire_push_button_is_released(&self, engine_number: usize) -> bool {
+        self.engine_fire_button_is_released(engine_number)
+    }
+}
+impl Default for A320EngineFireOverheadPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl SimulatorElementVisitable for A320EngineFireOverheadPanel {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for A320EngineFireOverheadPanel {
+    fn read(&mut self, state: &SimulatorReadState) {
+        for ((button, released), pushed_in) in self
+            .engine_fire_buttons
+            .iter_mut()
+            .zip(state.fire.engine_fire_button_released.iter())
+            .zip(state.fire.engine_fire_button_pushed_in.iter())
+        {
+            button.set(*released);
+            if *pushed_in {
+                button.reset();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod a320_engine_fire_overhead_panel_tests {
+    use super::*;
+
+    #[test]
+    fn released_button_stays_released() {
+        let mut overhead = A320EngineFireOverheadPanel::new();
+        overhead.read(&state_with_released_buttons([false, true]));
+        overhead.read(&state_with_released_buttons([false, false]));
+
+        assert!(!overhead.engine_fire_button_is_released(1));
+        assert!(overhead.engine_fire_button_is_released(2));
+    }
+
+    #[test]
+    fn reset_button_is_no_longer_released() {
+        let mut overhead = A320EngineFireOverheadPanel::new();
+        overhead.read(&state_with_released_buttons([true, false]));
+
+        overhead.reset_engine_fire_button(1);
+
+        assert!(!overhead.engine_fire_button_is_released(1));
+    }
+
+    #[test]
+    fn button_pushed_back_in_is_no_longer_released() {
+        let mut overhead = A320EngineFireOverheadPanel::new();
+        overhead.read(&state_with_released_buttons([true, true]));
+
+        let mut state = state_with_released_buttons([true, true]);
+        state.fire.engine_fire_button_pushed_in = [true, false];
+        overhead.read(&state);
+        overhead.read(&state_with_released_buttons([true, true]));
+
+        assert!(!overhead.engine_fire_button_is_released(1));
+        assert!(overhead.engine_fire_button_is_released(2));
+    }
+
+    fn state_with_released_buttons(released: [bool; 2]) -> SimulatorReadState {
+        let mut state = SimulatorReadState::default();
+        state.fire.engine_fire_button_released = released;
+
+        state
+    }
+}
@@ -1,148 +1,3609 @@
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use uom::si::{
-    area::square_meter, f64::*, force::newton, length::foot, length::meter,
-    mass_density::kilogram_per_cubic_meter, pressure::atmosphere, pressure::pascal, pressure::psi,
+    angle::degree, angular_velocity::revolution_per_minute, area::square_meter, f64::*, force::newton, length::foot, length::meter,
+    mass_density::kilogram_per_cubic_meter, power::kilowatt, power::watt, pressure::atmosphere, pressure::psi,
     ratio::percent, thermodynamic_temperature::degree_celsius, time::second, velocity::knot,
     volume::cubic_inch, volume::gallon, volume::liter, volume_rate::cubic_meter_per_second,
     volume_rate::gallon_per_second,
 };
-use crate::{hydraulic::{ElectricPump, EngineDrivenPump, HydFluid, HydLoop, LoopColor, Pump, RatPump, Ptu},engine::Engine, overhead::{AutoOffPushButton, NormalAltnPushButton, OnOffPushButton}, shared::DelayedTrueLogicGate, simulator::UpdateContext};
+#[cfg(feature = "dev-tuning")]
+use crate::hydraulic::{TuningParameter, TuningReceiver};
+use super::{BrakesReport, ElectricPumpReport, EngineDrivenPumpReport, HydraulicStateReport, LogicReport, LoopReport, PtuReport, RamAirTurbineReport, ValvesReport};
+use crate::pneumatic::BleedAirValveState;
+use crate::{electrical::{ElectricalBusType, EmergencyElectricalState, PowerConsumption}, hydraulic::{ActuatorId, ActuatorType, AntiSkid, BranchNode, EmergencyGeneratorHydraulicSupply, AutobrakeController, BrakeCircuit, BrakePedals, BrakeSourceSelector, BrakingMode, BrakeSteeringControlUnit, BrakeTemperature, ConsumerPriority, ControlSurface, DigitalSensor, ElectricPump, ElectricPumpDescription, PumpWear, EngineDrivenPump, EngineDrivenPumpDescription, EngineVariant, ExternalLoads, FilterTimeConstants, FlowSharing, GearPositionSensors, HydFluid, HydLoop, HydraulicConfig, HydraulicConsumer, HydraulicInput, HydraulicLoopDescription, LandingGearLeg, LeakMeasurement, LeakZone, HydraulicSystemDescription, LoopId, NoseWheelSteering, PtuDescription, PtuState, RamAirTurbine, RatPump, RudderTravelLimiter, RatStowError, Ptu, SensorLocation, TripleIndicator},engine::{Engine, EngineDrivenPumpLoads, EngineStateProvider}, overhead::{AutoOffFaultPushButton, AutoOffPushButton, NormalAltnPushButton, OnOffPushButton, PushButtonLights}, shared::{Arinc429Word, DelayedFalseLogicGate, DelayedTrueLogicGate, SignStatus}, simulator::{
+        Clock, SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorReadState,
+        SimulatorWriteState, UpdateContext,
+    }};
 
 pub struct A320Hydraulic {
-    blue_loop: HydLoop,
-    green_loop: HydLoop,
-    yellow_loop: HydLoop,
-    engine_driven_pump_1: EngineDrivenPump,
-    engine_driven_pump_2: EngineDrivenPump,
-    blue_electric_pump: ElectricPump,
-    yellow_electric_pump: ElectricPump,
+    loops: Vec<HydLoop>,
+    engine_driven_pumps: Vec<(EngineDrivenPumpDescription, EngineDrivenPump)>,
+    electric_pumps: Vec<(ElectricPumpDescription, ElectricPump)>,
+    electric_pump_power_consumptions: Vec<PowerConsumption>,
+    ptu_description: Option<PtuDescription>,
     ptu: Ptu,
+    rat: RamAirTurbine,
+    rat_pump: RatPump,
+    alternate_brakes: BrakeCircuit,
+    anti_skid: AntiSkid,
+    autobrake: AutobrakeController,
+    bscu: BrakeSteeringControlUnit,
+    brake_pedals: BrakePedals,
+    brake_source: BrakeSourceSelector,
+    normal_brakes: BrakeCircuit,
+    brake_temperatures: [BrakeTemperature; 4],
+    triple_indicator: TripleIndicator,
+    //Nose, left main and right main gears
+    landing_gears: [LandingGearLeg; 3],
+    gear_position_sensors: [GearPositionSensors; 3],
+    gear_lever_up: bool,
+    ailerons: [ControlSurface; 2],
+    elevators: [ControlSurface; 2],
+    left_spoilers: [ControlSurface; 5],
+    right_spoilers: [ControlSurface; 5],
+    //Loads supplied by a flight model outside the crate, see attach_external_loads
+    external_loads: Option<Box<dyn ExternalLoads>>,
+    rudder_travel_limiter: RudderTravelLimiter,
+    nose_wheel_steering: NoseWheelSteering,
+    pressure_sensors: [DigitalSensor; 3],
+    quantity_sensors: [DigitalSensor; 3],
+    sensor_model_enabled: bool,
+    engine_bleed_valve_closed: [bool; 2],
+    apu_bleed_air_valve_open: bool,
+    reservoir_bleed_air_available: bool,
+    engine_driven_pump_pb_auto: [bool; 2],
+    blue_electric_pump_pb_auto: bool,
+    yellow_electric_pump_pb_on: bool,
+    emergency_elec: bool,
+    //Loop whose leak measurement valves are closed for the leak measurement ground test
+    leak_measurement_loop: Option<LoopId>,
+    leak_measurement: LeakMeasurement,
+    state_snapshot_requested: bool,
+    state_snapshot_toggle: bool,
+    rat_stow_toggle: bool,
+    logic: A320HydraulicLogic,
     total_sim_time_elapsed: Duration,
     lag_time_accumulator: Duration,
+    //Loop pressures seen by the actuators during the current actuator step, in loops order
+    actuator_loop_pressures: Vec<Pressure>,
+    //Loop pressures at the start of the current hydraulic step, kept to avoid allocating every step
+    hydraulic_step_start_pressures: Vec<Pressure>,
+    #[cfg(feature = "dev-tuning")]
+    tuning: Option<TuningReceiver>,
     // Until hydraulic is implemented, we'll fake it with this boolean.
     // blue_pressurised: bool,
 }
 
 impl A320Hydraulic {
     const MIN_PRESS_PRESSURISED : f64 = 300.0;
+    const PUMP_LOW_PRESSURE : f64 = 1450.0; //pump pressure switch threshold, drives the FAULT lights
+    const ENGINE_COUNT: usize = 2;
     const HYDRAULIC_SIM_TIME_STEP : u64 = 100; //refresh rate of hydraulic simulation in ms
     const ACTUATORS_SIM_TIME_STEP_MULT : u32 = 2; //refresh rate of actuators as multiplier of hydraulics. 2 means double frequency update
+    const PARKING_BRAKE_PRESSURE : f64 = 2000.0; //pressure applied to the brakes by the parking brake valve
+    //Loops powering the actuators of each surface. Left elevator comes first
+    const AILERON_LOOPS: [LoopId; 2] = [LoopId::BLUE, LoopId::GREEN];
+    const ELEVATOR_LOOPS: [[LoopId; 2]; 2] = [[LoopId::BLUE, LoopId::GREEN], [LoopId::BLUE, LoopId::YELLOW]];
+    //Order of the loops in sensor arrays
+    const SENSED_LOOPS: [LoopId; 3] = [LoopId::BLUE, LoopId::GREEN, LoopId::YELLOW];
+    //ARINC 429 labels (octal) of the words sent to the SDAC, in SENSED_LOOPS order
+    const PRESSURE_LABELS: [u8; 3] = [0o160, 0o161, 0o162];
+    const QUANTITY_LABELS: [u8; 3] = [0o163, 0o164, 0o165];
+    const VALVE_STATES_LABEL: u8 = 0o166;
+    const PRESSURE_RESOLUTION_PSI: f64 = 1.;
+    const QUANTITY_RESOLUTION_GALLON: f64 = 0.01;
+    const SPOILER_LOOPS: [LoopId; 5] = [LoopId::GREEN, LoopId::YELLOW, LoopId::BLUE, LoopId::YELLOW, LoopId::GREEN];
+    const ACTUATOR_BANK_STATIC_LEAK_FLOW_AT_3000_PSI: f64 = 0.005; // in gallons per second
+    const EMERGENCY_GENERATOR_SHAFT_POWER_KW: f64 = 6.5; //5kVA emergency generator through its hydraulic motor
 
     pub fn new() -> A320Hydraulic {
-        A320Hydraulic {
+        A320Hydraulic::from_description(A320Hydraulic::a320_description())
+    }
 
-            blue_loop: HydLoop::new(
-                LoopColor::Blue,
-                false,
-                false,
-                Volume::new::<gallon>(1.5),
-                Volume::new::<gallon>(1.6),
-                Volume::new::<gallon>(1.6),
-                Volume::new::<gallon>(1.5),
-                HydFluid::new(Pressure::new::<pascal>(1450000000.0)),
-            ),
-            green_loop: HydLoop::new(
-                LoopColor::Green,
-                true,
-                false,
-                Volume::new::<gallon>(10.2),
-                Volume::new::<gallon>(10.2),
-                Volume::new::<gallon>(8.0),
-                Volume::new::<gallon>(3.3),
-                HydFluid::new(Pressure::new::<pascal>(1450000000.0))
-            ),
-            yellow_loop: HydLoop::new(
-                LoopColor::Blue,
-                false,
-                true,
-                Volume::new::<gallon>(26.00),
-                Volume::new::<gallon>(26.41),
-                Volume::new::<gallon>(10.0),
-                Volume::new::<gallon>(3.83),
-                HydFluid::new(Pressure::new::<pascal>(1450000000.0))
-            ),
-            engine_driven_pump_1: EngineDrivenPump::new(),
-            engine_driven_pump_2: EngineDrivenPump::new(),
-            blue_electric_pump: ElectricPump::new(),
-            yellow_electric_pump: ElectricPump::new(),
+    //Loops, pumps and PTU of the A320: green and yellow are connected by the PTU, blue has its own electric pump
+    pub fn a320_description() -> HydraulicSystemDescription {
+        HydraulicSystemDescription::new()
+            .with_loop(HydraulicLoopDescription::a320_blue())
+            .with_loop(HydraulicLoopDescription::a320_green())
+            .with_loop(HydraulicLoopDescription::a320_yellow())
+            .with_engine_driven_pump(1, LoopId::GREEN)
+            .with_engine_driven_pump(2, LoopId::YELLOW)
+            .with_electric_pump(LoopId::BLUE)
+            .with_electric_pump(LoopId::YELLOW)
+            .with_ptu(LoopId::GREEN, LoopId::YELLOW)
+    }
+
+    //The A320 ceo and neo differ by the pumps fitted to their engines
+    pub fn for_variant(variant: EngineVariant) -> A320Hydraulic {
+        A320Hydraulic::from_description(A320Hydraulic::a320_description().with_engine_variant(variant))
+    }
+
+    //The A320 hydraulic system with the built-in characteristics replaced by those of the config
+    pub fn from_config(config: &HydraulicConfig) -> A320Hydraulic {
+        let mut hydraulic = A320Hydraulic::from_description(A320Hydraulic::a320_description().with_config(config));
+        hydraulic.set_filter_time_constants(&config.filter_time_constants);
+
+        hydraulic
+    }
+
+    //Filters which aren't configured keep their built-in time constant
+    fn set_filter_time_constants(&mut self, time_constants: &FilterTimeConstants) {
+        if let Some(seconds) = time_constants.gauge_needle {
+            self.triple_indicator.set_needle_time_constant(Duration::from_secs_f64(seconds));
+        }
+        if let Some(seconds) = time_constants.rat_speed {
+            self.rat_pump.set_speed_time_constant(Duration::from_secs_f64(seconds));
+        }
+        if let Some(seconds) = time_constants.ptu_barking_decay {
+            self.ptu.set_barking_decay_time(Duration::from_secs_f64(seconds));
+        }
+    }
+
+    //Any number of engine driven pumps per loop, e.g. on both engines for the green loop of an A330,
+    //as long as they are driven by the aircraft's two engines
+    pub fn from_description(description: HydraulicSystemDescription) -> A320Hydraulic {
+        description.expect_engine_count(A320Hydraulic::ENGINE_COUNT);
+
+        let mut loops = description.build_loops();
+        for hyd_loop in loops.iter_mut() {
+            A320Hydraulic::distribute_static_leaks(hyd_loop);
+        }
+        A320Hydraulic {
+            actuator_loop_pressures: loops.iter().map(|hyd_loop| hyd_loop.get_pressure()).collect(),
+            hydraulic_step_start_pressures: loops.iter().map(|hyd_loop| hyd_loop.get_pressure()).collect(),
+            loops,
+            engine_driven_pumps: description
+                .engine_driven_pumps()
+                .iter()
+                .map(|pump| {
+                    let mut engine_driven_pump = EngineDrivenPump::for_variant(description.engine_variant());
+                    if let Some(map) = description.engine_driven_pump_displacement() {
+                        engine_driven_pump.set_displacement_map(map.pressure_breakpoints, map.displacement);
+                    }
+                    (*pump, engine_driven_pump)
+                })
+                .collect(),
+            electric_pumps: description
+                .electric_pumps()
+                .iter()
+                .map(|pump| (*pump, description.electric_pump_displacement().map_or_else(
+                    || ElectricPump::for_variant(description.engine_variant()),
+                    |map| ElectricPump::with_displacement_map(map.pressure_breakpoints, map.displacement),
+                )))
+                .collect(),
+            electric_pump_power_consumptions: description
+                .electric_pumps()
+                .iter()
+                .map(|pump| PowerConsumption::from_single(A320Hydraulic::electric_pump_bus(pump.loop_color)))
+                .collect(),
+            ptu_description: description.ptu(),
             ptu : Ptu::new(),
+            rat: RamAirTurbine::new(),
+            rat_pump: RatPump::new(),
+            alternate_brakes: BrakeCircuit::new(),
+            anti_skid: AntiSkid::new(),
+            autobrake: AutobrakeController::new(),
+            bscu: BrakeSteeringControlUnit::new(
+                ElectricalBusType::DirectCurrentEssential,
+                ElectricalBusType::DirectCurrent(2),
+            ),
+            brake_pedals: BrakePedals::new(),
+            brake_source: BrakeSourceSelector::new(),
+            normal_brakes: BrakeCircuit::new_without_accumulator(),
+            brake_temperatures: [
+                BrakeTemperature::default(),
+                BrakeTemperature::default(),
+                BrakeTemperature::default(),
+                BrakeTemperature::default(),
+            ],
+            triple_indicator: TripleIndicator::new(),
+            landing_gears: [LandingGearLeg::nose(), LandingGearLeg::main(), LandingGearLeg::main()],
+            gear_position_sensors: [GearPositionSensors::new(), GearPositionSensors::new(), GearPositionSensors::new()],
+            gear_lever_up: false,
+            ailerons: [ControlSurface::aileron(1), ControlSurface::aileron(2)],
+            elevators: [ControlSurface::elevator(), ControlSurface::elevator()],
+            left_spoilers: A320Hydraulic::spoilers(),
+            right_spoilers: A320Hydraulic::spoilers(),
+            external_loads: None,
+            rudder_travel_limiter: RudderTravelLimiter::new(),
+            nose_wheel_steering: NoseWheelSteering::new(),
+            pressure_sensors: [DigitalSensor::ecam_pressure(1), DigitalSensor::ecam_pressure(2), DigitalSensor::ecam_pressure(3)],
+            quantity_sensors: [DigitalSensor::ecam_quantity(4), DigitalSensor::ecam_quantity(5), DigitalSensor::ecam_quantity(6)],
+            sensor_model_enabled: true,
+            engine_bleed_valve_closed: [false; 2],
+            apu_bleed_air_valve_open: false,
+            reservoir_bleed_air_available: false,
+            logic: A320HydraulicLogic::new(),
             total_sim_time_elapsed: Duration::new(0,0),
             lag_time_accumulator: Duration::new(0,0),
+            engine_driven_pump_pb_auto: [true; 2],
+            blue_electric_pump_pb_auto: true,
+            yellow_electric_pump_pb_on: false,
+            emergency_elec: false,
+            leak_measurement_loop: None,
+            leak_measurement: LeakMeasurement::new(),
+            state_snapshot_requested: false,
+            state_snapshot_toggle: false,
+            rat_stow_toggle: false,
+            #[cfg(feature = "dev-tuning")]
+            tuning: None,
+        }
+    }
+
+    //Flight control actuator banks take their share of the static leakage of the loop, the rest stays in the manifold
+    fn distribute_static_leaks(hyd_loop: &mut HydLoop) {
+        let color = hyd_loop.get_color();
+        let total_leak: VolumeRate = hyd_loop.static_leaks().iter().map(|leak| leak.internal_flow_at_3000_psi()).sum();
+        let bank_leak = VolumeRate::new::<gallon_per_second>(A320Hydraulic::ACTUATOR_BANK_STATIC_LEAK_FLOW_AT_3000_PSI);
+
+        let mut banks = vec![ActuatorType::Rudder];
+        if A320Hydraulic::AILERON_LOOPS.contains(&color) {
+            banks.push(ActuatorType::Aileron);
+        }
+        if A320Hydraulic::ELEVATOR_LOOPS.iter().any(|loops| loops.contains(&color)) {
+            banks.push(ActuatorType::Elevator);
+        }
+        if A320Hydraulic::SPOILER_LOOPS.contains(&color) {
+            banks.push(ActuatorType::Spoiler);
+        }
+
+        for bank in banks.iter() {
+            hyd_loop.set_static_leak(LeakZone::ActuatorBank(*bank), bank_leak);
+        }
+        hyd_loop.set_static_leak(LeakZone::HighPressureManifold, total_leak - bank_leak * banks.len() as f64);
+    }
+
+    fn spoilers() -> [ControlSurface; 5] {
+        [
+            ControlSurface::spoiler(),
+            ControlSurface::spoiler(),
+            ControlSurface::spoiler(),
+            ControlSurface::spoiler(),
+            ControlSurface::spoiler(),
+        ]
+    }
+
+    //Tuning values sent over the channel are applied at the start of the next update
+    #[cfg(feature = "dev-tuning")]
+    pub fn attach_tuning(&mut self, receiver: TuningReceiver) {
+        self.tuning = Some(receiver);
+    }
+
+    #[cfg(feature = "dev-tuning")]
+    fn apply_tuning(&mut self) {
+        let values = match &self.tuning {
+            Some(receiver) => receiver.receive(),
+            None => return,
+        };
+
+        for (parameter, value) in values {
+            match parameter {
+                TuningParameter::ElectricPumpDisplacement(index) => {
+                    for (_, pump) in self.electric_pumps.iter_mut() {
+                        pump.set_displacement(index, value);
+                    }
+                }
+                TuningParameter::EngineDrivenPumpDisplacement(index) => {
+                    for (_, pump) in self.engine_driven_pumps.iter_mut() {
+                        pump.set_displacement(index, value);
+                    }
+                }
+                TuningParameter::AccumulatorFlow(index) => {
+                    for hyd_loop in self.loops.iter_mut() {
+                        hyd_loop.set_accumulator_flow(index, value);
+                    }
+                }
+                TuningParameter::GaugeNeedleTimeConstant => {
+                    self.triple_indicator.set_needle_time_constant(Duration::from_secs_f64(value));
+                }
+                TuningParameter::RatSpeedTimeConstant => {
+                    self.rat_pump.set_speed_time_constant(Duration::from_secs_f64(value));
+                }
+                TuningParameter::PtuBarkingDecayTime => {
+                    self.ptu.set_barking_decay_time(Duration::from_secs_f64(value));
+                }
+                TuningParameter::PtuBreakoutPressure => {
+                    self.ptu.characteristic_mut().set_breakout_pressure(Pressure::new::<psi>(value));
+                }
+                TuningParameter::PtuHoldingPressure => {
+                    self.ptu.characteristic_mut().set_holding_pressure(Pressure::new::<psi>(value));
+                }
+                TuningParameter::PtuMinDrivingPressure => {
+                    self.ptu.characteristic_mut().set_min_driving_pressure(Pressure::new::<psi>(value));
+                }
+            }
+        }
+    }
+
+    //Release builds only guard the loops against NaN and infinite values when enabled
+    pub fn set_finite_guard(&mut self, enabled: bool) {
+        for hyd_loop in self.loops.iter_mut() {
+            hyd_loop.set_finite_guard(enabled);
         }
     }
 
     pub fn is_blue_pressurised(&self) -> bool {
-        self.blue_loop.get_pressure().get::<psi>() >= A320Hydraulic::MIN_PRESS_PRESSURISED
+        self.is_pressurised(LoopId::BLUE)
     }
 
     pub fn is_green_pressurised(&self) -> bool {
-        self.green_loop.get_pressure().get::<psi>() >= A320Hydraulic::MIN_PRESS_PRESSURISED
+        self.is_pressurised(LoopId::GREEN)
     }
 
     pub fn is_yellow_pressurised(&self) -> bool {
-        self.yellow_loop.get_pressure().get::<psi>() >= A320Hydraulic::MIN_PRESS_PRESSURISED
+        self.is_pressurised(LoopId::YELLOW)
     }
 
-    pub fn update(&mut self, ct: &UpdateContext, engine1 : &Engine, engine2 : &Engine) {
+    //A loop which isn't part of the aircraft is never pressurised
+    pub fn is_pressurised(&self, color: LoopId) -> bool {
+        self.loop_pressure(color).get::<psi>() >= A320Hydraulic::MIN_PRESS_PRESSURISED
+    }
 
-        let min_hyd_loop_timestep = Duration::from_millis(A320Hydraulic::HYDRAULIC_SIM_TIME_STEP); //Hyd Sim rate = 10 Hz
+    pub fn loop_pressure(&self, color: LoopId) -> Pressure {
+        self.hyd_loop(color).map_or(Pressure::new::<psi>(0.), |hyd_loop| hyd_loop.get_pressure())
+    }
 
-        //time to catch up in our simulation
-        self.total_sim_time_elapsed += ct.delta;
+    //Pressure at a branch node of the loop: brakes and nose wheel steering see the pressure of their branch
+    pub fn node_pressure(&self, color: LoopId, node: BranchNode) -> Pressure {
+        self.hyd_loop(color).map_or(Pressure::new::<psi>(0.), |hyd_loop| hyd_loop.get_node_pressure(node))
+    }
 
-        let time_to_catch=self.total_sim_time_elapsed + self.lag_time_accumulator;
+    fn hyd_loop(&self, color: LoopId) -> Option<&HydLoop> {
+        self.loops.iter().find(|hyd_loop| hyd_loop.get_color() == color)
+    }
 
+    fn hyd_loop_mut(&mut self, color: LoopId) -> &mut HydLoop {
+        self.loops
+            .iter_mut()
+            .find(|hyd_loop| hyd_loop.get_color() == color)
+            .unwrap_or_else(|| panic!("There is no {:?} loop.", color))
+    }
 
-        //Number of time steps to do according to required time step
-        let numberOfSteps_f64 = time_to_catch.as_secs_f64()/min_hyd_loop_timestep.as_secs_f64();
+    //Leak measurement ground test: pressurise the loop with its pump, start the measurement which closes
+    //the leak measurement valves of the loop, then stop the pump and read the pressure decay rate
+    pub fn start_leak_measurement(&mut self, color: LoopId) {
+        self.stop_leak_measurement();
+        self.hyd_loop_mut(color).set_leak_measurement_valves_closed(true);
+        self.leak_measurement.start(self.loop_pressure(color));
+        self.leak_measurement_loop = Some(color);
+    }
 
-        if numberOfSteps_f64 < 1.0 {
-            //Can't do a full time step
-            //we can either do an update with smaller step or wait next iteration
-            //Other option is to update only actuator position based on known hydraulic
-            //state to avoid lag of control surfaces if sim runs really fast
-            self.lag_time_accumulator=Duration::from_secs_f64(numberOfSteps_f64 * min_hyd_loop_timestep.as_secs_f64()); //Time lag is float part of num of steps * fixed time step to get a result in time
-        } else {
-            //TRUE UPDATE LOOP HERE
-            let num_of_update_loops = numberOfSteps_f64.floor() as u32; //Int part is the actual number of loops to do
-            //Rest of floating part goes into accumulator
-            self.lag_time_accumulator= Duration::from_secs_f64((numberOfSteps_f64 - (num_of_update_loops as f64))* min_hyd_loop_timestep.as_secs_f64()); //Keep track of time left after all fixed loop are done
+    //Opens the leak measurement valves again, keeping the results of the measurement
+    pub fn stop_leak_measurement(&mut self) {
+        if let Some(color) = self.leak_measurement_loop.take() {
+            self.hyd_loop_mut(color).set_leak_measurement_valves_closed(false);
+            self.leak_measurement.stop();
+        }
+    }
 
+    pub fn leak_measurement(&self) -> &LeakMeasurement {
+        &self.leak_measurement
+    }
 
-            //UPDATING HYDRAULICS AT FIXED STEP
-            for curLoop in  0..num_of_update_loops {
-                //UPDATE HYDRAULICS FIXED TIME STEP
-                self.ptu.update(&self.green_loop, &self.yellow_loop);
-                self.engine_driven_pump_1.update(&min_hyd_loop_timestep,&ct, &self.green_loop, &engine1);
-                self.engine_driven_pump_2.update(&min_hyd_loop_timestep,&ct, &self.yellow_loop, &engine2);
-                self.yellow_electric_pump.update(&min_hyd_loop_timestep,&ct, &self.yellow_loop);
-                self.blue_electric_pump.update(&min_hyd_loop_timestep,&ct, &self.blue_loop);
+    fn is_loop_isolated(&self, color: LoopId) -> bool {
+        self.hyd_loop(color).map_or(false, |hyd_loop| hyd_loop.is_isolated())
+    }
+
+    /// Isolates and depressurises the given loop, as done for ground maintenance.
+    pub fn isolate_loop(&mut self, color: LoopId) {
+        self.hyd_loop_mut(color).isolate();
+    }
+
+    pub fn reconnect_loop(&mut self, color: LoopId) {
+        self.hyd_loop_mut(color).reconnect();
+    }
+
+    /// Mechanical power drawn by the engine driven pumps from the given engine's gearbox.
+    pub fn engine_driven_pump_shaft_power(&self, engine_number: usize) -> Power {
+        self.engine_driven_pumps
+            .iter()
+            .filter(|(description, _)| description.engine_number == engine_number)
+            .fold(Power::new::<watt>(0.), |power, (_, pump)| power + pump.get_shaft_power_demand())
+    }
+
+    /// Fits all engine driven and electric pumps with a wear model, as new pumps. Saved wear
+    /// states are restored through the pump wear accessors. Without it pumps never degrade.
+    pub fn enable_pump_wear_model(&mut self) {
+        for (_, pump) in self.engine_driven_pumps.iter_mut() {
+            pump.set_wear(PumpWear::new());
+        }
+        for (_, pump) in self.electric_pumps.iter_mut() {
+            pump.set_wear(PumpWear::new());
+        }
+    }
+
+    /// Wear of the engine driven pump on the given engine and loop, if the wear model is enabled.
+    pub fn engine_driven_pump_wear_mut(&mut self, engine_number: usize, color: LoopId) -> Option<&mut PumpWear> {
+        self.engine_driven_pumps
+            .iter_mut()
+            .find(|(description, _)| description.engine_number == engine_number && description.loop_color == color)
+            .and_then(|(_, pump)| pump.wear_mut())
+    }
+
+    /// Wear of the electric pump on the given loop, if the wear model is enabled.
+    pub fn electric_pump_wear_mut(&mut self, color: LoopId) -> Option<&mut PumpWear> {
+        self.electric_pumps
+            .iter_mut()
+            .find(|(description, _)| description.loop_color == color)
+            .and_then(|(_, pump)| pump.wear_mut())
+    }
+
+    /// Maintenance action: overhauls all pumps back to as new condition.
+    pub fn perform_pump_maintenance(&mut self) {
+        let wears = self
+            .engine_driven_pumps
+            .iter_mut()
+            .filter_map(|(_, pump)| pump.wear_mut())
+            .chain(self.electric_pumps.iter_mut().filter_map(|(_, pump)| pump.wear_mut()));
+        for wear in wears {
+            wear.perform_maintenance();
+        }
+    }
+
+    /// Speed of the engine driven pumps on the given engine's gearbox.
+    pub fn engine_driven_pump_rpm(&self, engine_number: usize) -> f64 {
+        self.engine_driven_pumps
+            .iter()
+            .filter(|(description, _)| description.engine_number == engine_number)
+            .fold(0., |rpm: f64, (_, pump)| rpm.max(pump.get_rpm()))
+    }
+
+    pub fn electric_pump_power(&self, color: LoopId) -> Power {
+        self.electric_pumps
+            .iter()
+            .filter(|(description, _)| description.loop_color == color)
+            .fold(Power::new::<watt>(0.), |power, (_, pump)| power + pump.get_electrical_power_demand())
+    }
+
+    pub fn blue_electric_pump_power(&self) -> Power {
+        self.electric_pump_power(LoopId::BLUE)
+    }
+
+    pub fn yellow_electric_pump_power(&self) -> Power {
+        self.electric_pump_power(LoopId::YELLOW)
+    }
+
+    //Blue loop pressure and flow available to the emergency generator. In emergency the blue loop is
+    //powered by the RAT, which stalls at low airspeed
+    pub fn emergency_generator_supply(&self, context: &UpdateContext) -> EmergencyGeneratorHydraulicSupply {
+        EmergencyGeneratorHydraulicSupply::from_rat_driven_blue_loop(self.loop_pressure(LoopId::BLUE), context.indicated_airspeed)
+    }
+
+    pub fn fill_reservoir(&mut self, color: LoopId, volume: Volume) {
+        self.hyd_loop_mut(color).fill_reservoir(volume);
+    }
+
+    pub fn drain_reservoir(&mut self, color: LoopId, volume: Volume) -> Volume {
+        self.hyd_loop_mut(color).drain_reservoir(volume)
+    }
+
+    //Failure injection: a leak in a zone of the loop loses fluid overboard, making its reservoir quantity drop
+    pub fn set_external_leak(&mut self, color: LoopId, zone: LeakZone, flow_at_3000_psi: VolumeRate) {
+        self.hyd_loop_mut(color).set_external_leak(zone, flow_at_3000_psi);
+    }
+
+    pub fn external_leak_volume(&self, color: LoopId) -> Volume {
+        self.hyd_loop(color).map_or(Volume::new::<gallon>(0.), |hyd_loop| hyd_loop.get_external_leak_volume())
+    }
+
+    //Failure injection: contaminates the fluid of the given loop, which progressively clogs its filters
+    pub fn set_fluid_contamination(&mut self, color: LoopId, contamination: Ratio) {
+        self.hyd_loop_mut(color).set_fluid_contamination(contamination);
+    }
+
+    pub fn replace_filter_elements(&mut self, color: LoopId) {
+        self.hyd_loop_mut(color).replace_filter_elements();
+    }
+
+    //Filter delta pressure indicators, checked by maintenance
+    pub fn is_hp_filter_bypass_indicated(&self, color: LoopId) -> bool {
+        self.hyd_loop(color).map_or(false, |hyd_loop| hyd_loop.get_hp_filter().is_bypass_indicator_popped())
+    }
+
+    pub fn is_return_filter_bypass_indicated(&self, color: LoopId) -> bool {
+        self.hyd_loop(color).map_or(false, |hyd_loop| hyd_loop.get_return_filter().is_bypass_indicator_popped())
+    }
+
+    pub fn deploy_rat(&mut self) {
+        self.rat.deploy();
+    }
+
+    pub fn is_rat_deployed(&self) -> bool {
+        self.rat.is_deployed()
+    }
+
+    //The RAT swings out in a few seconds and only drives its pump once fully deployed
+    pub fn is_rat_in_transit(&self) -> bool {
+        self.rat.is_in_transit()
+    }
+
+    pub fn rat_deployment_progress(&self) -> Ratio {
+        self.rat.deployment_progress()
+    }
+
+    pub fn rat_time_to_deploy(&self) -> Duration {
+        self.rat.time_to_deploy()
+    }
+
+    pub fn rat_rpm(&self) -> f64 {
+        self.rat_pump.get_rpm()
+    }
+
+    //Airspeed below which the RAT slows down under its current load
+    pub fn rat_stall_speed(&self) -> Velocity {
+        self.rat_pump.get_stall_speed()
+    }
+
+    //Maintenance action: restows the RAT on the ground with a hydraulic ground cart connected to the blue loop,
+    //after which the blue loop is back to its normal sources
+    pub fn stow_rat(&mut self) -> Result<(), RatStowError> {
+        self.rat.stow(self.logic.on_ground(), self.logic.blue_ground_cart_pressure())
+    }
+
+    pub fn set_cargo_door_operated(&mut self, operated: bool) {
+        self.logic.set_cargo_door_operated(operated);
+    }
+
+    //Loads not modelled by the crate itself, such as the cargo door actuators, updated with the loop at each fixed step
+    pub fn register_consumer(&mut self, color: LoopId, consumer: Box<dyn HydraulicConsumer>) {
+        self.hyd_loop_mut(color).register_consumer(consumer);
+    }
+
+    pub fn update_after_apu<T: BleedAirValveState>(&mut self, apu: &T) {
+        self.apu_bleed_air_valve_open = apu.bleed_air_valve_is_open();
+    }
+
+    //Both electric pumps are AC powered: they are shed in emergency electrical configuration
+    //and run again as soon as normal power returns
+    pub fn update_after_electrical<T: EmergencyElectricalState>(&mut self, electrical: &T) {
+        self.emergency_elec = electrical.is_in_emergency_elec();
+    }
+
+    pub fn are_electric_pumps_shed(&self) -> bool {
+        self.emergency_elec
+    }
 
+    //The blue electric pump only runs with its pushbutton in AUTO, the yellow one also runs when selected ON
+    pub fn update_after_hydraulic_overhead(&mut self, overhead: &A320HydraulicOverheadPanel) {
+        self.engine_driven_pump_pb_auto = [
+            overhead.engine_driven_pump_push_button_is_auto(1),
+            overhead.engine_driven_pump_push_button_is_auto(2),
+        ];
+        self.blue_electric_pump_pb_auto = overhead.blue_electric_pump_push_button_is_auto();
+        self.yellow_electric_pump_pb_on = overhead.yellow_electric_pump_push_button_is_on();
+    }
 
-                self.green_loop.update(&min_hyd_loop_timestep,&ct, Vec::new(), vec![&self.engine_driven_pump_1], Vec::new(), vec![&self.ptu]);
-                self.yellow_loop.update(&min_hyd_loop_timestep,&ct, vec![&self.yellow_electric_pump], vec![&self.engine_driven_pump_2], Vec::new(), vec![&self.ptu]);
-                self.blue_loop.update(&min_hyd_loop_timestep,&ct, vec![&self.blue_electric_pump], Vec::new(), Vec::new(), Vec::new());
+    //A released ENG FIRE pushbutton closes the fire shutoff valve of the pumps driven by that engine,
+    //and its bleed valve which no longer pressurises the reservoirs, until the pushbutton is reset
+    fn update_fire_shutoff_valves(&mut self, engines: &[&Engine]) {
+        for (description, pump) in self.engine_driven_pumps.iter_mut() {
+            if engines[description.engine_number - 1].is_fire_push_button_released() {
+                pump.close_fire_shutoff_valve();
+            } else {
+                pump.open_fire_shutoff_valve();
             }
+        }
+        for (closed, engine) in self.engine_bleed_valve_closed.iter_mut().zip(engines.iter()) {
+            *closed = engine.is_fire_push_button_released();
+        }
+    }
+
+    //Reservoirs are pressurised by engine 1 bleed air, or through the crossbleed duct by engine 2 or the APU.
+    //The reservoir of a loop driven by an engine whose ENG FIRE pushbutton is released gets no air at all
+    fn update_reservoir_bleed_air(&mut self, engines: &[&Engine]) {
+        let engine_bleed_available = engines.iter().zip(self.engine_bleed_valve_closed.iter()).any(|(engine, closed)| {
+            !closed && engine.is_running()
+        });
+        self.reservoir_bleed_air_available = engine_bleed_available || self.apu_bleed_air_valve_open;
+        let engine_driven_pumps = &self.engine_driven_pumps;
+        let engine_bleed_valve_closed = self.engine_bleed_valve_closed;
+        for hyd_loop in self.loops.iter_mut() {
+            let engine_fire_isolated = engine_driven_pumps.iter().any(|(description, _)| {
+                description.loop_color == hyd_loop.get_color() && engine_bleed_valve_closed[description.engine_number - 1]
+            });
+            hyd_loop.set_reservoir_bleed_air_available(self.reservoir_bleed_air_available && !engine_fire_isolated);
+        }
+    }
+
+    pub fn is_reservoir_bleed_air_available(&self) -> bool {
+        self.reservoir_bleed_air_available
+    }
+
+    //Absolute air pressure in the reservoir of the given loop, which is its pumps inlet pressure
+    pub fn reservoir_air_pressure(&self, color: LoopId) -> Pressure {
+        self.hyd_loop(color).map_or(Pressure::new::<psi>(0.), |hyd_loop| hyd_loop.get_reservoir_pressure())
+    }
+
+    //A pump section is at low pressure when the pump isn't turning or its loop is below the pressure switch threshold
+    fn is_pump_section_at_low_pressure(&self, color: LoopId, rpm: f64) -> bool {
+        rpm <= 0. || self.loop_pressure(color).get::<psi>() < A320Hydraulic::PUMP_LOW_PRESSURE
+    }
+
+    pub fn engine_driven_pump_has_low_pressure(&self, engine_number: usize) -> bool {
+        self.engine_driven_pumps
+            .iter()
+            .filter(|(description, _)| description.engine_number == engine_number)
+            .any(|(description, pump)| self.is_pump_section_at_low_pressure(description.loop_color, pump.get_rpm()))
+    }
+
+    //From the pump delivery pressure switch, which gives a pump commanded on some time to build pressure
+    pub fn electric_pump_has_low_pressure(&self, color: LoopId) -> bool {
+        self.electric_pumps
+            .iter()
+            .filter(|(description, _)| description.loop_color == color)
+            .any(|(_, pump)| pump.has_low_pressure())
+    }
+
+    //Case drain temperature of the pump above its overheat threshold, for the ENG PUMP OVHT procedure
+    pub fn engine_driven_pump_is_overheated(&self, engine_number: usize) -> bool {
+        self.engine_driven_pumps
+            .iter()
+            .filter(|(description, _)| description.engine_number == engine_number)
+            .any(|(_, pump)| pump.is_overheated())
+    }
+
+    pub fn engine_driven_pump_case_temperature(&self, engine_number: usize) -> Option<ThermodynamicTemperature> {
+        self.engine_driven_pumps
+            .iter()
+            .find(|(description, _)| description.engine_number == engine_number)
+            .map(|(_, pump)| pump.get_case_temperature())
+    }
 
-            //UPDATING ACTUATOR PHYSICS AT FIXED STEP / ACTUATORS_SIM_TIME_STEP_MULT
-            let num_of_actuators_update_loops = num_of_update_loops * A320Hydraulic::ACTUATORS_SIM_TIME_STEP_MULT;
-            for curLoop in  0..num_of_actuators_update_loops {
-                //UPDATE ACTUATORS FIXED TIME STEP
+    pub fn is_engine_driven_pump_depressurised(&self, engine_number: usize) -> bool {
+        self.engine_driven_pumps
+            .iter()
+            .filter(|(description, _)| description.engine_number == engine_number)
+            .all(|(_, pump)| pump.is_depressurised())
+    }
+
+    pub fn set_fluid_temperature(&mut self, color: LoopId, temperature: ThermodynamicTemperature) {
+        self.hyd_loop_mut(color).set_fluid_temperature(temperature);
+    }
+
+    pub fn electric_pump_has_pressure_not_built(&self, color: LoopId) -> bool {
+        self.electric_pumps
+            .iter()
+            .filter(|(description, _)| description.loop_color == color)
+            .any(|(_, pump)| pump.has_pressure_not_built_fault())
+    }
+
+    //Reservoir low level or low air pressure. A missing loop has no reservoir to fault
+    pub fn has_reservoir_fault(&self, color: LoopId) -> bool {
+        self.hyd_loop(color).map_or(false, |hyd_loop| {
+            hyd_loop.is_reservoir_level_low() || hyd_loop.is_reservoir_air_pressure_low()
+        })
+    }
+
+    pub fn is_engine_driven_pump_fire_valve_open(&self, engine_number: usize) -> bool {
+        self.engine_driven_pumps
+            .iter()
+            .filter(|(description, _)| description.engine_number == engine_number)
+            .all(|(_, pump)| pump.is_fire_shutoff_valve_open())
+    }
+
+    //Highest pressure among the loops powering a surface: any of its actuators can hold it
+    fn surface_pressure(&self, loops: &[LoopId]) -> Pressure {
+        loops.iter().fold(Pressure::new::<psi>(0.), |pressure, color| pressure.max(self.actuator_pressure(*color)))
+    }
+
+    fn actuator_pressure(&self, color: LoopId) -> Pressure {
+        self.loops
+            .iter()
+            .zip(self.actuator_loop_pressures.iter())
+            .find(|(hyd_loop, _)| hyd_loop.get_color() == color)
+            .map_or(Pressure::new::<psi>(0.), |(_, pressure)| *pressure)
+    }
+
+    //Actuators run ACTUATORS_SIM_TIME_STEP_MULT steps per hydraulic step. They see loop pressures
+    //interpolated between the start and the end of the hydraulic step instead of a pressure jump
+    fn update_actuators(&mut self, hydraulic_timestep: &Duration, ct: &UpdateContext) {
+        for spoilers in [&mut self.left_spoilers, &mut self.right_spoilers].iter_mut() {
+            spoilers.iter_mut().for_each(ControlSurface::store_previous_position);
+        }
+        self.ailerons.iter_mut().chain(self.elevators.iter_mut()).for_each(ControlSurface::store_previous_position);
+
+        let actuator_timestep = *hydraulic_timestep / A320Hydraulic::ACTUATORS_SIM_TIME_STEP_MULT;
+        for step in 1..=A320Hydraulic::ACTUATORS_SIM_TIME_STEP_MULT {
+            let ratio = step as f64 / A320Hydraulic::ACTUATORS_SIM_TIME_STEP_MULT as f64;
+            for ((pressure, before), hyd_loop) in self
+                .actuator_loop_pressures
+                .iter_mut()
+                .zip(self.hydraulic_step_start_pressures.iter())
+                .zip(self.loops.iter())
+            {
+                *pressure = *before + (hyd_loop.get_pressure() - *before) * ratio;
             }
+
+            self.update_control_surfaces(&actuator_timestep, ct);
+            self.rudder_travel_limiter.update(
+                &actuator_timestep,
+                ct,
+                self.actuator_pressure(LoopId::GREEN),
+                self.actuator_pressure(LoopId::YELLOW),
+            );
         }
     }
-}
 
-pub struct A320HydraulicOverheadPanel {
-}
+    //How far the sim time is into the next hydraulic step. Surface positions are shown that far between
+    //their positions at the start and the end of the last step, so they move every frame at high frame rates
+    fn actuator_output_ratio(&self) -> f64 {
+        (self.lag_time_accumulator.as_secs_f64() / Duration::from_millis(A320Hydraulic::HYDRAULIC_SIM_TIME_STEP).as_secs_f64()).min(1.)
+    }
 
-impl A320HydraulicOverheadPanel {
-    pub fn new() -> A320HydraulicOverheadPanel {
-        A320HydraulicOverheadPanel {
+    //Surfaces are held at neutral while pressurised, there is no flight control computer commanding them yet.
+    //Without pressure they go to their unpowered position: ailerons droop, spoilers float, elevators are damped
+    fn update_control_surfaces(&mut self, delta_time: &Duration, ct: &UpdateContext) {
+        let neutral = Angle::new::<degree>(0.);
+        let aileron_pressure = self.surface_pressure(&A320Hydraulic::AILERON_LOOPS);
+        let elevator_pressures = [
+            self.surface_pressure(&A320Hydraulic::ELEVATOR_LOOPS[0]),
+            self.surface_pressure(&A320Hydraulic::ELEVATOR_LOOPS[1]),
+        ];
+        let mut spoiler_pressures = [Pressure::new::<psi>(0.); 5];
+        for (pressure, color) in spoiler_pressures.iter_mut().zip(A320Hydraulic::SPOILER_LOOPS.iter()) {
+            *pressure = self.actuator_pressure(*color);
+        }
+
+        let external_loads = &mut self.external_loads;
+        let mut update_surface = |surface: &mut ControlSurface, id: ActuatorId, pressure: Pressure| {
+            let external_load = external_loads.as_ref().and_then(|loads| loads.load(id, surface.position()));
+            surface.update_with_external_load(delta_time, ct, pressure, neutral, external_load);
+            if let Some(loads) = external_loads.as_mut() {
+                loads.set_achieved_rate(id, surface.rate());
+            }
+        };
+
+        for (index, aileron) in self.ailerons.iter_mut().enumerate() {
+            update_surface(aileron, ActuatorId::new(ActuatorType::Aileron, index + 1, 1), aileron_pressure);
+        }
+        for (index, (elevator, pressure)) in self.elevators.iter_mut().zip(elevator_pressures.iter()).enumerate() {
+            update_surface(elevator, ActuatorId::new(ActuatorType::Elevator, index + 1, 1), *pressure);
+        }
+        for (side, spoilers) in [&mut self.left_spoilers, &mut self.right_spoilers].iter_mut().enumerate() {
+            for (index, (spoiler, pressure)) in spoilers.iter_mut().zip(spoiler_pressures.iter()).enumerate() {
+                update_surface(spoiler, ActuatorId::new(ActuatorType::Spoiler, side + 1, index + 1), *pressure);
+            }
+        }
+    }
+
+    //Lets the embedding application supply the external load on each flight control surface every
+    //actuator step, e.g. hinge moments from its flight model, and receive the rates they achieved
+    pub fn attach_external_loads(&mut self, loads: Box<dyn ExternalLoads>) {
+        self.external_loads = Some(loads);
+    }
+
+    //Surface positions, left side first
+    pub fn aileron_positions(&self) -> [Angle; 2] {
+        let ratio = self.actuator_output_ratio();
+        [self.ailerons[0].interpolated_position(ratio), self.ailerons[1].interpolated_position(ratio)]
+    }
+
+    pub fn elevator_positions(&self) -> [Angle; 2] {
+        let ratio = self.actuator_output_ratio();
+        [self.elevators[0].interpolated_position(ratio), self.elevators[1].interpolated_position(ratio)]
+    }
+
+    //Spoilers numbered 1 to 5 from wing root to tip
+    pub fn spoiler_positions(&self) -> [[Angle; 5]; 2] {
+        let mut positions = [[Angle::new::<degree>(0.); 5]; 2];
+        for (side, spoilers) in [&self.left_spoilers, &self.right_spoilers].iter().enumerate() {
+            for (number, spoiler) in spoilers.iter().enumerate() {
+                positions[side][number] = spoiler.interpolated_position(self.actuator_output_ratio());
+            }
+        }
+        positions
+    }
+
+    //One bit per spoiler panel, set while its actuator is pressurised: bits 0 to 4 for the left spoilers 1 to 5,
+    //bits 5 to 9 for the right ones. The flight control laws only use the available spoilers for roll,
+    //the others are maintained retracted or float
+    pub fn spoiler_availability(&self) -> u16 {
+        let mut availability = 0;
+        for (side, spoilers) in [&self.left_spoilers, &self.right_spoilers].iter().enumerate() {
+            for (number, spoiler) in spoilers.iter().enumerate() {
+                if spoiler.is_powered() {
+                    availability |= 1 << (side * 5 + number);
+                }
+            }
+        }
+        availability
+    }
+
+    fn update_sensors(&mut self, ct: &UpdateContext) {
+        for (index, color) in A320Hydraulic::SENSED_LOOPS.iter().enumerate() {
+            let pressure = self.loop_pressure(*color).get::<psi>();
+            let quantity = self.reservoir_volume(*color).get::<gallon>();
+            self.pressure_sensors[index].update(&ct.delta, pressure);
+            self.quantity_sensors[index].update(&ct.delta, quantity);
+        }
+    }
+
+    //When enabled, displayed values are sampled, noisy and quantized like the aircraft sensors.
+    //Otherwise displays show the raw simulated values
+    pub fn set_sensor_model_enabled(&mut self, enabled: bool) {
+        self.sensor_model_enabled = enabled;
+    }
+
+    pub fn reservoir_volume(&self, color: LoopId) -> Volume {
+        self.hyd_loop(color).map_or(Volume::new::<gallon>(0.), |hyd_loop| hyd_loop.get_reservoir_volume())
+    }
+
+    fn sensor_index(color: LoopId) -> usize {
+        A320Hydraulic::SENSED_LOOPS.iter().position(|sensed| *sensed == color).unwrap()
+    }
+
+    //Loop pressure as published to the displays
+    pub fn displayed_loop_pressure(&self, color: LoopId) -> Pressure {
+        if self.sensor_model_enabled {
+            Pressure::new::<psi>(self.pressure_sensors[A320Hydraulic::sensor_index(color)].output())
+        } else {
+            self.loop_pressure(color)
+        }
+    }
+
+    //Reservoir quantity as published to the displays
+    pub fn displayed_reservoir_volume(&self, color: LoopId) -> Volume {
+        if self.sensor_model_enabled {
+            Volume::new::<gallon>(self.quantity_sensors[A320Hydraulic::sensor_index(color)].output())
+        } else {
+            self.reservoir_volume(color)
+        }
+    }
 
+    //Displayed pressures and quantities, then valve states, encoded as ARINC 429 words for the avionics.
+    //Loops missing from the system description are sent as no computed data.
+    //Valve states discretes: 0-1 engine driven pumps fire valves open, 2 RAT deployed, 3-5 HP filters bypass
+    pub fn arinc429_words(&self) -> Vec<Arinc429Word> {
+        let mut words = Vec::new();
+        for (index, color) in A320Hydraulic::SENSED_LOOPS.iter().enumerate() {
+            let ssm = if self.hyd_loop(*color).is_some() { SignStatus::NormalOperation } else { SignStatus::NoComputedData };
+            words.push(Arinc429Word::new_bnr(A320Hydraulic::PRESSURE_LABELS[index], self.displayed_loop_pressure(*color).get::<psi>(), A320Hydraulic::PRESSURE_RESOLUTION_PSI, ssm));
+            words.push(Arinc429Word::new_bnr(A320Hydraulic::QUANTITY_LABELS[index], self.displayed_reservoir_volume(*color).get::<gallon>(), A320Hydraulic::QUANTITY_RESOLUTION_GALLON, ssm));
         }
+
+        let mut discretes = [
+            self.is_engine_driven_pump_fire_valve_open(1),
+            self.is_engine_driven_pump_fire_valve_open(2),
+            self.is_rat_deployed(),
+        ].to_vec();
+        for color in A320Hydraulic::SENSED_LOOPS.iter() {
+            discretes.push(self.is_hp_filter_bypass_indicated(*color));
+        }
+        let valve_states = discretes.iter().enumerate().fold(0, |bits, (position, state)| bits | ((*state as u32) << position));
+        words.push(Arinc429Word::new_discrete(A320Hydraulic::VALVE_STATES_LABEL, valve_states, SignStatus::NormalOperation));
+
+        words
+    }
+
+    //Which loop the PTU takes power from, for the ECAM and sound
+    pub fn ptu_state(&self) -> PtuState {
+        let transfer = self.ptu_description.and_then(|description| {
+            if self.ptu.is_active_left() {
+                Some((description.left_loop, description.right_loop))
+            } else if self.ptu.is_active_right() {
+                Some((description.right_loop, description.left_loop))
+            } else {
+                None
+            }
+        });
+
+        match transfer {
+            Some((LoopId::GREEN, LoopId::YELLOW)) => PtuState::GreenToYellow,
+            Some((LoopId::YELLOW, LoopId::GREEN)) => PtuState::YellowToGreen,
+            _ => PtuState::Off,
+        }
+    }
+
+    pub fn ptu_flow(&self) -> VolumeRate {
+        self.ptu.get_flow()
+    }
+
+    pub fn ptu_shaft_speed(&self) -> AngularVelocity {
+        AngularVelocity::new::<revolution_per_minute>(self.ptu.get_shaft_speed())
+    }
+
+    pub fn ptu_efficiency(&self) -> f64 {
+        self.ptu.get_efficiency()
+    }
+
+    pub fn is_ptu_self_test_in_progress(&self) -> bool {
+        self.logic.is_ptu_self_test_in_progress()
+    }
+
+    //Drives the PTU barking sound, from 0 to 1
+    pub fn ptu_barking_intensity(&self) -> f64 {
+        self.ptu.get_barking_intensity()
+    }
+
+    //Maximum rudder deflection either side, frozen at its last value when green and yellow are lost
+    pub fn rudder_travel_limit(&self) -> Angle {
+        self.rudder_travel_limiter.limit()
+    }
+
+    pub fn is_nose_wheel_steering_available(&self) -> bool {
+        self.nose_wheel_steering.is_available()
+    }
+
+    //A consumer is supplied when its loop is pressurised and connected to its pumps. Secondary
+    //consumers also need the priority valve of the loop to be open
+    pub fn is_consumer_supplied(&self, color: LoopId, consumer: ActuatorType) -> bool {
+        let pressure = self.loop_pressure(color);
+        self.is_pressurised(color)
+            && !self.is_loop_isolated(color)
+            && (ConsumerPriority::from(consumer) == ConsumerPriority::Primary
+                || FlowSharing::is_priority_valve_open(pressure))
+    }
+
+    //Green normal brakes supplied and controlled by the BSCU
+    pub fn normal_braking_available(&self) -> bool {
+        self.bscu.normal_braking_available() && self.is_consumer_supplied(LoopId::GREEN, ActuatorType::BrakesNormal)
+    }
+
+    //Nose wheel steering supplied and engaged, i.e. not towed
+    pub fn steering_available(&self) -> bool {
+        self.is_nose_wheel_steering_available()
+            && self.is_consumer_supplied(LoopId::GREEN, ActuatorType::NoseWheelSteering)
+    }
+
+    //The trimmable horizontal stabilizer has a hydraulic motor on each of green and yellow
+    pub fn ths_available(&self) -> bool {
+        self.is_consumer_supplied(LoopId::GREEN, ActuatorType::Stabilizer)
+            || self.is_consumer_supplied(LoopId::YELLOW, ActuatorType::Stabilizer)
+    }
+
+    //Nose wheel angle the ground handling code may use: steering limit, towing limit or none
+    pub fn nose_wheel_steering_angle_limit(&self) -> Angle {
+        self.nose_wheel_steering.angle_limit()
     }
 
-    pub fn update(&mut self, context: &UpdateContext) {
+    pub fn brake_accumulator_pressure(&self) -> Pressure {
+        self.alternate_brakes.accumulator_pressure()
+    }
+
+    pub fn left_brake_pressure(&self) -> Pressure {
+        self.normal_brakes.left_brake_pressure().max(self.alternate_brakes.left_brake_pressure())
+    }
+
+    pub fn right_brake_pressure(&self) -> Pressure {
+        self.normal_brakes.right_brake_pressure().max(self.alternate_brakes.right_brake_pressure())
+    }
+
+    /// Temperature of the brake of the given main gear wheel (1 to 4, from left to right).
+    pub fn brake_temperature(&self, wheel_number: usize) -> ThermodynamicTemperature {
+        self.brake_temperatures[wheel_number - 1].temperature()
+    }
+
+    /// The gear of the given number: 1 for the nose gear, 2 and 3 for the left and right main gears.
+    pub fn landing_gear(&self, number: usize) -> &LandingGearLeg {
+        &self.landing_gears[number - 1]
+    }
+
+    /// The LGCIU proximity sensors of the gear of the given number, numbered as the gears.
+    pub fn gear_position_sensors(&self, number: usize) -> &GearPositionSensors {
+        &self.gear_position_sensors[number - 1]
+    }
+
+    /// Fails a proximity sensor of the given LGCIU (1 or 2) on the gear of the given number.
+    pub fn fail_gear_position_sensor(&mut self, number: usize, unit: usize, location: SensorLocation, stuck_near: bool) {
+        self.gear_position_sensors[number - 1].fail_sensor(unit, location, stuck_near);
+    }
+
+    //The gear selector valve ports green pressure to the uplocks and the extension side
+    //of the gear actuators with the lever down, to the downlocks and the retraction side with the lever up
+    fn update_landing_gears(&mut self, delta_time: &Duration) {
+        let pressure = self.node_pressure(LoopId::GREEN, BranchNode::PriorityValveDownstream);
+        let down_selected = !self.gear_lever_up;
+        for (gear, sensors) in self.landing_gears.iter_mut().zip(self.gear_position_sensors.iter_mut()) {
+            gear.update(delta_time, pressure, down_selected);
+            sensors.update(gear.position());
+        }
+    }
+
+    // The parking brake ports yellow system or yellow brake accumulator pressure to the brakes.
+    fn parking_brake_demand(&self) -> Pressure {
+        if self.logic.parking_brake_applied() {
+            Pressure::new::<psi>(A320Hydraulic::PARKING_BRAKE_PRESSURE)
+        } else {
+            Pressure::new::<psi>(0.)
+        }
+    }
+
+    // The dual distribution valve only meters pedal pressure to the alternate brakes once normal braking is lost,
+    // limited when the anti-skid can't release the wheels
+    fn alternate_brake_demands(&self) -> (Pressure, Pressure) {
+        let (left_pedal_demand, right_pedal_demand) = self.brake_pedals.alternate_demands();
+        match self.brake_source.mode() {
+            BrakingMode::Normal => (Pressure::new::<psi>(0.), Pressure::new::<psi>(0.)),
+            BrakingMode::Parking => {
+                let parking_brake_demand = self.parking_brake_demand();
+                (left_pedal_demand.max(parking_brake_demand), right_pedal_demand.max(parking_brake_demand))
+            }
+            _ => (self.brake_source.limit(left_pedal_demand), self.brake_source.limit(right_pedal_demand)),
+        }
+    }
+
+    pub fn braking_mode(&self) -> BrakingMode {
+        self.brake_source.mode()
+    }
+
+    //Full applications of both alternate brakes left in the brake accumulator
+    pub fn brake_accumulator_residual_applications(&self) -> usize {
+        self.alternate_brakes.residual_applications()
+    }
+
+    //Needle readings of the brake and accumulator pressure indicator, which shows the yellow alternate brakes
+    pub fn triple_indicator(&self) -> &TripleIndicator {
+        &self.triple_indicator
+    }
+
+    pub fn update(&mut self, ct: &UpdateContext, engine1 : &Engine, engine2 : &Engine) {
+        #[cfg(feature = "dev-tuning")]
+        self.apply_tuning();
+        let engines = [engine1, engine2];
+        self.update_fire_shutoff_valves(&engines);
+        self.logic.update(ct, engine1, engine2);
+        self.update_electric_pump_demands();
+        self.bscu.update(ct);
+        self.autobrake.update(ct, self.is_green_pressurised() && self.bscu.autobrake_available());
+        // Anti-skid isn't available when braking on the brake accumulator only.
+        self.anti_skid.update(
+            ct,
+            self.bscu.anti_skid_available()
+                && (self.is_green_pressurised() || self.is_yellow_pressurised()),
+        );
+        self.ptu.enabling(
+            self.ptu_description.map_or(false, |ptu| {
+                !self.logic.ptu_is_inhibited()
+                    && !self.is_loop_isolated(ptu.left_loop)
+                    && !self.is_loop_isolated(ptu.right_loop)
+            }),
+        );
+        self.update_reservoir_bleed_air(&engines);
+
+        let min_hyd_loop_timestep = Duration::from_millis(A320Hydraulic::HYDRAULIC_SIM_TIME_STEP); //Hyd Sim rate = 10 Hz
+
+        //time to catch up in our simulation
+        self.total_sim_time_elapsed += ct.delta;
+
+        //Number of time steps to do according to required time step
+        let numberOfSteps_f64 = self.steps_to_catch_up(ct.delta);
+
+        if numberOfSteps_f64 < 1.0 {
+            //Can't do a full time step
+            //we can either do an update with smaller step or wait next iteration
+            //Other option is to update only actuator position based on known hydraulic
+            //state to avoid lag of control surfaces if sim runs really fast
+            self.lag_time_accumulator=Duration::from_secs_f64(numberOfSteps_f64 * min_hyd_loop_timestep.as_secs_f64()); //Time lag is float part of num of steps * fixed time step to get a result in time
+        } else {
+            //TRUE UPDATE LOOP HERE
+            let num_of_update_loops = numberOfSteps_f64.floor() as u32; //Int part is the actual number of loops to do
+            log::trace!(
+                target: "hydraulic",
+                "{} hydraulic steps for a {:.3} s frame, {:.3} s lag",
+                num_of_update_loops,
+                ct.delta.as_secs_f64(),
+                self.lag_time_accumulator.as_secs_f64(),
+            );
+            //Rest of floating part goes into accumulator
+            self.lag_time_accumulator= Duration::from_secs_f64((numberOfSteps_f64 - (num_of_update_loops as f64))* min_hyd_loop_timestep.as_secs_f64()); //Keep track of time left after all fixed loop are done
+
+
+            //UPDATING HYDRAULICS AT FIXED STEP
+            for curLoop in  0..num_of_update_loops {
+                for (pressure, hyd_loop) in self.hydraulic_step_start_pressures.iter_mut().zip(self.loops.iter()) {
+                    *pressure = hyd_loop.get_pressure();
+                }
+
+                //UPDATE HYDRAULICS FIXED TIME STEP
+                self.rat.update(&min_hyd_loop_timestep);
+                self.update_pumps(&min_hyd_loop_timestep, ct, &engines);
+                self.update_loops(&min_hyd_loop_timestep, ct);
+
+                self.brake_source.update(
+                    self.normal_braking_available(),
+                    self.is_yellow_pressurised(),
+                    self.bscu.anti_skid_available(),
+                    self.logic.parking_brake_applied(),
+                );
+                let (left_alternate_demand, right_alternate_demand) = self.alternate_brake_demands();
+                let (left_alternate_demand, right_alternate_demand) = if self.brake_source.uses_anti_skid() {
+                    self.anti_skid_demands(left_alternate_demand, right_alternate_demand)
+                } else {
+                    (left_alternate_demand, right_alternate_demand)
+                };
+                self.alternate_brakes.update(
+                    &min_hyd_loop_timestep,
+                    self.node_pressure(LoopId::YELLOW, BranchNode::BrakeLine),
+                    left_alternate_demand,
+                    right_alternate_demand,
+                );
+                //The brake accumulator charges from the yellow loop and its spent fluid goes back to the yellow reservoir
+                let charged_volume = self.alternate_brakes.charged_volume();
+                let returned_volume = self.alternate_brakes.returned_volume();
+                if let Some(yellow_loop) = self.loops.iter_mut().find(|hyd_loop| hyd_loop.get_color() == LoopId::YELLOW) {
+                    yellow_loop.draw_stored_volume(charged_volume);
+                    yellow_loop.return_stored_volume(returned_volume);
+                }
+
+                let (left_normal_demand, right_normal_demand) = if self.brake_source.mode() == BrakingMode::Normal {
+                    let (left_pedal_demand, right_pedal_demand) = self.brake_pedals.normal_demands();
+                    let autobrake_demand = self.autobrake.brake_pressure_command();
+                    self.anti_skid_demands(left_pedal_demand.max(autobrake_demand), right_pedal_demand.max(autobrake_demand))
+                } else {
+                    (Pressure::new::<psi>(0.), Pressure::new::<psi>(0.))
+                };
+                self.normal_brakes.update(
+                    &min_hyd_loop_timestep,
+                    self.node_pressure(LoopId::GREEN, BranchNode::BrakeLine),
+                    left_normal_demand,
+                    right_normal_demand,
+                );
+                self.update_brake_temperatures(&min_hyd_loop_timestep, ct);
+                self.update_landing_gears(&min_hyd_loop_timestep);
+
+                //UPDATING ACTUATOR PHYSICS AT FIXED STEP / ACTUATORS_SIM_TIME_STEP_MULT
+                self.update_actuators(&min_hyd_loop_timestep, ct);
+            }
+        }
+
+        if let Some(color) = self.leak_measurement_loop {
+            self.leak_measurement.update(&ct.delta, self.loop_pressure(color));
+        }
+        self.update_electric_pump_power_consumptions();
+        self.update_sensors(ct);
+        self.triple_indicator.update(
+            &ct.delta,
+            self.alternate_brakes.accumulator_pressure(),
+            self.alternate_brakes.left_brake_pressure(),
+            self.alternate_brakes.right_brake_pressure(),
+        );
+        self.nose_wheel_steering.update(
+            self.node_pressure(LoopId::GREEN, BranchNode::PriorityValveDownstream),
+            self.logic.nws_tow_engaged(),
+            self.bscu.nose_wheel_steering_available(),
+        );
+
+        if self.state_snapshot_requested {
+            log::info!(target: "hydraulic", "{}", self.state_snapshot());
+            self.state_snapshot_requested = false;
+        }
+    }
+
+    //Logs a full state snapshot at the end of the next update, to attach to bug reports
+    pub fn request_state_snapshot(&mut self) {
+        self.state_snapshot_requested = true;
+    }
+
+    //Human readable dump of the whole hydraulic state, one line per component
+    pub fn state_snapshot(&self) -> String {
+        self.state_report().to_string()
+    }
+
+    //Structured dump of the whole hydraulic state, serialisable to JSON for issue reports
+    pub fn state_report(&self) -> HydraulicStateReport {
+        HydraulicStateReport {
+            time_elapsed_seconds: self.total_sim_time_elapsed.as_secs_f64(),
+            loops: self.loops.iter().map(|hyd_loop| LoopReport {
+                color: hyd_loop.get_color(),
+                pressure_psi: hyd_loop.get_pressure().get::<psi>(),
+                loop_volume_gallon: hyd_loop.get_loop_volume().get::<gallon>(),
+                reservoir_volume_gallon: hyd_loop.get_reservoir_volume().get::<gallon>(),
+                reservoir_air_pressure_psi: hyd_loop.get_reservoir_pressure().get::<psi>(),
+                accumulator_fluid_volume_gallon: hyd_loop.get_accumulator_fluid_volume().get::<gallon>(),
+                isolated: hyd_loop.is_isolated(),
+            }).collect(),
+            engine_driven_pumps: self.engine_driven_pumps.iter().map(|(description, pump)| EngineDrivenPumpReport {
+                engine_number: description.engine_number,
+                loop_color: description.loop_color,
+                fire_shutoff_valve_open: pump.is_fire_shutoff_valve_open(),
+                outlet_check_valve_open: pump.is_outlet_check_valve_open(),
+                shaft_power_watt: pump.get_shaft_power_demand().get::<watt>(),
+            }).collect(),
+            electric_pumps: self.electric_pumps.iter().map(|(description, pump)| ElectricPumpReport {
+                loop_color: description.loop_color,
+                outlet_check_valve_open: pump.is_outlet_check_valve_open(),
+                electrical_power_watt: pump.get_electrical_power_demand().get::<watt>(),
+            }).collect(),
+            ptu: self.ptu_description.map(|description| PtuReport {
+                left_loop: description.left_loop,
+                right_loop: description.right_loop,
+                state: self.ptu_state(),
+                flow_gallon_per_second: self.ptu_flow().get::<gallon_per_second>(),
+                shaft_speed_rpm: self.ptu_shaft_speed().get::<revolution_per_minute>(),
+            }),
+            ram_air_turbine: RamAirTurbineReport {
+                deployed: self.is_rat_deployed(),
+                in_transit: self.is_rat_in_transit(),
+                deployment_progress_percent: self.rat_deployment_progress().get::<percent>(),
+                turbine_speed_rpm: self.rat_rpm(),
+                stall_speed_knots: self.rat_stall_speed().get::<knot>(),
+            },
+            valves: ValvesReport {
+                engine_bleed_valve_closed: self.engine_bleed_valve_closed,
+                apu_bleed_air_valve_open: self.apu_bleed_air_valve_open,
+                reservoir_bleed_air_available: self.reservoir_bleed_air_available,
+            },
+            brakes: BrakesReport {
+                left_normal_pressure_psi: self.normal_brakes.left_brake_pressure().get::<psi>(),
+                right_normal_pressure_psi: self.normal_brakes.right_brake_pressure().get::<psi>(),
+                left_alternate_pressure_psi: self.alternate_brakes.left_brake_pressure().get::<psi>(),
+                right_alternate_pressure_psi: self.alternate_brakes.right_brake_pressure().get::<psi>(),
+                accumulator_pressure_psi: self.alternate_brakes.accumulator_pressure().get::<psi>(),
+                temperatures_celsius: [
+                    self.brake_temperatures[0].temperature().get::<degree_celsius>(),
+                    self.brake_temperatures[1].temperature().get::<degree_celsius>(),
+                    self.brake_temperatures[2].temperature().get::<degree_celsius>(),
+                    self.brake_temperatures[3].temperature().get::<degree_celsius>(),
+                ],
+            },
+            logic: self.logic.report(),
+        }
+    }
+}
+
+//Headless API: runs the system from input samples without a simulator, e.g. for parameter sweeps
+impl A320Hydraulic {
+    pub fn step(&mut self, input: &HydraulicInput) -> A320HydraulicOutput {
+        self.update(&input.context(), &input.engine(1), &input.engine(2));
+
+        A320HydraulicOutput {
+            time: self.total_sim_time_elapsed,
+            blue_pressure: self.loop_pressure(LoopId::BLUE),
+            green_pressure: self.loop_pressure(LoopId::GREEN),
+            yellow_pressure: self.loop_pressure(LoopId::YELLOW),
+            engine_driven_pump_shaft_power: [
+                self.engine_driven_pump_shaft_power(1),
+                self.engine_driven_pump_shaft_power(2),
+            ],
+            blue_electric_pump_power: self.blue_electric_pump_power(),
+            yellow_electric_pump_power: self.yellow_electric_pump_power(),
+            brake_accumulator_pressure: self.brake_accumulator_pressure(),
+        }
+    }
+
+    //Applies the samples in order and returns the output of each step
+    pub fn run(&mut self, inputs: &[HydraulicInput]) -> Vec<A320HydraulicOutput> {
+        inputs.iter().map(|input| self.step(input)).collect()
+    }
+
+    //Runs for the given duration in steps of the clock, all other conditions being those of the
+    //given context. An accelerated clock fast-forwards a scenario through long delays
+    pub fn run_with_clock<C: Clock>(&mut self, clock: &mut C, context: &UpdateContext, duration: Duration, engine1: &Engine, engine2: &Engine) {
+        let end = clock.elapsed() + duration;
+        while clock.elapsed() < end {
+            let context = clock.next_context(context);
+            self.update(&context, engine1, engine2);
+        }
+    }
+}
+
+/// The state of the A320 hydraulic system after an input sample was applied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct A320HydraulicOutput {
+    pub time: Duration,
+    pub blue_pressure: Pressure,
+    pub green_pressure: Pressure,
+    pub yellow_pressure: Pressure,
+    pub engine_driven_pump_shaft_power: [Power; 2],
+    pub blue_electric_pump_power: Power,
+    pub yellow_electric_pump_power: Power,
+    pub brake_accumulator_pressure: Pressure,
+}
+
+impl A320Hydraulic {
+    //The blue electric pump is supplied by AC bus 1, the yellow one by AC bus 2
+    fn electric_pump_bus(color: LoopId) -> ElectricalBusType {
+        match color {
+            LoopId::YELLOW => ElectricalBusType::AlternatingCurrent(2),
+            _ => ElectricalBusType::AlternatingCurrent(1),
+        }
+    }
+
+    //The electrical system sees the power each electric pump draws from its bus
+    fn update_electric_pump_power_consumptions(&mut self) {
+        for (consumption, (_, pump)) in self.electric_pump_power_consumptions.iter_mut().zip(self.electric_pumps.iter()) {
+            consumption.demand(pump.get_electrical_power_demand());
+        }
+    }
+
+    fn update_electric_pump_demands(&mut self) {
+        let yellow_runs = !self.emergency_elec && (self.yellow_electric_pump_pb_on || self.logic.yellow_electric_pump_auto_run());
+        let blue_runs = !self.emergency_elec && self.blue_electric_pump_pb_auto && self.logic.blue_electric_pump_auto_run();
+        for (description, pump) in self.electric_pumps.iter_mut() {
+            let runs = match description.loop_color {
+                LoopId::YELLOW => yellow_runs,
+                LoopId::BLUE => blue_runs,
+                _ => continue,
+            };
+            if runs {
+                pump.start();
+            } else {
+                pump.stop();
+            }
+        }
+    }
+
+    fn update_pumps(&mut self, delta_time: &Duration, context: &UpdateContext, engines: &[&Engine; 2]) {
+        let loops = &self.loops;
+        let find_loop = |color: LoopId| {
+            loops
+                .iter()
+                .find(|hyd_loop| hyd_loop.get_color() == color)
+                .unwrap()
+        };
+
+        if let Some(ptu) = self.ptu_description {
+            self.ptu.update(delta_time, find_loop(ptu.left_loop), find_loop(ptu.right_loop));
+        }
+        for (description, pump) in self.engine_driven_pumps.iter_mut() {
+            //ENG PUMP pushbutton OFF energises the pump depressurisation solenoid
+            if self.engine_driven_pump_pb_auto[description.engine_number - 1] {
+                pump.pressurise();
+            } else {
+                pump.depressurise();
+            }
+            pump.update(
+                delta_time,
+                context,
+                find_loop(description.loop_color),
+                engines[description.engine_number - 1],
+            );
+        }
+        for (description, pump) in self.electric_pumps.iter_mut() {
+            pump.update(delta_time, context, find_loop(description.loop_color));
+        }
+        if self.rat.is_fully_deployed() {
+            if let Some(blue_loop) = loops.iter().find(|hyd_loop| hyd_loop.get_color() == LoopId::BLUE) {
+                //The emergency generator hydraulic motor loads the RAT on top of the blue loop consumers
+                let generator_driven = EmergencyGeneratorHydraulicSupply::from_rat_driven_blue_loop(blue_loop.get_pressure(), context.indicated_airspeed).can_drive_generator();
+                self.rat_pump.set_emergency_generator_load(Power::new::<kilowatt>(if generator_driven { A320Hydraulic::EMERGENCY_GENERATOR_SHAFT_POWER_KW } else { 0. }));
+                self.rat_pump.update(delta_time, context, blue_loop);
+            }
+        }
+    }
+
+    // Each loop receives the flow of the pumps described as pressurising it.
+    fn update_loops(&mut self, delta_time: &Duration, context: &UpdateContext) {
+        let rat_deployed = self.rat.is_fully_deployed();
+        for hyd_loop in self.loops.iter_mut() {
+            let color = hyd_loop.get_color();
+            let electric_pumps = self
+                .electric_pumps
+                .iter()
+                .filter(move |(description, _)| description.loop_color == color)
+                .map(|(_, pump)| pump);
+            let engine_driven_pumps = self
+                .engine_driven_pumps
+                .iter()
+                .filter(move |(description, _)| description.loop_color == color)
+                .map(|(_, pump)| pump);
+            let rat_pump = Some(&self.rat_pump).filter(|_| color == LoopId::BLUE && rat_deployed);
+            let ptu = &self.ptu;
+            let ptu = self.ptu_description
+                .filter(|description| description.left_loop == color || description.right_loop == color)
+                .map(|_| ptu);
+
+            hyd_loop.update(delta_time, context, electric_pumps, engine_driven_pumps, rat_pump, ptu);
+        }
+    }
+
+    //Fixed time steps, and fraction of one, catching up the given frame and the time the previous frames left over
+    fn steps_to_catch_up(&self, delta: Duration) -> f64 {
+        let time_to_catch = delta + self.lag_time_accumulator;
+
+        time_to_catch.as_secs_f64() / Duration::from_millis(A320Hydraulic::HYDRAULIC_SIM_TIME_STEP).as_secs_f64()
+    }
+
+    // A brake pair on a main gear is limited by its most skidding wheel.
+    fn anti_skid_demands(&self, left_demand: Pressure, right_demand: Pressure) -> (Pressure, Pressure) {
+        (
+            self.anti_skid.modulate(1, left_demand).min(self.anti_skid.modulate(2, left_demand)),
+            self.anti_skid.modulate(3, right_demand).min(self.anti_skid.modulate(4, right_demand)),
+        )
+    }
+
+    // Wheels 1 and 2 are on the left main gear, wheels 3 and 4 on the right main gear.
+    fn update_brake_temperatures(&mut self, delta_time: &Duration, context: &UpdateContext) {
+        let left_brake_pressure = self.left_brake_pressure();
+        let right_brake_pressure = self.right_brake_pressure();
+        let brake_fans_running = self.logic.brake_fans_running();
+
+        for (index, brake) in self.brake_temperatures.iter_mut().enumerate() {
+            brake.update(
+                delta_time,
+                context.ambient_temperature,
+                context.ground_speed,
+                context.indicated_airspeed,
+                if index < 2 {
+                    left_brake_pressure
+                } else {
+                    right_brake_pressure
+                },
+                brake_fans_running,
+            );
+        }
+    }
+}
+
+impl SimulatorElementVisitable for A320Hydraulic {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        self.logic.accept(visitor);
+        self.anti_skid.accept(visitor);
+        self.autobrake.accept(visitor);
+        self.bscu.accept(visitor);
+        self.brake_pedals.accept(visitor);
+        for consumption in self.electric_pump_power_consumptions.iter_mut() {
+            consumption.accept(visitor);
+        }
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl EngineDrivenPumpLoads for A320Hydraulic {
+    fn engine_driven_pump_shaft_power(&self, engine_number: usize) -> Power {
+        A320Hydraulic::engine_driven_pump_shaft_power(self, engine_number)
+    }
+}
+impl SimulatorElement for A320Hydraulic {
+    fn read(&mut self, state: &SimulatorReadState) {
+        //The snapshot toggle is a push button: dump once per press
+        let toggle = state.hydraulic.dump_state_requested;
+        if toggle && !self.state_snapshot_toggle {
+            self.request_state_snapshot();
+        }
+        self.state_snapshot_toggle = toggle;
+        //Like the snapshot, one stow attempt per press. The logic was read first, so the ground cart is known
+        let toggle = state.hydraulic.rat_stow_requested;
+        if toggle && !self.rat_stow_toggle {
+            if let Err(error) = self.stow_rat() {
+                log::warn!(target: "hydraulic", "{}", error);
+            }
+        }
+        self.rat_stow_toggle = toggle;
+        self.gear_lever_up = state.landing_gear.gear_lever_up;
+    }
+
+    fn write(&self, state: &mut SimulatorWriteState) {
+        for (index, gear) in self.landing_gears.iter().enumerate() {
+            state.hydraulic.gear_uplocked[index] = gear.is_uplocked();
+            state.hydraulic.gear_downlocked[index] = gear.is_downlocked();
+        }
+        for (index, sensors) in self.gear_position_sensors.iter().enumerate() {
+            state.hydraulic.gear_position_disagree[index] = sensors.has_disagreement();
+        }
+        state.hydraulic.brake_accumulator_pressure =
+            self.alternate_brakes.accumulator_pressure();
+        state.hydraulic.left_alternate_brake_pressure =
+            self.alternate_brakes.left_brake_pressure();
+        state.hydraulic.right_alternate_brake_pressure =
+            self.alternate_brakes.right_brake_pressure();
+        state.hydraulic.blue_electric_pump_power = self.blue_electric_pump_power();
+        state.hydraulic.engine_driven_pump_shaft_power = [
+            self.engine_driven_pump_shaft_power(1),
+            self.engine_driven_pump_shaft_power(2),
+        ];
+        state.hydraulic.yellow_electric_pump_power = self.yellow_electric_pump_power();
+        state.hydraulic.left_normal_brake_pressure = self.normal_brakes.left_brake_pressure();
+        state.hydraulic.right_normal_brake_pressure = self.normal_brakes.right_brake_pressure();
+        for (index, brake) in self.brake_temperatures.iter().enumerate() {
+            state.hydraulic.brake_temperatures[index] = brake.temperature();
+        }
+        state.hydraulic.aileron_positions = self.aileron_positions();
+        state.hydraulic.elevator_positions = self.elevator_positions();
+        let [left_spoiler_positions, right_spoiler_positions] = self.spoiler_positions();
+        state.hydraulic.left_spoiler_positions = left_spoiler_positions;
+        state.hydraulic.right_spoiler_positions = right_spoiler_positions;
+        state.hydraulic.rudder_travel_limit = self.rudder_travel_limit();
+        state.hydraulic.spoiler_availability = self.spoiler_availability();
+        state.hydraulic.triple_indicator_accumulator_pressure =
+            self.triple_indicator.accumulator_pressure();
+        state.hydraulic.triple_indicator_left_brake_pressure =
+            self.triple_indicator.left_brake_pressure();
+        state.hydraulic.triple_indicator_right_brake_pressure =
+            self.triple_indicator.right_brake_pressure();
+        state.hydraulic.nose_wheel_steering_available = self.is_nose_wheel_steering_available();
+        state.hydraulic.nose_wheel_steering_angle_limit = self.nose_wheel_steering_angle_limit();
+        state.hydraulic.rat_deployment_progress = self.rat_deployment_progress();
+        state.hydraulic.rat_in_transit = self.is_rat_in_transit();
+        state.hydraulic.rat_time_to_deploy = self.rat_time_to_deploy();
+        state.hydraulic.ptu_state = self.ptu_state();
+        state.hydraulic.ptu_flow = self.ptu_flow();
+        state.hydraulic.ptu_shaft_speed = self.ptu_shaft_speed();
+        state.hydraulic.ptu_barking_intensity = self.ptu_barking_intensity();
+        state.hydraulic.ptu_self_test_in_progress = self.is_ptu_self_test_in_progress();
+        state.hydraulic.blue_pressure = self.displayed_loop_pressure(LoopId::BLUE);
+        state.hydraulic.green_pressure = self.displayed_loop_pressure(LoopId::GREEN);
+        state.hydraulic.yellow_pressure = self.displayed_loop_pressure(LoopId::YELLOW);
+        state.hydraulic.blue_reservoir_volume = self.displayed_reservoir_volume(LoopId::BLUE);
+        state.hydraulic.green_reservoir_volume = self.displayed_reservoir_volume(LoopId::GREEN);
+        state.hydraulic.yellow_reservoir_volume = self.displayed_reservoir_volume(LoopId::YELLOW);
+    }
+}
+
+/// Determines when the automatic functions of the hydraulic system are allowed to operate.
+pub struct A320HydraulicLogic {
+    brake_fans_running: bool,
+    cargo_door_operated: bool,
+    cargo_door_ptu_inhibit: DelayedFalseLogicGate,
+    cargo_door_pump_run: DelayedFalseLogicGate,
+    cargo_door_selector_debounce: DelayedTrueLogicGate,
+    cargo_door_selector_operated: bool,
+    blue_pump_override_pressed: bool,
+    blue_ground_cart_pressure: Pressure,
+    any_engine_running: bool,
+    nws_tow_pin_inserted: bool,
+    on_ground: bool,
+    parking_brake_applied: bool,
+    pushback_tug_attached: bool,
+    ptu_self_test_done: bool,
+    ptu_self_test_remaining: Duration,
+    single_engine_started: bool,
+}
+
+impl A320HydraulicLogic {
+    // The PTU stays inhibited for this long after the last cargo door operation.
+    const CARGO_DOOR_PTU_INHIBIT_DELAY_SECS: u64 = 40;
+    // The yellow electric pump stops this long after the cargo door reached its end position.
+    const CARGO_DOOR_PUMP_RUN_DELAY_SECS: u64 = 20;
+    // A door selector must be held for this long before the door operation is recognised.
+    const CARGO_DOOR_SELECTOR_DEBOUNCE_MILLIS: u64 = 500;
+    // How long the PTU is let to transfer during its self-test.
+    const PTU_SELF_TEST_DURATION_SECS: u64 = 3;
+
+    pub fn new() -> A320HydraulicLogic {
+        A320HydraulicLogic {
+            brake_fans_running: false,
+            cargo_door_operated: false,
+            cargo_door_ptu_inhibit: DelayedFalseLogicGate::new(Duration::from_secs(
+                A320HydraulicLogic::CARGO_DOOR_PTU_INHIBIT_DELAY_SECS,
+            )),
+            cargo_door_pump_run: DelayedFalseLogicGate::new(Duration::from_secs(
+                A320HydraulicLogic::CARGO_DOOR_PUMP_RUN_DELAY_SECS,
+            )),
+            cargo_door_selector_debounce: DelayedTrueLogicGate::new(Duration::from_millis(
+                A320HydraulicLogic::CARGO_DOOR_SELECTOR_DEBOUNCE_MILLIS,
+            )),
+            cargo_door_selector_operated: false,
+            blue_pump_override_pressed: false,
+            blue_ground_cart_pressure: Pressure::new::<psi>(0.),
+            any_engine_running: false,
+            nws_tow_pin_inserted: false,
+            on_ground: true,
+            parking_brake_applied: false,
+            pushback_tug_attached: false,
+            ptu_self_test_done: false,
+            ptu_self_test_remaining: Duration::from_secs(0),
+            single_engine_started: false,
+        }
+    }
+
+    pub fn set_cargo_door_operated(&mut self, operated: bool) {
+        self.cargo_door_operated = operated;
+    }
+
+    pub fn update<T: EngineStateProvider>(&mut self, context: &UpdateContext, engine1: &T, engine2: &T) {
+        self.cargo_door_selector_debounce
+            .update(context, self.cargo_door_selector_operated);
+        self.cargo_door_ptu_inhibit
+            .update(context, self.cargo_door_operated());
+        self.cargo_door_pump_run
+            .update(context, self.cargo_door_operated());
+        self.single_engine_started =
+            engine1.is_started() != engine2.is_started();
+        let running_engines = [engine1, engine2]
+            .iter()
+            .filter(|engine| engine.is_running())
+            .count();
+        self.any_engine_running = running_engines > 0;
+        self.update_ptu_self_test(context, running_engines == 1);
+    }
+
+    // The self-test starts once the first engine runs on the ground, when only one loop is
+    // pressurised by its engine driven pump. It is done once per flight and never while towed
+    // or while a cargo door is operated.
+    fn update_ptu_self_test(&mut self, context: &UpdateContext, single_engine_running: bool) {
+        if !self.on_ground {
+            self.ptu_self_test_done = false;
+            self.ptu_self_test_remaining = Duration::from_secs(0);
+        } else if !self.ptu_self_test_done
+            && single_engine_running
+            && !self.nws_tow_engaged()
+            && !self.cargo_door_ptu_inhibit.output()
+        {
+            self.ptu_self_test_done = true;
+            self.ptu_self_test_remaining = Duration::from_secs(A320HydraulicLogic::PTU_SELF_TEST_DURATION_SECS);
+        } else {
+            self.ptu_self_test_remaining = self
+                .ptu_self_test_remaining
+                .checked_sub(context.delta)
+                .unwrap_or_default();
+        }
+    }
+
+    /// The PTU is transferring on purpose to check it works, whatever the first engine
+    /// start inhibition.
+    pub fn is_ptu_self_test_in_progress(&self) -> bool {
+        self.ptu_self_test_remaining > Duration::from_secs(0)
+    }
+
+    /// The nose wheel steering is disconnected when the tow pin is inserted
+    /// or a pushback tug is attached to the nose gear.
+    pub fn nws_tow_engaged(&self) -> bool {
+        self.nws_tow_pin_inserted || self.pushback_tug_attached
+    }
+
+    pub fn parking_brake_applied(&self) -> bool {
+        self.parking_brake_applied
+    }
+
+    pub fn on_ground(&self) -> bool {
+        self.on_ground
+    }
+
+    /// Delivery pressure of the hydraulic ground cart connected to the blue loop, zero without cart.
+    pub fn blue_ground_cart_pressure(&self) -> Pressure {
+        self.blue_ground_cart_pressure
+    }
+
+    /// A cargo door is operated, either through its selector or as set directly.
+    pub fn cargo_door_operated(&self) -> bool {
+        self.cargo_door_operated || self.cargo_door_selector_debounce.output()
+    }
+
+    /// The blue electric pump runs automatically once an engine is running. On the ground
+    /// the BLUE PUMP OVRD maintenance push button runs it with the engines off, it is
+    /// inhibited in flight.
+    pub fn blue_electric_pump_auto_run(&self) -> bool {
+        self.any_engine_running || (self.on_ground && self.blue_pump_override_pressed)
+    }
+
+    /// The yellow electric pump runs automatically to power the cargo doors. It keeps
+    /// running for a while once the door operation ended, the door having then reached its
+    /// end position, and stops by itself. Selecting the pump ON keeps it running.
+    pub fn yellow_electric_pump_auto_run(&self) -> bool {
+        self.cargo_door_pump_run.output()
+    }
+
+    pub fn brake_fans_running(&self) -> bool {
+        self.brake_fans_running
+    }
+
+    fn report(&self) -> LogicReport {
+        LogicReport {
+            on_ground: self.on_ground,
+            parking_brake_applied: self.parking_brake_applied,
+            nws_tow_engaged: self.nws_tow_engaged(),
+            cargo_door_operated: self.cargo_door_operated(),
+            cargo_door_ptu_inhibit: self.cargo_door_ptu_inhibit.output(),
+            single_engine_started: self.single_engine_started,
+            ptu_inhibited: self.ptu_is_inhibited(),
+            ptu_self_test_in_progress: self.is_ptu_self_test_in_progress(),
+            yellow_electric_pump_auto_run: self.yellow_electric_pump_auto_run(),
+        }
+    }
+
+    /// The PTU is inhibited during cargo door operation and during the first engine start,
+    /// when a single engine is started while the parking brake is applied or the nose wheel
+    /// steering is in towing position. It operates again once the second engine is started,
+    /// and briefly during its self-test.
+    pub fn ptu_is_inhibited(&self) -> bool {
+        self.cargo_door_ptu_inhibit.output()
+            || (self.single_engine_started
+                && (self.parking_brake_applied || self.nws_tow_engaged())
+                && !self.is_ptu_self_test_in_progress())
+    }
+}
+impl SimulatorElementVisitable for A320HydraulicLogic {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for A320HydraulicLogic {
+    fn read(&mut self, state: &SimulatorReadState) {
+        self.blue_pump_override_pressed = state.hydraulic.blue_pump_override_pb_on;
+        self.blue_ground_cart_pressure = state.hydraulic.blue_ground_cart_pressure;
+        self.brake_fans_running = state.hydraulic.brake_fans_running;
+        self.cargo_door_selector_operated = state.hydraulic.cargo_door_selector_operated[0]
+            || state.hydraulic.cargo_door_selector_operated[1];
+        self.nws_tow_pin_inserted = state.hydraulic.nose_wheel_steering_tow_pin_inserted;
+        self.on_ground = state.on_ground;
+        self.parking_brake_applied = state.hydraulic.parking_brake_applied;
+        self.pushback_tug_attached = state.hydraulic.pushback_tug_attached;
+    }
+}
+
+/// The HYD panel push buttons and their annunciation. The FAULT light of a pump
+/// comes on for a reservoir fault of its loop, or for low pump pressure unless the
+/// pump is switched off. An engine pump FAULT light also comes on when the pump
+/// overheats, and switching it off depressurises the pump.
+/// The PTU FAULT light comes on for a green or yellow reservoir fault.
+pub struct A320HydraulicOverheadPanel {
+    engine_driven_pumps: [AutoOffFaultPushButton; 2],
+    blue_electric_pump: AutoOffFaultPushButton,
+    yellow_electric_pump_on: bool,
+    ptu: AutoOffFaultPushButton,
+    annunciator_light_test: bool,
+}
+
+impl A320HydraulicOverheadPanel {
+    pub fn new() -> A320HydraulicOverheadPanel {
+        A320HydraulicOverheadPanel {
+            engine_driven_pumps: [AutoOffFaultPushButton::new_auto(), AutoOffFaultPushButton::new_auto()],
+            blue_electric_pump: AutoOffFaultPushButton::new_auto(),
+            yellow_electric_pump_on: false,
+            ptu: AutoOffFaultPushButton::new_auto(),
+            annunciator_light_test: false,
+        }
+    }
+
+    pub fn update_after_hydraulic(&mut self, hydraulic: &A320Hydraulic) {
+        for (index, button) in self.engine_driven_pumps.iter_mut().enumerate() {
+            let engine_number = index + 1;
+            let color = if engine_number == 1 { LoopId::GREEN } else { LoopId::YELLOW };
+            button.set_fault(
+                (button.is_auto() && hydraulic.engine_driven_pump_has_low_pressure(engine_number))
+                    || hydraulic.engine_driven_pump_is_overheated(engine_number)
+                    || hydraulic.has_reservoir_fault(color),
+            );
+        }
+        self.blue_electric_pump.set_fault(
+            (self.blue_electric_pump.is_auto() && hydraulic.electric_pump_has_low_pressure(LoopId::BLUE))
+                || hydraulic.has_reservoir_fault(LoopId::BLUE),
+        );
+        self.ptu.set_fault(
+            hydraulic.has_reservoir_fault(LoopId::GREEN) || hydraulic.has_reservoir_fault(LoopId::YELLOW),
+        );
+    }
+
+    pub fn engine_driven_pump_push_button_is_auto(&self, engine_number: usize) -> bool {
+        self.engine_driven_pumps[engine_number - 1].is_auto()
+    }
+
+    pub fn blue_electric_pump_push_button_is_auto(&self) -> bool {
+        self.blue_electric_pump.is_auto()
+    }
+
+    pub fn yellow_electric_pump_push_button_is_on(&self) -> bool {
+        self.yellow_electric_pump_on
+    }
+
+    pub fn ptu_push_button_is_auto(&self) -> bool {
+        self.ptu.is_auto()
+    }
+
+    pub fn engine_driven_pump_lights(&self, engine_number: usize) -> PushButtonLights {
+        self.engine_driven_pumps[engine_number - 1].lights(self.annunciator_light_test)
+    }
+
+    pub fn blue_electric_pump_lights(&self) -> PushButtonLights {
+        self.blue_electric_pump.lights(self.annunciator_light_test)
+    }
+
+    pub fn ptu_lights(&self) -> PushButtonLights {
+        self.ptu.lights(self.annunciator_light_test)
+    }
+}
+impl Default for A320HydraulicOverheadPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl SimulatorElementVisitable for A320HydraulicOverheadPanel {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for A320HydraulicOverheadPanel {
+    fn read(&mut self, state: &SimulatorReadState) {
+        for (button, auto) in self.engine_driven_pumps.iter_mut().zip(state.hydraulic.engine_pump_pb_auto.iter()) {
+            button.set_auto(*auto);
+        }
+        self.blue_electric_pump.set_auto(state.hydraulic.blue_electric_pump_pb_auto);
+        self.yellow_electric_pump_on = state.hydraulic.yellow_electric_pump_pb_on;
+        self.ptu.set_auto(state.hydraulic.ptu_pb_auto);
+        self.annunciator_light_test = state.annunciator_light_test;
+    }
+
+    fn write(&self, state: &mut SimulatorWriteState) {
+        state.hydraulic.engine_pump_pb_lights = [self.engine_driven_pump_lights(1), self.engine_driven_pump_lights(2)];
+        state.hydraulic.blue_electric_pump_pb_lights = self.blue_electric_pump_lights();
+        state.hydraulic.ptu_pb_lights = self.ptu_lights();
+    }
+}
+
+#[cfg(test)]
+mod a320_hydraulic_tests {
+    use super::*;
+    use crate::{
+        a320::A320EngineFireOverheadPanel,
+        electrical::{Current, ElectricPowerSource, ElectricalBus, PowerConsumptionHandler, PowerConsumptionState, PowerSupply, Powerable},
+        hydraulic::{RatGovernor, SensedPosition},
+        simulator::{test_helpers::context_with, FixedStepClock},
+    };
+    use std::{cell::RefCell, rc::Rc};
+    use uom::si::ratio::ratio;
+
+    #[test]
+    fn engine_driven_pumps_pressurise_green_and_yellow() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(hydraulic.is_green_pressurised());
+        assert!(hydraulic.is_yellow_pressurised());
+    }
+
+    #[test]
+    fn consumers_are_available_with_engines_running() {
+        let mut hydraulic = A320Hydraulic::new();
+        power_bscu(&mut hydraulic);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(hydraulic.normal_braking_available());
+        assert!(hydraulic.steering_available());
+        assert!(hydraulic.ths_available());
+    }
+
+    #[test]
+    fn consumers_are_not_available_with_engines_stopped() {
+        let mut hydraulic = A320Hydraulic::new();
+        power_bscu(&mut hydraulic);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert!(!hydraulic.normal_braking_available());
+        assert!(!hydraulic.steering_available());
+        assert!(!hydraulic.ths_available());
+    }
+
+    #[test]
+    fn ths_stays_available_on_yellow_with_green_isolated() {
+        let mut hydraulic = A320Hydraulic::new();
+        power_bscu(&mut hydraulic);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+        hydraulic.isolate_loop(LoopId::GREEN);
+
+        assert!(!hydraulic.is_consumer_supplied(LoopId::GREEN, ActuatorType::Stabilizer));
+        assert!(!hydraulic.normal_braking_available());
+        assert!(!hydraulic.steering_available());
+        assert!(hydraulic.ths_available());
+    }
+
+    #[test]
+    fn normal_braking_is_not_available_without_bscu() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(hydraulic.is_consumer_supplied(LoopId::GREEN, ActuatorType::BrakesNormal));
+        assert!(!hydraulic.normal_braking_available());
+    }
+
+    #[test]
+    fn isolated_loop_depressurises_while_other_loop_keeps_running() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        hydraulic.isolate_loop(LoopId::YELLOW);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 60);
+
+        assert!(!hydraulic.is_yellow_pressurised());
+        assert!(hydraulic.is_green_pressurised());
+    }
+
+    #[test]
+    fn reconnected_loop_pressurises_again() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+        hydraulic.isolate_loop(LoopId::YELLOW);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 60);
+
+        hydraulic.reconnect_loop(LoopId::YELLOW);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(hydraulic.is_yellow_pressurised());
+    }
+
+    #[test]
+    fn engine_2_pressurises_the_yellow_loop() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &Engine::new(1), &running_engine(2), 10);
+
+        assert!(hydraulic.is_yellow_pressurised());
+        assert!(hydraulic.loop_pressure(LoopId::YELLOW) > hydraulic.loop_pressure(LoopId::GREEN));
+    }
+
+    #[test]
+    fn variant_without_blue_loop_pressurises_described_loops_only() {
+        let description = A320Hydraulic::a320_description();
+        let mut variant_description = HydraulicSystemDescription::new();
+        for loop_description in description.loops().iter().filter(|l| l.color != LoopId::BLUE) {
+            variant_description = variant_description.with_loop(*loop_description);
+        }
+        let variant_description = variant_description
+            .with_engine_driven_pump(1, LoopId::GREEN)
+            .with_engine_driven_pump(2, LoopId::YELLOW);
+
+        let mut hydraulic = A320Hydraulic::from_description(variant_description);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(hydraulic.is_green_pressurised());
+        assert!(hydraulic.is_yellow_pressurised());
+        assert!(!hydraulic.is_blue_pressurised());
+        assert_eq!(hydraulic.blue_electric_pump_power(), Power::new::<watt>(0.));
+    }
+
+    #[test]
+    fn variant_with_two_pumps_on_one_engine_draws_power_for_both() {
+        let description = A320Hydraulic::a320_description().with_engine_driven_pump(1, LoopId::YELLOW);
+        let mut hydraulic = A320Hydraulic::from_description(description);
+        run(&mut hydraulic, &running_engine(1), &Engine::new(2), 10);
+
+        assert!(hydraulic.is_yellow_pressurised());
+        assert!(hydraulic.engine_driven_pump_shaft_power(1) > Power::new::<watt>(0.));
+        assert_eq!(hydraulic.engine_driven_pump_shaft_power(2), Power::new::<watt>(0.));
+    }
+
+    #[test]
+    fn ceo_engine_driven_pumps_turn_slower_than_neo_ones() {
+        let mut ceo = A320Hydraulic::for_variant(EngineVariant::Ceo);
+        let mut neo = A320Hydraulic::for_variant(EngineVariant::Neo);
+        run(&mut ceo, &running_engine(1), &running_engine(2), 5);
+        run(&mut neo, &running_engine(1), &running_engine(2), 5);
+
+        assert!(ceo.is_green_pressurised());
+        assert!(ceo.engine_driven_pump_rpm(1) < neo.engine_driven_pump_rpm(1));
+    }
+
+    #[test]
+    fn pumps_do_not_wear_unless_the_wear_model_is_enabled() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 5);
+
+        assert!(hydraulic.engine_driven_pump_wear_mut(1, LoopId::GREEN).is_none());
+    }
+
+    #[test]
+    fn running_pumps_wear_until_maintenance() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.enable_pump_wear_model();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 5);
+
+        let wear = hydraulic.engine_driven_pump_wear_mut(1, LoopId::GREEN).unwrap();
+        assert!(wear.operating_time() >= Duration::from_secs(4));
+        assert_eq!(wear.pressure_cycles(), 1);
+        assert_eq!(hydraulic.electric_pump_wear_mut(LoopId::YELLOW).unwrap().operating_time(), Duration::from_secs(0));
+
+        hydraulic.perform_pump_maintenance();
+
+        assert_eq!(hydraulic.engine_driven_pump_wear_mut(1, LoopId::GREEN).unwrap().operating_time(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn worn_engine_driven_pump_still_pressurises_its_loop() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.enable_pump_wear_model();
+        *hydraulic.engine_driven_pump_wear_mut(1, LoopId::GREEN).unwrap() =
+            PumpWear::with_state(Duration::from_secs(72_000_000), 200_000, Ratio::new::<ratio>(1.));
+        run(&mut hydraulic, &running_engine(1), &Engine::new(2), 10);
+
+        assert!(hydraulic.is_green_pressurised());
+        assert!(hydraulic.loop_pressure(LoopId::GREEN) > Pressure::new::<psi>(2500.));
+    }
+
+    #[test]
+    fn new_engine_driven_pump_does_not_overheat() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.set_fluid_temperature(LoopId::GREEN, ThermodynamicTemperature::new::<degree_celsius>(90.));
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 150);
+
+        assert!(hydraulic.engine_driven_pump_case_temperature(1).unwrap() > ThermodynamicTemperature::new::<degree_celsius>(60.));
+        assert!(!hydraulic.engine_driven_pump_is_overheated(1));
+    }
+
+    #[test]
+    fn worn_engine_driven_pump_overheats_until_switched_off() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.enable_pump_wear_model();
+        *hydraulic.engine_driven_pump_wear_mut(1, LoopId::GREEN).unwrap() =
+            PumpWear::with_state(Duration::from_secs(72_000_000), 200_000, Ratio::new::<ratio>(1.));
+        hydraulic.set_fluid_temperature(LoopId::GREEN, ThermodynamicTemperature::new::<degree_celsius>(90.));
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 150);
+
+        assert!(hydraulic.engine_driven_pump_is_overheated(1));
+        assert!(!hydraulic.engine_driven_pump_is_overheated(2));
+
+        let mut overhead = A320HydraulicOverheadPanel::new();
+        overhead.update_after_hydraulic(&hydraulic);
+        assert!(overhead.engine_driven_pump_lights(1).fault);
+
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.engine_pump_pb_auto = [false, true];
+        state.hydraulic.blue_electric_pump_pb_auto = true;
+        state.hydraulic.ptu_pb_auto = true;
+        overhead.read(&state);
+        hydraulic.update_after_hydraulic_overhead(&overhead);
+        let overheat_temperature = hydraulic.engine_driven_pump_case_temperature(1).unwrap();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+
+        assert!(hydraulic.is_engine_driven_pump_depressurised(1));
+        assert!(hydraulic.engine_driven_pump_case_temperature(1).unwrap() < overheat_temperature);
+    }
+
+    #[test]
+    fn static_leaks_are_shared_between_manifold_and_flight_control_banks() {
+        let hydraulic = A320Hydraulic::new();
+
+        for color in A320Hydraulic::SENSED_LOOPS.iter() {
+            let leaks = hydraulic.hyd_loop(*color).unwrap().static_leaks();
+            let total: f64 = leaks.iter().map(|leak| leak.internal_flow_at_3000_psi().get::<gallon_per_second>()).sum();
+
+            assert!((total - 0.04).abs() < 1e-9);
+            assert!(leaks.iter().any(|leak| leak.zone() == LeakZone::ActuatorBank(ActuatorType::Rudder)));
+        }
+        assert!(hydraulic.hyd_loop(LoopId::YELLOW).unwrap().static_leaks().iter().all(|leak| leak.zone() != LeakZone::ActuatorBank(ActuatorType::Aileron)));
+    }
+
+    #[test]
+    fn external_leak_of_an_actuator_bank_drains_its_reservoir() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut reference = A320Hydraulic::new();
+        hydraulic.set_external_leak(LoopId::GREEN, LeakZone::ActuatorBank(ActuatorType::Spoiler), VolumeRate::new::<gallon_per_second>(0.05));
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+        run(&mut reference, &running_engine(1), &running_engine(2), 20);
+
+        assert!(hydraulic.external_leak_volume(LoopId::GREEN) > Volume::new::<gallon>(0.5));
+        assert!(hydraulic.reservoir_volume(LoopId::GREEN) < reference.reservoir_volume(LoopId::GREEN) - Volume::new::<gallon>(0.5));
+        assert_eq!(hydraulic.external_leak_volume(LoopId::YELLOW), Volume::new::<gallon>(0.));
+        assert!(hydraulic.is_green_pressurised());
+    }
+
+    #[test]
+    fn flight_controls_are_held_at_neutral_when_pressurised() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert_eq!(hydraulic.aileron_positions(), [Angle::new::<degree>(0.); 2]);
+        assert_eq!(hydraulic.elevator_positions(), [Angle::new::<degree>(0.); 2]);
+    }
+
+    #[test]
+    fn flight_controls_go_to_their_unpowered_position_on_total_hydraulic_loss_in_flight() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert_eq!(hydraulic.aileron_positions(), [Angle::new::<degree>(25.); 2]);
+        assert_eq!(hydraulic.elevator_positions(), [Angle::new::<degree>(0.); 2]);
+        for spoiler_positions in hydraulic.spoiler_positions().iter() {
+            assert!(spoiler_positions.iter().all(|position| *position > Angle::new::<degree>(0.)));
+        }
+    }
+
+    #[test]
+    fn surfaces_move_every_frame_when_frames_are_shorter_than_a_hydraulic_step() {
+        let mut hydraulic = A320Hydraulic::new();
+        let context = context_with().delta(Duration::from_millis(20)).build();
+        // Surfaces start moving after the first hydraulic step.
+        for _ in 0..5 {
+            hydraulic.update(&context, &Engine::new(1), &Engine::new(2));
+        }
+        let mut previous_position = hydraulic.aileron_positions()[0];
+        for _ in 0..50 {
+            hydraulic.update(&context, &Engine::new(1), &Engine::new(2));
+            let position = hydraulic.aileron_positions()[0];
+
+            // The unpowered aileron drooping at a constant rate.
+            assert!(position > previous_position);
+            assert!(position < Angle::new::<degree>(25.));
+            previous_position = position;
+        }
+    }
+
+    #[test]
+    fn rudder_travel_is_limited_at_high_speed_by_green_or_yellow() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &Engine::new(1), &running_engine(2), 30);
+
+        assert!(hydraulic.rudder_travel_limit() < Angle::new::<degree>(30.));
+    }
+
+    #[test]
+    fn displayed_pressure_is_quantized() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        let displayed = hydraulic.displayed_loop_pressure(LoopId::GREEN).get::<psi>();
+        assert_eq!(displayed % 50., 0.);
+        assert!((displayed - hydraulic.loop_pressure(LoopId::GREEN).get::<psi>()).abs() <= 50.);
+    }
+
+    #[test]
+    fn arinc429_words_carry_displayed_pressures_and_valve_states() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        let words = hydraulic.arinc429_words();
+        let green_pressure = words.iter().find(|word| word.label() == 0o161).unwrap();
+        assert_eq!(green_pressure.ssm(), SignStatus::NormalOperation);
+        assert_eq!(green_pressure.bnr_value(1.), hydraulic.displayed_loop_pressure(LoopId::GREEN).get::<psi>());
+
+        let valve_states = words.iter().find(|word| word.label() == 0o166).unwrap();
+        assert!(valve_states.discrete(0));
+        assert!(valve_states.discrete(1));
+        assert!(!valve_states.discrete(2));
+    }
+
+    #[test]
+    fn displayed_values_are_raw_without_sensor_model() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.set_sensor_model_enabled(false);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert_eq!(hydraulic.displayed_loop_pressure(LoopId::GREEN), hydraulic.loop_pressure(LoopId::GREEN));
+        assert_eq!(hydraulic.displayed_reservoir_volume(LoopId::GREEN), hydraulic.reservoir_volume(LoopId::GREEN));
+    }
+
+    #[test]
+    fn run_records_one_output_per_input_sample() {
+        let mut hydraulic = A320Hydraulic::new();
+        let input = HydraulicInput {
+            delta: Duration::from_secs(1),
+            engine_n2: [Ratio::new::<percent>(80.), Ratio::new::<percent>(0.)],
+            ..HydraulicInput::default()
+        };
+
+        let outputs = hydraulic.run(&[input; 10]);
+
+        assert_eq!(outputs.len(), 10);
+        assert_eq!(outputs[9].time, Duration::from_secs(10));
+        assert!(outputs[9].green_pressure > Pressure::new::<psi>(2900.));
+        assert!(outputs[9].engine_driven_pump_shaft_power[0] > Power::new::<watt>(0.));
+        assert_eq!(outputs[9].engine_driven_pump_shaft_power[1], Power::new::<watt>(0.));
+    }
+
+    #[test]
+    #[cfg(feature = "dev-tuning")]
+    fn tuned_pump_displacement_is_picked_up_on_next_update() {
+        let mut hydraulic = A320Hydraulic::new();
+        let (sender, receiver) = crate::hydraulic::tuning_channel();
+        hydraulic.attach_tuning(receiver);
+        for index in 0..9 {
+            sender.set(&format!("engine_driven_pump.displacement.{}", index), 0.).unwrap();
+        }
+
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(!hydraulic.is_green_pressurised());
+    }
+
+    #[test]
+    #[cfg(feature = "dev-tuning")]
+    fn tuned_ptu_breakout_pressure_keeps_the_ptu_from_turning() {
+        let mut hydraulic = A320Hydraulic::new();
+        let (sender, receiver) = crate::hydraulic::tuning_channel();
+        hydraulic.attach_tuning(receiver);
+        sender.set("ptu.breakout_pressure", 5000.).unwrap();
+
+        run(&mut hydraulic, &starting_engine(1), &running_engine(2), 1);
+
+        assert_eq!(hydraulic.ptu_flow(), VolumeRate::new::<gallon_per_second>(0.));
+    }
+
+    #[test]
+    #[cfg(feature = "dev-tuning")]
+    fn tuned_gauge_needle_time_constant_slows_the_triple_indicator() {
+        let mut hydraulic = A320Hydraulic::new();
+        let (sender, receiver) = crate::hydraulic::tuning_channel();
+        hydraulic.attach_tuning(receiver);
+        sender.set("filter.gauge_needle", 60.).unwrap();
+        press_brake_pedals(&mut hydraulic, 100., 0.);
+
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+
+        assert!(hydraulic.triple_indicator().left_brake_pressure() < Pressure::new::<psi>(500.));
+    }
+
+    // Engine 2 is started during pushback, engine 1 once the tug is disconnected
+    // and the parking brake is set again.
+    #[test]
+    fn pushback_sequence_activates_ptu_at_second_engine_start_only() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.pushback_tug_attached = true;
+        hydraulic.logic.read(&state);
+        run(&mut hydraulic, &Engine::new(1), &running_engine(2), 30);
+
+        assert!(hydraulic.is_yellow_pressurised());
+        assert!(!hydraulic.is_green_pressurised());
+
+        state.hydraulic.pushback_tug_attached = false;
+        state.hydraulic.parking_brake_applied = true;
+        hydraulic.logic.read(&state);
+        run(&mut hydraulic, &Engine::new(1), &running_engine(2), 30);
+
+        assert!(!hydraulic.is_green_pressurised());
+
+        run(&mut hydraulic, &starting_engine(1), &running_engine(2), 10);
+
+        assert!(hydraulic.is_green_pressurised());
+    }
+
+    #[test]
+    fn loop_with_pumps_on_both_engines_is_pressurised_by_either_engine() {
+        let mut hydraulic = A320Hydraulic::from_description(green_on_both_engines_description());
+        run(&mut hydraulic, &Engine::new(1), &running_engine(2), 10);
+
+        assert!(hydraulic.is_green_pressurised());
+        assert_eq!(hydraulic.engine_driven_pump_shaft_power(1), Power::new::<watt>(0.));
+        assert!(hydraulic.engine_driven_pump_shaft_power(2) > Power::new::<watt>(0.));
+    }
+
+    #[test]
+    fn loop_with_pumps_on_both_engines_draws_power_from_both() {
+        let mut hydraulic = A320Hydraulic::from_description(green_on_both_engines_description());
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(hydraulic.is_green_pressurised());
+        assert!(hydraulic.engine_driven_pump_shaft_power(1) > Power::new::<watt>(0.));
+        assert!(hydraulic.engine_driven_pump_shaft_power(2) > Power::new::<watt>(0.));
+    }
+
+    #[test]
+    #[should_panic]
+    fn pumps_cannot_be_driven_by_a_third_engine() {
+        A320Hydraulic::from_description(A320Hydraulic::a320_description().with_engine_driven_pump(3, LoopId::GREEN));
+    }
+
+    fn green_on_both_engines_description() -> HydraulicSystemDescription {
+        HydraulicSystemDescription::new()
+            .with_loop(HydraulicLoopDescription::a320_green())
+            .with_engine_driven_pump(1, LoopId::GREEN)
+            .with_engine_driven_pump(2, LoopId::GREEN)
+    }
+
+    #[test]
+    fn ptu_transfers_yellow_power_to_green_with_engine_1_off() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &starting_engine(1), &running_engine(2), 1);
+
+        assert_eq!(hydraulic.ptu_state(), PtuState::YellowToGreen);
+        assert!(hydraulic.ptu_flow() > VolumeRate::new::<gallon_per_second>(0.));
+        assert!(hydraulic.ptu_shaft_speed() > AngularVelocity::new::<revolution_per_minute>(0.));
+        assert!(hydraulic.ptu_efficiency() > 0. && hydraulic.ptu_efficiency() < 1.);
+        assert!(hydraulic.ptu_barking_intensity() > 0.);
+    }
+
+    #[test]
+    fn ptu_is_off_with_both_engines_running() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert_eq!(hydraulic.ptu_state(), PtuState::Off);
+        assert_eq!(hydraulic.ptu_shaft_speed(), AngularVelocity::new::<revolution_per_minute>(0.));
+    }
+
+    #[test]
+    fn ptu_transfers_during_self_test_after_first_engine_start() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut state = SimulatorReadState::default();
+        state.on_ground = true;
+        state.hydraulic.parking_brake_applied = true;
+        hydraulic.logic.read(&state);
+        run(&mut hydraulic, &Engine::new(1), &running_engine(2), 1);
+
+        assert!(hydraulic.is_ptu_self_test_in_progress());
+        assert_eq!(hydraulic.ptu_state(), PtuState::YellowToGreen);
+
+        run(&mut hydraulic, &Engine::new(1), &running_engine(2), 5);
+
+        assert!(!hydraulic.is_ptu_self_test_in_progress());
+        assert_eq!(hydraulic.ptu_state(), PtuState::Off);
+    }
+
+    #[test]
+    fn cargo_door_operation_runs_the_yellow_electric_pump() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.cargo_door_selector_operated = [true, false];
+        hydraulic.logic.read(&state);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert!(hydraulic.yellow_electric_pump_power() > Power::new::<watt>(0.));
+        assert!(hydraulic.is_yellow_pressurised());
+        assert!(!hydraulic.is_green_pressurised());
+    }
+
+    #[test]
+    fn yellow_electric_pump_loads_ac_bus_2() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.yellow_electric_pump_pb_on = true;
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        let mut ac_bus_2 = ElectricalBus::new(ElectricalBusType::AlternatingCurrent(2));
+        ac_bus_2.set_input(Current::some(ElectricPowerSource::EngineGenerator(2)));
+        let mut supply = PowerSupply::new();
+        supply.add(&ac_bus_2);
+        let mut handler = PowerConsumptionHandler::new(&supply);
+        handler.supply_power_to_elements(&mut Box::new(&mut hydraulic));
+        handler.determine_power_consumption(&mut Box::new(&mut hydraulic));
+        let mut generator = GeneratorLoad::new(ElectricPowerSource::EngineGenerator(2));
+        handler.write_power_consumption(&mut Box::new(&mut generator));
+
+        assert!(generator.load > Power::new::<watt>(0.));
+        assert_eq!(generator.load, hydraulic.yellow_electric_pump_power());
+    }
+
+    #[test]
+    fn engines_carry_the_load_of_their_engine_driven_pumps() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut engine1 = running_engine(1);
+        let engine2 = Engine::new(2);
+        run(&mut hydraulic, &engine1, &engine2, 10);
+        engine1.update_after_hydraulic(&hydraulic);
+
+        assert!(engine1.hydraulic_pump_load() > Power::new::<watt>(0.));
+        assert_eq!(engine1.hydraulic_pump_load(), hydraulic.engine_driven_pump_shaft_power(1));
+    }
+
+    #[test]
+    fn leak_measurement_gives_the_pressure_decay_rate_of_the_loop() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.yellow_electric_pump_pb_on = true;
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        hydraulic.start_leak_measurement(LoopId::YELLOW);
+        hydraulic.yellow_electric_pump_pb_on = false;
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert!(hydraulic.hyd_loop(LoopId::YELLOW).unwrap().are_leak_measurement_valves_closed());
+        assert!(hydraulic.leak_measurement().is_in_progress());
+        assert!(hydraulic.leak_measurement().decay_rate().unwrap() > Pressure::new::<psi>(0.));
+
+        hydraulic.stop_leak_measurement();
+
+        assert!(!hydraulic.hyd_loop(LoopId::YELLOW).unwrap().are_leak_measurement_valves_closed());
+        assert!(hydraulic.leak_measurement().decay_rate().is_some());
+    }
+
+    #[test]
+    fn closed_leak_measurement_valves_slow_the_pressure_decay() {
+        let decay_rate = |closed: bool| {
+            let mut hydraulic = A320Hydraulic::new();
+            hydraulic.yellow_electric_pump_pb_on = true;
+            run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+            hydraulic.start_leak_measurement(LoopId::YELLOW);
+            hydraulic.hyd_loop_mut(LoopId::YELLOW).set_leak_measurement_valves_closed(closed);
+            hydraulic.yellow_electric_pump_pb_on = false;
+            run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+            hydraulic.leak_measurement().decay_rate().unwrap()
+        };
+
+        assert!(decay_rate(true) < decay_rate(false));
+    }
+
+    #[test]
+    fn electric_pumps_are_shed_in_emergency_electrical_configuration() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.yellow_electric_pump_pb_on = true;
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+        assert!(hydraulic.blue_electric_pump_power() > Power::new::<watt>(0.));
+        assert!(hydraulic.yellow_electric_pump_power() > Power::new::<watt>(0.));
+
+        hydraulic.update_after_electrical(&TestElectrical { emergency_elec: true });
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 5);
+
+        assert!(hydraulic.are_electric_pumps_shed());
+        assert_eq!(hydraulic.blue_electric_pump_power(), Power::new::<watt>(0.));
+        assert_eq!(hydraulic.yellow_electric_pump_power(), Power::new::<watt>(0.));
+    }
+
+    #[test]
+    fn electric_pumps_run_again_when_normal_power_returns() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.yellow_electric_pump_pb_on = true;
+        hydraulic.update_after_electrical(&TestElectrical { emergency_elec: true });
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 5);
+
+        hydraulic.update_after_electrical(&TestElectrical { emergency_elec: false });
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 5);
+
+        assert!(!hydraulic.are_electric_pumps_shed());
+        assert!(hydraulic.blue_electric_pump_power() > Power::new::<watt>(0.));
+        assert!(hydraulic.yellow_electric_pump_power() > Power::new::<watt>(0.));
+    }
+
+    #[test]
+    fn in_emergency_electrical_configuration_yellow_pump_cannot_pressurise_yellow_loop() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.yellow_electric_pump_pb_on = true;
+        hydraulic.update_after_electrical(&TestElectrical { emergency_elec: true });
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert!(!hydraulic.is_yellow_pressurised());
+        assert!(!hydraulic.is_blue_pressurised());
+    }
+
+    #[test]
+    fn yellow_electric_pump_stops_by_itself_after_cargo_door_operation() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.logic.set_cargo_door_operated(true);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        hydraulic.logic.set_cargo_door_operated(false);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 25);
+
+        assert_eq!(hydraulic.yellow_electric_pump_power(), Power::new::<watt>(0.));
+    }
+
+    #[test]
+    fn yellow_electric_pump_selected_on_keeps_running_after_cargo_door_operation() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.logic.set_cargo_door_operated(true);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        let mut overhead = A320HydraulicOverheadPanel::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.yellow_electric_pump_pb_on = true;
+        overhead.read(&state);
+        hydraulic.update_after_hydraulic_overhead(&overhead);
+        hydraulic.logic.set_cargo_door_operated(false);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 25);
+
+        assert!(hydraulic.yellow_electric_pump_power() > Power::new::<watt>(0.));
+        assert!(hydraulic.is_yellow_pressurised());
+    }
+
+    #[test]
+    fn blue_electric_pump_holds_nominal_pressure_with_engines_running() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+
+        assert!(hydraulic.is_blue_pressurised());
+        assert!((hydraulic.loop_pressure(LoopId::BLUE).get::<psi>() - 3000.).abs() < 50.);
+    }
+
+    #[test]
+    fn blue_pump_override_pressurises_the_blue_loop_with_engines_off() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut state = SimulatorReadState::default();
+        state.on_ground = true;
+        state.hydraulic.blue_pump_override_pb_on = true;
+        hydraulic.logic.read(&state);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert!(hydraulic.is_blue_pressurised());
+        assert!(!hydraulic.is_green_pressurised());
+    }
+
+    #[test]
+    fn released_engine_fire_button_stops_its_engine_driven_pump() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        let mut engine_1 = running_engine(1);
+        engine_1.update_after_engine_fire_overhead(&released_engine_1_fire_button());
+        run(&mut hydraulic, &engine_1, &running_engine(2), 60);
+
+        assert!(!hydraulic.is_engine_driven_pump_fire_valve_open(1));
+        assert!(hydraulic.is_engine_driven_pump_fire_valve_open(2));
+        assert_eq!(hydraulic.engine_driven_pump_shaft_power(1), Power::new::<watt>(0.));
+        assert!(hydraulic.is_yellow_pressurised());
+    }
+
+    #[test]
+    fn engine_driven_pump_recovers_after_fire_button_reset() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut overhead = released_engine_1_fire_button();
+        let mut engine_1 = running_engine(1);
+        engine_1.update_after_engine_fire_overhead(&overhead);
+        run(&mut hydraulic, &engine_1, &Engine::new(2), 10);
+
+        assert!(!hydraulic.is_green_pressurised());
+
+        overhead.reset_engine_fire_button(1);
+        engine_1.update_after_engine_fire_overhead(&overhead);
+        run(&mut hydraulic, &engine_1, &Engine::new(2), 10);
+
+        assert!(hydraulic.is_green_pressurised());
+    }
+
+    #[test]
+    fn running_engine_pressurises_reservoirs_with_bleed_air() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &Engine::new(2), 10);
+
+        assert!(hydraulic.is_reservoir_bleed_air_available());
+        assert!(hydraulic.reservoir_air_pressure(LoopId::GREEN) > Pressure::new::<psi>(50.));
+    }
+
+    #[test]
+    fn released_engine_fire_button_cuts_reservoir_bleed_air_from_that_engine() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut engine_1 = running_engine(1);
+        engine_1.update_after_engine_fire_overhead(&released_engine_1_fire_button());
+
+        run(&mut hydraulic, &engine_1, &Engine::new(2), 1);
+        assert!(!hydraulic.is_reservoir_bleed_air_available());
+
+        run(&mut hydraulic, &engine_1, &running_engine(2), 1);
+        assert!(hydraulic.is_reservoir_bleed_air_available());
+    }
+
+    #[test]
+    fn released_engine_fire_button_depressurises_the_reservoir_of_that_side() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut overhead = released_engine_1_fire_button();
+        let mut engine_1 = running_engine(1);
+        engine_1.update_after_engine_fire_overhead(&overhead);
+        run(&mut hydraulic, &engine_1, &running_engine(2), 60);
+
+        assert!(hydraulic.is_reservoir_bleed_air_available());
+        assert!(hydraulic.reservoir_air_pressure(LoopId::GREEN) < hydraulic.reservoir_air_pressure(LoopId::YELLOW) - Pressure::new::<psi>(2.));
+
+        overhead.reset_engine_fire_button(1);
+        engine_1.update_after_engine_fire_overhead(&overhead);
+        run(&mut hydraulic, &engine_1, &running_engine(2), 20);
+
+        assert!((hydraulic.reservoir_air_pressure(LoopId::GREEN) - hydraulic.reservoir_air_pressure(LoopId::YELLOW)).abs() < Pressure::new::<psi>(0.1));
+    }
+
+    #[test]
+    fn apu_bleed_air_pressurises_reservoirs_through_crossbleed() {
+        struct OpenApuBleedValve;
+        impl BleedAirValveState for OpenApuBleedValve {
+            fn bleed_air_valve_is_open(&self) -> bool {
+                true
+            }
+        }
+
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 1);
+        assert!(!hydraulic.is_reservoir_bleed_air_available());
+
+        hydraulic.update_after_apu(&OpenApuBleedValve);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 1);
+        assert!(hydraulic.is_reservoir_bleed_air_available());
+    }
+
+    #[test]
+    fn blue_electric_pump_builds_pressure_without_fault() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(!hydraulic.electric_pump_has_low_pressure(LoopId::BLUE));
+        assert!(!hydraulic.electric_pump_has_pressure_not_built(LoopId::BLUE));
+    }
+
+    #[test]
+    fn blue_electric_pump_without_fluid_has_pressure_not_built_fault() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.drain_reservoir(LoopId::BLUE, Volume::new::<gallon>(10.));
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(hydraulic.electric_pump_has_pressure_not_built(LoopId::BLUE));
+        assert!(hydraulic.electric_pump_has_low_pressure(LoopId::BLUE));
+    }
+
+    #[test]
+    fn rat_is_governed_below_max_overspeed_in_cruise() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.deploy_rat();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert!(hydraulic.rat_rpm() > RatGovernor::NORMAL_RPM);
+        assert!(hydraulic.rat_rpm() <= RatGovernor::MAX_OVERSPEED_RPM);
+    }
+
+    #[test]
+    fn emergency_generator_raises_rat_stall_speed() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.deploy_rat();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+        let stall_speed_with_generator = hydraulic.rat_stall_speed();
+
+        // Too slow to drive the emergency generator, the RAT only drives the blue consumers.
+        let context = context_with().delta(Duration::from_millis(33)).indicated_airspeed(Velocity::new::<knot>(90.)).build();
+        for _ in 0..150 {
+            hydraulic.update(&context, &Engine::new(1), &Engine::new(2));
+        }
+
+        assert!(!hydraulic.emergency_generator_supply(&context).can_drive_generator());
+        assert!(hydraulic.rat_stall_speed() < stall_speed_with_generator);
+    }
+
+    #[test]
+    fn deployed_rat_pressurises_the_blue_loop() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.deploy_rat();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert!(hydraulic.is_blue_pressurised());
+    }
+
+    #[test]
+    fn pushback_tug_disconnects_nose_wheel_steering() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.pushback_tug_attached = true;
+        hydraulic.logic.read(&state);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 5);
+
+        assert!(hydraulic.is_green_pressurised());
+        assert!(!hydraulic.is_nose_wheel_steering_available());
+        assert_eq!(hydraulic.nose_wheel_steering_angle_limit(), Angle::new::<degree>(95.));
+    }
+
+    #[test]
+    fn rat_in_transit_does_not_pressurise_the_blue_loop() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.deploy_rat();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 2);
+
+        assert!(hydraulic.is_rat_in_transit());
+        assert!(hydraulic.rat_deployment_progress() > Ratio::new::<percent>(0.));
+        assert!(hydraulic.rat_deployment_progress() < Ratio::new::<percent>(100.));
+        assert!(hydraulic.rat_time_to_deploy() > Duration::from_secs(0));
+        assert!(!hydraulic.is_blue_pressurised());
+
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert!(!hydraulic.is_rat_in_transit());
+        assert_eq!(hydraulic.rat_deployment_progress(), Ratio::new::<percent>(100.));
+        assert!(hydraulic.is_blue_pressurised());
+    }
+
+    #[test]
+    fn state_snapshot_describes_each_loop() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 5);
+
+        let snapshot = hydraulic.state_snapshot();
+        for color in ["Blue", "Green", "Yellow"].iter() {
+            assert!(snapshot.contains(&format!("{} loop:", color)));
+        }
+        assert!(snapshot.contains("PTU:"));
+        assert!(snapshot.contains("Brakes:"));
+    }
+
+    #[test]
+    fn state_report_serialises_every_loop_to_json() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 5);
+
+        let report = hydraulic.state_report();
+        assert_eq!(report.loops.len(), 3);
+        assert_eq!(report.engine_driven_pumps.len(), 2);
+        assert!(report.ptu.is_some());
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["loops"][1]["color"], "Green");
+        assert!(json["loops"][1]["pressure_psi"].as_f64().unwrap() > 2500.);
+        assert_eq!(json["logic"]["ptu_inhibited"], false);
+    }
+
+    #[test]
+    fn state_snapshot_toggle_requests_one_snapshot_per_press() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.dump_state_requested = true;
+        hydraulic.read(&state);
+
+        assert!(hydraulic.state_snapshot_requested);
+
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 1);
+        hydraulic.read(&state);
+
+        assert!(!hydraulic.state_snapshot_requested);
+
+        state.hydraulic.dump_state_requested = false;
+        hydraulic.read(&state);
+        state.hydraulic.dump_state_requested = true;
+        hydraulic.read(&state);
+
+        assert!(hydraulic.state_snapshot_requested);
+    }
+
+    #[test]
+    fn rat_cannot_be_stowed_in_flight() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut state = SimulatorReadState::default();
+        state.on_ground = false;
+        hydraulic.logic.read(&state);
+        hydraulic.deploy_rat();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert_eq!(hydraulic.stow_rat(), Err(RatStowError::NotOnGround));
+        assert!(hydraulic.is_rat_deployed());
+    }
+
+    #[test]
+    fn rat_cannot_be_stowed_with_only_the_rat_pressurising_the_blue_loop() {
+        let mut hydraulic = A320Hydraulic::new();
+        let state = SimulatorReadState { on_ground: true, ..SimulatorReadState::default() };
+        hydraulic.logic.read(&state);
+        hydraulic.deploy_rat();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert!(hydraulic.loop_pressure(LoopId::BLUE) > Pressure::new::<psi>(2500.));
+        assert_eq!(hydraulic.stow_rat(), Err(RatStowError::InsufficientPressure(Pressure::new::<psi>(0.))));
+        assert!(hydraulic.is_rat_deployed());
+    }
+
+    #[test]
+    fn stowed_rat_no_longer_pressurises_the_blue_loop() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut state = SimulatorReadState::default();
+        state.on_ground = true;
+        state.hydraulic.blue_ground_cart_pressure = Pressure::new::<psi>(3000.);
+        hydraulic.logic.read(&state);
+        hydraulic.deploy_rat();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        assert_eq!(hydraulic.stow_rat(), Ok(()));
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 60);
+
+        assert!(!hydraulic.is_rat_deployed());
+        assert!(!hydraulic.is_blue_pressurised());
+    }
+
+    #[test]
+    fn rat_stow_request_from_the_sim_restows_the_rat() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.deploy_rat();
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 10);
+
+        let mut state = SimulatorReadState { on_ground: true, ..SimulatorReadState::default() };
+        state.hydraulic.blue_ground_cart_pressure = Pressure::new::<psi>(3000.);
+        state.hydraulic.rat_stow_requested = true;
+        hydraulic.logic.read(&state);
+        hydraulic.read(&state);
+
+        assert!(!hydraulic.is_rat_deployed());
+    }
+
+    #[test]
+    fn accelerated_clock_fast_forwards_through_cargo_door_delays() {
+        let mut hydraulic = A320Hydraulic::new();
+        let mut clock = FixedStepClock::accelerated(Duration::from_millis(50), 20);
+        let context = context_with().build();
+        hydraulic.logic.set_cargo_door_operated(true);
+        hydraulic.run_with_clock(&mut clock, &context, Duration::from_secs(10), &Engine::new(1), &Engine::new(2));
+
+        assert!(hydraulic.logic.ptu_is_inhibited());
+        assert!(hydraulic.is_yellow_pressurised());
+
+        hydraulic.logic.set_cargo_door_operated(false);
+        hydraulic.run_with_clock(&mut clock, &context, Duration::from_secs(45), &Engine::new(1), &Engine::new(2));
+
+        assert_eq!(clock.elapsed(), Duration::from_secs(55));
+        assert!(!hydraulic.logic.ptu_is_inhibited());
+        assert_eq!(hydraulic.yellow_electric_pump_power(), Power::new::<watt>(0.));
+    }
+
+    #[test]
+    fn same_clock_steps_give_the_same_results() {
+        let run_with_fixed_clock = || {
+            let mut hydraulic = A320Hydraulic::new();
+            let mut clock = FixedStepClock::new(Duration::from_millis(33));
+            hydraulic.run_with_clock(&mut clock, &context_with().build(), Duration::from_secs(5), &running_engine(1), &Engine::new(2));
+
+            hydraulic.loop_pressure(LoopId::GREEN)
+        };
+
+        assert_eq!(run_with_fixed_clock(), run_with_fixed_clock());
+    }
+
+    //Holds the left aileron down with a load its actuators can't overcome
+    struct StalledAileronLoads {
+        rates: Rc<RefCell<Vec<(ActuatorId, AngularVelocity)>>>,
+    }
+    impl ExternalLoads for StalledAileronLoads {
+        fn load(&self, actuator: ActuatorId, _: Angle) -> Option<Force> {
+            if actuator == ActuatorId::new(ActuatorType::Aileron, 1, 1) {
+                Some(Force::new::<newton>(-1e6))
+            } else {
+                None
+            }
+        }
+
+        fn set_achieved_rate(&mut self, actuator: ActuatorId, rate: AngularVelocity) {
+            self.rates.borrow_mut().push((actuator, rate));
+        }
+    }
+
+    #[test]
+    fn flight_control_surfaces_move_against_external_loads() {
+        let mut hydraulic = A320Hydraulic::new();
+        let rates = Rc::new(RefCell::new(Vec::new()));
+        hydraulic.attach_external_loads(Box::new(StalledAileronLoads { rates: rates.clone() }));
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 5);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        let positions = hydraulic.aileron_positions();
+        assert!(positions[0] > Angle::new::<degree>(20.));
+        assert!(positions[1].abs() < Angle::new::<degree>(0.1));
+
+        let rates = rates.borrow();
+        assert!(rates.iter().any(|(actuator, rate)| {
+            *actuator == ActuatorId::new(ActuatorType::Aileron, 2, 1) && rate.get::<revolution_per_minute>() < 0.
+        }));
+        assert!(rates.iter().any(|(actuator, _)| *actuator == ActuatorId::new(ActuatorType::Spoiler, 2, 5)));
+    }
+
+    #[test]
+    fn zero_and_microsecond_frames_keep_loops_steady() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+        let colors = [LoopId::GREEN, LoopId::BLUE, LoopId::YELLOW];
+        let pressures: Vec<Pressure> = colors.iter().map(|color| hydraulic.loop_pressure(*color)).collect();
+
+        for _ in 0..100 {
+            hydraulic.update(&context_with().delta(Duration::from_secs(0)).build(), &running_engine(1), &running_engine(2));
+        }
+        for (color, pressure) in colors.iter().zip(pressures.iter()) {
+            assert_eq!(hydraulic.loop_pressure(*color), *pressure);
+        }
+
+        for _ in 0..10000 {
+            hydraulic.update(&context_with().delta(Duration::from_micros(1)).build(), &running_engine(1), &running_engine(2));
+        }
+        for (color, pressure) in colors.iter().zip(pressures.iter()) {
+            assert!(hydraulic.loop_pressure(*color).get::<psi>().is_finite());
+            assert!((hydraulic.loop_pressure(*color) - *pressure).abs() < Pressure::new::<psi>(50.));
+        }
+    }
+
+    #[test]
+    fn frames_only_catch_up_their_own_time() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 3);
+
+        // What lagged behind stays below one fixed step, whatever the time already simulated.
+        let steps = hydraulic.steps_to_catch_up(Duration::from_millis(33));
+        assert!(steps >= 0.33 && steps < 1.33);
+    }
+
+    fn running_engine(number: usize) -> Engine {
+        let mut engine = Engine::new(number);
+        engine.n2 = Ratio::new::<percent>(80.);
+
+        engine
+    }
+
+    fn starting_engine(number: usize) -> Engine {
+        let mut engine = Engine::new(number);
+        engine.master_on = true;
+
+        engine
+    }
+
+    fn released_engine_1_fire_button() -> A320EngineFireOverheadPanel {
+        let mut overhead = A320EngineFireOverheadPanel::new();
+        let mut state = SimulatorReadState::default();
+        state.fire.engine_fire_button_released = [true, false];
+        overhead.read(&state);
+
+        overhead
+    }
+
+    struct TestElectrical {
+        emergency_elec: bool,
+    }
+    impl EmergencyElectricalState for TestElectrical {
+        fn is_in_emergency_elec(&self) -> bool {
+            self.emergency_elec
+        }
+    }
+
+    #[test]
+    fn gears_retract_and_uplock_with_the_lever_up() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+        select_gear_lever_up(&mut hydraulic, true);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+
+        for number in 1..=3 {
+            assert!(hydraulic.landing_gear(number).is_uplocked());
+            assert!(!hydraulic.landing_gear(number).is_downlocked());
+        }
+    }
+
+    #[test]
+    fn gears_stay_down_and_locked_without_green_pressure() {
+        let mut hydraulic = A320Hydraulic::new();
+        select_gear_lever_up(&mut hydraulic, true);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 20);
+
+        for number in 1..=3 {
+            assert!(hydraulic.landing_gear(number).is_downlocked());
+        }
+    }
+
+    #[test]
+    fn gear_lock_states_are_written_to_the_simulator() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+        select_gear_lever_up(&mut hydraulic, true);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+
+        let mut state = SimulatorWriteState::default();
+        hydraulic.write(&mut state);
+
+        assert_eq!(state.hydraulic.gear_uplocked, [true; 3]);
+        assert_eq!(state.hydraulic.gear_downlocked, [false; 3]);
+    }
+
+    #[test]
+    fn gear_position_sensors_follow_the_gears() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+        assert_eq!(hydraulic.gear_position_sensors(1).position(), Some(SensedPosition::Downlocked));
+
+        select_gear_lever_up(&mut hydraulic, true);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+
+        for number in 1..=3 {
+            assert_eq!(hydraulic.gear_position_sensors(number).position(), Some(SensedPosition::Uplocked));
+        }
+    }
+
+    #[test]
+    fn failed_gear_position_sensor_is_written_as_a_disagreement() {
+        let mut hydraulic = A320Hydraulic::new();
+        hydraulic.fail_gear_position_sensor(2, 1, SensorLocation::Down, false);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 1);
+
+        let mut state = SimulatorWriteState::default();
+        hydraulic.write(&mut state);
+
+        assert_eq!(state.hydraulic.gear_position_disagree, [false, true, false]);
+    }
+
+    #[test]
+    fn brake_pedals_apply_the_normal_brakes_with_bscu() {
+        let mut hydraulic = A320Hydraulic::new();
+        power_bscu(&mut hydraulic);
+        press_brake_pedals(&mut hydraulic, 100., 0.);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert!(hydraulic.normal_brakes.left_brake_pressure() > Pressure::new::<psi>(2500.));
+        assert_eq!(hydraulic.normal_brakes.right_brake_pressure(), Pressure::new::<psi>(0.));
+        assert_eq!(hydraulic.alternate_brakes.left_brake_pressure(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn brake_pedals_meter_the_alternate_brakes_without_bscu() {
+        let mut hydraulic = A320Hydraulic::new();
+        press_brake_pedals(&mut hydraulic, 100., 100.);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+
+        assert_eq!(hydraulic.normal_brakes.left_brake_pressure(), Pressure::new::<psi>(0.));
+        //Without BSCU there is no anti-skid either, the metered pressure is limited
+        assert_eq!(hydraulic.braking_mode(), BrakingMode::AlternateWithoutAntiSkid);
+        assert_eq!(hydraulic.alternate_brakes.left_brake_pressure(), Pressure::new::<psi>(1000.));
+        assert_eq!(hydraulic.alternate_brakes.right_brake_pressure(), Pressure::new::<psi>(1000.));
+    }
+
+    #[test]
+    fn braking_reverts_to_the_accumulator_once_yellow_is_lost() {
+        let mut hydraulic = A320Hydraulic::new();
+        power_bscu(&mut hydraulic);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+        assert_eq!(hydraulic.braking_mode(), BrakingMode::Normal);
+
+        hydraulic.isolate_loop(LoopId::GREEN);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 60);
+        assert_eq!(hydraulic.braking_mode(), BrakingMode::AlternateWithAntiSkid);
+
+        hydraulic.isolate_loop(LoopId::YELLOW);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 60);
+        assert_eq!(hydraulic.braking_mode(), BrakingMode::AccumulatorOnly);
+        assert_eq!(hydraulic.brake_accumulator_residual_applications(), 7);
+    }
+
+    #[test]
+    fn accumulator_braking_is_limited_and_uses_up_applications() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 10);
+        hydraulic.isolate_loop(LoopId::YELLOW);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 60);
+        let applications = hydraulic.brake_accumulator_residual_applications();
+
+        press_brake_pedals(&mut hydraulic, 100., 100.);
+        run(&mut hydraulic, &Engine::new(1), &Engine::new(2), 1);
+
+        assert_eq!(hydraulic.alternate_brakes.left_brake_pressure(), Pressure::new::<psi>(1000.));
+        assert_eq!(hydraulic.brake_accumulator_residual_applications(), applications - 1);
+    }
+
+    #[test]
+    fn configured_gauge_needle_time_constant_slows_the_triple_indicator() {
+        let config = HydraulicConfig::from_toml("[filter_time_constants]\ngauge_needle = 60.0").unwrap();
+        let mut hydraulic = A320Hydraulic::from_config(&config);
+        press_brake_pedals(&mut hydraulic, 100., 0.);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+
+        assert!(hydraulic.triple_indicator().left_brake_pressure() < Pressure::new::<psi>(500.));
+    }
+
+    #[test]
+    fn triple_indicator_shows_accumulator_and_alternate_brake_pressures() {
+        let mut hydraulic = A320Hydraulic::new();
+        press_brake_pedals(&mut hydraulic, 100., 0.);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+
+        let indicator = hydraulic.triple_indicator();
+        let accumulator_error = indicator.accumulator_pressure() - hydraulic.brake_accumulator_pressure();
+        assert!(accumulator_error.get::<psi>().abs() < 10.);
+        assert!((indicator.left_brake_pressure().get::<psi>() - 1000.).abs() < 10.);
+        assert_eq!(indicator.right_brake_pressure(), Pressure::new::<psi>(0.));
+
+        let mut state = SimulatorWriteState::default();
+        hydraulic.write(&mut state);
+        assert_eq!(state.hydraulic.triple_indicator_left_brake_pressure, indicator.left_brake_pressure());
+    }
+
+    #[test]
+    fn triple_indicator_needles_lag_behind_the_brakes() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+        press_brake_pedals(&mut hydraulic, 100., 100.);
+        for _ in 0..6 {
+            hydraulic.update(
+                &context_with().delta(Duration::from_millis(33)).build(),
+                &running_engine(1),
+                &running_engine(2),
+            );
+        }
+
+        assert!(hydraulic.triple_indicator().left_brake_pressure() < hydraulic.alternate_brakes.left_brake_pressure());
+    }
+
+    fn press_brake_pedals(hydraulic: &mut A320Hydraulic, left_percent: f64, right_percent: f64) {
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.left_brake_pedal_position = Ratio::new::<percent>(left_percent);
+        state.hydraulic.right_brake_pedal_position = Ratio::new::<percent>(right_percent);
+        hydraulic.brake_pedals.read(&state);
+    }
+
+    #[test]
+    fn all_spoilers_are_available_with_all_loops_pressurised() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+
+        assert_eq!(hydraulic.spoiler_availability(), 0b11111_11111);
+    }
+
+    #[test]
+    fn spoilers_of_a_lost_loop_are_unavailable() {
+        let mut hydraulic = A320Hydraulic::new();
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 20);
+        hydraulic.isolate_loop(LoopId::YELLOW);
+        run(&mut hydraulic, &running_engine(1), &running_engine(2), 60);
+
+        //Spoilers 2 and 4 are supplied by the yellow loop
+        assert_eq!(hydraulic.spoiler_availability(), 0b10101_10101);
+
+        let mut state = SimulatorWriteState::default();
+        hydraulic.write(&mut state);
+        assert_eq!(state.hydraulic.spoiler_availability, 0b10101_10101);
+    }
+
+    fn select_gear_lever_up(hydraulic: &mut A320Hydraulic, up: bool) {
+        let mut state = SimulatorReadState::default();
+        state.landing_gear.gear_lever_up = up;
+        hydraulic.read(&state);
+    }
+
+    //Records the load the electrical consumers put on a power source
+    struct GeneratorLoad {
+        source: ElectricPowerSource,
+        load: Power,
+    }
+    impl GeneratorLoad {
+        fn new(source: ElectricPowerSource) -> Self {
+            GeneratorLoad { source, load: Power::new::<watt>(0.) }
+        }
+    }
+    impl SimulatorElementVisitable for GeneratorLoad {
+        fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+            visitor.visit(&mut Box::new(self));
+        }
+    }
+    impl SimulatorElement for GeneratorLoad {
+        fn write_power_consumption(&mut self, state: &PowerConsumptionState) {
+            self.load = state.get_total_consumption_for(&self.source);
+        }
+    }
+
+    fn power_bscu(hydraulic: &mut A320Hydraulic) {
+        let mut dc_ess_bus = ElectricalBus::new(ElectricalBusType::DirectCurrentEssential);
+        dc_ess_bus.set_input(Current::some(ElectricPowerSource::Batteries));
+        let mut supply = PowerSupply::new();
+        supply.add(&dc_ess_bus);
+
+        PowerConsumptionHandler::new(&supply).supply_power_to_elements(&mut Box::new(hydraulic));
+    }
+
+    fn run(hydraulic: &mut A320Hydraulic, engine1: &Engine, engine2: &Engine, seconds: u64) {
+        // Frames deliberately don't align with the fixed hydraulic time step.
+        for _ in 0..seconds * 30 {
+            hydraulic.update(
+                &context_with().delta(Duration::from_millis(33)).build(),
+                engine1,
+                engine2,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod a320_hydraulic_logic_tests {
+    use super::*;
+    use crate::simulator::test_helpers::context_with;
+
+    #[test]
+    fn ptu_is_not_inhibited_when_cargo_door_never_operated() {
+        let mut logic = A320HydraulicLogic::new();
+        update(&mut logic, Duration::from_secs(1));
+
+        assert!(!logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_is_inhibited_while_cargo_door_operates() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.set_cargo_door_operated(true);
+        update(&mut logic, Duration::from_secs(1));
+
+        assert!(logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_stays_inhibited_less_than_40_seconds_after_cargo_door_operation() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.set_cargo_door_operated(true);
+        update(&mut logic, Duration::from_secs(1));
+
+        logic.set_cargo_door_operated(false);
+        update(&mut logic, Duration::from_secs(1));
+        update(&mut logic, Duration::from_secs(39));
+
+        assert!(logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_is_no_longer_inhibited_40_seconds_after_cargo_door_operation() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.set_cargo_door_operated(true);
+        update(&mut logic, Duration::from_secs(1));
+
+        logic.set_cargo_door_operated(false);
+        update(&mut logic, Duration::from_secs(1));
+        update(&mut logic, Duration::from_secs(40));
+
+        assert!(!logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn reopening_cargo_door_within_40_seconds_restarts_the_inhibit_delay() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.set_cargo_door_operated(true);
+        update(&mut logic, Duration::from_secs(1));
+
+        logic.set_cargo_door_operated(false);
+        update(&mut logic, Duration::from_secs(1));
+        update(&mut logic, Duration::from_secs(30));
+
+        logic.set_cargo_door_operated(true);
+        update(&mut logic, Duration::from_secs(1));
+
+        logic.set_cargo_door_operated(false);
+        update(&mut logic, Duration::from_secs(1));
+        update(&mut logic, Duration::from_secs(30));
+
+        assert!(logic.ptu_is_inhibited());
+
+        update(&mut logic, Duration::from_secs(10));
+
+        assert!(!logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn nws_tow_is_engaged_when_tow_pin_inserted() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.read(&read_state(true, false));
+
+        assert!(logic.nws_tow_engaged());
+    }
+
+    #[test]
+    fn nws_tow_is_engaged_when_pushback_tug_attached() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.read(&read_state(false, true));
+
+        assert!(logic.nws_tow_engaged());
+    }
+
+    #[test]
+    fn nws_tow_is_not_engaged_without_tow_pin_or_tug() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.read(&read_state(false, false));
+
+        assert!(!logic.nws_tow_engaged());
+    }
+
+    #[test]
+    fn ptu_is_inhibited_during_pushback_with_one_engine_running() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.read(&read_state(false, true));
+        update_with_engines(&mut logic, &running_engine(), &stopped_engine());
+
+        assert!(logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_is_not_inhibited_during_pushback_with_both_engines_running() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.read(&read_state(false, true));
+        update_with_engines(&mut logic, &running_engine(), &running_engine());
+
+        assert!(!logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_is_not_inhibited_with_one_engine_running_without_tow_engaged() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.read(&read_state(false, false));
+        update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+
+        assert!(!logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_is_inhibited_with_one_engine_master_on_and_parking_brake_applied() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = read_state(false, false);
+        state.hydraulic.parking_brake_applied = true;
+        logic.read(&state);
+        update_with_engines(&mut logic, &stopped_engine(), &starting_engine());
+
+        assert!(logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_is_not_inhibited_with_one_engine_master_on_and_parking_brake_released() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.read(&read_state(false, false));
+        update_with_engines(&mut logic, &stopped_engine(), &starting_engine());
+
+        assert!(!logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_is_inhibited_with_one_engine_master_on_while_towed() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.read(&read_state(true, false));
+        update_with_engines(&mut logic, &starting_engine(), &stopped_engine());
+
+        assert!(logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_inhibit_ends_when_second_engine_master_is_set_on() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = read_state(false, false);
+        state.hydraulic.parking_brake_applied = true;
+        logic.read(&state);
+        update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+
+        update_with_engines(&mut logic, &starting_engine(), &running_engine());
+
+        assert!(!logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_self_test_runs_when_first_engine_starts_with_parking_brake_applied() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = read_state(false, false);
+        state.on_ground = true;
+        state.hydraulic.parking_brake_applied = true;
+        logic.read(&state);
+        update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+
+        assert!(logic.is_ptu_self_test_in_progress());
+        assert!(!logic.ptu_is_inhibited());
+
+        update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+        update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+        update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+
+        assert!(!logic.is_ptu_self_test_in_progress());
+        assert!(logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_self_test_runs_once_per_flight() {
+        let mut logic = A320HydraulicLogic::new();
+        for _ in 0..4 {
+            update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+        }
+        update_with_engines(&mut logic, &stopped_engine(), &stopped_engine());
+        update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+
+        assert!(!logic.is_ptu_self_test_in_progress());
+
+        let mut state = read_state(false, false);
+        state.on_ground = false;
+        logic.read(&state);
+        update_with_engines(&mut logic, &running_engine(), &running_engine());
+        state.on_ground = true;
+        logic.read(&state);
+        update_with_engines(&mut logic, &stopped_engine(), &stopped_engine());
+        update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+
+        assert!(logic.is_ptu_self_test_in_progress());
+    }
+
+    #[test]
+    fn ptu_self_test_does_not_run_while_towed() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = read_state(true, false);
+        state.on_ground = true;
+        logic.read(&state);
+        update_with_engines(&mut logic, &running_engine(), &stopped_engine());
+
+        assert!(!logic.is_ptu_self_test_in_progress());
+        assert!(logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn ptu_self_test_does_not_run_in_flight() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = read_state(false, false);
+        state.on_ground = false;
+        logic.read(&state);
+        update_with_engines(&mut logic, &running_engine(), &stopped_engine());
+
+        assert!(!logic.is_ptu_self_test_in_progress());
+    }
+
+    #[test]
+    fn held_cargo_door_selector_inhibits_ptu() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = read_state(false, false);
+        state.hydraulic.cargo_door_selector_operated = [false, true];
+        logic.read(&state);
+        update(&mut logic, Duration::from_millis(300));
+        update(&mut logic, Duration::from_millis(300));
+        update(&mut logic, Duration::from_millis(300));
+
+        assert!(logic.cargo_door_operated());
+        assert!(logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn briefly_touched_cargo_door_selector_is_ignored() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = read_state(false, false);
+        state.hydraulic.cargo_door_selector_operated = [true, false];
+        logic.read(&state);
+        update(&mut logic, Duration::from_millis(100));
+        update(&mut logic, Duration::from_millis(100));
+
+        state.hydraulic.cargo_door_selector_operated = [false, false];
+        logic.read(&state);
+        update(&mut logic, Duration::from_millis(100));
+
+        assert!(!logic.cargo_door_operated());
+        assert!(!logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn parking_brake_applied_is_read_from_simulator() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.parking_brake_applied = true;
+        logic.read(&state);
+
+        assert!(logic.parking_brake_applied());
+    }
+
+    #[test]
+    fn yellow_electric_pump_runs_less_than_20_seconds_after_cargo_door_operation() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.set_cargo_door_operated(true);
+        update(&mut logic, Duration::from_secs(1));
+
+        logic.set_cargo_door_operated(false);
+        update(&mut logic, Duration::from_secs(1));
+        update(&mut logic, Duration::from_secs(19));
+
+        assert!(logic.yellow_electric_pump_auto_run());
+    }
+
+    #[test]
+    fn yellow_electric_pump_stops_20_seconds_after_cargo_door_operation() {
+        let mut logic = A320HydraulicLogic::new();
+        logic.set_cargo_door_operated(true);
+        update(&mut logic, Duration::from_secs(1));
+
+        logic.set_cargo_door_operated(false);
+        update(&mut logic, Duration::from_secs(1));
+        update(&mut logic, Duration::from_secs(20));
+
+        assert!(!logic.yellow_electric_pump_auto_run());
+        assert!(logic.ptu_is_inhibited());
+    }
+
+    #[test]
+    fn blue_electric_pump_does_not_run_with_engines_off() {
+        let mut logic = A320HydraulicLogic::new();
+        update(&mut logic, Duration::from_secs(1));
+
+        assert!(!logic.blue_electric_pump_auto_run());
+    }
+
+    #[test]
+    fn blue_electric_pump_runs_once_an_engine_runs() {
+        let mut logic = A320HydraulicLogic::new();
+        update_with_engines(&mut logic, &stopped_engine(), &running_engine());
+
+        assert!(logic.blue_electric_pump_auto_run());
+    }
+
+    #[test]
+    fn blue_pump_override_runs_the_blue_electric_pump_on_ground() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = SimulatorReadState::default();
+        state.on_ground = true;
+        state.hydraulic.blue_pump_override_pb_on = true;
+        logic.read(&state);
+        update(&mut logic, Duration::from_secs(1));
+
+        assert!(logic.blue_electric_pump_auto_run());
+    }
+
+    #[test]
+    fn blue_pump_override_is_inhibited_in_flight() {
+        let mut logic = A320HydraulicLogic::new();
+        let mut state = SimulatorReadState::default();
+        state.on_ground = false;
+        state.hydraulic.blue_pump_override_pb_on = true;
+        logic.read(&state);
+        update(&mut logic, Duration::from_secs(1));
+
+        assert!(!logic.blue_electric_pump_auto_run());
+    }
+
+    fn update(logic: &mut A320HydraulicLogic, delta: Duration) {
+        logic.update(
+            &context_with().delta(delta).build(),
+            &stopped_engine(),
+            &stopped_engine(),
+        );
+    }
+
+    fn update_with_engines(logic: &mut A320HydraulicLogic, engine1: &Engine, engine2: &Engine) {
+        logic.update(
+            &context_with().delta(Duration::from_secs(1)).build(),
+            engine1,
+            engine2,
+        );
+    }
+
+    fn read_state(tow_pin_inserted: bool, pushback_tug_attached: bool) -> SimulatorReadState {
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.nose_wheel_steering_tow_pin_inserted = tow_pin_inserted;
+        state.hydraulic.pushback_tug_attached = pushback_tug_attached;
+
+        state
+    }
+
+    fn running_engine() -> Engine {
+        let mut engine = Engine::new(1);
+        engine.n2 = Ratio::new::<percent>(80.);
+
+        engine
+    }
+
+    fn starting_engine() -> Engine {
+        let mut engine = Engine::new(1);
+        engine.master_on = true;
+
+        engine
+    }
+
+    fn stopped_engine() -> Engine {
+        Engine::new(1)
+    }
+}
+
+#[cfg(test)]
+mod a320_hydraulic_overhead_panel_tests {
+    use super::*;
+    use crate::simulator::test_helpers::context_with;
+
+    #[test]
+    fn engine_pump_fault_light_is_on_when_engine_is_stopped() {
+        let mut overhead = A320HydraulicOverheadPanel::new();
+        let hydraulic = run(&running_engine(1), &Engine::new(2));
+        overhead.update_after_hydraulic(&hydraulic);
+
+        assert_eq!(overhead.engine_driven_pump_lights(1), PushButtonLights::default());
+        assert_eq!(overhead.engine_driven_pump_lights(2), PushButtonLights { fault: true, off: false });
+    }
+
+    #[test]
+    fn low_pressure_fault_is_inhibited_when_pump_is_off() {
+        let mut overhead = A320HydraulicOverheadPanel::new();
+        overhead.engine_driven_pumps[1].push_off();
+        let hydraulic = run(&running_engine(1), &Engine::new(2));
+        overhead.update_after_hydraulic(&hydraulic);
+
+        assert_eq!(overhead.engine_driven_pump_lights(2), PushButtonLights { fault: false, off: true });
+    }
+
+    #[test]
+    fn reservoir_fault_shows_together_with_off_light() {
+        let mut overhead = A320HydraulicOverheadPanel::new();
+        overhead.engine_driven_pumps[0].push_off();
+        let mut hydraulic = run(&running_engine(1), &running_engine(2));
+        hydraulic.drain_reservoir(LoopId::GREEN, Volume::new::<gallon>(10.));
+        overhead.update_after_hydraulic(&hydraulic);
+
+        assert!(hydraulic.has_reservoir_fault(LoopId::GREEN));
+        assert_eq!(overhead.engine_driven_pump_lights(1), PushButtonLights { fault: true, off: true });
+        assert!(overhead.ptu_lights().fault);
+    }
+
+    #[test]
+    fn light_test_turns_all_lights_on() {
+        let mut overhead = A320HydraulicOverheadPanel::new();
+        let mut state = SimulatorReadState::default();
+        state.annunciator_light_test = true;
+        state.hydraulic.engine_pump_pb_auto = [true, true];
+        state.hydraulic.blue_electric_pump_pb_auto = true;
+        state.hydraulic.ptu_pb_auto = true;
+        overhead.read(&state);
+        overhead.update_after_hydraulic(&run(&running_engine(1), &running_engine(2)));
+
+        let all_on = PushButtonLights { fault: true, off: true };
+        assert_eq!(overhead.engine_driven_pump_lights(1), all_on);
+        assert_eq!(overhead.engine_driven_pump_lights(2), all_on);
+        assert_eq!(overhead.blue_electric_pump_lights(), all_on);
+        assert_eq!(overhead.ptu_lights(), all_on);
+    }
+
+    #[test]
+    fn push_button_states_are_read_from_simulator() {
+        let mut overhead = A320HydraulicOverheadPanel::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.engine_pump_pb_auto = [true, false];
+        state.hydraulic.ptu_pb_auto = true;
+        overhead.read(&state);
+
+        assert!(overhead.engine_driven_pump_push_button_is_auto(1));
+        assert!(!overhead.engine_driven_pump_push_button_is_auto(2));
+        assert!(!overhead.blue_electric_pump_push_button_is_auto());
+        assert!(overhead.ptu_push_button_is_auto());
+    }
+
+    fn running_engine(number: usize) -> Engine {
+        let mut engine = Engine::new(number);
+        engine.n2 = Ratio::new::<percent>(80.);
+
+        engine
+    }
+
+    fn run(engine1: &Engine, engine2: &Engine) -> A320Hydraulic {
+        let mut hydraulic = A320Hydraulic::new();
+        for _ in 0..100 {
+            hydraulic.update(
+                &context_with().delta(Duration::from_millis(100)).build(),
+                engine1,
+                engine2,
+            );
+        }
+
+        hydraulic
     }
 }
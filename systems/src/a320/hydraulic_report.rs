@@ -0,0 +1,161 @@
+use crate::hydraulic::{LoopId, PtuState};
+use serde::Serialize;
+use std::fmt;
+
+/// Machine readable dump of the hydraulic system state, to attach to issue reports.
+/// Values are in psi, gallons, gallons per second, watts and degrees Celsius.
+#[derive(Debug, Serialize)]
+pub struct HydraulicStateReport {
+    pub time_elapsed_seconds: f64,
+    pub loops: Vec<LoopReport>,
+    pub engine_driven_pumps: Vec<EngineDrivenPumpReport>,
+    pub electric_pumps: Vec<ElectricPumpReport>,
+    pub ptu: Option<PtuReport>,
+    pub ram_air_turbine: RamAirTurbineReport,
+    pub valves: ValvesReport,
+    pub brakes: BrakesReport,
+    pub logic: LogicReport,
+}
+impl HydraulicStateReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("A hydraulic state report is always valid JSON.")
+    }
+}
+/// Human readable dump of the report, one line per component, for the logs.
+impl fmt::Display for HydraulicStateReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hydraulic state at {:.1} s", self.time_elapsed_seconds)?;
+        for hyd_loop in self.loops.iter() {
+            write!(
+                f,
+                "\n{} loop: {:.0} psi, loop {:.3} gal, reservoir {:.3} gal at {:.1} psi, accumulator {:.3} gal, isolated {}",
+                hyd_loop.color,
+                hyd_loop.pressure_psi,
+                hyd_loop.loop_volume_gallon,
+                hyd_loop.reservoir_volume_gallon,
+                hyd_loop.reservoir_air_pressure_psi,
+                hyd_loop.accumulator_fluid_volume_gallon,
+                hyd_loop.isolated,
+            )?;
+        }
+        for pump in self.engine_driven_pumps.iter() {
+            write!(
+                f,
+                "\nEDP{} ({} loop): {:.0} W, fire shutoff valve open {}",
+                pump.engine_number,
+                pump.loop_color,
+                pump.shaft_power_watt,
+                pump.fire_shutoff_valve_open,
+            )?;
+        }
+        for pump in self.electric_pumps.iter() {
+            write!(
+                f,
+                "\n{} electric pump: {:.0} W",
+                pump.loop_color, pump.electrical_power_watt,
+            )?;
+        }
+        write!(
+            f,
+            "\nReservoir bleed air {}",
+            self.valves.reservoir_bleed_air_available
+        )?;
+        if let Some(ptu) = &self.ptu {
+            write!(
+                f,
+                "\nPTU: {:?}, {:.3} gal/s",
+                ptu.state, ptu.flow_gallon_per_second,
+            )?;
+        }
+        write!(
+            f,
+            "\nRAT: deployed {}, progress {:.0} %",
+            self.ram_air_turbine.deployed, self.ram_air_turbine.deployment_progress_percent,
+        )?;
+        write!(
+            f,
+            "\nBrakes: normal {:.0}/{:.0} psi, alternate {:.0}/{:.0} psi, accumulator {:.0} psi, temperatures {:?} C",
+            self.brakes.left_normal_pressure_psi,
+            self.brakes.right_normal_pressure_psi,
+            self.brakes.left_alternate_pressure_psi,
+            self.brakes.right_alternate_pressure_psi,
+            self.brakes.accumulator_pressure_psi,
+            self.brakes.temperatures_celsius.iter().map(|temperature| temperature.round()).collect::<Vec<f64>>(),
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoopReport {
+    pub color: LoopId,
+    pub pressure_psi: f64,
+    pub loop_volume_gallon: f64,
+    pub reservoir_volume_gallon: f64,
+    pub reservoir_air_pressure_psi: f64,
+    pub accumulator_fluid_volume_gallon: f64,
+    pub isolated: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EngineDrivenPumpReport {
+    pub engine_number: usize,
+    pub loop_color: LoopId,
+    pub fire_shutoff_valve_open: bool,
+    pub outlet_check_valve_open: bool,
+    pub shaft_power_watt: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ElectricPumpReport {
+    pub loop_color: LoopId,
+    pub outlet_check_valve_open: bool,
+    pub electrical_power_watt: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PtuReport {
+    pub left_loop: LoopId,
+    pub right_loop: LoopId,
+    pub state: PtuState,
+    pub flow_gallon_per_second: f64,
+    pub shaft_speed_rpm: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RamAirTurbineReport {
+    pub deployed: bool,
+    pub in_transit: bool,
+    pub deployment_progress_percent: f64,
+    pub turbine_speed_rpm: f64,
+    pub stall_speed_knots: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValvesReport {
+    pub engine_bleed_valve_closed: [bool; 2],
+    pub apu_bleed_air_valve_open: bool,
+    pub reservoir_bleed_air_available: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BrakesReport {
+    pub left_normal_pressure_psi: f64,
+    pub right_normal_pressure_psi: f64,
+    pub left_alternate_pressure_psi: f64,
+    pub right_alternate_pressure_psi: f64,
+    pub accumulator_pressure_psi: f64,
+    pub temperatures_celsius: [f64; 4],
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogicReport {
+    pub on_ground: bool,
+    pub parking_brake_applied: bool,
+    pub nws_tow_engaged: bool,
+    pub cargo_door_operated: bool,
+    pub cargo_door_ptu_inhibit: bool,
+    pub single_engine_started: bool,
+    pub ptu_inhibited: bool,
+    pub ptu_self_test_in_progress: bool,
+    pub yellow_electric_pump_auto_run: bool,
+}
@@ -0,0 +1,218 @@
+use super::A320Hydraulic;
+use crate::{
+    hydraulic::LoopId,
+    simulator::{
+        SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorReadState,
+        SimulatorWriteState,
+    },
+};
+
+/// A hydraulic ECAM caution detected by the flight warning computer (FWC).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HydraulicCaution {
+    GreenSystemLowPressure,
+    BlueSystemLowPressure,
+    YellowSystemLowPressure,
+    Engine1PumpLowPressure,
+    Engine2PumpLowPressure,
+    BlueElectricPumpLowPressure,
+    Engine1PumpOverheat,
+    Engine2PumpOverheat,
+}
+impl HydraulicCaution {
+    const ALL: [HydraulicCaution; 8] = [
+        HydraulicCaution::GreenSystemLowPressure,
+        HydraulicCaution::BlueSystemLowPressure,
+        HydraulicCaution::YellowSystemLowPressure,
+        HydraulicCaution::Engine1PumpLowPressure,
+        HydraulicCaution::Engine2PumpLowPressure,
+        HydraulicCaution::BlueElectricPumpLowPressure,
+        HydraulicCaution::Engine1PumpOverheat,
+        HydraulicCaution::Engine2PumpOverheat,
+    ];
+
+    fn is_detected(self, hydraulic: &A320Hydraulic) -> bool {
+        match self {
+            HydraulicCaution::GreenSystemLowPressure => !hydraulic.is_green_pressurised(),
+            HydraulicCaution::BlueSystemLowPressure => !hydraulic.is_blue_pressurised(),
+            HydraulicCaution::YellowSystemLowPressure => !hydraulic.is_yellow_pressurised(),
+            HydraulicCaution::Engine1PumpLowPressure => {
+                hydraulic.engine_driven_pump_has_low_pressure(1)
+            }
+            HydraulicCaution::Engine2PumpLowPressure => {
+                hydraulic.engine_driven_pump_has_low_pressure(2)
+            }
+            HydraulicCaution::BlueElectricPumpLowPressure => {
+                hydraulic.electric_pump_has_low_pressure(LoopId::BLUE)
+            }
+            HydraulicCaution::Engine1PumpOverheat => hydraulic.engine_driven_pump_is_overheated(1),
+            HydraulicCaution::Engine2PumpOverheat => hydraulic.engine_driven_pump_is_overheated(2),
+        }
+    }
+}
+
+/// The hydraulic cautions as shown on the ECAM.
+///
+/// The FWC flight phase goes from 1 (electrical power) to 10 (five minutes after
+/// the second engine shutdown). Hydraulic cautions are inhibited with the engines
+/// stopped on the ground (phases 1 and 10) and during the takeoff and landing
+/// phases (3 to 5, 7 and 8), where they would only distract the crew. A caution
+/// detected while inhibited is shown once the flight phase allows it.
+pub struct A320HydraulicWarnings {
+    flight_phase: u8,
+    detected: Vec<HydraulicCaution>,
+}
+impl A320HydraulicWarnings {
+    const INHIBITED_FLIGHT_PHASES: [u8; 7] = [1, 3, 4, 5, 7, 8, 10];
+
+    pub fn new() -> Self {
+        A320HydraulicWarnings {
+            flight_phase: 0,
+            detected: Vec::new(),
+        }
+    }
+
+    pub fn update_after_hydraulic(&mut self, hydraulic: &A320Hydraulic) {
+        self.detected = HydraulicCaution::ALL
+            .iter()
+            .copied()
+            .filter(|caution| caution.is_detected(hydraulic))
+            .collect();
+    }
+
+    pub fn set_flight_phase(&mut self, flight_phase: u8) {
+        self.flight_phase = flight_phase;
+    }
+
+    pub fn flight_phase(&self) -> u8 {
+        self.flight_phase
+    }
+
+    /// The caution condition is present, whether or not it is shown.
+    pub fn is_detected(&self, caution: HydraulicCaution) -> bool {
+        self.detected.contains(&caution)
+    }
+
+    pub fn is_inhibited(&self) -> bool {
+        A320HydraulicWarnings::INHIBITED_FLIGHT_PHASES.contains(&self.flight_phase)
+    }
+
+    pub fn is_shown(&self, caution: HydraulicCaution) -> bool {
+        !self.is_inhibited() && self.is_detected(caution)
+    }
+
+    pub fn shown_cautions(&self) -> Vec<HydraulicCaution> {
+        if self.is_inhibited() {
+            Vec::new()
+        } else {
+            self.detected.clone()
+        }
+    }
+}
+impl Default for A320HydraulicWarnings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl SimulatorElementVisitable for A320HydraulicWarnings {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for A320HydraulicWarnings {
+    fn read(&mut self, state: &SimulatorReadState) {
+        self.flight_phase = state.flight_phase;
+    }
+
+    fn write(&self, state: &mut SimulatorWriteState) {
+        state.hydraulic.system_low_pressure_caution = [
+            self.is_shown(HydraulicCaution::GreenSystemLowPressure),
+            self.is_shown(HydraulicCaution::BlueSystemLowPressure),
+            self.is_shown(HydraulicCaution::YellowSystemLowPressure),
+        ];
+        state.hydraulic.engine_pump_low_pressure_caution = [
+            self.is_shown(HydraulicCaution::Engine1PumpLowPressure),
+            self.is_shown(HydraulicCaution::Engine2PumpLowPressure),
+        ];
+        state.hydraulic.blue_electric_pump_low_pressure_caution =
+            self.is_shown(HydraulicCaution::BlueElectricPumpLowPressure);
+        state.hydraulic.engine_pump_overheat_caution = [
+            self.is_shown(HydraulicCaution::Engine1PumpOverheat),
+            self.is_shown(HydraulicCaution::Engine2PumpOverheat),
+        ];
+    }
+}
+
+#[cfg(test)]
+mod a320_hydraulic_warnings_tests {
+    use super::*;
+    use crate::{engine::Engine, simulator::test_helpers::context_with};
+    use std::time::Duration;
+    use uom::si::{f64::*, ratio::percent};
+
+    #[test]
+    fn low_pressure_is_detected_with_engines_stopped() {
+        let warnings = warnings_after(&Engine::new(1), &Engine::new(2), 5);
+
+        assert!(warnings.is_detected(HydraulicCaution::GreenSystemLowPressure));
+        assert!(warnings.is_detected(HydraulicCaution::Engine1PumpLowPressure));
+    }
+
+    #[test]
+    fn no_caution_is_shown_in_electrical_power_phase() {
+        let mut warnings = warnings_after(&Engine::new(1), &Engine::new(2), 5);
+        warnings.set_flight_phase(1);
+
+        assert!(warnings.is_inhibited());
+        assert!(!warnings.is_shown(HydraulicCaution::GreenSystemLowPressure));
+        assert!(warnings.shown_cautions().is_empty());
+    }
+
+    #[test]
+    fn no_caution_is_shown_during_takeoff() {
+        let mut warnings = warnings_after(&running_engine(1), &Engine::new(2), 10);
+        for flight_phase in 3..=5 {
+            warnings.set_flight_phase(flight_phase);
+
+            assert!(warnings.is_detected(HydraulicCaution::Engine2PumpLowPressure));
+            assert!(!warnings.is_shown(HydraulicCaution::Engine2PumpLowPressure));
+        }
+    }
+
+    #[test]
+    fn inhibited_caution_is_shown_after_takeoff() {
+        let mut warnings = warnings_after(&running_engine(1), &Engine::new(2), 10);
+        warnings.set_flight_phase(6);
+
+        assert!(warnings.is_shown(HydraulicCaution::Engine2PumpLowPressure));
+        assert!(!warnings.is_shown(HydraulicCaution::Engine1PumpLowPressure));
+    }
+
+    #[test]
+    fn no_caution_is_detected_with_engines_running() {
+        let mut warnings = warnings_after(&running_engine(1), &running_engine(2), 10);
+        warnings.set_flight_phase(6);
+
+        assert!(warnings.shown_cautions().is_empty());
+    }
+
+    fn warnings_after(engine1: &Engine, engine2: &Engine, seconds: u64) -> A320HydraulicWarnings {
+        let mut hydraulic = A320Hydraulic::new();
+        let context = context_with().delta(Duration::from_millis(100)).build();
+        for _ in 0..seconds * 10 {
+            hydraulic.update(&context, engine1, engine2);
+        }
+
+        let mut warnings = A320HydraulicWarnings::new();
+        warnings.update_after_hydraulic(&hydraulic);
+
+        warnings
+    }
+
+    fn running_engine(number: usize) -> Engine {
+        let mut engine = Engine::new(number);
+        engine.n2 = Ratio::new::<percent>(80.);
+
+        engine
+    }
+}
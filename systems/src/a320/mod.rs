@@ -5,6 +5,7 @@ use crate::{
     },
     electrical::{ElectricalBusStateFactory, ExternalPowerSource, PowerConsumptionHandler},
     engine::Engine,
+    hydraulic::{HydraulicConfig, RatStowError},
     simulator::{
         Aircraft, SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor,
         UpdateContext,
@@ -15,9 +16,18 @@ use uom::si::f64::*;
 mod electrical;
 pub use electrical::*;
 
+mod fire;
+pub use fire::*;
+
 mod hydraulic;
 pub use hydraulic::*;
 
+mod hydraulic_report;
+pub use hydraulic_report::*;
+
+mod hydraulic_warnings;
+pub use hydraulic_warnings::*;
+
 mod fuel;
 
 mod pneumatic;
@@ -28,27 +38,65 @@ pub struct A320 {
     apu_overhead: AuxiliaryPowerUnitOverheadPanel,
     pneumatic_overhead: A320PneumaticOverheadPanel,
     electrical_overhead: A320ElectricalOverheadPanel,
+    engine_fire_overhead: A320EngineFireOverheadPanel,
     fuel: A320Fuel,
     engine_1: Engine,
     engine_2: Engine,
     electrical: A320Electrical,
     ext_pwr: ExternalPowerSource,
     hydraulic: A320Hydraulic,
+    hydraulic_overhead: A320HydraulicOverheadPanel,
+    hydraulic_warnings: A320HydraulicWarnings,
 }
 impl A320 {
     pub fn new() -> A320 {
+        A320::new_with_hydraulic(A320Hydraulic::new())
+    }
+
+    /// Creates an A320 whose hydraulic model is tuned by the given config.
+    pub fn new_with_hydraulic_config(config: &HydraulicConfig) -> A320 {
+        A320::new_with_hydraulic(A320Hydraulic::from_config(config))
+    }
+
+    /// Lets hydraulic constants be changed through the given channel while the simulation runs.
+    #[cfg(feature = "dev-tuning")]
+    pub fn attach_hydraulic_tuning(&mut self, receiver: crate::hydraulic::TuningReceiver) {
+        self.hydraulic.attach_tuning(receiver);
+    }
+
+    /// Pushes the released fire button of the given engine back in,
+    /// which opens the fire shutoff valves closed by it.
+    pub fn reset_engine_fire_button(&mut self, engine_number: usize) {
+        self.engine_fire_overhead
+            .reset_engine_fire_button(engine_number);
+    }
+
+    /// Restows the ram air turbine as a maintenance action on the ground.
+    pub fn stow_rat(&mut self) -> Result<(), RatStowError> {
+        self.hydraulic.stow_rat()
+    }
+
+    /// Structured dump of the hydraulic system state, see `HydraulicStateReport::to_json`.
+    pub fn hydraulic_state_report(&self) -> HydraulicStateReport {
+        self.hydraulic.state_report()
+    }
+
+    fn new_with_hydraulic(hydraulic: A320Hydraulic) -> A320 {
         A320 {
             apu: AuxiliaryPowerUnit::new_aps3200(),
             apu_fire_overhead: AuxiliaryPowerUnitFireOverheadPanel::new(),
             apu_overhead: AuxiliaryPowerUnitOverheadPanel::new(),
             pneumatic_overhead: A320PneumaticOverheadPanel::new(),
             electrical_overhead: A320ElectricalOverheadPanel::new(),
+            engine_fire_overhead: A320EngineFireOverheadPanel::new(),
             fuel: A320Fuel::new(),
             engine_1: Engine::new(1),
             engine_2: Engine::new(2),
             electrical: A320Electrical::new(),
             ext_pwr: ExternalPowerSource::new(),
-            hydraulic: A320Hydraulic::new(),
+            hydraulic,
+            hydraulic_overhead: A320HydraulicOverheadPanel::new(),
+            hydraulic_warnings: A320HydraulicWarnings::new(),
         }
     }
 }
@@ -87,11 +135,23 @@ impl Aircraft for A320 {
             &self.electrical_overhead,
         );
 
+        self.hydraulic.update_after_electrical(&self.electrical);
+        self.engine_1
+            .update_after_engine_fire_overhead(&self.engine_fire_overhead);
+        self.engine_2
+            .update_after_engine_fire_overhead(&self.engine_fire_overhead);
+        self.hydraulic.update_after_apu(&self.apu);
+        self.hydraulic
+            .update_after_hydraulic_overhead(&self.hydraulic_overhead);
         self.hydraulic.update(
             context,
             &self.engine_1,
             &self.engine_2,
         );
+        self.engine_1.update_after_hydraulic(&self.hydraulic);
+        self.engine_2.update_after_hydraulic(&self.hydraulic);
+        self.hydraulic_overhead.update_after_hydraulic(&self.hydraulic);
+        self.hydraulic_warnings.update_after_hydraulic(&self.hydraulic);
 
         let power_supply = self.electrical.create_power_supply();
         let mut power_consumption_handler = PowerConsumptionHandler::new(&power_supply);
@@ -109,12 +169,16 @@ impl SimulatorElementVisitable for A320 {
         self.apu_fire_overhead.accept(visitor);
         self.apu_overhead.accept(visitor);
         self.electrical_overhead.accept(visitor);
+        self.engine_fire_overhead.accept(visitor);
         self.fuel.accept(visitor);
         self.pneumatic_overhead.accept(visitor);
         self.engine_1.accept(visitor);
         self.engine_2.accept(visitor);
         self.electrical.accept(visitor);
         self.ext_pwr.accept(visitor);
+        self.hydraulic.accept(visitor);
+        self.hydraulic_overhead.accept(visitor);
+        self.hydraulic_warnings.accept(visitor);
         visitor.visit(&mut Box::new(self));
     }
 }
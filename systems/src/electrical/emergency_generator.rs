@@ -1,24 +1,27 @@
 use super::{Current, ElectricPowerSource, ElectricSource, PowerConsumptionState};
-use crate::simulator::{
-    SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorWriteState,
+use crate::{
+    hydraulic::EmergencyGeneratorHydraulicSupply,
+    simulator::{
+        SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorWriteState,
+    },
 };
 use uom::si::{electric_potential::volt, f64::*, frequency::hertz};
 
 pub struct EmergencyGenerator {
     running: bool,
-    is_blue_pressurised: bool,
+    is_hydraulically_driven: bool,
 }
 impl EmergencyGenerator {
     pub fn new() -> EmergencyGenerator {
         EmergencyGenerator {
             running: false,
-            is_blue_pressurised: false,
+            is_hydraulically_driven: false,
         }
     }
 
-    pub fn update(&mut self, is_blue_pressurised: bool) {
-        // TODO: The emergency generator is driven by the blue hydraulic circuit. Still to be implemented.
-        self.is_blue_pressurised = is_blue_pressurised;
+    /// The emergency generator is driven by a hydraulic motor supplied by the blue loop.
+    pub fn update(&mut self, hydraulic_supply: &EmergencyGeneratorHydraulicSupply) {
+        self.is_hydraulically_driven = hydraulic_supply.can_drive_generator();
     }
 
     #[cfg(test)]
@@ -27,7 +30,7 @@ impl EmergencyGenerator {
     }
 
     pub fn is_running(&self) -> bool {
-        self.is_blue_pressurised && self.running
+        self.is_hydraulically_driven && self.running
     }
 }
 impl ElectricSource for EmergencyGenerator {
@@ -83,6 +86,7 @@ impl SimulatorElement for EmergencyGenerator {
 #[cfg(test)]
 mod emergency_generator_tests {
     use super::*;
+    use uom::si::{pressure::psi, velocity::knot};
 
     #[test]
     fn starts_without_output() {
@@ -93,7 +97,7 @@ mod emergency_generator_tests {
     fn when_started_provides_output() {
         let mut emer_gen = emergency_generator();
         emer_gen.attempt_start();
-        emer_gen.update(true);
+        emer_gen.update(&sufficient_supply());
 
         assert!(emer_gen.is_powered());
     }
@@ -102,7 +106,19 @@ mod emergency_generator_tests {
     fn when_started_without_hydraulic_pressure_is_unpowered() {
         let mut emer_gen = emergency_generator();
         emer_gen.attempt_start();
-        emer_gen.update(false);
+        emer_gen.update(&EmergencyGeneratorHydraulicSupply::none());
+
+        assert!(emer_gen.is_unpowered());
+    }
+
+    #[test]
+    fn when_started_with_stalled_ram_air_turbine_is_unpowered() {
+        let mut emer_gen = emergency_generator();
+        emer_gen.attempt_start();
+        emer_gen.update(&EmergencyGeneratorHydraulicSupply::from_rat_driven_blue_loop(
+            Pressure::new::<psi>(3000.),
+            Velocity::new::<knot>(80.),
+        ));
 
         assert!(emer_gen.is_unpowered());
     }
@@ -110,4 +126,11 @@ mod emergency_generator_tests {
     fn emergency_generator() -> EmergencyGenerator {
         EmergencyGenerator::new()
     }
+
+    fn sufficient_supply() -> EmergencyGeneratorHydraulicSupply {
+        EmergencyGeneratorHydraulicSupply::from_rat_driven_blue_loop(
+            Pressure::new::<psi>(3000.),
+            Velocity::new::<knot>(250.),
+        )
+    }
 }
@@ -72,6 +72,13 @@ pub trait ElectricSource {
     }
 }
 
+/// Tells whether the electrical system is in emergency configuration: the main
+/// AC buses are lost and only the emergency generator or the batteries supply
+/// the essential buses. Systems shed their AC powered loads in this configuration.
+pub trait EmergencyElectricalState {
+    fn is_in_emergency_elec(&self) -> bool;
+}
+
 pub trait Powerable {
     /// Provides input power from the given source. When the source has
     /// output, this element is powered by the source. When the source has no
@@ -1,23 +1,84 @@
-use uom::si::{f64::*, ratio::percent};
+use uom::si::{f64::*, power::watt, ratio::percent};
 
 use crate::simulator::{
     SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorReadState,
     UpdateContext,
 };
 
+/// The state of an engine as seen by the systems depending on it.
+/// Systems read this rather than the simulator, so they all agree
+/// on whether an engine is started or running.
+pub trait EngineStateProvider {
+    fn n2(&self) -> Ratio;
+    fn is_master_on(&self) -> bool;
+    fn is_fire_push_button_released(&self) -> bool;
+
+    fn is_running(&self) -> bool {
+        self.n2() > Ratio::new::<percent>(Engine::RUNNING_N2_THRESHOLD)
+    }
+
+    /// An engine is being started from the moment its master switch is set to on.
+    fn is_started(&self) -> bool {
+        self.is_master_on() || self.is_running()
+    }
+}
+
+pub trait EngineFirePushButtons {
+    fn engine_fire_push_button_is_released(&self, engine_number: usize) -> bool;
+}
+
+pub trait EngineDrivenPumpLoads {
+    /// Mechanical power the engine driven pumps draw from the given engine's gearbox.
+    fn engine_driven_pump_shaft_power(&self, engine_number: usize) -> Power;
+}
+
 pub struct Engine {
     number: usize,
     pub n2: Ratio,
+    pub master_on: bool,
+    fire_push_button_released: bool,
+    hydraulic_pump_load: Power,
 }
 impl Engine {
+    const RUNNING_N2_THRESHOLD: f64 = 50.;
+
     pub fn new(number: usize) -> Engine {
         Engine {
             number,
             n2: Ratio::new::<percent>(0.),
+            master_on: false,
+            fire_push_button_released: false,
+            hydraulic_pump_load: Power::new::<watt>(0.),
         }
     }
 
     pub fn update(&mut self, _: &UpdateContext) {}
+
+    pub fn update_after_engine_fire_overhead<T: EngineFirePushButtons>(&mut self, overhead: &T) {
+        self.fire_push_button_released = overhead.engine_fire_push_button_is_released(self.number);
+    }
+
+    pub fn update_after_hydraulic<T: EngineDrivenPumpLoads>(&mut self, hydraulic: &T) {
+        self.hydraulic_pump_load = hydraulic.engine_driven_pump_shaft_power(self.number);
+    }
+
+    /// The power the engine driven hydraulic pumps take off the engine's gearbox.
+    pub fn hydraulic_pump_load(&self) -> Power {
+        self.hydraulic_pump_load
+    }
+}
+impl EngineStateProvider for Engine {
+    fn n2(&self) -> Ratio {
+        self.n2
+    }
+
+    fn is_master_on(&self) -> bool {
+        self.master_on
+    }
+
+    fn is_fire_push_button_released(&self) -> bool {
+        self.fire_push_button_released
+    }
 }
 impl SimulatorElementVisitable for Engine {
     fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
@@ -27,5 +88,82 @@ impl SimulatorElementVisitable for Engine {
 impl SimulatorElement for Engine {
     fn read(&mut self, state: &SimulatorReadState) {
         self.n2 = state.engine_n2[self.number - 1];
+        self.master_on = state.engine_master_on[self.number - 1];
+    }
+}
+
+#[cfg(test)]
+mod engine_tests {
+    use super::*;
+
+    #[test]
+    fn engine_above_half_n2_is_running() {
+        let mut engine = Engine::new(1);
+        engine.n2 = Ratio::new::<percent>(51.);
+
+        assert!(engine.is_running());
+        assert!(engine.is_started());
+    }
+
+    #[test]
+    fn engine_with_master_on_is_started_before_it_runs() {
+        let mut engine = Engine::new(2);
+        engine.read(&state_with_master_on([false, true]));
+
+        assert!(engine.is_started());
+        assert!(!engine.is_running());
+    }
+
+    #[test]
+    fn engine_reads_its_own_master_switch() {
+        let mut engine = Engine::new(1);
+        engine.read(&state_with_master_on([false, true]));
+
+        assert!(!engine.is_master_on());
+    }
+
+    #[test]
+    fn engine_knows_its_fire_push_button_is_released() {
+        let mut engine = Engine::new(2);
+        engine.update_after_engine_fire_overhead(&TestFireOverhead {
+            released: [false, true],
+        });
+
+        assert!(engine.is_fire_push_button_released());
+    }
+
+    #[test]
+    fn engine_carries_the_load_of_its_own_hydraulic_pumps() {
+        let mut engine = Engine::new(2);
+        engine.update_after_hydraulic(&TestPumpLoads {
+            shaft_power: [Power::new::<watt>(1000.), Power::new::<watt>(2000.)],
+        });
+
+        assert_eq!(engine.hydraulic_pump_load(), Power::new::<watt>(2000.));
+    }
+
+    fn state_with_master_on(master_on: [bool; 2]) -> SimulatorReadState {
+        let mut state = SimulatorReadState::default();
+        state.engine_master_on = master_on;
+
+        state
+    }
+
+    struct TestFireOverhead {
+        released: [bool; 2],
+    }
+    impl EngineFirePushButtons for TestFireOverhead {
+        fn engine_fire_push_button_is_released(&self, engine_number: usize) -> bool {
+            self.released[engine_number - 1]
+        }
+    }
+
+    struct TestPumpLoads {
+        shaft_power: [Power; 2],
+    }
+    impl EngineDrivenPumpLoads for TestPumpLoads {
+        fn engine_driven_pump_shaft_power(&self, engine_number: usize) -> Power {
+            self.shaft_power[engine_number - 1]
+        }
     }
 }
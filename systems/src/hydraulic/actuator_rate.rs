@@ -0,0 +1,130 @@
+use uom::si::{f64::*, pressure::psi, ratio::ratio};
+
+/// Maps the pressure available to an actuator and the load opposing it to the
+/// ratio of its maximum rate it can achieve.
+///
+/// Flow through the servo valve varies with the square root of the pressure
+/// left once the load is balanced, so a surface slows down when pressure drops
+/// or load increases, and stalls when the load reaches the actuator stall load.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActuatorRate {
+    nominal_pressure: Pressure,
+    stall_load: Force,
+}
+impl ActuatorRate {
+    const NOMINAL_PRESSURE_PSI: f64 = 3000.;
+
+    /// An actuator stalling under the given load at nominal loop pressure.
+    pub fn new(stall_load: Force) -> Self {
+        ActuatorRate {
+            nominal_pressure: Pressure::new::<psi>(ActuatorRate::NOMINAL_PRESSURE_PSI),
+            stall_load,
+        }
+    }
+
+    /// Actuator force scales with pressure, and so does the load it stalls at.
+    pub fn stall_load_at(&self, pressure: Pressure) -> Force {
+        self.stall_load * self.pressure_ratio(pressure)
+    }
+
+    /// Whether the given opposing load stops the actuator at the given pressure.
+    pub fn is_stalled(&self, pressure: Pressure, load: Force) -> bool {
+        load >= self.stall_load_at(pressure)
+    }
+
+    /// Ratio of the maximum rate the actuator achieves, from 0 when stalled to 1.
+    /// Opposing loads are positive, aiding loads negative.
+    pub fn rate_ratio(&self, pressure: Pressure, load: Force) -> f64 {
+        let load_ratio = (load / self.stall_load).get::<ratio>();
+        let remaining_pressure_ratio = self.pressure_ratio(pressure) - load_ratio;
+
+        if remaining_pressure_ratio > 0. {
+            remaining_pressure_ratio.sqrt().min(1.)
+        } else {
+            0.
+        }
+    }
+
+    fn pressure_ratio(&self, pressure: Pressure) -> f64 {
+        (pressure / self.nominal_pressure).get::<ratio>().max(0.)
+    }
+}
+
+#[cfg(test)]
+mod actuator_rate_tests {
+    use super::*;
+    use uom::si::force::newton;
+
+    #[test]
+    fn unloaded_actuator_moves_at_full_rate_at_nominal_pressure() {
+        assert_eq!(rate().rate_ratio(pressure(3000.), load(0.)), 1.);
+    }
+
+    #[test]
+    fn actuator_slows_down_when_pressure_drops() {
+        let rate = rate();
+
+        assert!(
+            rate.rate_ratio(pressure(1500.), load(0.)) < rate.rate_ratio(pressure(3000.), load(0.))
+        );
+        assert_eq!(rate.rate_ratio(pressure(0.), load(0.)), 0.);
+    }
+
+    #[test]
+    fn actuator_slows_down_under_load() {
+        let rate = rate();
+
+        assert!(
+            rate.rate_ratio(pressure(3000.), load(20000.))
+                < rate.rate_ratio(pressure(3000.), load(0.))
+        );
+    }
+
+    #[test]
+    fn actuator_stalls_at_stall_load() {
+        let rate = rate();
+
+        assert!(rate.is_stalled(pressure(3000.), load(40000.)));
+        assert_eq!(rate.rate_ratio(pressure(3000.), load(40000.)), 0.);
+    }
+
+    #[test]
+    fn actuator_stalls_under_lower_load_at_low_pressure() {
+        let rate = rate();
+
+        assert!(!rate.is_stalled(pressure(3000.), load(30000.)));
+        assert!(rate.is_stalled(pressure(1500.), load(30000.)));
+    }
+
+    #[test]
+    fn aiding_load_does_not_exceed_full_rate() {
+        assert_eq!(rate().rate_ratio(pressure(3000.), load(-20000.)), 1.);
+    }
+
+    #[test]
+    fn flaps_driven_by_one_of_two_motors_move_at_half_rate() {
+        // The flaps power control unit sums the speed of two hydraulic motors,
+        // each on its own loop.
+        let rate = rate();
+        let both_loops = (rate.rate_ratio(pressure(3000.), load(0.))
+            + rate.rate_ratio(pressure(3000.), load(0.)))
+            / 2.;
+        let single_loop = (rate.rate_ratio(pressure(3000.), load(0.))
+            + rate.rate_ratio(pressure(0.), load(0.)))
+            / 2.;
+
+        assert_eq!(single_loop, both_loops / 2.);
+    }
+
+    fn rate() -> ActuatorRate {
+        ActuatorRate::new(load(40000.))
+    }
+
+    fn pressure(pressure: f64) -> Pressure {
+        Pressure::new::<psi>(pressure)
+    }
+
+    fn load(force: f64) -> Force {
+        Force::new::<newton>(force)
+    }
+}
@@ -0,0 +1,211 @@
+use crate::simulator::{
+    SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorReadState,
+    UpdateContext,
+};
+use std::f64::consts::PI;
+use uom::si::{
+    angular_velocity::revolution_per_minute,
+    f64::*,
+    velocity::{knot, meter_per_second},
+};
+
+/// Prevents the main gear wheels from locking up by releasing brake pressure on
+/// wheels which rotate significantly slower than the aircraft moves over the ground.
+///
+/// Anti-skid is deactivated below a certain ground speed, so the aircraft can
+/// come to a full stop, and when it isn't available to the brakes in use.
+pub struct AntiSkid {
+    wheel_rpm: [AngularVelocity; 4],
+    pressure_ratio: [f64; 4],
+    active: bool,
+}
+impl AntiSkid {
+    const WHEEL_RADIUS: f64 = 0.58; // in meters
+    const MIN_ACTIVE_GROUND_SPEED: f64 = 20.; // in knots
+    const MAX_SLIP_RATIO: f64 = 0.15;
+    const RELEASE_RATE: f64 = 5.; // fraction of brake pressure per second
+    const REAPPLY_RATE: f64 = 2.; // fraction of brake pressure per second
+
+    pub fn new() -> AntiSkid {
+        AntiSkid {
+            wheel_rpm: [AngularVelocity::new::<revolution_per_minute>(0.); 4],
+            pressure_ratio: [1.; 4],
+            active: false,
+        }
+    }
+
+    /// Updates the anti-skid. `available` indicates if anti-skid is available
+    /// to the brakes currently in use.
+    pub fn update(&mut self, context: &UpdateContext, available: bool) {
+        let reference_speed = context.ground_speed;
+        self.active =
+            available && reference_speed > Velocity::new::<knot>(AntiSkid::MIN_ACTIVE_GROUND_SPEED);
+
+        let delta = context.delta.as_secs_f64();
+        for wheel_index in 0..4 {
+            if self.active
+                && self.slip_ratio(wheel_index, reference_speed) > AntiSkid::MAX_SLIP_RATIO
+            {
+                self.pressure_ratio[wheel_index] =
+                    (self.pressure_ratio[wheel_index] - AntiSkid::RELEASE_RATE * delta).max(0.);
+            } else {
+                self.pressure_ratio[wheel_index] =
+                    (self.pressure_ratio[wheel_index] + AntiSkid::REAPPLY_RATE * delta).min(1.);
+            }
+        }
+    }
+
+    fn slip_ratio(&self, wheel_index: usize, reference_speed: Velocity) -> f64 {
+        let wheel_speed = self.wheel_speed(wheel_index);
+
+        1. - (wheel_speed / reference_speed).value
+    }
+
+    fn wheel_speed(&self, wheel_index: usize) -> Velocity {
+        let circumference = 2. * PI * AntiSkid::WHEEL_RADIUS;
+
+        Velocity::new::<meter_per_second>(
+            self.wheel_rpm[wheel_index].get::<revolution_per_minute>() * circumference / 60.,
+        )
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Returns the brake pressure to apply to the given wheel (1 to 4, from left to right)
+    /// for the given demanded pressure.
+    pub fn modulate(&self, wheel_number: usize, demand: Pressure) -> Pressure {
+        if self.active {
+            demand * self.pressure_ratio[wheel_number - 1]
+        } else {
+            demand
+        }
+    }
+}
+impl Default for AntiSkid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl SimulatorElementVisitable for AntiSkid {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for AntiSkid {
+    fn read(&mut self, state: &SimulatorReadState) {
+        self.wheel_rpm = state.landing_gear.wheel_rpm;
+    }
+}
+
+#[cfg(test)]
+mod anti_skid_tests {
+    use super::*;
+    use crate::simulator::test_helpers::context_with;
+    use std::time::Duration;
+    use uom::si::pressure::psi;
+
+    #[test]
+    fn does_not_modulate_when_wheels_roll_freely() {
+        let mut anti_skid = AntiSkid::new();
+        roll(&mut anti_skid, 100., 100., true, 1);
+
+        assert_eq!(
+            anti_skid.modulate(1, Pressure::new::<psi>(2000.)),
+            Pressure::new::<psi>(2000.)
+        );
+    }
+
+    #[test]
+    fn releases_pressure_on_a_skidding_wheel() {
+        let mut anti_skid = AntiSkid::new();
+        roll(&mut anti_skid, 100., 50., true, 1);
+
+        assert!(anti_skid.modulate(1, Pressure::new::<psi>(2000.)) < Pressure::new::<psi>(2000.));
+    }
+
+    #[test]
+    fn reapplies_pressure_once_the_wheel_spins_up_again() {
+        let mut anti_skid = AntiSkid::new();
+        roll(&mut anti_skid, 100., 50., true, 1);
+        roll(&mut anti_skid, 100., 100., true, 1);
+
+        assert_eq!(
+            anti_skid.modulate(1, Pressure::new::<psi>(2000.)),
+            Pressure::new::<psi>(2000.)
+        );
+    }
+
+    #[test]
+    fn is_inactive_below_minimum_ground_speed() {
+        let mut anti_skid = AntiSkid::new();
+        roll(&mut anti_skid, 15., 0., true, 1);
+
+        assert!(!anti_skid.is_active());
+        assert_eq!(
+            anti_skid.modulate(1, Pressure::new::<psi>(2000.)),
+            Pressure::new::<psi>(2000.)
+        );
+    }
+
+    #[test]
+    fn is_inactive_when_not_available() {
+        let mut anti_skid = AntiSkid::new();
+        roll(&mut anti_skid, 100., 50., false, 1);
+
+        assert!(!anti_skid.is_active());
+        assert_eq!(
+            anti_skid.modulate(1, Pressure::new::<psi>(2000.)),
+            Pressure::new::<psi>(2000.)
+        );
+    }
+
+    #[test]
+    fn only_modulates_the_skidding_wheel() {
+        let mut anti_skid = AntiSkid::new();
+        let mut state = SimulatorReadState::default();
+        state.landing_gear.wheel_rpm = [rpm_at(50.), rpm_at(100.), rpm_at(100.), rpm_at(100.)];
+        anti_skid.read(&state);
+        update(&mut anti_skid, 100., true, 1);
+
+        assert!(anti_skid.modulate(1, Pressure::new::<psi>(2000.)) < Pressure::new::<psi>(2000.));
+        assert_eq!(
+            anti_skid.modulate(2, Pressure::new::<psi>(2000.)),
+            Pressure::new::<psi>(2000.)
+        );
+    }
+
+    fn rpm_at(knots: f64) -> AngularVelocity {
+        AngularVelocity::new::<revolution_per_minute>(
+            Velocity::new::<knot>(knots).get::<meter_per_second>() * 60.
+                / (2. * PI * AntiSkid::WHEEL_RADIUS),
+        )
+    }
+
+    fn roll(
+        anti_skid: &mut AntiSkid,
+        ground_speed: f64,
+        wheel_speed: f64,
+        available: bool,
+        seconds: u64,
+    ) {
+        let mut state = SimulatorReadState::default();
+        state.landing_gear.wheel_rpm = [rpm_at(wheel_speed); 4];
+        anti_skid.read(&state);
+
+        update(anti_skid, ground_speed, available, seconds);
+    }
+
+    fn update(anti_skid: &mut AntiSkid, ground_speed: f64, available: bool, seconds: u64) {
+        for _ in 0..seconds * 10 {
+            anti_skid.update(
+                &context_with()
+                    .delta(Duration::from_millis(100))
+                    .ground_speed(Velocity::new::<knot>(ground_speed))
+                    .build(),
+                available,
+            );
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use crate::simulator::{
+    SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorReadState,
+    SimulatorWriteState, UpdateContext,
+};
+use std::time::Duration;
+use uom::si::{
+    acceleration::meter_per_second_squared, f64::*, pressure::psi, ratio::percent, time::second,
+    velocity::knot,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AutobrakeMode {
+    None,
+    Low,
+    Medium,
+    Max,
+}
+
+/// The autobrake controller commands the normal brakes to achieve a selected deceleration
+/// after touchdown (LO and MED) or during a rejected takeoff (MAX).
+///
+/// A mode is armed by pressing its push button and disarmed by pressing it again.
+/// The armed mode is lost when a brake pedal is pressed deep enough or when the green
+/// hydraulic system is no longer pressurised.
+pub struct AutobrakeController {
+    lo_pb_pressed: bool,
+    med_pb_pressed: bool,
+    max_pb_pressed: bool,
+    left_brake_pedal_position: Ratio,
+    right_brake_pedal_position: Ratio,
+    ground_spoilers_deployed: bool,
+    on_ground: bool,
+
+    armed_mode: AutobrakeMode,
+    active_for: Duration,
+    previous_ground_speed: Velocity,
+    deceleration: Acceleration,
+    brake_pressure_command: Pressure,
+}
+impl AutobrakeController {
+    const LOW_DECELERATION: f64 = 1.7; // in meters per second squared
+    const MEDIUM_DECELERATION: f64 = 3.0; // in meters per second squared
+    const MAX_DECELERATION: f64 = 6.0; // in meters per second squared
+    const LOW_ACTIVATION_DELAY_SECS: f64 = 4.;
+    const MEDIUM_ACTIVATION_DELAY_SECS: f64 = 2.;
+    const MAX_ARMING_GROUND_SPEED_KNOTS: f64 = 72.;
+    const PEDAL_DISARM_POSITION_PERCENT: f64 = 53.;
+    const MAX_BRAKE_PRESSURE_PSI: f64 = 3000.;
+    // Change in commanded brake pressure for each m/s² of deceleration error, per second.
+    const PRESSURE_GAIN: f64 = 500.;
+
+    pub fn new() -> AutobrakeController {
+        AutobrakeController {
+            lo_pb_pressed: false,
+            med_pb_pressed: false,
+            max_pb_pressed: false,
+            left_brake_pedal_position: Ratio::new::<percent>(0.),
+            right_brake_pedal_position: Ratio::new::<percent>(0.),
+            ground_spoilers_deployed: false,
+            on_ground: false,
+
+            armed_mode: AutobrakeMode::None,
+            active_for: Duration::from_secs(0),
+            previous_ground_speed: Velocity::new::<knot>(0.),
+            deceleration: Acceleration::new::<meter_per_second_squared>(0.),
+            brake_pressure_command: Pressure::new::<psi>(0.),
+        }
+    }
+
+    pub fn update(&mut self, context: &UpdateContext, green_pressurised: bool) {
+        self.update_deceleration(context);
+
+        if self.should_disarm(green_pressurised) {
+            self.armed_mode = AutobrakeMode::None;
+        }
+
+        if self.is_active() {
+            self.active_for += context.delta;
+        } else {
+            self.active_for = Duration::from_secs(0);
+        }
+
+        self.update_brake_pressure_command(context);
+    }
+
+    fn update_deceleration(&mut self, context: &UpdateContext) {
+        let delta = context.delta.as_secs_f64();
+        if delta > 0. {
+            self.deceleration =
+                (self.previous_ground_speed - context.ground_speed) / Time::new::<second>(delta);
+        }
+        self.previous_ground_speed = context.ground_speed;
+    }
+
+    fn should_disarm(&self, green_pressurised: bool) -> bool {
+        let pedal_disarm_position = Ratio::new::<percent>(Self::PEDAL_DISARM_POSITION_PERCENT);
+
+        !green_pressurised
+            || self.left_brake_pedal_position > pedal_disarm_position
+            || self.right_brake_pedal_position > pedal_disarm_position
+    }
+
+    fn update_brake_pressure_command(&mut self, context: &UpdateContext) {
+        if self.is_braking() {
+            let error = self.target_deceleration() - self.deceleration;
+            let change = error.get::<meter_per_second_squared>()
+                * Self::PRESSURE_GAIN
+                * context.delta.as_secs_f64();
+
+            self.brake_pressure_command = Pressure::new::<psi>(
+                (self.brake_pressure_command.get::<psi>() + change)
+                    .clamp(0., Self::MAX_BRAKE_PRESSURE_PSI),
+            );
+        } else {
+            self.brake_pressure_command = Pressure::new::<psi>(0.);
+        }
+    }
+
+    fn target_deceleration(&self) -> Acceleration {
+        Acceleration::new::<meter_per_second_squared>(match self.armed_mode {
+            AutobrakeMode::None => 0.,
+            AutobrakeMode::Low => Self::LOW_DECELERATION,
+            AutobrakeMode::Medium => Self::MEDIUM_DECELERATION,
+            AutobrakeMode::Max => Self::MAX_DECELERATION,
+        })
+    }
+
+    fn activation_delay(&self) -> Duration {
+        Duration::from_secs_f64(match self.armed_mode {
+            AutobrakeMode::Low => Self::LOW_ACTIVATION_DELAY_SECS,
+            AutobrakeMode::Medium => Self::MEDIUM_ACTIVATION_DELAY_SECS,
+            AutobrakeMode::None | AutobrakeMode::Max => 0.,
+        })
+    }
+
+    fn arm_or_disarm(&mut self, mode: AutobrakeMode, context_allows_arming: bool) {
+        if self.armed_mode == mode {
+            self.armed_mode = AutobrakeMode::None;
+        } else if context_allows_arming {
+            self.armed_mode = mode;
+        }
+    }
+
+    pub fn armed_mode(&self) -> AutobrakeMode {
+        self.armed_mode
+    }
+
+    /// Indicates if the autobrake is active, which happens once the ground
+    /// spoilers extend on ground with a mode armed.
+    pub fn is_active(&self) -> bool {
+        self.armed_mode != AutobrakeMode::None && self.on_ground && self.ground_spoilers_deployed
+    }
+
+    fn is_braking(&self) -> bool {
+        self.is_active() && self.active_for >= self.activation_delay()
+    }
+
+    /// Indicates that the actual deceleration reached 80% of the selected deceleration.
+    pub fn shows_decel(&self) -> bool {
+        self.is_braking() && self.deceleration >= self.target_deceleration() * 0.8
+    }
+
+    pub fn brake_pressure_command(&self) -> Pressure {
+        self.brake_pressure_command
+    }
+}
+impl Default for AutobrakeController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl SimulatorElementVisitable for AutobrakeController {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for AutobrakeController {
+    fn read(&mut self, state: &SimulatorReadState) {
+        self.on_ground = state.on_ground;
+        self.ground_spoilers_deployed = state.hydraulic.ground_spoilers_deployed;
+        self.left_brake_pedal_position = state.hydraulic.left_brake_pedal_position;
+        self.right_brake_pedal_position = state.hydraulic.right_brake_pedal_position;
+
+        // Modes are armed or disarmed on the press of a button, not while it is held.
+        if state.hydraulic.autobrake_lo_pb_pressed && !self.lo_pb_pressed {
+            self.arm_or_disarm(AutobrakeMode::Low, true);
+        }
+        if state.hydraulic.autobrake_med_pb_pressed && !self.med_pb_pressed {
+            self.arm_or_disarm(AutobrakeMode::Medium, true);
+        }
+        if state.hydraulic.autobrake_max_pb_pressed && !self.max_pb_pressed {
+            // MAX can only be armed on ground, for a rejected takeoff.
+            let allows_arming = state.on_ground
+                && state.ground_speed < Velocity::new::<knot>(Self::MAX_ARMING_GROUND_SPEED_KNOTS);
+            self.arm_or_disarm(AutobrakeMode::Max, allows_arming);
+        }
+
+        self.lo_pb_pressed = state.hydraulic.autobrake_lo_pb_pressed;
+        self.med_pb_pressed = state.hydraulic.autobrake_med_pb_pressed;
+        self.max_pb_pressed = state.hydraulic.autobrake_max_pb_pressed;
+    }
+
+    fn write(&self, state: &mut SimulatorWriteState) {
+        state.hydraulic.autobrake_lo_armed = self.armed_mode == AutobrakeMode::Low;
+        state.hydraulic.autobrake_med_armed = self.armed_mode == AutobrakeMode::Medium;
+        state.hydraulic.autobrake_max_armed = self.armed_mode == AutobrakeMode::Max;
+        state.hydraulic.autobrake_decel = self.shows_decel();
+    }
+}
+
+#[cfg(test)]
+mod autobrake_controller_tests {
+    use super::*;
+    use crate::simulator::test_helpers::context_with;
+
+    #[test]
+    fn starts_disarmed() {
+        let autobrake = AutobrakeController::new();
+
+        assert_eq!(autobrake.armed_mode(), AutobrakeMode::None);
+        assert_eq!(autobrake.brake_pressure_command(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn pressing_lo_arms_low() {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, |state| {
+            state.hydraulic.autobrake_lo_pb_pressed = true
+        });
+
+        assert_eq!(autobrake.armed_mode(), AutobrakeMode::Low);
+    }
+
+    #[test]
+    fn pressing_med_arms_medium() {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, |state| {
+            state.hydraulic.autobrake_med_pb_pressed = true
+        });
+
+        assert_eq!(autobrake.armed_mode(), AutobrakeMode::Medium);
+    }
+
+    #[test]
+    fn pressing_the_armed_mode_again_disarms() {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, |state| {
+            state.hydraulic.autobrake_lo_pb_pressed = true
+        });
+        press(&mut autobrake, |state| {
+            state.hydraulic.autobrake_lo_pb_pressed = true
+        });
+
+        assert_eq!(autobrake.armed_mode(), AutobrakeMode::None);
+    }
+
+    #[test]
+    fn holding_a_button_does_not_toggle_the_mode() {
+        let mut autobrake = AutobrakeController::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.autobrake_lo_pb_pressed = true;
+        autobrake.read(&state);
+        autobrake.read(&state);
+
+        assert_eq!(autobrake.armed_mode(), AutobrakeMode::Low);
+    }
+
+    #[test]
+    fn max_arms_on_ground() {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, |state| {
+            state.on_ground = true;
+            state.hydraulic.autobrake_max_pb_pressed = true;
+        });
+
+        assert_eq!(autobrake.armed_mode(), AutobrakeMode::Max);
+    }
+
+    #[test]
+    fn max_does_not_arm_in_flight() {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, |state| {
+            state.hydraulic.autobrake_max_pb_pressed = true
+        });
+
+        assert_eq!(autobrake.armed_mode(), AutobrakeMode::None);
+    }
+
+    #[test]
+    fn disarms_when_green_is_not_pressurised() {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, |state| {
+            state.hydraulic.autobrake_lo_pb_pressed = true
+        });
+        autobrake.update(&context_with().build(), false);
+
+        assert_eq!(autobrake.armed_mode(), AutobrakeMode::None);
+    }
+
+    #[test]
+    fn disarms_when_a_brake_pedal_is_pressed() {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, |state| {
+            state.hydraulic.autobrake_lo_pb_pressed = true
+        });
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.left_brake_pedal_position = Ratio::new::<percent>(80.);
+        autobrake.read(&state);
+        autobrake.update(&context_with().build(), true);
+
+        assert_eq!(autobrake.armed_mode(), AutobrakeMode::None);
+    }
+
+    #[test]
+    fn does_not_brake_in_flight() {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, |state| {
+            state.hydraulic.autobrake_med_pb_pressed = true
+        });
+        roll(&mut autobrake, 140., 0., 10);
+
+        assert_eq!(autobrake.brake_pressure_command(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn low_does_not_brake_before_the_activation_delay() {
+        let mut autobrake =
+            armed_and_landed(|state| state.hydraulic.autobrake_lo_pb_pressed = true);
+        roll(&mut autobrake, 140., 0., 3);
+
+        assert_eq!(autobrake.brake_pressure_command(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn low_brakes_after_the_activation_delay() {
+        let mut autobrake =
+            armed_and_landed(|state| state.hydraulic.autobrake_lo_pb_pressed = true);
+        roll(&mut autobrake, 140., 0., 5);
+
+        assert!(autobrake.brake_pressure_command() > Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn brake_pressure_command_decreases_when_decelerating_too_much() {
+        let mut autobrake =
+            armed_and_landed(|state| state.hydraulic.autobrake_lo_pb_pressed = true);
+        roll(&mut autobrake, 140., 0., 6);
+        let command = autobrake.brake_pressure_command();
+        // Decelerating at 5 m/s², well above the LO target.
+        roll(&mut autobrake, 140., 5., 1);
+
+        assert!(autobrake.brake_pressure_command() < command);
+    }
+
+    #[test]
+    fn max_brakes_immediately_on_rejected_takeoff() {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, |state| {
+            state.on_ground = true;
+            state.hydraulic.autobrake_max_pb_pressed = true;
+        });
+        press(&mut autobrake, |state| {
+            state.on_ground = true;
+            state.hydraulic.ground_spoilers_deployed = true;
+        });
+        roll(&mut autobrake, 120., 0., 1);
+
+        assert!(autobrake.brake_pressure_command() > Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn shows_decel_when_reaching_the_target_deceleration() {
+        let mut autobrake =
+            armed_and_landed(|state| state.hydraulic.autobrake_med_pb_pressed = true);
+        roll(&mut autobrake, 140., 3., 5);
+
+        assert!(autobrake.shows_decel());
+    }
+
+    #[test]
+    fn does_not_show_decel_when_not_decelerating() {
+        let mut autobrake =
+            armed_and_landed(|state| state.hydraulic.autobrake_med_pb_pressed = true);
+        roll(&mut autobrake, 140., 0., 5);
+
+        assert!(!autobrake.shows_decel());
+    }
+
+    fn press<T: Fn(&mut SimulatorReadState)>(autobrake: &mut AutobrakeController, setup: T) {
+        let mut state = SimulatorReadState::default();
+        setup(&mut state);
+        autobrake.read(&state);
+
+        // Release all buttons again.
+        let mut state = SimulatorReadState::default();
+        state.on_ground = autobrake.on_ground;
+        state.hydraulic.ground_spoilers_deployed = autobrake.ground_spoilers_deployed;
+        autobrake.read(&state);
+    }
+
+    fn armed_and_landed<T: Fn(&mut SimulatorReadState)>(arm: T) -> AutobrakeController {
+        let mut autobrake = AutobrakeController::new();
+        press(&mut autobrake, arm);
+        press(&mut autobrake, |state| {
+            state.on_ground = true;
+            state.hydraulic.ground_spoilers_deployed = true;
+        });
+
+        autobrake
+    }
+
+    /// Rolls for the given number of seconds, starting at the given ground speed
+    /// and decelerating at the given rate.
+    fn roll(
+        autobrake: &mut AutobrakeController,
+        initial_ground_speed_knots: f64,
+        deceleration: f64,
+        seconds: u64,
+    ) {
+        let delta = Duration::from_millis(100);
+        let mut ground_speed = Velocity::new::<knot>(initial_ground_speed_knots);
+        autobrake.previous_ground_speed = ground_speed;
+
+        for _ in 0..seconds * 10 {
+            ground_speed -= Acceleration::new::<meter_per_second_squared>(deceleration)
+                * Time::new::<second>(delta.as_secs_f64());
+            autobrake.update(
+                &context_with()
+                    .delta(delta)
+                    .ground_speed(ground_speed)
+                    .build(),
+                true,
+            );
+        }
+    }
+}
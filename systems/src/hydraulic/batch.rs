@@ -0,0 +1,197 @@
+use super::{ElectricPump, EngineDrivenPump, HydLoop};
+use crate::{engine::Engine, simulator::UpdateContext};
+use std::time::Duration;
+use uom::si::{
+    f64::*, length::foot, ratio::percent, thermodynamic_temperature::degree_celsius, velocity::knot,
+};
+
+/// One sample of the inputs driving an offline hydraulic simulation.
+/// Each sample advances the simulation by `delta`.
+#[derive(Clone, Copy, Debug)]
+pub struct HydraulicInput {
+    pub delta: Duration,
+    pub engine_n2: [Ratio; 2],
+    /// Only used by single loop simulations, which have one electric pump.
+    pub electric_pump_running: bool,
+    pub indicated_airspeed: Velocity,
+    pub indicated_altitude: Length,
+    pub ambient_temperature: ThermodynamicTemperature,
+    pub ground_speed: Velocity,
+}
+impl HydraulicInput {
+    /// Creates a context for updating systems with the environment of this sample.
+    pub fn context(&self) -> UpdateContext {
+        UpdateContext::new(
+            self.delta,
+            self.indicated_airspeed,
+            self.indicated_altitude,
+            self.ambient_temperature,
+            self.ground_speed,
+        )
+    }
+
+    /// Creates the engine with the given number (1 or 2), running at the N2 of this sample.
+    pub fn engine(&self, number: usize) -> Engine {
+        let mut engine = Engine::new(number);
+        engine.n2 = self.engine_n2[number - 1];
+
+        engine
+    }
+}
+impl Default for HydraulicInput {
+    /// An aircraft standing on the ground at sea level with engines and pumps off.
+    fn default() -> Self {
+        HydraulicInput {
+            delta: Duration::from_millis(100),
+            engine_n2: [Ratio::new::<percent>(0.), Ratio::new::<percent>(0.)],
+            electric_pump_running: false,
+            indicated_airspeed: Velocity::new::<knot>(0.),
+            indicated_altitude: Length::new::<foot>(0.),
+            ambient_temperature: ThermodynamicTemperature::new::<degree_celsius>(15.),
+            ground_speed: Velocity::new::<knot>(0.),
+        }
+    }
+}
+
+/// The state of a single loop simulation after a sample was applied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HydLoopOutput {
+    pub time: Duration,
+    pub pressure: Pressure,
+    pub loop_volume: Volume,
+    pub reservoir_volume: Volume,
+    pub pumps_delivery_ratio: f64,
+}
+
+/// A single loop pressurised by an electric pump and a pump driven by engine 1,
+/// for running parameter sweeps on a loop without the rest of the aircraft.
+pub struct HydLoopSimulation {
+    hyd_loop: HydLoop,
+    electric_pump: ElectricPump,
+    engine_driven_pump: EngineDrivenPump,
+    time: Duration,
+}
+impl HydLoopSimulation {
+    pub fn new(hyd_loop: HydLoop) -> HydLoopSimulation {
+        HydLoopSimulation::with_pumps(hyd_loop, ElectricPump::new(), EngineDrivenPump::new())
+    }
+
+    pub fn with_pumps(
+        hyd_loop: HydLoop,
+        electric_pump: ElectricPump,
+        engine_driven_pump: EngineDrivenPump,
+    ) -> HydLoopSimulation {
+        HydLoopSimulation {
+            hyd_loop,
+            electric_pump,
+            engine_driven_pump,
+            time: Duration::from_secs(0),
+        }
+    }
+
+    pub fn hyd_loop(&self) -> &HydLoop {
+        &self.hyd_loop
+    }
+
+    pub fn step(&mut self, input: &HydraulicInput) -> HydLoopOutput {
+        let context = input.context();
+        let engine = input.engine(1);
+
+        if input.electric_pump_running {
+            self.electric_pump.start();
+        } else {
+            self.electric_pump.stop();
+        }
+        self.electric_pump
+            .update(&input.delta, &context, &self.hyd_loop);
+        self.engine_driven_pump
+            .update(&input.delta, &context, &self.hyd_loop, &engine);
+        self.hyd_loop.update(
+            &input.delta,
+            &context,
+            Some(&self.electric_pump),
+            Some(&self.engine_driven_pump),
+            None,
+            None,
+        );
+        self.time += input.delta;
+
+        HydLoopOutput {
+            time: self.time,
+            pressure: self.hyd_loop.get_pressure(),
+            loop_volume: self.hyd_loop.get_loop_volume(),
+            reservoir_volume: self.hyd_loop.get_reservoir_volume(),
+            pumps_delivery_ratio: self.hyd_loop.get_pumps_delivery_ratio(),
+        }
+    }
+
+    /// Applies the samples in order and returns the output of each step.
+    pub fn run(&mut self, inputs: &[HydraulicInput]) -> Vec<HydLoopOutput> {
+        inputs.iter().map(|input| self.step(input)).collect()
+    }
+}
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+    use uom::si::pressure::psi;
+
+    #[test]
+    fn running_electric_pump_pressurises_loop() {
+        let mut simulation = HydLoopSimulation::new(yellow_loop());
+
+        let outputs = simulation.run(&[electric_pump_running(); 300]);
+
+        assert_eq!(outputs.len(), 300);
+        assert!(outputs.last().unwrap().pressure > Pressure::new::<psi>(2900.));
+    }
+
+    #[test]
+    fn running_engine_pressurises_loop() {
+        let mut simulation = HydLoopSimulation::new(yellow_loop());
+        let input = HydraulicInput {
+            engine_n2: [Ratio::new::<percent>(80.), Ratio::new::<percent>(0.)],
+            ..HydraulicInput::default()
+        };
+
+        let outputs = simulation.run(&[input; 300]);
+
+        assert!(outputs.last().unwrap().pressure > Pressure::new::<psi>(2900.));
+    }
+
+    #[test]
+    fn outputs_are_timed_by_input_deltas() {
+        let mut simulation = HydLoopSimulation::new(yellow_loop());
+        let slow = HydraulicInput {
+            delta: Duration::from_millis(250),
+            ..HydraulicInput::default()
+        };
+
+        let outputs = simulation.run(&[HydraulicInput::default(), slow]);
+
+        assert_eq!(outputs[0].time, Duration::from_millis(100));
+        assert_eq!(outputs[1].time, Duration::from_millis(350));
+    }
+
+    #[test]
+    fn identical_inputs_give_identical_outputs() {
+        let mut inputs = vec![electric_pump_running(); 100];
+        inputs.extend(vec![HydraulicInput::default(); 100]);
+
+        let first = HydLoopSimulation::new(yellow_loop()).run(&inputs);
+        let second = HydLoopSimulation::new(yellow_loop()).run(&inputs);
+
+        assert_eq!(first, second);
+    }
+
+    fn electric_pump_running() -> HydraulicInput {
+        HydraulicInput {
+            electric_pump_running: true,
+            ..HydraulicInput::default()
+        }
+    }
+
+    fn yellow_loop() -> HydLoop {
+        HydLoop::a320_yellow()
+    }
+}
@@ -0,0 +1,146 @@
+use crate::simulator::{
+    SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorReadState,
+};
+use uom::si::{f64::*, pressure::psi, ratio::percent, ratio::ratio};
+
+/// Meters brake pressure from the deflection of a brake pedal. Past the breakout
+/// deflection, the metered pressure grows linearly up to its maximum at full deflection.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BrakePedalMetering {
+    breakout: Ratio,
+    full_deflection_pressure: Pressure,
+}
+impl BrakePedalMetering {
+    pub fn new(breakout: Ratio, full_deflection_pressure: Pressure) -> Self {
+        BrakePedalMetering {
+            breakout,
+            full_deflection_pressure,
+        }
+    }
+
+    pub fn metered_pressure(&self, deflection: Ratio) -> Pressure {
+        let travel = ((deflection - self.breakout) / (Ratio::new::<ratio>(1.) - self.breakout))
+            .get::<ratio>()
+            .clamp(0., 1.);
+
+        self.full_deflection_pressure * travel
+    }
+}
+
+/// The brake pedals of both pilots, as seen by the normal and alternate brakes.
+///
+/// In normal braking the pedal transmitters are read by the BSCU, which commands
+/// the normal brake selector valve. In alternate braking the pedals mechanically
+/// drive the dual distribution valve, which meters the alternate brake pressure
+/// with a lower gain: the same pressure takes a deeper, heavier push on the pedals.
+pub struct BrakePedals {
+    left_position: Ratio,
+    right_position: Ratio,
+    normal: BrakePedalMetering,
+    dual_distribution_valve: BrakePedalMetering,
+}
+impl BrakePedals {
+    const NORMAL_BREAKOUT_PERCENT: f64 = 5.;
+    const NORMAL_FULL_DEFLECTION_PRESSURE_PSI: f64 = 3000.;
+    const ALTERNATE_BREAKOUT_PERCENT: f64 = 10.;
+    const ALTERNATE_FULL_DEFLECTION_PRESSURE_PSI: f64 = 2000.;
+
+    pub fn new() -> Self {
+        BrakePedals {
+            left_position: Ratio::new::<percent>(0.),
+            right_position: Ratio::new::<percent>(0.),
+            normal: BrakePedalMetering::new(
+                Ratio::new::<percent>(BrakePedals::NORMAL_BREAKOUT_PERCENT),
+                Pressure::new::<psi>(BrakePedals::NORMAL_FULL_DEFLECTION_PRESSURE_PSI),
+            ),
+            dual_distribution_valve: BrakePedalMetering::new(
+                Ratio::new::<percent>(BrakePedals::ALTERNATE_BREAKOUT_PERCENT),
+                Pressure::new::<psi>(BrakePedals::ALTERNATE_FULL_DEFLECTION_PRESSURE_PSI),
+            ),
+        }
+    }
+
+    /// The left and right brake pressures the BSCU commands from the pedals.
+    pub fn normal_demands(&self) -> (Pressure, Pressure) {
+        self.demands(&self.normal)
+    }
+
+    /// The left and right brake pressures metered by the dual distribution valve.
+    pub fn alternate_demands(&self) -> (Pressure, Pressure) {
+        self.demands(&self.dual_distribution_valve)
+    }
+
+    fn demands(&self, metering: &BrakePedalMetering) -> (Pressure, Pressure) {
+        (
+            metering.metered_pressure(self.left_position),
+            metering.metered_pressure(self.right_position),
+        )
+    }
+}
+impl Default for BrakePedals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl SimulatorElementVisitable for BrakePedals {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for BrakePedals {
+    fn read(&mut self, state: &SimulatorReadState) {
+        self.left_position = state.hydraulic.left_brake_pedal_position;
+        self.right_position = state.hydraulic.right_brake_pedal_position;
+    }
+}
+
+#[cfg(test)]
+mod brake_pedals_tests {
+    use super::*;
+
+    #[test]
+    fn no_pressure_is_metered_below_breakout() {
+        let pedals = pedals(4., 4.);
+
+        assert_eq!(pedals.normal_demands().0, Pressure::new::<psi>(0.));
+        assert_eq!(pedals.alternate_demands().0, Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn full_deflection_meters_full_pressure() {
+        let pedals = pedals(100., 100.);
+
+        assert!(is_about(pedals.normal_demands().0, 3000.));
+        assert!(is_about(pedals.alternate_demands().1, 2000.));
+    }
+
+    #[test]
+    fn alternate_braking_needs_deeper_deflection_for_the_same_pressure() {
+        let pedals = pedals(50., 50.);
+
+        assert!(pedals.alternate_demands().0 < pedals.normal_demands().0 * 0.7);
+    }
+
+    #[test]
+    fn each_pedal_meters_its_own_brake() {
+        let pedals = pedals(100., 0.);
+        let (left, right) = pedals.normal_demands();
+
+        assert!(is_about(left, 3000.));
+        assert_eq!(right, Pressure::new::<psi>(0.));
+    }
+
+    fn pedals(left_percent: f64, right_percent: f64) -> BrakePedals {
+        let mut pedals = BrakePedals::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.left_brake_pedal_position = Ratio::new::<percent>(left_percent);
+        state.hydraulic.right_brake_pedal_position = Ratio::new::<percent>(right_percent);
+        pedals.read(&state);
+
+        pedals
+    }
+
+    fn is_about(pressure: Pressure, expected_psi: f64) -> bool {
+        (pressure.get::<psi>() - expected_psi).abs() < 0.001
+    }
+}
@@ -0,0 +1,164 @@
+use uom::si::{f64::*, pressure::psi};
+
+/// The source of the brake pressure, in order of precedence once the parking
+/// brake is released.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BrakingMode {
+    /// Green loop brakes commanded by the BSCU, with anti-skid and autobrake.
+    Normal,
+    /// Yellow loop brakes metered by the pedals, with anti-skid.
+    AlternateWithAntiSkid,
+    /// Yellow loop brakes metered by the pedals, limited to avoid locking the wheels.
+    AlternateWithoutAntiSkid,
+    /// Yellow brakes supplied by the brake accumulator alone, limited as without anti-skid.
+    AccumulatorOnly,
+    /// Yellow brakes applied by the parking brake valve, normal braking deactivated.
+    Parking,
+}
+
+/// Selects the source of the brake pressure, switching automatically to the next
+/// source of the hierarchy when a loop or the BSCU is lost.
+pub struct BrakeSourceSelector {
+    mode: BrakingMode,
+}
+impl BrakeSourceSelector {
+    const PRESSURE_LIMIT_WITHOUT_ANTI_SKID_PSI: f64 = 1000.;
+
+    pub fn new() -> Self {
+        BrakeSourceSelector {
+            mode: BrakingMode::AccumulatorOnly,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        normal_braking_available: bool,
+        yellow_pressurised: bool,
+        anti_skid_available: bool,
+        parking_brake_applied: bool,
+    ) {
+        self.mode = if parking_brake_applied {
+            BrakingMode::Parking
+        } else if normal_braking_available {
+            BrakingMode::Normal
+        } else if !yellow_pressurised {
+            BrakingMode::AccumulatorOnly
+        } else if anti_skid_available {
+            BrakingMode::AlternateWithAntiSkid
+        } else {
+            BrakingMode::AlternateWithoutAntiSkid
+        };
+    }
+
+    pub fn mode(&self) -> BrakingMode {
+        self.mode
+    }
+
+    pub fn uses_anti_skid(&self) -> bool {
+        matches!(
+            self.mode,
+            BrakingMode::Normal | BrakingMode::AlternateWithAntiSkid
+        )
+    }
+
+    /// The highest brake pressure metered by the pedals in the current mode.
+    pub fn pressure_limit(&self) -> Option<Pressure> {
+        match self.mode {
+            BrakingMode::AlternateWithoutAntiSkid | BrakingMode::AccumulatorOnly => Some(
+                Pressure::new::<psi>(BrakeSourceSelector::PRESSURE_LIMIT_WITHOUT_ANTI_SKID_PSI),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Limits the given demand to the pressure limit of the current mode.
+    pub fn limit(&self, demand: Pressure) -> Pressure {
+        self.pressure_limit()
+            .map_or(demand, |limit| demand.min(limit))
+    }
+}
+impl Default for BrakeSourceSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod brake_source_tests {
+    use super::*;
+
+    #[test]
+    fn brakes_are_normal_with_green_and_bscu() {
+        assert_eq!(mode(true, true, true, false), BrakingMode::Normal);
+    }
+
+    #[test]
+    fn loss_of_normal_braking_switches_to_alternate_with_anti_skid() {
+        assert_eq!(
+            mode(false, true, true, false),
+            BrakingMode::AlternateWithAntiSkid
+        );
+    }
+
+    #[test]
+    fn loss_of_anti_skid_switches_to_alternate_without_anti_skid() {
+        assert_eq!(
+            mode(false, true, false, false),
+            BrakingMode::AlternateWithoutAntiSkid
+        );
+    }
+
+    #[test]
+    fn loss_of_yellow_leaves_the_accumulator_only() {
+        assert_eq!(
+            mode(false, false, true, false),
+            BrakingMode::AccumulatorOnly
+        );
+    }
+
+    #[test]
+    fn parking_brake_takes_precedence() {
+        assert_eq!(mode(true, true, true, true), BrakingMode::Parking);
+    }
+
+    #[test]
+    fn pressure_is_limited_without_anti_skid() {
+        let mut selector = BrakeSourceSelector::new();
+        selector.update(false, true, false, false);
+
+        assert!(!selector.uses_anti_skid());
+        assert_eq!(
+            selector.limit(Pressure::new::<psi>(3000.)),
+            Pressure::new::<psi>(1000.)
+        );
+    }
+
+    #[test]
+    fn pressure_is_not_limited_with_anti_skid() {
+        let mut selector = BrakeSourceSelector::new();
+        selector.update(false, true, true, false);
+
+        assert!(selector.uses_anti_skid());
+        assert_eq!(
+            selector.limit(Pressure::new::<psi>(3000.)),
+            Pressure::new::<psi>(3000.)
+        );
+    }
+
+    fn mode(
+        normal_braking_available: bool,
+        yellow_pressurised: bool,
+        anti_skid_available: bool,
+        parking_brake_applied: bool,
+    ) -> BrakingMode {
+        let mut selector = BrakeSourceSelector::new();
+        selector.update(
+            normal_braking_available,
+            yellow_pressurised,
+            anti_skid_available,
+            parking_brake_applied,
+        );
+
+        selector.mode()
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{
+    electrical::{ElectricalBusType, PowerConsumption},
+    simulator::{
+        SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, SimulatorReadState,
+        SimulatorWriteState, UpdateContext,
+    },
+};
+use std::time::Duration;
+
+/// One of the two identical channels of the BSCU. A channel runs a self-test
+/// each time it is powered up and cannot control the brakes until the test completes.
+pub struct BrakeSteeringControlUnitChannel {
+    power_consumption: PowerConsumption,
+    was_powered: bool,
+    self_test_remaining: Duration,
+    failed: bool,
+}
+impl BrakeSteeringControlUnitChannel {
+    const SELF_TEST_DURATION_SECS: u64 = 4;
+
+    pub fn new(powered_by: ElectricalBusType) -> BrakeSteeringControlUnitChannel {
+        BrakeSteeringControlUnitChannel {
+            power_consumption: PowerConsumption::from_single(powered_by),
+            was_powered: false,
+            self_test_remaining: Duration::from_secs(0),
+            failed: false,
+        }
+    }
+
+    pub fn update(&mut self, context: &UpdateContext) {
+        let is_powered = self.power_consumption.is_powered();
+        if is_powered && !self.was_powered {
+            self.self_test_remaining =
+                Duration::from_secs(BrakeSteeringControlUnitChannel::SELF_TEST_DURATION_SECS);
+        } else if is_powered {
+            self.self_test_remaining = self
+                .self_test_remaining
+                .checked_sub(context.delta)
+                .unwrap_or_default();
+        }
+
+        self.was_powered = is_powered;
+    }
+
+    pub fn fail(&mut self) {
+        self.failed = true;
+    }
+
+    pub fn is_self_testing(&self) -> bool {
+        self.power_consumption.is_powered() && self.self_test_remaining > Duration::from_secs(0)
+    }
+
+    pub fn is_operational(&self) -> bool {
+        self.power_consumption.is_powered() && !self.is_self_testing() && !self.failed
+    }
+
+    /// A channel is at fault when it is powered and isn't operational after its self-test.
+    pub fn has_fault(&self) -> bool {
+        self.power_consumption.is_powered() && !self.is_self_testing() && self.failed
+    }
+}
+impl SimulatorElementVisitable for BrakeSteeringControlUnitChannel {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        self.power_consumption.accept(visitor);
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for BrakeSteeringControlUnitChannel {}
+
+/// The Brake and Steering Control Unit (BSCU) supervises normal braking, autobrake,
+/// anti-skid and nose wheel steering. It consists of two channels, of which one is in
+/// control while the other is on standby. When both channels are lost, or when the
+/// A/SKID & N/WS switch is off, braking reverts to the alternate mode.
+pub struct BrakeSteeringControlUnit {
+    channels: [BrakeSteeringControlUnitChannel; 2],
+    anti_skid_and_nws_switch_on: bool,
+}
+impl BrakeSteeringControlUnit {
+    pub fn new(
+        channel_1_powered_by: ElectricalBusType,
+        channel_2_powered_by: ElectricalBusType,
+    ) -> BrakeSteeringControlUnit {
+        BrakeSteeringControlUnit {
+            channels: [
+                BrakeSteeringControlUnitChannel::new(channel_1_powered_by),
+                BrakeSteeringControlUnitChannel::new(channel_2_powered_by),
+            ],
+            anti_skid_and_nws_switch_on: true,
+        }
+    }
+
+    pub fn update(&mut self, context: &UpdateContext) {
+        for channel in self.channels.iter_mut() {
+            channel.update(context);
+        }
+    }
+
+    /// Fails the given channel (1 or 2).
+    pub fn fail_channel(&mut self, number: usize) {
+        self.channels[number - 1].fail();
+    }
+
+    /// The number of the channel currently in control, if any.
+    pub fn active_channel(&self) -> Option<usize> {
+        self.channels
+            .iter()
+            .position(|channel| channel.is_operational())
+            .map(|index| index + 1)
+    }
+
+    pub fn channel_has_fault(&self, number: usize) -> bool {
+        self.channels[number - 1].has_fault()
+    }
+
+    fn is_in_control(&self) -> bool {
+        self.active_channel().is_some() && self.anti_skid_and_nws_switch_on
+    }
+
+    /// Indicates if the green system normal brakes can be used.
+    pub fn normal_braking_available(&self) -> bool {
+        self.is_in_control()
+    }
+
+    pub fn autobrake_available(&self) -> bool {
+        self.is_in_control()
+    }
+
+    pub fn anti_skid_available(&self) -> bool {
+        self.is_in_control()
+    }
+
+    pub fn nose_wheel_steering_available(&self) -> bool {
+        self.is_in_control()
+    }
+}
+impl SimulatorElementVisitable for BrakeSteeringControlUnit {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        for channel in self.channels.iter_mut() {
+            channel.accept(visitor);
+        }
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for BrakeSteeringControlUnit {
+    fn read(&mut self, state: &SimulatorReadState) {
+        self.anti_skid_and_nws_switch_on = state.hydraulic.anti_skid_and_nws_switch_on;
+    }
+
+    fn write(&self, state: &mut SimulatorWriteState) {
+        state.hydraulic.bscu_channel_fault = [self.channel_has_fault(1), self.channel_has_fault(2)];
+    }
+}
+
+#[cfg(test)]
+mod brake_steering_control_unit_tests {
+    use super::*;
+    use crate::{
+        electrical::{Current, ElectricPowerSource, ElectricalBus, PowerSupply, Powerable},
+        simulator::test_helpers::context_with,
+    };
+
+    #[test]
+    fn is_not_in_control_when_unpowered() {
+        let mut bscu = bscu();
+        run(&mut bscu, &supply(false, false), 10);
+
+        assert_eq!(bscu.active_channel(), None);
+        assert!(!bscu.normal_braking_available());
+    }
+
+    #[test]
+    fn is_not_in_control_during_self_test() {
+        let mut bscu = bscu();
+        run(&mut bscu, &supply(true, true), 1);
+
+        assert_eq!(bscu.active_channel(), None);
+        assert!(!bscu.normal_braking_available());
+    }
+
+    #[test]
+    fn channel_1_is_in_control_after_self_test() {
+        let mut bscu = bscu();
+        run(&mut bscu, &supply(true, true), 5);
+
+        assert_eq!(bscu.active_channel(), Some(1));
+        assert!(bscu.normal_braking_available());
+        assert!(bscu.autobrake_available());
+        assert!(bscu.anti_skid_available());
+        assert!(bscu.nose_wheel_steering_available());
+    }
+
+    #[test]
+    fn channel_2_takes_over_when_channel_1_fails() {
+        let mut bscu = bscu();
+        run(&mut bscu, &supply(true, true), 5);
+        bscu.fail_channel(1);
+
+        assert_eq!(bscu.active_channel(), Some(2));
+        assert!(bscu.channel_has_fault(1));
+        assert!(bscu.normal_braking_available());
+    }
+
+    #[test]
+    fn channel_2_takes_over_when_channel_1_loses_power() {
+        let mut bscu = bscu();
+        run(&mut bscu, &supply(true, true), 5);
+        run(&mut bscu, &supply(false, true), 1);
+
+        assert_eq!(bscu.active_channel(), Some(2));
+    }
+
+    #[test]
+    fn self_test_runs_again_after_power_loss() {
+        let mut bscu = bscu();
+        run(&mut bscu, &supply(true, false), 5);
+        run(&mut bscu, &supply(false, false), 1);
+        run(&mut bscu, &supply(true, false), 1);
+
+        assert_eq!(bscu.active_channel(), None);
+    }
+
+    #[test]
+    fn reverts_to_alternate_braking_when_both_channels_fail() {
+        let mut bscu = bscu();
+        run(&mut bscu, &supply(true, true), 5);
+        bscu.fail_channel(1);
+        bscu.fail_channel(2);
+
+        assert_eq!(bscu.active_channel(), None);
+        assert!(!bscu.normal_braking_available());
+        assert!(!bscu.anti_skid_available());
+    }
+
+    #[test]
+    fn reverts_to_alternate_braking_when_switch_is_off() {
+        let mut bscu = bscu();
+        run(&mut bscu, &supply(true, true), 5);
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.anti_skid_and_nws_switch_on = false;
+        bscu.read(&state);
+
+        assert_eq!(bscu.active_channel(), Some(1));
+        assert!(!bscu.normal_braking_available());
+        assert!(!bscu.anti_skid_available());
+        assert!(!bscu.nose_wheel_steering_available());
+    }
+
+    fn bscu() -> BrakeSteeringControlUnit {
+        BrakeSteeringControlUnit::new(
+            ElectricalBusType::DirectCurrentEssential,
+            ElectricalBusType::DirectCurrent(2),
+        )
+    }
+
+    fn supply(dc_ess_powered: bool, dc_2_powered: bool) -> PowerSupply {
+        let mut supply = PowerSupply::new();
+        supply.add(&bus(
+            ElectricalBusType::DirectCurrentEssential,
+            dc_ess_powered,
+        ));
+        supply.add(&bus(ElectricalBusType::DirectCurrent(2), dc_2_powered));
+
+        supply
+    }
+
+    fn bus(bus_type: ElectricalBusType, powered: bool) -> ElectricalBus {
+        let mut bus = ElectricalBus::new(bus_type);
+        bus.set_input(if powered {
+            Current::some(ElectricPowerSource::Batteries)
+        } else {
+            Current::none()
+        });
+
+        bus
+    }
+
+    fn run(bscu: &mut BrakeSteeringControlUnit, supply: &PowerSupply, seconds: u64) {
+        for channel in bscu.channels.iter_mut() {
+            channel.power_consumption.supply_power(supply);
+        }
+
+        for _ in 0..seconds * 10 {
+            bscu.update(&context_with().delta(Duration::from_millis(100)).build());
+        }
+    }
+}
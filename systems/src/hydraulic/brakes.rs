@@ -0,0 +1,713 @@
+use super::{CheckValve, ShuttleValve, ShuttleValvePort};
+use std::time::Duration;
+use uom::si::{
+    f64::*,
+    force::newton,
+    power::watt,
+    pressure::psi,
+    ratio::ratio,
+    thermodynamic_temperature::{degree_celsius, kelvin},
+    time::second,
+    velocity::knot,
+    volume::{cubic_inch, gallon},
+    volume_rate::{cubic_inch_per_second, gallon_per_second},
+};
+
+/// Accumulator which keeps the brakes pressurised when the loop feeding it is
+/// depressurised. It is charged by its loop through a check valve, so it holds
+/// its pressure once the loop pressure drops. Internal leakage slowly depletes it
+/// over the course of hours. Once its fluid is exhausted, the nitrogen keeps its
+/// pre-charge pressure but the accumulator can't deliver anything.
+pub struct BrakeAccumulator {
+    charge_check_valve: CheckValve,
+    fluid_volume: Volume,
+}
+impl BrakeAccumulator {
+    const GAS_PRE_CHARGE: f64 = 1000.; // Nitrogen PSI
+    const TOTAL_VOLUME: f64 = 0.5; // in gallons
+    const MAX_CHARGE_FLOW: f64 = 0.1; // in gallons per second
+    const LEAK_FLOW_AT_3000_PSI: f64 = 0.003; // in cubic inches per second (~11 in3 per hour)
+    const CHARGE_CHECK_VALVE_CRACKING_PRESSURE: f64 = 5.; // PSI
+
+    pub fn new() -> BrakeAccumulator {
+        BrakeAccumulator {
+            charge_check_valve: CheckValve::with_cracking_pressure(Pressure::new::<psi>(
+                BrakeAccumulator::CHARGE_CHECK_VALVE_CRACKING_PRESSURE,
+            )),
+            fluid_volume: Volume::new::<gallon>(0.),
+        }
+    }
+
+    /// The nitrogen pressure, which never drops below the gas pre-charge.
+    pub fn pressure(&self) -> Pressure {
+        let total_volume = Volume::new::<gallon>(BrakeAccumulator::TOTAL_VOLUME);
+        (Pressure::new::<psi>(BrakeAccumulator::GAS_PRE_CHARGE) * total_volume)
+            / (total_volume - self.fluid_volume.max(Volume::new::<gallon>(0.)))
+    }
+
+    /// The pressure the accumulator can deliver, none once its fluid is exhausted.
+    pub fn delivery_pressure(&self) -> Pressure {
+        if self.fluid_volume <= Volume::new::<gallon>(0.) {
+            Pressure::new::<psi>(0.)
+        } else {
+            self.pressure()
+        }
+    }
+
+    pub fn fluid_volume(&self) -> Volume {
+        self.fluid_volume
+    }
+
+    /// Charges the accumulator from the loop and leaks its fluid internally.
+    /// Returns the volume charged from the loop.
+    pub fn update(&mut self, delta_time: &Duration, loop_pressure: Pressure) -> Volume {
+        let delta_time = Time::new::<second>(delta_time.as_secs_f64());
+
+        let fluid_volume_before_charge = self.fluid_volume;
+        self.charge_check_valve
+            .update(loop_pressure, self.pressure());
+        if self.charge_check_valve.is_open() {
+            let fluid_volume_at_loop_pressure = Volume::new::<gallon>(
+                BrakeAccumulator::TOTAL_VOLUME
+                    * (1. - BrakeAccumulator::GAS_PRE_CHARGE / loop_pressure.get::<psi>()),
+            );
+            let max_charge =
+                VolumeRate::new::<gallon_per_second>(BrakeAccumulator::MAX_CHARGE_FLOW)
+                    * delta_time;
+
+            self.fluid_volume = fluid_volume_at_loop_pressure
+                .min(self.fluid_volume + max_charge)
+                .max(self.fluid_volume);
+        }
+        let charged_volume = self.fluid_volume - fluid_volume_before_charge;
+
+        let leak =
+            VolumeRate::new::<cubic_inch_per_second>(BrakeAccumulator::LEAK_FLOW_AT_3000_PSI)
+                * delta_time
+                * (self.pressure().get::<psi>() / 3000.);
+        self.draw(leak);
+
+        charged_volume
+    }
+
+    /// Draws up to the given volume of fluid from the accumulator.
+    /// Returns the volume which was actually drawn.
+    pub fn draw(&mut self, volume: Volume) -> Volume {
+        let drawn = volume.min(self.fluid_volume).max(Volume::new::<gallon>(0.));
+        self.fluid_volume -= drawn;
+
+        drawn
+    }
+}
+impl Default for BrakeAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The left and right main landing gear brakes supplied by a hydraulic loop, optionally
+/// backed up by a brake accumulator. A shuttle valve selects the accumulator to supply
+/// the brakes when the loop pressure drops below the accumulator pressure.
+pub struct BrakeCircuit {
+    accumulator: Option<BrakeAccumulator>,
+    source_selection: ShuttleValve,
+    left_brake_pressure: Pressure,
+    right_brake_pressure: Pressure,
+    charged_volume: Volume,
+    returned_volume: Volume,
+}
+impl BrakeCircuit {
+    // Volume of fluid needed to pressurise a brake by one PSI. A full application of
+    // both brakes without anti-skid takes 10 cubic inches, so a charged accumulator
+    // holds 7 of them.
+    const BRAKE_VOLUME_PER_PSI: f64 = 0.005; // in cubic inches
+    const FULL_APPLICATION_PRESSURE: f64 = 1000.; // PSI
+    const SOURCE_SELECTION_HYSTERESIS: f64 = 50.; // PSI
+
+    pub fn new() -> BrakeCircuit {
+        BrakeCircuit {
+            accumulator: Some(BrakeAccumulator::new()),
+            source_selection: BrakeCircuit::new_source_selection(),
+            left_brake_pressure: Pressure::new::<psi>(0.),
+            right_brake_pressure: Pressure::new::<psi>(0.),
+            charged_volume: Volume::new::<gallon>(0.),
+            returned_volume: Volume::new::<gallon>(0.),
+        }
+    }
+
+    pub fn new_without_accumulator() -> BrakeCircuit {
+        BrakeCircuit {
+            accumulator: None,
+            source_selection: BrakeCircuit::new_source_selection(),
+            left_brake_pressure: Pressure::new::<psi>(0.),
+            right_brake_pressure: Pressure::new::<psi>(0.),
+            charged_volume: Volume::new::<gallon>(0.),
+            returned_volume: Volume::new::<gallon>(0.),
+        }
+    }
+
+    fn new_source_selection() -> ShuttleValve {
+        ShuttleValve::new(Pressure::new::<psi>(
+            BrakeCircuit::SOURCE_SELECTION_HYSTERESIS,
+        ))
+    }
+
+    pub fn left_brake_pressure(&self) -> Pressure {
+        self.left_brake_pressure
+    }
+
+    pub fn right_brake_pressure(&self) -> Pressure {
+        self.right_brake_pressure
+    }
+
+    pub fn accumulator_pressure(&self) -> Pressure {
+        match &self.accumulator {
+            Some(accumulator) => accumulator.pressure(),
+            None => Pressure::new::<psi>(0.),
+        }
+    }
+
+    /// The volume the accumulator charged from the loop during the last update.
+    pub fn charged_volume(&self) -> Volume {
+        self.charged_volume
+    }
+
+    /// The volume which went back to the reservoir during the last update: the
+    /// accumulator internal leakage and the fluid it spent on the brakes.
+    pub fn returned_volume(&self) -> Volume {
+        self.returned_volume
+    }
+
+    /// The number of full applications of both brakes the accumulator can still
+    /// deliver on its own.
+    pub fn residual_applications(&self) -> usize {
+        match &self.accumulator {
+            Some(accumulator) => {
+                let application_volume = BrakeCircuit::volume_to_pressurise(
+                    Pressure::new::<psi>(0.),
+                    Pressure::new::<psi>(BrakeCircuit::FULL_APPLICATION_PRESSURE),
+                ) * 2.;
+
+                (accumulator.fluid_volume() / application_volume)
+                    .get::<ratio>()
+                    .floor() as usize
+            }
+            None => 0,
+        }
+    }
+
+    /// Updates the brake pressures towards the demanded pressures, limited by
+    /// the pressure available from the loop or the accumulator.
+    pub fn update(
+        &mut self,
+        delta_time: &Duration,
+        loop_pressure: Pressure,
+        left_demand: Pressure,
+        right_demand: Pressure,
+    ) {
+        if let Some(accumulator) = &mut self.accumulator {
+            let fluid_volume_before_update = accumulator.fluid_volume();
+            self.charged_volume = accumulator.update(delta_time, loop_pressure);
+            self.source_selection
+                .update(loop_pressure, accumulator.delivery_pressure());
+
+            if self.source_selection.selected() == ShuttleValvePort::Alternate {
+                let available = accumulator.delivery_pressure();
+                let required_volume = BrakeCircuit::volume_to_pressurise(
+                    self.left_brake_pressure,
+                    left_demand.min(available),
+                ) + BrakeCircuit::volume_to_pressurise(
+                    self.right_brake_pressure,
+                    right_demand.min(available),
+                );
+                accumulator.draw(required_volume);
+            }
+            self.returned_volume =
+                fluid_volume_before_update + self.charged_volume - accumulator.fluid_volume();
+        }
+
+        let available = if self.accumulator.is_some() {
+            self.source_selection.outlet_pressure()
+        } else {
+            loop_pressure
+        };
+        self.left_brake_pressure = left_demand.min(available);
+        self.right_brake_pressure = right_demand.min(available);
+    }
+
+    fn volume_to_pressurise(from: Pressure, to: Pressure) -> Volume {
+        Volume::new::<cubic_inch>(
+            (to - from).get::<psi>().max(0.) * BrakeCircuit::BRAKE_VOLUME_PER_PSI,
+        )
+    }
+}
+impl Default for BrakeCircuit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The temperature of a single wheel brake. The brake heats up by absorbing the
+/// kinetic energy of the aircraft while braking on the ground, and cools down towards
+/// the ambient temperature. Cooling is improved by airflow over the brake and by the
+/// brake fans.
+pub struct BrakeTemperature {
+    temperature: ThermodynamicTemperature,
+}
+impl BrakeTemperature {
+    const FRICTION_FORCE_PER_PSI: f64 = 16.; // in newtons
+    const HEAT_CAPACITY: f64 = 50_000.; // in joules per kelvin
+    const NATURAL_COOLING_COEFFICIENT: f64 = 14.; // in watts per kelvin
+    const FANS_COOLING_COEFFICIENT: f64 = 55.; // in watts per kelvin
+    const AIRFLOW_COOLING_COEFFICIENT_PER_KNOT: f64 = 0.5; // in watts per kelvin
+
+    pub fn new(temperature: ThermodynamicTemperature) -> BrakeTemperature {
+        BrakeTemperature { temperature }
+    }
+
+    pub fn temperature(&self) -> ThermodynamicTemperature {
+        self.temperature
+    }
+
+    pub fn update(
+        &mut self,
+        delta_time: &Duration,
+        ambient_temperature: ThermodynamicTemperature,
+        ground_speed: Velocity,
+        airspeed: Velocity,
+        brake_pressure: Pressure,
+        brake_fans_running: bool,
+    ) {
+        let delta_time = delta_time.as_secs_f64();
+
+        let friction_force = Force::new::<newton>(
+            brake_pressure.get::<psi>().max(0.) * BrakeTemperature::FRICTION_FORCE_PER_PSI,
+        );
+        let heating: Power = friction_force * ground_speed.abs();
+
+        let cooling_coefficient = if brake_fans_running {
+            BrakeTemperature::FANS_COOLING_COEFFICIENT
+        } else {
+            BrakeTemperature::NATURAL_COOLING_COEFFICIENT
+        } + BrakeTemperature::AIRFLOW_COOLING_COEFFICIENT_PER_KNOT
+            * airspeed.get::<knot>().abs();
+        let temperature_difference =
+            self.temperature.get::<kelvin>() - ambient_temperature.get::<kelvin>();
+
+        let heating_delta = heating.get::<watt>() * delta_time / BrakeTemperature::HEAT_CAPACITY;
+        // Cooling moves the brake towards the ambient temperature with the time constant
+        // of the brake heat capacity over its cooling coefficient. In this form it never
+        // overshoots the ambient temperature, whatever the time step.
+        let time_constant = BrakeTemperature::HEAT_CAPACITY / cooling_coefficient;
+        let cooling_delta = temperature_difference * delta_time / (time_constant + delta_time);
+
+        self.temperature = ThermodynamicTemperature::new::<kelvin>(
+            self.temperature.get::<kelvin>() + heating_delta - cooling_delta,
+        );
+    }
+}
+impl Default for BrakeTemperature {
+    fn default() -> Self {
+        BrakeTemperature::new(ThermodynamicTemperature::new::<degree_celsius>(15.))
+    }
+}
+
+#[cfg(test)]
+mod brake_accumulator_tests {
+    use super::*;
+
+    #[test]
+    fn starts_empty_at_gas_pre_charge() {
+        let accumulator = BrakeAccumulator::new();
+
+        assert_eq!(accumulator.fluid_volume(), Volume::new::<gallon>(0.));
+        assert_eq!(accumulator.pressure(), Pressure::new::<psi>(1000.));
+        assert_eq!(accumulator.delivery_pressure(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn charges_to_loop_pressure() {
+        let mut accumulator = BrakeAccumulator::new();
+        run(&mut accumulator, Pressure::new::<psi>(3000.), 60);
+
+        assert!((accumulator.pressure().get::<psi>() - 3000.).abs() < 10.);
+    }
+
+    #[test]
+    fn does_not_charge_below_gas_pre_charge() {
+        let mut accumulator = BrakeAccumulator::new();
+        run(&mut accumulator, Pressure::new::<psi>(900.), 60);
+
+        assert_eq!(accumulator.fluid_volume(), Volume::new::<gallon>(0.));
+    }
+
+    #[test]
+    fn holds_pressure_when_loop_depressurises() {
+        let mut accumulator = BrakeAccumulator::new();
+        run(&mut accumulator, Pressure::new::<psi>(3000.), 60);
+        run(&mut accumulator, Pressure::new::<psi>(0.), 60);
+
+        assert!(accumulator.pressure() > Pressure::new::<psi>(2900.));
+    }
+
+    #[test]
+    fn draw_is_limited_to_available_fluid() {
+        let mut accumulator = BrakeAccumulator::new();
+        run(&mut accumulator, Pressure::new::<psi>(3000.), 60);
+        let available = accumulator.fluid_volume();
+
+        assert_eq!(accumulator.draw(Volume::new::<gallon>(10.)), available);
+        assert_eq!(accumulator.delivery_pressure(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn keeps_gas_pre_charge_until_fluid_is_exhausted() {
+        let mut accumulator = BrakeAccumulator::new();
+        run(&mut accumulator, Pressure::new::<psi>(3000.), 60);
+        accumulator.draw(accumulator.fluid_volume() * 0.99);
+
+        assert!((accumulator.pressure().get::<psi>() - 1000.).abs() < 10.);
+        assert_eq!(accumulator.delivery_pressure(), accumulator.pressure());
+
+        accumulator.draw(Volume::new::<gallon>(10.));
+
+        assert_eq!(accumulator.pressure(), Pressure::new::<psi>(1000.));
+        assert_eq!(accumulator.delivery_pressure(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn charged_volume_is_the_fluid_taken_from_the_loop() {
+        let mut accumulator = BrakeAccumulator::new();
+        let mut charged_volume = Volume::new::<gallon>(0.);
+        for _ in 0..600 {
+            charged_volume +=
+                accumulator.update(&Duration::from_millis(100), Pressure::new::<psi>(3000.));
+        }
+
+        assert!(charged_volume > Volume::new::<gallon>(0.));
+        assert!(charged_volume >= accumulator.fluid_volume());
+        assert!((charged_volume - accumulator.fluid_volume()).get::<cubic_inch>() < 2.);
+    }
+
+    fn run(accumulator: &mut BrakeAccumulator, loop_pressure: Pressure, seconds: u64) {
+        for _ in 0..seconds * 10 {
+            accumulator.update(&Duration::from_millis(100), loop_pressure);
+        }
+    }
+}
+
+#[cfg(test)]
+mod brake_circuit_tests {
+    use super::*;
+
+    #[test]
+    fn brakes_are_not_pressurised_without_demand() {
+        let mut brakes = BrakeCircuit::new();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(3000.),
+            Pressure::new::<psi>(0.),
+            60,
+        );
+
+        assert_eq!(brakes.left_brake_pressure(), Pressure::new::<psi>(0.));
+        assert_eq!(brakes.right_brake_pressure(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn brakes_are_pressurised_to_demand_by_loop() {
+        let mut brakes = BrakeCircuit::new();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(3000.),
+            Pressure::new::<psi>(2000.),
+            1,
+        );
+
+        assert_eq!(brakes.left_brake_pressure(), Pressure::new::<psi>(2000.));
+        assert_eq!(brakes.right_brake_pressure(), Pressure::new::<psi>(2000.));
+    }
+
+    #[test]
+    fn brake_pressure_is_limited_by_available_pressure() {
+        let mut brakes = BrakeCircuit::new();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(800.),
+            Pressure::new::<psi>(2000.),
+            1,
+        );
+
+        assert_eq!(brakes.left_brake_pressure(), Pressure::new::<psi>(800.));
+    }
+
+    #[test]
+    fn accumulator_holds_brakes_when_loop_depressurised() {
+        let mut brakes = BrakeCircuit::new();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(3000.),
+            Pressure::new::<psi>(0.),
+            60,
+        );
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(0.),
+            Pressure::new::<psi>(1000.),
+            60,
+        );
+
+        assert_eq!(brakes.left_brake_pressure(), Pressure::new::<psi>(1000.));
+        assert_eq!(brakes.right_brake_pressure(), Pressure::new::<psi>(1000.));
+        assert!(brakes.accumulator_pressure() > Pressure::new::<psi>(2000.));
+    }
+
+    #[test]
+    fn applying_brakes_from_accumulator_lowers_accumulator_pressure() {
+        let mut brakes = BrakeCircuit::new();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(3000.),
+            Pressure::new::<psi>(0.),
+            60,
+        );
+        let charged_pressure = brakes.accumulator_pressure();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(0.),
+            Pressure::new::<psi>(2000.),
+            1,
+        );
+
+        assert!(brakes.accumulator_pressure() < charged_pressure);
+    }
+
+    #[test]
+    fn parking_brake_is_lost_after_hours_without_loop_pressure() {
+        let mut brakes = BrakeCircuit::new();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(3000.),
+            Pressure::new::<psi>(2000.),
+            60,
+        );
+
+        for _ in 0..24 * 60 {
+            brakes.update(
+                &Duration::from_secs(60),
+                Pressure::new::<psi>(0.),
+                Pressure::new::<psi>(2000.),
+                Pressure::new::<psi>(2000.),
+            );
+        }
+
+        assert!(brakes.left_brake_pressure() < Pressure::new::<psi>(100.));
+        assert!(brakes.right_brake_pressure() < Pressure::new::<psi>(100.));
+    }
+
+    #[test]
+    fn charged_accumulator_holds_seven_full_applications() {
+        let mut brakes = BrakeCircuit::new();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(3000.),
+            Pressure::new::<psi>(0.),
+            60,
+        );
+        assert_eq!(brakes.residual_applications(), 7);
+
+        for applications in 1..=7 {
+            run(
+                &mut brakes,
+                Pressure::new::<psi>(0.),
+                Pressure::new::<psi>(1000.),
+                1,
+            );
+            assert_eq!(brakes.left_brake_pressure(), Pressure::new::<psi>(1000.));
+            run(
+                &mut brakes,
+                Pressure::new::<psi>(0.),
+                Pressure::new::<psi>(0.),
+                1,
+            );
+            assert_eq!(brakes.residual_applications(), 7 - applications);
+        }
+    }
+
+    #[test]
+    fn circuit_without_accumulator_is_not_pressurised_without_loop_pressure() {
+        let mut brakes = BrakeCircuit::new_without_accumulator();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(3000.),
+            Pressure::new::<psi>(0.),
+            60,
+        );
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(0.),
+            Pressure::new::<psi>(2000.),
+            1,
+        );
+
+        assert_eq!(brakes.accumulator_pressure(), Pressure::new::<psi>(0.));
+        assert_eq!(brakes.left_brake_pressure(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn fluid_spent_from_accumulator_returns_to_reservoir() {
+        let mut brakes = BrakeCircuit::new();
+        run(
+            &mut brakes,
+            Pressure::new::<psi>(3000.),
+            Pressure::new::<psi>(0.),
+            60,
+        );
+        brakes.update(
+            &Duration::from_millis(100),
+            Pressure::new::<psi>(0.),
+            Pressure::new::<psi>(1000.),
+            Pressure::new::<psi>(1000.),
+        );
+
+        assert_eq!(brakes.charged_volume(), Volume::new::<gallon>(0.));
+        assert!(brakes.returned_volume() >= Volume::new::<cubic_inch>(10.));
+    }
+
+    fn run(brakes: &mut BrakeCircuit, loop_pressure: Pressure, demand: Pressure, seconds: u64) {
+        for _ in 0..seconds * 10 {
+            brakes.update(&Duration::from_millis(100), loop_pressure, demand, demand);
+        }
+    }
+}
+
+#[cfg(test)]
+mod brake_temperature_tests {
+    use super::*;
+
+    #[test]
+    fn heats_up_when_braking_while_moving() {
+        let mut brake = brake_at(15.);
+        run(&mut brake, 15., 100., 100., 3000., false, 20);
+
+        assert!(brake.temperature() > ThermodynamicTemperature::new::<degree_celsius>(100.));
+    }
+
+    #[test]
+    fn does_not_heat_up_when_braking_while_stationary() {
+        let mut brake = brake_at(15.);
+        run(&mut brake, 15., 0., 0., 3000., false, 60);
+
+        assert_eq!(
+            brake.temperature(),
+            ThermodynamicTemperature::new::<degree_celsius>(15.)
+        );
+    }
+
+    #[test]
+    fn does_not_heat_up_when_moving_without_braking() {
+        let mut brake = brake_at(15.);
+        run(&mut brake, 15., 100., 100., 0., false, 60);
+
+        assert_eq!(
+            brake.temperature(),
+            ThermodynamicTemperature::new::<degree_celsius>(15.)
+        );
+    }
+
+    #[test]
+    fn cools_down_towards_ambient_temperature() {
+        let mut brake = brake_at(300.);
+        run(&mut brake, 15., 0., 0., 0., false, 600);
+
+        assert!(brake.temperature() < ThermodynamicTemperature::new::<degree_celsius>(300.));
+        assert!(brake.temperature() > ThermodynamicTemperature::new::<degree_celsius>(15.));
+    }
+
+    #[test]
+    fn does_not_cool_below_ambient_temperature() {
+        let mut brake = brake_at(20.);
+        run(&mut brake, 15., 0., 0., 0., true, 24 * 3600);
+
+        assert!(
+            brake.temperature().get::<degree_celsius>() >= 15. - 0.000001,
+            "Brake cooled below ambient."
+        );
+    }
+
+    #[test]
+    fn cools_down_faster_with_brake_fans() {
+        let mut without_fans = brake_at(300.);
+        run(&mut without_fans, 15., 0., 0., 0., false, 600);
+
+        let mut with_fans = brake_at(300.);
+        run(&mut with_fans, 15., 0., 0., 0., true, 600);
+
+        assert!(with_fans.temperature() < without_fans.temperature());
+    }
+
+    #[test]
+    fn cools_down_faster_with_airflow() {
+        let mut without_airflow = brake_at(300.);
+        run(&mut without_airflow, 15., 0., 0., 0., false, 600);
+
+        let mut with_airflow = brake_at(300.);
+        run(&mut with_airflow, 15., 0., 200., 0., false, 600);
+
+        assert!(with_airflow.temperature() < without_airflow.temperature());
+    }
+
+    #[test]
+    fn cooling_does_not_depend_on_time_step() {
+        let mut fine_steps = brake_at(300.);
+        run_with_step(&mut fine_steps, Duration::from_millis(50), 600);
+
+        let mut coarse_steps = brake_at(300.);
+        run_with_step(&mut coarse_steps, Duration::from_millis(100), 600);
+
+        assert!(
+            (fine_steps.temperature().get::<degree_celsius>()
+                - coarse_steps.temperature().get::<degree_celsius>())
+            .abs()
+                < 0.1
+        );
+    }
+
+    fn run_with_step(brake: &mut BrakeTemperature, step: Duration, seconds: u64) {
+        for _ in 0..(seconds * 1000 / step.as_millis() as u64) {
+            brake.update(
+                &step,
+                ThermodynamicTemperature::new::<degree_celsius>(15.),
+                Velocity::new::<knot>(0.),
+                Velocity::new::<knot>(0.),
+                Pressure::new::<psi>(0.),
+                false,
+            );
+        }
+    }
+
+    fn brake_at(celsius: f64) -> BrakeTemperature {
+        BrakeTemperature::new(ThermodynamicTemperature::new::<degree_celsius>(celsius))
+    }
+
+    fn run(
+        brake: &mut BrakeTemperature,
+        ambient: f64,
+        ground_speed: f64,
+        airspeed: f64,
+        brake_pressure: f64,
+        brake_fans_running: bool,
+        seconds: u64,
+    ) {
+        for _ in 0..seconds * 10 {
+            brake.update(
+                &Duration::from_millis(100),
+                ThermodynamicTemperature::new::<degree_celsius>(ambient),
+                Velocity::new::<knot>(ground_speed),
+                Velocity::new::<knot>(airspeed),
+                Pressure::new::<psi>(brake_pressure),
+                brake_fans_running,
+            );
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use super::{ActuatorType, ConsumerPriority, FlowSharing, HydFluid, HydraulicRestrictor};
+use uom::si::{
+    f64::*, length::meter, length::millimeter, pressure::psi, ratio::ratio,
+    volume_rate::gallon_per_second,
+};
+
+/// A point of the high pressure side of a loop with a pressure of its own.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BranchNode {
+    /// Where the pumps, the PTU and the accumulator deliver. Its pressure is
+    /// the lumped loop pressure.
+    PumpManifold,
+    /// Secondary consumers, supplied through the priority valve.
+    PriorityValveDownstream,
+    /// Brakes, supplied through the brake line.
+    BrakeLine,
+}
+impl BranchNode {
+    /// The node the given consumer draws its flow from.
+    pub fn supplying(consumer: ActuatorType) -> Self {
+        match consumer {
+            ActuatorType::BrakesNormal
+            | ActuatorType::BrakesAlternate
+            | ActuatorType::BrakesParking => BranchNode::BrakeLine,
+            _ => match ConsumerPriority::from(consumer) {
+                ConsumerPriority::Primary => BranchNode::PumpManifold,
+                ConsumerPriority::Secondary => BranchNode::PriorityValveDownstream,
+            },
+        }
+    }
+}
+
+/// The flow drawn through each branch of a loop during its last update.
+///
+/// Branches are connected to the pump manifold through restrictors: the flow drawn
+/// through a branch lowers the pressure of its node below the manifold pressure,
+/// so braking drops the brake line pressure while steering drops the pressure
+/// downstream of the priority valve. The priority valve narrows its branch as it
+/// closes and cuts it off once closed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BranchFlows {
+    priority_valve: VolumeRate,
+    brake_line: VolumeRate,
+}
+impl BranchFlows {
+    // The fully open priority valve drops about 50 psi at 10 gpm.
+    const PRIORITY_VALVE_DIAMETER_MILLIMETER: f64 = 6.6;
+    const BRAKE_LINE_DIAMETER_MILLIMETER: f64 = 7.5;
+    const BRAKE_LINE_LENGTH_METER: f64 = 10.;
+
+    pub fn new(priority_valve: VolumeRate, brake_line: VolumeRate) -> Self {
+        BranchFlows {
+            priority_valve,
+            brake_line,
+        }
+    }
+
+    pub fn none() -> Self {
+        BranchFlows::new(
+            VolumeRate::new::<gallon_per_second>(0.),
+            VolumeRate::new::<gallon_per_second>(0.),
+        )
+    }
+
+    pub fn flow(&self, node: BranchNode) -> VolumeRate {
+        match node {
+            BranchNode::PumpManifold => VolumeRate::new::<gallon_per_second>(0.),
+            BranchNode::PriorityValveDownstream => self.priority_valve,
+            BranchNode::BrakeLine => self.brake_line,
+        }
+    }
+
+    /// The pressure of the given node when the pump manifold is at the given pressure.
+    pub fn node_pressure(
+        &self,
+        node: BranchNode,
+        manifold_pressure: Pressure,
+        fluid: &HydFluid,
+    ) -> Pressure {
+        let drop = match node {
+            BranchNode::PumpManifold => Pressure::new::<psi>(0.),
+            BranchNode::PriorityValveDownstream => {
+                let opening = FlowSharing::priority_valve_opening(manifold_pressure);
+                if opening <= 0. {
+                    return Pressure::new::<psi>(0.);
+                }
+
+                let mut priority_valve = HydraulicRestrictor::orifice(Length::new::<millimeter>(
+                    BranchFlows::PRIORITY_VALVE_DIAMETER_MILLIMETER,
+                ));
+                priority_valve.set_opening(Ratio::new::<ratio>(opening));
+                priority_valve.pressure_drop(self.priority_valve, fluid)
+            }
+            BranchNode::BrakeLine => HydraulicRestrictor::line(
+                Length::new::<millimeter>(BranchFlows::BRAKE_LINE_DIAMETER_MILLIMETER),
+                Length::new::<meter>(BranchFlows::BRAKE_LINE_LENGTH_METER),
+            )
+            .pressure_drop(self.brake_line, fluid),
+        };
+
+        (manifold_pressure - drop).max(Pressure::new::<psi>(0.))
+    }
+}
+impl Default for BranchFlows {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+#[cfg(test)]
+mod branch_tests {
+    use super::*;
+    use uom::si::pressure::pascal;
+
+    #[test]
+    fn consumers_draw_from_the_node_of_their_branch() {
+        assert_eq!(
+            BranchNode::supplying(ActuatorType::BrakesNormal),
+            BranchNode::BrakeLine
+        );
+        assert_eq!(
+            BranchNode::supplying(ActuatorType::NoseWheelSteering),
+            BranchNode::PriorityValveDownstream
+        );
+        assert_eq!(
+            BranchNode::supplying(ActuatorType::Aileron),
+            BranchNode::PumpManifold
+        );
+    }
+
+    #[test]
+    fn nodes_are_at_manifold_pressure_without_flow() {
+        let flows = BranchFlows::none();
+
+        for node in [
+            BranchNode::PumpManifold,
+            BranchNode::PriorityValveDownstream,
+            BranchNode::BrakeLine,
+        ]
+        .iter()
+        {
+            assert_eq!(flows.node_pressure(*node, psi(3000.), &fluid()), psi(3000.));
+        }
+    }
+
+    #[test]
+    fn flow_drops_the_pressure_of_its_branch_only() {
+        let flows = BranchFlows::new(
+            VolumeRate::new::<gallon_per_second>(0.),
+            VolumeRate::new::<gallon_per_second>(0.05),
+        );
+
+        assert!(flows.node_pressure(BranchNode::BrakeLine, psi(3000.), &fluid()) < psi(3000.));
+        assert_eq!(
+            flows.node_pressure(BranchNode::PriorityValveDownstream, psi(3000.), &fluid()),
+            psi(3000.)
+        );
+    }
+
+    #[test]
+    fn open_priority_valve_drops_about_50_psi_at_10_gpm() {
+        let flows = BranchFlows::new(
+            VolumeRate::new::<gallon_per_second>(0.1667),
+            VolumeRate::new::<gallon_per_second>(0.),
+        );
+        let drop = psi(3000.)
+            - flows.node_pressure(BranchNode::PriorityValveDownstream, psi(3000.), &fluid());
+
+        assert!(drop > psi(40.) && drop < psi(60.));
+    }
+
+    #[test]
+    fn closing_priority_valve_increases_the_drop() {
+        let flows = BranchFlows::new(
+            VolumeRate::new::<gallon_per_second>(0.1667),
+            VolumeRate::new::<gallon_per_second>(0.),
+        );
+
+        let open_drop = psi(3000.)
+            - flows.node_pressure(BranchNode::PriorityValveDownstream, psi(3000.), &fluid());
+        let closing_drop = psi(2000.)
+            - flows.node_pressure(BranchNode::PriorityValveDownstream, psi(2000.), &fluid());
+
+        assert!(closing_drop > open_drop);
+    }
+
+    #[test]
+    fn closed_priority_valve_cuts_its_branch_off() {
+        assert_eq!(
+            BranchFlows::none().node_pressure(
+                BranchNode::PriorityValveDownstream,
+                psi(1500.),
+                &fluid()
+            ),
+            psi(0.)
+        );
+    }
+
+    fn fluid() -> HydFluid {
+        HydFluid::new(Pressure::new::<pascal>(1450000000.))
+    }
+
+    fn psi(pressure: f64) -> Pressure {
+        Pressure::new::<psi>(pressure)
+    }
+}
@@ -0,0 +1,126 @@
+use uom::si::{f64::*, pressure::psi, volume::gallon};
+
+/// A check valve lets fluid flow one way only. At a pump outlet, fluid can flow
+/// from the pump into the loop but not back: a stopped, failed or reversed pump
+/// can't drain the loop through its outlet into the reservoir. In front of an
+/// accumulator, it isolates the accumulator once the loop pressure drops.
+///
+/// The valve only cracks open once the upstream pressure exceeds the downstream
+/// pressure by its cracking pressure, and reseats as soon as the flow would reverse.
+pub struct CheckValve {
+    cracking_pressure: Pressure,
+    open: bool,
+}
+impl CheckValve {
+    pub fn new() -> Self {
+        CheckValve::with_cracking_pressure(Pressure::new::<psi>(0.))
+    }
+
+    pub fn with_cracking_pressure(cracking_pressure: Pressure) -> Self {
+        CheckValve {
+            cracking_pressure,
+            open: false,
+        }
+    }
+
+    /// Opens or closes the valve from the pressures on both of its sides.
+    pub fn update(&mut self, upstream_pressure: Pressure, downstream_pressure: Pressure) {
+        let pressure_difference = upstream_pressure - downstream_pressure;
+
+        self.open = if self.open {
+            pressure_difference > Pressure::new::<psi>(0.)
+        } else {
+            pressure_difference > self.cracking_pressure
+        };
+    }
+
+    /// Returns the volume which passes the valve when the pump pushes the given
+    /// volume towards the loop. Negative volumes would flow back and close the valve.
+    pub fn pass(&mut self, volume: Volume) -> Volume {
+        self.open = volume > Volume::new::<gallon>(0.);
+
+        if self.open {
+            volume
+        } else {
+            Volume::new::<gallon>(0.)
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+}
+impl Default for CheckValve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod check_valve_tests {
+    use super::*;
+
+    #[test]
+    fn starts_closed() {
+        assert!(!CheckValve::new().is_open());
+    }
+
+    #[test]
+    fn lets_pump_flow_into_the_loop() {
+        let mut valve = CheckValve::new();
+
+        assert_eq!(
+            valve.pass(Volume::new::<gallon>(0.5)),
+            Volume::new::<gallon>(0.5)
+        );
+        assert!(valve.is_open());
+    }
+
+    #[test]
+    fn stops_back_flow() {
+        let mut valve = CheckValve::new();
+        valve.pass(Volume::new::<gallon>(0.5));
+
+        assert_eq!(
+            valve.pass(Volume::new::<gallon>(-0.5)),
+            Volume::new::<gallon>(0.)
+        );
+        assert!(!valve.is_open());
+    }
+
+    #[test]
+    fn stays_closed_below_cracking_pressure() {
+        let mut valve = CheckValve::with_cracking_pressure(Pressure::new::<psi>(20.));
+        valve.update(Pressure::new::<psi>(3010.), Pressure::new::<psi>(3000.));
+
+        assert!(!valve.is_open());
+    }
+
+    #[test]
+    fn opens_above_cracking_pressure() {
+        let mut valve = CheckValve::with_cracking_pressure(Pressure::new::<psi>(20.));
+        valve.update(Pressure::new::<psi>(3030.), Pressure::new::<psi>(3000.));
+
+        assert!(valve.is_open());
+    }
+
+    #[test]
+    fn open_valve_stays_open_until_the_flow_would_reverse() {
+        let mut valve = CheckValve::with_cracking_pressure(Pressure::new::<psi>(20.));
+        valve.update(Pressure::new::<psi>(3030.), Pressure::new::<psi>(3000.));
+        valve.update(Pressure::new::<psi>(3005.), Pressure::new::<psi>(3000.));
+        assert!(valve.is_open());
+
+        valve.update(Pressure::new::<psi>(2990.), Pressure::new::<psi>(3000.));
+        assert!(!valve.is_open());
+    }
+
+    #[test]
+    fn closes_without_flow() {
+        let mut valve = CheckValve::new();
+        valve.pass(Volume::new::<gallon>(0.5));
+        valve.pass(Volume::new::<gallon>(0.));
+
+        assert!(!valve.is_open());
+    }
+}
@@ -0,0 +1,348 @@
+use super::LoopId;
+use serde::Deserialize;
+use std::{error::Error, fmt, fs, io, path::Path};
+
+/// Pump displacement in cubic inches per revolution at the given loop pressures in psi.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DisplacementMap {
+    pub pressure_breakpoints: [f64; 9],
+    pub displacement: [f64; 9],
+}
+
+/// Accumulator flow in gallons per second at the given pressure differences in psi.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AccumulatorCharacteristic {
+    pub pressure_breakpoints: [f64; 9],
+    pub flow: [f64; 9],
+}
+
+/// Reservoir volumes in gallons.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ReservoirVolumes {
+    pub blue: Option<f64>,
+    pub green: Option<f64>,
+    pub yellow: Option<f64>,
+}
+impl ReservoirVolumes {
+    pub fn volume(&self, color: LoopId) -> Option<f64> {
+        match color {
+            LoopId::BLUE => self.blue,
+            LoopId::GREEN => self.green,
+            LoopId::YELLOW => self.yellow,
+            _ => None,
+        }
+    }
+}
+
+/// Time constants in seconds of the low-pass filters smoothing the model's outputs.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FilterTimeConstants {
+    /// Brake and accumulator pressure indicator needles.
+    pub gauge_needle: Option<f64>,
+    /// Ram air turbine speed.
+    pub rat_speed: Option<f64>,
+    /// Decay of the PTU bark.
+    pub ptu_barking_decay: Option<f64>,
+}
+impl FilterTimeConstants {
+    fn all(&self) -> [(&'static str, Option<f64>); 3] {
+        [
+            ("gauge_needle", self.gauge_needle),
+            ("rat_speed", self.rat_speed),
+            ("ptu_barking_decay", self.ptu_barking_decay),
+        ]
+    }
+}
+
+/// Tuning parameters of the hydraulic model which are loaded at startup.
+/// Any parameter which isn't configured keeps its built-in value.
+///
+/// ```toml
+/// [engine_driven_pump]
+/// pressure_breakpoints = [0.0, 500.0, 1000.0, 1500.0, 2800.0, 2900.0, 3000.0, 3050.0, 3500.0]
+/// displacement = [2.4, 2.4, 2.4, 2.4, 2.4, 2.4, 2.0, 0.0, 0.0]
+///
+/// [reservoir_volume]
+/// green = 3.3
+///
+/// [filter_time_constants]
+/// gauge_needle = 0.5
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HydraulicConfig {
+    pub electric_pump: Option<DisplacementMap>,
+    pub engine_driven_pump: Option<DisplacementMap>,
+    pub accumulator: Option<AccumulatorCharacteristic>,
+    #[serde(default)]
+    pub reservoir_volume: ReservoirVolumes,
+    #[serde(default)]
+    pub filter_time_constants: FilterTimeConstants,
+}
+impl HydraulicConfig {
+    pub fn from_toml(content: &str) -> Result<HydraulicConfig, HydraulicConfigError> {
+        let config: HydraulicConfig =
+            toml::from_str(content).map_err(HydraulicConfigError::Parse)?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<HydraulicConfig, HydraulicConfigError> {
+        let content = fs::read_to_string(path).map_err(HydraulicConfigError::Io)?;
+
+        HydraulicConfig::from_toml(&content)
+    }
+
+    fn validate(&self) -> Result<(), HydraulicConfigError> {
+        if let Some(map) = &self.electric_pump {
+            validate_table(
+                "electric_pump",
+                &map.pressure_breakpoints,
+                &map.displacement,
+            )?;
+        }
+        if let Some(map) = &self.engine_driven_pump {
+            validate_table(
+                "engine_driven_pump",
+                &map.pressure_breakpoints,
+                &map.displacement,
+            )?;
+        }
+        if let Some(characteristic) = &self.accumulator {
+            validate_table(
+                "accumulator",
+                &characteristic.pressure_breakpoints,
+                &characteristic.flow,
+            )?;
+        }
+
+        for color in &[LoopId::BLUE, LoopId::GREEN, LoopId::YELLOW] {
+            if let Some(volume) = self.reservoir_volume.volume(*color) {
+                if !(volume.is_finite() && volume > 0.) {
+                    return Err(HydraulicConfigError::Invalid(format!(
+                        "{:?} reservoir volume must be positive.",
+                        color
+                    )));
+                }
+            }
+        }
+
+        for (name, time_constant) in self.filter_time_constants.all().iter() {
+            if let Some(time_constant) = time_constant {
+                if !(time_constant.is_finite() && *time_constant >= 0.) {
+                    return Err(HydraulicConfigError::Invalid(format!(
+                        "{} filter time constant must not be negative.",
+                        name
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Interpolated tables need strictly increasing breakpoints and finite, non negative values.
+fn validate_table(
+    name: &str,
+    breakpoints: &[f64],
+    values: &[f64],
+) -> Result<(), HydraulicConfigError> {
+    if breakpoints.iter().any(|x| !x.is_finite())
+        || breakpoints.windows(2).any(|pair| pair[0] >= pair[1])
+    {
+        return Err(HydraulicConfigError::Invalid(format!(
+            "{} pressure breakpoints must be strictly increasing.",
+            name
+        )));
+    }
+
+    if values.iter().any(|y| !(y.is_finite() && *y >= 0.)) {
+        return Err(HydraulicConfigError::Invalid(format!(
+            "{} values must not be negative.",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum HydraulicConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+impl fmt::Display for HydraulicConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HydraulicConfigError::Io(error) => write!(f, "Cannot read hydraulic config: {}", error),
+            HydraulicConfigError::Parse(error) => {
+                write!(f, "Cannot parse hydraulic config: {}", error)
+            }
+            HydraulicConfigError::Invalid(reason) => {
+                write!(f, "Invalid hydraulic config: {}", reason)
+            }
+        }
+    }
+}
+impl Error for HydraulicConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            HydraulicConfigError::Io(error) => Some(error),
+            HydraulicConfigError::Parse(error) => Some(error),
+            HydraulicConfigError::Invalid(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    #[test]
+    fn empty_config_keeps_everything_built_in() {
+        let config = HydraulicConfig::from_toml("").unwrap();
+
+        assert_eq!(config, HydraulicConfig::default());
+    }
+
+    #[test]
+    fn loads_pump_displacement_map() {
+        let config = HydraulicConfig::from_toml(
+            r#"
+            [engine_driven_pump]
+            pressure_breakpoints = [0.0, 500.0, 1000.0, 1500.0, 2800.0, 2900.0, 3000.0, 3050.0, 3500.0]
+            displacement = [2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 1.5, 0.0, 0.0]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.engine_driven_pump.unwrap().displacement[0], 2.0);
+        assert!(config.electric_pump.is_none());
+    }
+
+    #[test]
+    fn loads_accumulator_characteristic() {
+        let config = HydraulicConfig::from_toml(
+            r#"
+            [accumulator]
+            pressure_breakpoints = [0.0, 5.0, 10.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 10000.0]
+            flow = [0.0, 0.005, 0.008, 0.01, 0.02, 0.08, 0.15, 0.35, 0.5]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.accumulator.unwrap().flow[8], 0.5);
+    }
+
+    #[test]
+    fn loads_reservoir_volumes() {
+        let config = HydraulicConfig::from_toml(
+            r#"
+            [reservoir_volume]
+            green = 4.0
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.reservoir_volume.volume(LoopId::GREEN), Some(4.0));
+        assert_eq!(config.reservoir_volume.volume(LoopId::BLUE), None);
+    }
+
+    #[test]
+    fn loads_filter_time_constants() {
+        let config = HydraulicConfig::from_toml(
+            r#"
+            [filter_time_constants]
+            rat_speed = 1.5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.filter_time_constants.rat_speed, Some(1.5));
+        assert_eq!(config.filter_time_constants.gauge_needle, None);
+    }
+
+    #[test]
+    fn rejects_negative_filter_time_constant() {
+        let result = HydraulicConfig::from_toml(
+            r#"
+            [filter_time_constants]
+            gauge_needle = -0.5
+            "#,
+        );
+
+        assert!(matches!(result, Err(HydraulicConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn rejects_table_of_wrong_length() {
+        let result = HydraulicConfig::from_toml(
+            r#"
+            [electric_pump]
+            pressure_breakpoints = [0.0, 500.0]
+            displacement = [0.263, 0.0]
+            "#,
+        );
+
+        assert!(matches!(result, Err(HydraulicConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn rejects_unknown_parameters() {
+        let result = HydraulicConfig::from_toml("ptu_gain = 1.0");
+
+        assert!(matches!(result, Err(HydraulicConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn rejects_decreasing_breakpoints() {
+        let result = HydraulicConfig::from_toml(
+            r#"
+            [electric_pump]
+            pressure_breakpoints = [0.0, 500.0, 1000.0, 1500.0, 2800.0, 2900.0, 3000.0, 3500.0, 3050.0]
+            displacement = [0.263, 0.263, 0.263, 0.263, 0.263, 0.263, 0.163, 0.0, 0.0]
+            "#,
+        );
+
+        assert!(matches!(result, Err(HydraulicConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn rejects_negative_displacement() {
+        let result = HydraulicConfig::from_toml(
+            r#"
+            [electric_pump]
+            pressure_breakpoints = [0.0, 500.0, 1000.0, 1500.0, 2800.0, 2900.0, 3000.0, 3050.0, 3500.0]
+            displacement = [0.263, 0.263, 0.263, 0.263, 0.263, 0.263, 0.163, -0.1, 0.0]
+            "#,
+        );
+
+        assert!(matches!(result, Err(HydraulicConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn rejects_empty_reservoir() {
+        let result = HydraulicConfig::from_toml(
+            r#"
+            [reservoir_volume]
+            yellow = 0.0
+            "#,
+        );
+
+        assert!(matches!(result, Err(HydraulicConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn reports_missing_file() {
+        let result = HydraulicConfig::from_file("does/not/exist.toml");
+
+        assert!(matches!(result, Err(HydraulicConfigError::Io(_))));
+    }
+}
@@ -0,0 +1,27 @@
+use super::FlowDemand;
+use uom::si::{f64::*, volume_rate::gallon_per_second};
+
+/// A hydraulic load registered on a loop with `HydLoop::register_consumer`.
+/// On each update the loop asks every registered consumer for its demand, shares
+/// its flow between them, tells each of them the pressure and flow it got and
+/// takes back the fluid they return to the reservoir.
+///
+/// Fluid a consumer is served and doesn't return, such as the volume held in an
+/// extending actuator, leaves the loop until the consumer returns it.
+pub trait HydraulicConsumer {
+    /// The flow needed to move at full rate at nominal loop pressure.
+    fn demanded_flow(&self) -> FlowDemand;
+
+    /// The flow currently sent back to the reservoir.
+    fn returned_flow(&self) -> VolumeRate;
+
+    /// Called once per loop update with the loop pressure and the flow the
+    /// consumer was served from its demand.
+    fn set_supply_pressure(&mut self, pressure: Pressure, served_flow: VolumeRate);
+
+    /// Internal leakage of the consumer's actuators at 3000 psi, which adds to
+    /// the static leaks of the loop and flows back to the reservoir.
+    fn static_leak_at_3000_psi(&self) -> VolumeRate {
+        VolumeRate::new::<gallon_per_second>(0.)
+    }
+}
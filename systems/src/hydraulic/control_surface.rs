@@ -0,0 +1,447 @@
+use super::{ActuatorRate, HingeLoad};
+use crate::simulator::UpdateContext;
+use std::time::Duration;
+use uom::si::{
+    angle::degree, angular_velocity::degree_per_second, f64::*, force::newton, velocity::knot,
+};
+
+/// What a flight control surface does once its actuators can no longer hold it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnpoweredBehaviour {
+    /// The surface falls under gravity to its trailing edge down stop.
+    Droop,
+    /// The airflow over the wing lifts the surface to a small floating angle.
+    Float,
+    /// The actuators switch to damping mode: the surface slowly trails with
+    /// the airflow, or sags to its trailing edge down stop on the ground.
+    Damped,
+}
+
+/// A flight control surface moved by hydraulic actuators.
+/// Positive angles are trailing edge down, or extended for spoilers.
+pub struct ControlSurface {
+    behaviour: UnpoweredBehaviour,
+    position: Angle,
+    // Position at the start of the last simulation step.
+    previous_position: Angle,
+    rate: AngularVelocity,
+    powered: bool,
+    min_position: Angle,
+    max_position: Angle,
+    max_rate_degrees_per_second: f64,
+    unpowered_rate_degrees_per_second: f64,
+    actuator_rate: ActuatorRate,
+    // Gravity and aerodynamic load the actuators need to overcome to hold the surface.
+    hold_load: Force,
+    hinge_load: Option<HingeLoad>,
+}
+impl ControlSurface {
+    // Below this airspeed the airflow has no noticeable effect on an unpowered surface.
+    const AIRFLOW_MIN_SPEED_KNOTS: f64 = 60.;
+    const SPOILER_FLOATING_ANGLE_DEGREES: f64 = 3.;
+
+    pub fn new(
+        behaviour: UnpoweredBehaviour,
+        min_position: Angle,
+        max_position: Angle,
+        max_rate_degrees_per_second: f64,
+        unpowered_rate_degrees_per_second: f64,
+        stall_load: Force,
+        hold_load: Force,
+    ) -> Self {
+        ControlSurface {
+            behaviour,
+            position: Angle::new::<degree>(0.),
+            previous_position: Angle::new::<degree>(0.),
+            rate: AngularVelocity::new::<degree_per_second>(0.),
+            powered: false,
+            min_position,
+            max_position,
+            max_rate_degrees_per_second,
+            unpowered_rate_degrees_per_second,
+            actuator_rate: ActuatorRate::new(stall_load),
+            hold_load,
+            hinge_load: None,
+        }
+    }
+
+    /// Makes the aerodynamic load on the surface slow down or stall its actuators.
+    pub fn with_hinge_load(mut self, hinge_load: HingeLoad) -> Self {
+        self.hinge_load = Some(hinge_load);
+        self
+    }
+
+    /// The aileron of the given wing (1 for left, 2 for right).
+    pub fn aileron(wing_number: usize) -> Self {
+        ControlSurface::new(
+            UnpoweredBehaviour::Droop,
+            Angle::new::<degree>(-25.),
+            Angle::new::<degree>(25.),
+            40.,
+            10.,
+            Force::new::<newton>(47000.),
+            Force::new::<newton>(2000.),
+        )
+        .with_hinge_load(HingeLoad::aileron(wing_number))
+    }
+
+    pub fn spoiler() -> Self {
+        ControlSurface::new(
+            UnpoweredBehaviour::Float,
+            Angle::new::<degree>(0.),
+            Angle::new::<degree>(50.),
+            40.,
+            5.,
+            Force::new::<newton>(35000.),
+            Force::new::<newton>(1000.),
+        )
+    }
+
+    pub fn elevator() -> Self {
+        ControlSurface::new(
+            UnpoweredBehaviour::Damped,
+            Angle::new::<degree>(-30.),
+            Angle::new::<degree>(17.),
+            30.,
+            1.,
+            Force::new::<newton>(60000.),
+            Force::new::<newton>(3000.),
+        )
+        .with_hinge_load(HingeLoad::elevator())
+    }
+
+    /// Moves the surface towards the commanded position when the actuators are pressurised
+    /// by the given pressure, or lets it go to its unpowered position otherwise.
+    pub fn update(
+        &mut self,
+        delta_time: &Duration,
+        context: &UpdateContext,
+        pressure: Pressure,
+        commanded: Angle,
+    ) {
+        self.update_with_external_load(delta_time, context, pressure, commanded, None);
+    }
+
+    /// Updates the surface against the given external load instead of its own
+    /// hinge load model when there is one, see `ExternalLoads`.
+    pub fn update_with_external_load(
+        &mut self,
+        delta_time: &Duration,
+        context: &UpdateContext,
+        pressure: Pressure,
+        commanded: Angle,
+        external_load: Option<Force>,
+    ) {
+        self.powered = self.actuator_rate.rate_ratio(pressure, self.hold_load) > 0.;
+        let (target, rate_degrees_per_second) = if self.powered {
+            // The aerodynamic load opposes moving away from the trailing position and aids moving back to it.
+            let aerodynamic_load = external_load.unwrap_or_else(|| {
+                self.hinge_load
+                    .map_or(Force::new::<newton>(0.), |hinge_load| {
+                        hinge_load.load(context, self.position)
+                    })
+            });
+            let opposing_load = if commanded >= self.position {
+                aerodynamic_load
+            } else {
+                -aerodynamic_load
+            };
+            let rate_ratio = self
+                .actuator_rate
+                .rate_ratio(pressure, self.hold_load + opposing_load);
+
+            (commanded, self.max_rate_degrees_per_second * rate_ratio)
+        } else {
+            (
+                self.unpowered_position(context),
+                self.unpowered_rate_degrees_per_second,
+            )
+        };
+
+        let max_step = Angle::new::<degree>(rate_degrees_per_second * delta_time.as_secs_f64());
+        let step = (target - self.position).max(-max_step).min(max_step);
+        let position = (self.position + step)
+            .max(self.min_position)
+            .min(self.max_position);
+        self.rate = if delta_time.as_secs_f64() > 0. {
+            AngularVelocity::new::<degree_per_second>(
+                (position - self.position).get::<degree>() / delta_time.as_secs_f64(),
+            )
+        } else {
+            AngularVelocity::new::<degree_per_second>(0.)
+        };
+        self.position = position;
+    }
+
+    /// Indicates if the actuators held the surface during its last update. A powered
+    /// spoiler is maintained at its commanded position, retracted unless deflected,
+    /// while an unpowered one is blown down by the airflow and floats.
+    pub fn is_powered(&self) -> bool {
+        self.powered
+    }
+
+    /// The rate the surface moved at during its last update.
+    pub fn rate(&self) -> AngularVelocity {
+        self.rate
+    }
+
+    fn unpowered_position(&self, context: &UpdateContext) -> Angle {
+        let in_airflow = context.indicated_airspeed
+            > Velocity::new::<knot>(ControlSurface::AIRFLOW_MIN_SPEED_KNOTS);
+
+        match self.behaviour {
+            UnpoweredBehaviour::Droop => self.max_position,
+            UnpoweredBehaviour::Float if in_airflow => {
+                Angle::new::<degree>(ControlSurface::SPOILER_FLOATING_ANGLE_DEGREES)
+            }
+            UnpoweredBehaviour::Float => Angle::new::<degree>(0.),
+            UnpoweredBehaviour::Damped if in_airflow => Angle::new::<degree>(0.),
+            UnpoweredBehaviour::Damped => self.max_position,
+        }
+    }
+
+    pub fn position(&self) -> Angle {
+        self.position
+    }
+
+    /// Marks the start of a simulation step, which positions shown between
+    /// steps are interpolated from.
+    pub fn store_previous_position(&mut self) {
+        self.previous_position = self.position;
+    }
+
+    /// The position the given ratio of the way through the last simulation step,
+    /// for showing the surface moving smoothly when frames are shorter than a step.
+    pub fn interpolated_position(&self, ratio: f64) -> Angle {
+        self.previous_position + (self.position - self.previous_position) * ratio
+    }
+}
+
+#[cfg(test)]
+mod control_surface_tests {
+    use super::*;
+    use crate::simulator::test_helpers::context_with;
+    use uom::si::pressure::psi;
+
+    #[test]
+    fn pressurised_surface_is_held_at_commanded_position() {
+        let mut aileron = ControlSurface::aileron(1);
+        run(&mut aileron, 0., 3000., 10.);
+
+        assert_position(&aileron, 10.);
+    }
+
+    #[test]
+    fn pressurised_surface_moves_at_limited_rate() {
+        let mut aileron = ControlSurface::aileron(1);
+        update(&mut aileron, 0., 3000., 25., Duration::from_millis(100));
+
+        assert!(aileron.position() < Angle::new::<degree>(25.));
+        assert!(aileron.position() > Angle::new::<degree>(0.));
+    }
+
+    #[test]
+    fn aerodynamic_load_slows_surface_at_high_airspeed() {
+        let mut slow_aileron = ControlSurface::aileron(1);
+        let mut fast_aileron = ControlSurface::aileron(1);
+        update(
+            &mut slow_aileron,
+            100.,
+            3000.,
+            20.,
+            Duration::from_millis(100),
+        );
+        update(
+            &mut fast_aileron,
+            300.,
+            3000.,
+            20.,
+            Duration::from_millis(100),
+        );
+        update(
+            &mut slow_aileron,
+            100.,
+            3000.,
+            20.,
+            Duration::from_millis(100),
+        );
+        update(
+            &mut fast_aileron,
+            300.,
+            3000.,
+            20.,
+            Duration::from_millis(100),
+        );
+
+        assert!(fast_aileron.position() < slow_aileron.position());
+    }
+
+    #[test]
+    fn surface_stalls_against_aerodynamic_load_at_low_pressure() {
+        let mut aileron = ControlSurface::aileron(1);
+        for _ in 0..100 {
+            update(&mut aileron, 300., 1000., 20., Duration::from_millis(100));
+        }
+
+        assert!(aileron.position() > Angle::new::<degree>(0.));
+        assert!(aileron.position() < Angle::new::<degree>(20.));
+    }
+
+    #[test]
+    fn unpowered_aileron_droops() {
+        let mut aileron = ControlSurface::aileron(1);
+        run(&mut aileron, 0., 0., 0.);
+
+        assert_position(&aileron, 25.);
+    }
+
+    #[test]
+    fn unpowered_spoiler_floats_in_flight() {
+        let mut spoiler = ControlSurface::spoiler();
+        run(&mut spoiler, 250., 0., 0.);
+
+        assert_position(&spoiler, ControlSurface::SPOILER_FLOATING_ANGLE_DEGREES);
+    }
+
+    #[test]
+    fn extended_spoiler_blows_down_when_unpowered() {
+        let mut spoiler = ControlSurface::spoiler();
+        run(&mut spoiler, 250., 3000., 40.);
+        assert!(spoiler.is_powered());
+
+        run(&mut spoiler, 250., 0., 40.);
+
+        assert!(!spoiler.is_powered());
+        assert_position(&spoiler, ControlSurface::SPOILER_FLOATING_ANGLE_DEGREES);
+    }
+
+    #[test]
+    fn unpowered_spoiler_rests_retracted_on_ground() {
+        let mut spoiler = ControlSurface::spoiler();
+        run(&mut spoiler, 0., 0., 0.);
+
+        assert_position(&spoiler, 0.);
+    }
+
+    #[test]
+    fn unpowered_elevator_trails_in_flight() {
+        let mut elevator = ControlSurface::elevator();
+        run(&mut elevator, 0., 3000., -10.);
+
+        run(&mut elevator, 250., 0., -10.);
+
+        assert_position(&elevator, 0.);
+    }
+
+    #[test]
+    fn unpowered_elevator_sags_slowly_on_ground() {
+        let mut elevator = ControlSurface::elevator();
+        update(&mut elevator, 0., 0., 0., Duration::from_secs(5));
+
+        assert_position(&elevator, 5.);
+    }
+
+    #[test]
+    fn residual_pressure_cannot_hold_surface() {
+        let mut aileron = ControlSurface::aileron(1);
+        run(&mut aileron, 0., 14.7, 0.);
+
+        assert_position(&aileron, 25.);
+    }
+
+    #[test]
+    fn interpolated_position_lies_within_last_step() {
+        let mut aileron = ControlSurface::aileron(1);
+        aileron.store_previous_position();
+        update(&mut aileron, 0., 0., 0., Duration::from_secs(1));
+
+        assert_eq!(aileron.interpolated_position(0.), Angle::new::<degree>(0.));
+        assert!((aileron.interpolated_position(0.5).get::<degree>() - 5.).abs() < 1e-9);
+        assert_eq!(aileron.interpolated_position(1.), aileron.position());
+    }
+
+    #[test]
+    fn external_load_replaces_the_hinge_load() {
+        let mut aileron = ControlSurface::aileron(1);
+        let context = context_with()
+            .indicated_airspeed(Velocity::new::<knot>(300.))
+            .build();
+        for _ in 0..100 {
+            aileron.update_with_external_load(
+                &Duration::from_millis(100),
+                &context,
+                Pressure::new::<psi>(3000.),
+                Angle::new::<degree>(20.),
+                Some(Force::new::<newton>(0.)),
+            );
+        }
+
+        assert_position(&aileron, 20.);
+    }
+
+    #[test]
+    fn surface_stalls_against_large_external_load() {
+        let mut aileron = ControlSurface::aileron(1);
+        aileron.update_with_external_load(
+            &Duration::from_millis(100),
+            &context_with().build(),
+            Pressure::new::<psi>(3000.),
+            Angle::new::<degree>(20.),
+            Some(Force::new::<newton>(100000.)),
+        );
+
+        assert_position(&aileron, 0.);
+        assert_eq!(
+            aileron.rate(),
+            AngularVelocity::new::<degree_per_second>(0.)
+        );
+    }
+
+    #[test]
+    fn rate_is_the_movement_over_the_last_update() {
+        let mut spoiler = ControlSurface::spoiler();
+        update(&mut spoiler, 0., 3000., 40., Duration::from_millis(100));
+
+        assert!(spoiler.rate().get::<degree_per_second>() > 0.);
+        assert!(
+            (spoiler.rate().get::<degree_per_second>() - spoiler.position().get::<degree>() / 0.1)
+                .abs()
+                < 1e-9
+        );
+    }
+
+    fn assert_position(surface: &ControlSurface, degrees: f64) {
+        assert!((surface.position().get::<degree>() - degrees).abs() < 1e-9);
+    }
+
+    // Updates long enough for the surface to reach its final position.
+    fn run(surface: &mut ControlSurface, airspeed: f64, pressure: f64, commanded: f64) {
+        for _ in 0..60 {
+            update(
+                surface,
+                airspeed,
+                pressure,
+                commanded,
+                Duration::from_secs(1),
+            );
+        }
+    }
+
+    fn update(
+        surface: &mut ControlSurface,
+        airspeed: f64,
+        pressure: f64,
+        commanded: f64,
+        delta: Duration,
+    ) {
+        surface.update(
+            &delta,
+            &context_with()
+                .delta(delta)
+                .indicated_airspeed(Velocity::new::<knot>(airspeed))
+                .build(),
+            Pressure::new::<psi>(pressure),
+            Angle::new::<degree>(commanded),
+        );
+    }
+}
@@ -0,0 +1,506 @@
+use super::{
+    AccumulatorCharacteristic, DisplacementMap, EngineVariant, HydFluid, HydLoop, HydraulicConfig,
+    IntegrationScheme, LoopId,
+};
+use uom::si::{f64::*, pressure::pascal, volume::gallon};
+
+/// The physical dimensions of a single hydraulic loop.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HydraulicLoopDescription {
+    pub color: LoopId,
+    pub loop_volume: Volume,
+    pub max_loop_volume: Volume,
+    pub high_pressure_volume: Volume,
+    pub reservoir_volume: Volume,
+    pub fluid_bulk_modulus: Pressure,
+}
+impl HydraulicLoopDescription {
+    const A320_FLUID_BULK_MODULUS_PASCAL: f64 = 1450000000.;
+
+    pub fn a320_blue() -> Self {
+        HydraulicLoopDescription::a320(LoopId::BLUE, 15.7, 15.85, 10., 1.7)
+    }
+
+    pub fn a320_green() -> Self {
+        HydraulicLoopDescription::a320(LoopId::GREEN, 10.2, 10.2, 8., 3.3)
+    }
+
+    pub fn a320_yellow() -> Self {
+        HydraulicLoopDescription::a320(LoopId::YELLOW, 26., 26.41, 10., 3.83)
+    }
+
+    fn a320(
+        color: LoopId,
+        loop_gallons: f64,
+        max_loop_gallons: f64,
+        high_pressure_gallons: f64,
+        reservoir_gallons: f64,
+    ) -> Self {
+        HydraulicLoopDescription {
+            color,
+            loop_volume: Volume::new::<gallon>(loop_gallons),
+            max_loop_volume: Volume::new::<gallon>(max_loop_gallons),
+            high_pressure_volume: Volume::new::<gallon>(high_pressure_gallons),
+            reservoir_volume: Volume::new::<gallon>(reservoir_gallons),
+            fluid_bulk_modulus: Pressure::new::<pascal>(
+                HydraulicLoopDescription::A320_FLUID_BULK_MODULUS_PASCAL,
+            ),
+        }
+    }
+
+    /// Builds the loop, connected to the side of the PTU given by the PTU's
+    /// description when the PTU connects it.
+    pub fn build(&self, ptu: Option<PtuDescription>) -> HydLoop {
+        HydLoop::new(
+            self.color,
+            matches!(ptu, Some(ptu) if ptu.left_loop == self.color),
+            matches!(ptu, Some(ptu) if ptu.right_loop == self.color),
+            self.loop_volume,
+            self.max_loop_volume,
+            self.high_pressure_volume,
+            self.reservoir_volume,
+            HydFluid::new(self.fluid_bulk_modulus),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EngineDrivenPumpDescription {
+    pub engine_number: usize,
+    pub loop_color: LoopId,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ElectricPumpDescription {
+    pub loop_color: LoopId,
+}
+
+/// The PTU's left side is its non variable displacement side,
+/// its right side is the variable displacement side.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PtuDescription {
+    pub left_loop: LoopId,
+    pub right_loop: LoopId,
+}
+impl PtuDescription {
+    /// The A320 PTU, whose fixed displacement side is on the green loop.
+    pub fn a320() -> Self {
+        PtuDescription {
+            left_loop: LoopId::GREEN,
+            right_loop: LoopId::YELLOW,
+        }
+    }
+}
+
+/// Declares which loops an aircraft's hydraulic system consists of, which pumps
+/// pressurise them and how they are connected to each other. An aircraft variant
+/// is described by building a different description rather than different code.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HydraulicSystemDescription {
+    loops: Vec<HydraulicLoopDescription>,
+    engine_driven_pumps: Vec<EngineDrivenPumpDescription>,
+    electric_pumps: Vec<ElectricPumpDescription>,
+    ptu: Option<PtuDescription>,
+    electric_pump_displacement: Option<DisplacementMap>,
+    engine_driven_pump_displacement: Option<DisplacementMap>,
+    accumulator: Option<AccumulatorCharacteristic>,
+    integration_scheme: IntegrationScheme,
+    engine_variant: EngineVariant,
+}
+impl HydraulicSystemDescription {
+    pub fn new() -> HydraulicSystemDescription {
+        HydraulicSystemDescription::default()
+    }
+
+    pub fn with_loop(mut self, description: HydraulicLoopDescription) -> Self {
+        if self.has_loop(description.color) {
+            panic!("The {:?} loop is described twice.", description.color);
+        }
+
+        self.loops.push(description);
+        self
+    }
+
+    /// Engines are numbered from 1. A loop can be pressurised by the pumps of
+    /// several engines, and an engine can drive pumps on several loops, but an
+    /// engine drives at most one pump per loop.
+    pub fn with_engine_driven_pump(mut self, engine_number: usize, loop_color: LoopId) -> Self {
+        self.expect_loop(loop_color);
+        if engine_number == 0 {
+            panic!("Engines are numbered from 1.");
+        }
+
+        let description = EngineDrivenPumpDescription {
+            engine_number,
+            loop_color,
+        };
+        if self.engine_driven_pumps.contains(&description) {
+            panic!(
+                "Engine {} already drives a pump on the {:?} loop.",
+                engine_number, loop_color
+            );
+        }
+
+        self.engine_driven_pumps.push(description);
+        self
+    }
+
+    pub fn with_electric_pump(mut self, loop_color: LoopId) -> Self {
+        self.expect_loop(loop_color);
+        self.electric_pumps
+            .push(ElectricPumpDescription { loop_color });
+        self
+    }
+
+    /// The PTU transfers power between two distinct loops. There is at most one PTU.
+    pub fn with_ptu(mut self, left_loop: LoopId, right_loop: LoopId) -> Self {
+        self.expect_loop(left_loop);
+        self.expect_loop(right_loop);
+        if left_loop == right_loop {
+            panic!(
+                "The PTU must connect two distinct loops, not the {:?} loop to itself.",
+                left_loop
+            );
+        }
+        if let Some(ptu) = self.ptu {
+            panic!(
+                "A PTU already connects the {:?} and {:?} loops.",
+                ptu.left_loop, ptu.right_loop
+            );
+        }
+
+        self.ptu = Some(PtuDescription {
+            left_loop,
+            right_loop,
+        });
+        self
+    }
+
+    pub fn with_integration_scheme(mut self, scheme: IntegrationScheme) -> Self {
+        self.integration_scheme = scheme;
+        self
+    }
+
+    /// Fits the pump models of the given engine variant. Displacement maps found
+    /// in a config still replace those of the variant.
+    pub fn with_engine_variant(mut self, variant: EngineVariant) -> Self {
+        self.engine_variant = variant;
+        self
+    }
+
+    /// Overrides the built-in characteristics with those found in the given config.
+    /// Reservoir volumes only apply to loops which are already described.
+    pub fn with_config(mut self, config: &HydraulicConfig) -> Self {
+        for description in self.loops.iter_mut() {
+            if let Some(volume) = config.reservoir_volume.volume(description.color) {
+                description.reservoir_volume = Volume::new::<gallon>(volume);
+            }
+        }
+
+        self.electric_pump_displacement = config.electric_pump.or(self.electric_pump_displacement);
+        self.engine_driven_pump_displacement = config
+            .engine_driven_pump
+            .or(self.engine_driven_pump_displacement);
+        self.accumulator = config.accumulator.or(self.accumulator);
+        self
+    }
+
+    pub fn electric_pump_displacement(&self) -> Option<DisplacementMap> {
+        self.electric_pump_displacement
+    }
+
+    pub fn engine_driven_pump_displacement(&self) -> Option<DisplacementMap> {
+        self.engine_driven_pump_displacement
+    }
+
+    pub fn engine_variant(&self) -> EngineVariant {
+        self.engine_variant
+    }
+
+    pub fn loops(&self) -> &[HydraulicLoopDescription] {
+        &self.loops
+    }
+
+    pub fn engine_driven_pumps(&self) -> &[EngineDrivenPumpDescription] {
+        &self.engine_driven_pumps
+    }
+
+    pub fn engine_driven_pumps_on(
+        &self,
+        loop_color: LoopId,
+    ) -> impl Iterator<Item = &EngineDrivenPumpDescription> {
+        self.engine_driven_pumps
+            .iter()
+            .filter(move |description| description.loop_color == loop_color)
+    }
+
+    pub fn electric_pumps(&self) -> &[ElectricPumpDescription] {
+        &self.electric_pumps
+    }
+
+    pub fn ptu(&self) -> Option<PtuDescription> {
+        self.ptu
+    }
+
+    pub fn has_loop(&self, color: LoopId) -> bool {
+        self.loops
+            .iter()
+            .any(|description| description.color == color)
+    }
+
+    /// Creates the described loops, connected to the PTU where described.
+    pub fn build_loops(&self) -> Vec<HydLoop> {
+        self.loops
+            .iter()
+            .map(|description| {
+                let mut hyd_loop = description.build(self.ptu);
+                hyd_loop.set_integration_scheme(self.integration_scheme);
+                if let Some(accumulator) = self.accumulator {
+                    hyd_loop.set_accumulator_characteristic(
+                        accumulator.pressure_breakpoints,
+                        accumulator.flow,
+                    );
+                }
+
+                hyd_loop
+            })
+            .collect()
+    }
+
+    /// Panics when a pump is driven by an engine the aircraft doesn't have,
+    /// as that pump would never run.
+    pub fn expect_engine_count(&self, engine_count: usize) {
+        if let Some(pump) = self
+            .engine_driven_pumps
+            .iter()
+            .find(|pump| pump.engine_number > engine_count)
+        {
+            panic!(
+                "Engine {} driving a pump on the {:?} loop doesn't exist.",
+                pump.engine_number, pump.loop_color
+            );
+        }
+    }
+
+    fn expect_loop(&self, color: LoopId) {
+        if !self.has_loop(color) {
+            panic!(
+                "The {:?} loop must be described before connecting to it.",
+                color
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod description_tests {
+    use super::*;
+    use uom::si::pressure::pascal;
+
+    #[test]
+    fn builds_the_described_loops() {
+        let loops = description().build_loops();
+
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].get_color(), LoopId::GREEN);
+        assert_eq!(loops[1].get_color(), LoopId::YELLOW);
+    }
+
+    #[test]
+    fn builds_loops_beyond_the_a320_colours() {
+        let loops = description()
+            .with_loop(loop_description(LoopId::BLUE))
+            .with_loop(loop_description(LoopId::new(3)))
+            .with_engine_driven_pump(3, LoopId::new(3))
+            .build_loops();
+
+        assert_eq!(loops.len(), 4);
+        assert_eq!(loops[3].get_color(), LoopId::new(3));
+    }
+
+    #[test]
+    fn builds_loops_with_their_reservoir_volume() {
+        let loops = description().build_loops();
+
+        assert_eq!(loops[0].get_reservoir_volume(), Volume::new::<gallon>(3.));
+    }
+
+    #[test]
+    fn fits_neo_pumps_unless_described_otherwise() {
+        assert_eq!(description().engine_variant(), EngineVariant::Neo);
+        assert_eq!(
+            description()
+                .with_engine_variant(EngineVariant::Ceo)
+                .engine_variant(),
+            EngineVariant::Ceo
+        );
+    }
+
+    #[test]
+    fn keeps_track_of_pumps_and_ptu() {
+        let description = description()
+            .with_engine_driven_pump(1, LoopId::GREEN)
+            .with_electric_pump(LoopId::YELLOW)
+            .with_ptu(LoopId::GREEN, LoopId::YELLOW);
+
+        assert_eq!(
+            description.engine_driven_pumps(),
+            &[EngineDrivenPumpDescription {
+                engine_number: 1,
+                loop_color: LoopId::GREEN
+            }]
+        );
+        assert_eq!(
+            description.electric_pumps(),
+            &[ElectricPumpDescription {
+                loop_color: LoopId::YELLOW
+            }]
+        );
+        assert_eq!(
+            description.ptu(),
+            Some(PtuDescription {
+                left_loop: LoopId::GREEN,
+                right_loop: LoopId::YELLOW
+            })
+        );
+    }
+
+    #[test]
+    fn config_overrides_reservoir_volume_of_described_loops() {
+        let config = HydraulicConfig::from_toml(
+            r#"
+            [reservoir_volume]
+            green = 4.0
+            blue = 2.0
+            "#,
+        )
+        .unwrap();
+        let description = description().with_config(&config);
+
+        assert!(!description.has_loop(LoopId::BLUE));
+        assert_eq!(
+            description.loops()[0].reservoir_volume,
+            Volume::new::<gallon>(4.)
+        );
+        assert_eq!(
+            description.loops()[1].reservoir_volume,
+            Volume::new::<gallon>(3.)
+        );
+    }
+
+    #[test]
+    fn config_overrides_pump_displacement() {
+        let config = HydraulicConfig::from_toml(
+            r#"
+            [electric_pump]
+            pressure_breakpoints = [0.0, 500.0, 1000.0, 1500.0, 2800.0, 2900.0, 3000.0, 3050.0, 3500.0]
+            displacement = [0.3, 0.3, 0.3, 0.3, 0.3, 0.3, 0.2, 0.0, 0.0]
+            "#,
+        )
+        .unwrap();
+        let description = description().with_config(&config);
+
+        assert_eq!(
+            description.electric_pump_displacement(),
+            config.electric_pump
+        );
+        assert_eq!(description.engine_driven_pump_displacement(), None);
+    }
+
+    #[test]
+    fn a_loop_can_be_pressurised_by_pumps_of_several_engines() {
+        let description = description()
+            .with_engine_driven_pump(1, LoopId::GREEN)
+            .with_engine_driven_pump(2, LoopId::GREEN)
+            .with_engine_driven_pump(2, LoopId::YELLOW);
+
+        assert_eq!(
+            description
+                .engine_driven_pumps_on(LoopId::GREEN)
+                .map(|pump| pump.engine_number)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            description
+                .engine_driven_pumps_on(LoopId::YELLOW)
+                .map(|pump| pump.engine_number)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn an_engine_drives_at_most_one_pump_per_loop() {
+        description()
+            .with_engine_driven_pump(1, LoopId::GREEN)
+            .with_engine_driven_pump(1, LoopId::GREEN);
+    }
+
+    #[test]
+    #[should_panic]
+    fn engines_are_numbered_from_one() {
+        description().with_engine_driven_pump(0, LoopId::GREEN);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ptu_cannot_connect_a_loop_to_itself() {
+        description().with_ptu(LoopId::GREEN, LoopId::GREEN);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cannot_describe_a_second_ptu() {
+        description()
+            .with_loop(loop_description(LoopId::BLUE))
+            .with_ptu(LoopId::GREEN, LoopId::YELLOW)
+            .with_ptu(LoopId::BLUE, LoopId::YELLOW);
+    }
+
+    #[test]
+    fn pumps_driven_by_existing_engines_are_accepted() {
+        description()
+            .with_engine_driven_pump(1, LoopId::GREEN)
+            .with_engine_driven_pump(2, LoopId::YELLOW)
+            .expect_engine_count(2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn pumps_cannot_be_driven_by_missing_engines() {
+        description()
+            .with_engine_driven_pump(3, LoopId::GREEN)
+            .expect_engine_count(2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cannot_connect_a_pump_to_an_undescribed_loop() {
+        description().with_electric_pump(LoopId::BLUE);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cannot_describe_a_loop_twice() {
+        description().with_loop(loop_description(LoopId::GREEN));
+    }
+
+    fn description() -> HydraulicSystemDescription {
+        HydraulicSystemDescription::new()
+            .with_loop(loop_description(LoopId::GREEN))
+            .with_loop(loop_description(LoopId::YELLOW))
+    }
+
+    fn loop_description(color: LoopId) -> HydraulicLoopDescription {
+        HydraulicLoopDescription {
+            color,
+            loop_volume: Volume::new::<gallon>(10.),
+            max_loop_volume: Volume::new::<gallon>(10.),
+            high_pressure_volume: Volume::new::<gallon>(8.),
+            reservoir_volume: Volume::new::<gallon>(3.),
+            fluid_bulk_modulus: Pressure::new::<pascal>(1450000000.0),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::HydraulicMotor;
+use uom::si::{f64::*, pressure::psi, velocity::knot, volume_rate::gallon_per_minute};
+
+/// The hydraulic power the blue loop makes available to the hydraulic motor
+/// driving the emergency generator.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EmergencyGeneratorHydraulicSupply {
+    pressure: Pressure,
+    available_flow: VolumeRate,
+}
+impl EmergencyGeneratorHydraulicSupply {
+    // Below this airspeed the ram air turbine stalls and no longer delivers flow.
+    const RAT_STALL_SPEED_KNOTS: f64 = 100.;
+    // From this airspeed on the ram air turbine governor maintains full flow.
+    const RAT_FULL_FLOW_SPEED_KNOTS: f64 = 140.;
+    const RAT_MAX_FLOW_GPM: f64 = 6.6;
+    const MIN_DRIVE_PRESSURE_PSI: f64 = 1500.;
+    const MIN_DRIVE_FLOW_GPM: f64 = 2.;
+
+    pub fn new(pressure: Pressure, available_flow: VolumeRate) -> Self {
+        EmergencyGeneratorHydraulicSupply {
+            pressure,
+            available_flow,
+        }
+    }
+
+    pub fn none() -> Self {
+        EmergencyGeneratorHydraulicSupply::new(
+            Pressure::new::<psi>(0.),
+            VolumeRate::new::<gallon_per_minute>(0.),
+        )
+    }
+
+    /// The supply of a blue loop powered by the ram air turbine, whose flow
+    /// decreases with airspeed until the turbine stalls.
+    pub fn from_rat_driven_blue_loop(pressure: Pressure, indicated_airspeed: Velocity) -> Self {
+        let speed_ratio = (indicated_airspeed.get::<knot>()
+            - EmergencyGeneratorHydraulicSupply::RAT_STALL_SPEED_KNOTS)
+            / (EmergencyGeneratorHydraulicSupply::RAT_FULL_FLOW_SPEED_KNOTS
+                - EmergencyGeneratorHydraulicSupply::RAT_STALL_SPEED_KNOTS);
+
+        EmergencyGeneratorHydraulicSupply::new(
+            pressure,
+            VolumeRate::new::<gallon_per_minute>(
+                EmergencyGeneratorHydraulicSupply::RAT_MAX_FLOW_GPM * speed_ratio.clamp(0., 1.),
+            ),
+        )
+    }
+
+    pub fn pressure(&self) -> Pressure {
+        self.pressure
+    }
+
+    pub fn available_flow(&self) -> VolumeRate {
+        self.available_flow
+    }
+
+    /// The speed the available flow turns the hydraulic motor of the generator at.
+    pub fn generator_speed(&self) -> AngularVelocity {
+        HydraulicMotor::emergency_generator().speed_for(self.available_flow)
+    }
+
+    pub fn can_drive_generator(&self) -> bool {
+        self.pressure
+            >= Pressure::new::<psi>(EmergencyGeneratorHydraulicSupply::MIN_DRIVE_PRESSURE_PSI)
+            && self.available_flow
+                >= VolumeRate::new::<gallon_per_minute>(
+                    EmergencyGeneratorHydraulicSupply::MIN_DRIVE_FLOW_GPM,
+                )
+    }
+}
+
+#[cfg(test)]
+mod emergency_generator_supply_tests {
+    use super::*;
+
+    #[test]
+    fn no_supply_cannot_drive_generator() {
+        assert!(!EmergencyGeneratorHydraulicSupply::none().can_drive_generator());
+    }
+
+    #[test]
+    fn rat_delivers_full_flow_at_high_airspeed() {
+        let supply = rat_supply(3000., 250.);
+
+        assert_eq!(
+            supply.available_flow(),
+            VolumeRate::new::<gallon_per_minute>(
+                EmergencyGeneratorHydraulicSupply::RAT_MAX_FLOW_GPM
+            )
+        );
+        assert!(supply.can_drive_generator());
+    }
+
+    #[test]
+    fn rat_flow_decreases_when_slowing_down() {
+        assert!(
+            rat_supply(3000., 120.).available_flow() < rat_supply(3000., 130.).available_flow()
+        );
+    }
+
+    #[test]
+    fn rat_stalls_at_low_airspeed() {
+        let supply = rat_supply(3000., 90.);
+
+        assert_eq!(
+            supply.available_flow(),
+            VolumeRate::new::<gallon_per_minute>(0.)
+        );
+        assert!(!supply.can_drive_generator());
+    }
+
+    #[test]
+    fn generator_slows_down_with_the_rat() {
+        assert!(
+            rat_supply(3000., 120.).generator_speed() < rat_supply(3000., 250.).generator_speed()
+        );
+    }
+
+    #[test]
+    fn cannot_drive_generator_without_blue_pressure() {
+        assert!(!rat_supply(500., 250.).can_drive_generator());
+    }
+
+    fn rat_supply(pressure: f64, indicated_airspeed: f64) -> EmergencyGeneratorHydraulicSupply {
+        EmergencyGeneratorHydraulicSupply::from_rat_driven_blue_loop(
+            Pressure::new::<psi>(pressure),
+            Velocity::new::<knot>(indicated_airspeed),
+        )
+    }
+}
@@ -0,0 +1,35 @@
+use super::ActuatorType;
+use uom::si::f64::*;
+
+/// Identifies an actuated surface, on the left (1) or right (2) side of the
+/// aircraft and numbered from 1 from the aircraft centreline outwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActuatorId {
+    pub actuator_type: ActuatorType,
+    pub side: usize,
+    pub number: usize,
+}
+impl ActuatorId {
+    pub fn new(actuator_type: ActuatorType, side: usize, number: usize) -> Self {
+        ActuatorId {
+            actuator_type,
+            side,
+            number,
+        }
+    }
+}
+
+/// Couples the actuators with a flight model outside the crate, such as the
+/// simulator's own or an external FDM.
+///
+/// On each actuator step, every actuated surface asks for the external load
+/// on it before moving, then reports the rate it achieved against that load.
+pub trait ExternalLoads {
+    /// The load on the surface at the given position, positive when pushing it
+    /// towards negative angles, e.g. the aerodynamic hinge load. `None` keeps the
+    /// load model of the surface itself.
+    fn load(&self, actuator: ActuatorId, position: Angle) -> Option<Force>;
+
+    /// The rate the surface moved at during the last actuator step.
+    fn set_achieved_rate(&mut self, _actuator: ActuatorId, _rate: AngularVelocity) {}
+}
@@ -0,0 +1,198 @@
+use uom::si::{
+    f64::*, pressure::psi, ratio::ratio, volume::gallon, volume_rate::gallon_per_minute,
+};
+
+/// A loop filter element. Contaminated fluid progressively clogs the element,
+/// which increases the pressure drop across the filter. When the pressure drop
+/// reaches the bypass setting, the bypass valve opens and the delta pressure
+/// indicator pops out. The indicator stays out until the element is replaced.
+pub struct HydraulicFilter {
+    clean_pressure_drop_at_rated_flow: Pressure,
+    rated_flow: VolumeRate,
+    bypass_pressure_drop: Pressure,
+    contamination: Ratio,
+    clogging: Ratio,
+    pressure_drop: Pressure,
+    bypass_indicator_popped: bool,
+}
+impl HydraulicFilter {
+    // Volume of fully contaminated fluid the element can filter before being fully clogged.
+    const DIRT_HOLDING_VOLUME_GALLON: f64 = 50.;
+    // A fully clogged element would block the flow, the bypass valve opens long before.
+    const MAX_CLOGGING: f64 = 0.95;
+
+    const HIGH_PRESSURE_RATED_FLOW_GPM: f64 = 37.;
+    const HIGH_PRESSURE_CLEAN_PRESSURE_DROP_PSI: f64 = 25.;
+    const HIGH_PRESSURE_BYPASS_PRESSURE_DROP_PSI: f64 = 100.;
+
+    const RETURN_RATED_FLOW_GPM: f64 = 40.;
+    const RETURN_CLEAN_PRESSURE_DROP_PSI: f64 = 10.;
+    const RETURN_BYPASS_PRESSURE_DROP_PSI: f64 = 50.;
+
+    pub fn new(
+        clean_pressure_drop_at_rated_flow: Pressure,
+        rated_flow: VolumeRate,
+        bypass_pressure_drop: Pressure,
+    ) -> Self {
+        HydraulicFilter {
+            clean_pressure_drop_at_rated_flow,
+            rated_flow,
+            bypass_pressure_drop,
+            contamination: Ratio::new::<ratio>(0.),
+            clogging: Ratio::new::<ratio>(0.),
+            pressure_drop: Pressure::new::<psi>(0.),
+            bypass_indicator_popped: false,
+        }
+    }
+
+    /// The filter on the pumps high pressure outlet.
+    pub fn high_pressure() -> Self {
+        HydraulicFilter::new(
+            Pressure::new::<psi>(HydraulicFilter::HIGH_PRESSURE_CLEAN_PRESSURE_DROP_PSI),
+            VolumeRate::new::<gallon_per_minute>(HydraulicFilter::HIGH_PRESSURE_RATED_FLOW_GPM),
+            Pressure::new::<psi>(HydraulicFilter::HIGH_PRESSURE_BYPASS_PRESSURE_DROP_PSI),
+        )
+    }
+
+    /// The filter on the return line to the reservoir.
+    pub fn return_line() -> Self {
+        HydraulicFilter::new(
+            Pressure::new::<psi>(HydraulicFilter::RETURN_CLEAN_PRESSURE_DROP_PSI),
+            VolumeRate::new::<gallon_per_minute>(HydraulicFilter::RETURN_RATED_FLOW_GPM),
+            Pressure::new::<psi>(HydraulicFilter::RETURN_BYPASS_PRESSURE_DROP_PSI),
+        )
+    }
+
+    /// Sets the contamination level of the fluid going through the filter,
+    /// from 0 for clean fluid to 1 for fully contaminated fluid.
+    pub fn set_contamination(&mut self, contamination: Ratio) {
+        self.contamination = contamination
+            .max(Ratio::new::<ratio>(0.))
+            .min(Ratio::new::<ratio>(1.));
+    }
+
+    pub fn contamination(&self) -> Ratio {
+        self.contamination
+    }
+
+    /// Maintenance action: fits a clean element and pushes the indicator back in.
+    pub fn replace_element(&mut self) {
+        self.clogging = Ratio::new::<ratio>(0.);
+        self.pressure_drop = Pressure::new::<psi>(0.);
+        self.bypass_indicator_popped = false;
+    }
+
+    pub fn update(&mut self, filtered_volume: Volume, flow: VolumeRate) {
+        let filtered_gallons = filtered_volume.get::<gallon>().max(0.);
+        self.clogging = Ratio::new::<ratio>(
+            (self.clogging.get::<ratio>()
+                + self.contamination.get::<ratio>() * filtered_gallons
+                    / HydraulicFilter::DIRT_HOLDING_VOLUME_GALLON)
+                .min(HydraulicFilter::MAX_CLOGGING),
+        );
+
+        let flow_ratio = (flow / self.rated_flow).get::<ratio>().max(0.);
+        let open_area_ratio = 1. - self.clogging.get::<ratio>();
+        let element_pressure_drop =
+            self.clean_pressure_drop_at_rated_flow * flow_ratio / open_area_ratio.powi(2);
+
+        if element_pressure_drop >= self.bypass_pressure_drop {
+            self.bypass_indicator_popped = true;
+        }
+        self.pressure_drop = element_pressure_drop.min(self.bypass_pressure_drop);
+    }
+
+    pub fn clogging(&self) -> Ratio {
+        self.clogging
+    }
+
+    pub fn pressure_drop(&self) -> Pressure {
+        self.pressure_drop
+    }
+
+    pub fn is_bypass_indicator_popped(&self) -> bool {
+        self.bypass_indicator_popped
+    }
+}
+
+#[cfg(test)]
+mod hydraulic_filter_tests {
+    use super::*;
+
+    #[test]
+    fn clean_fluid_does_not_clog_filter() {
+        let mut filter = HydraulicFilter::high_pressure();
+        filter_at_rated_flow(&mut filter, 1000);
+
+        assert_eq!(filter.clogging(), Ratio::new::<ratio>(0.));
+        assert_eq!(
+            filter.pressure_drop(),
+            Pressure::new::<psi>(HydraulicFilter::HIGH_PRESSURE_CLEAN_PRESSURE_DROP_PSI)
+        );
+        assert!(!filter.is_bypass_indicator_popped());
+    }
+
+    #[test]
+    fn contaminated_fluid_increases_pressure_drop() {
+        let mut filter = HydraulicFilter::high_pressure();
+        filter.set_contamination(Ratio::new::<ratio>(0.1));
+        filter_at_rated_flow(&mut filter, 1);
+        let pressure_drop_before = filter.pressure_drop();
+
+        filter_at_rated_flow(&mut filter, 100);
+
+        assert!(filter.clogging() > Ratio::new::<ratio>(0.));
+        assert!(filter.pressure_drop() > pressure_drop_before);
+    }
+
+    #[test]
+    fn clogged_filter_is_bypassed_and_pops_indicator() {
+        let mut filter = HydraulicFilter::high_pressure();
+        filter.set_contamination(Ratio::new::<ratio>(1.));
+        filter_at_rated_flow(&mut filter, 1000);
+
+        assert!(filter.is_bypass_indicator_popped());
+        assert_eq!(
+            filter.pressure_drop(),
+            Pressure::new::<psi>(HydraulicFilter::HIGH_PRESSURE_BYPASS_PRESSURE_DROP_PSI)
+        );
+    }
+
+    #[test]
+    fn indicator_stays_popped_when_flow_stops() {
+        let mut filter = HydraulicFilter::return_line();
+        filter.set_contamination(Ratio::new::<ratio>(1.));
+        filter_at_rated_flow(&mut filter, 1000);
+
+        filter.update(
+            Volume::new::<gallon>(0.),
+            VolumeRate::new::<gallon_per_minute>(0.),
+        );
+
+        assert!(filter.is_bypass_indicator_popped());
+        assert_eq!(filter.pressure_drop(), Pressure::new::<psi>(0.));
+    }
+
+    #[test]
+    fn replaced_element_is_clean() {
+        let mut filter = HydraulicFilter::high_pressure();
+        filter.set_contamination(Ratio::new::<ratio>(1.));
+        filter_at_rated_flow(&mut filter, 1000);
+
+        filter.replace_element();
+
+        assert_eq!(filter.clogging(), Ratio::new::<ratio>(0.));
+        assert!(!filter.is_bypass_indicator_popped());
+    }
+
+    // Filters fluid at rated flow for the given number of seconds.
+    fn filter_at_rated_flow(filter: &mut HydraulicFilter, seconds: usize) {
+        let flow = filter.rated_flow;
+        for _ in 0..seconds {
+            filter.update(
+                Volume::new::<gallon>(flow.get::<gallon_per_minute>() / 60.),
+                flow,
+            );
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt;
+use uom::si::{f64::*, pressure::psi, volume::gallon, volume_rate::gallon_per_second};
+
+/// The state variables of a loop carried from one update to the next.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoopState {
+    pub pressure: Pressure,
+    pub loop_volume: Volume,
+    pub reservoir_volume: Volume,
+    pub return_line_volume: Volume,
+    pub accumulator_fluid_volume: Volume,
+    pub accumulator_gas_volume: Volume,
+    pub accumulator_gas_pressure: Pressure,
+    pub flow: VolumeRate,
+    pub ptu_delivered_flow: VolumeRate,
+}
+impl LoopState {
+    /// The name of the first quantity which is NaN or infinite, if any.
+    pub fn non_finite_quantity(&self) -> Option<&'static str> {
+        let quantities = [
+            ("pressure", self.pressure.get::<psi>()),
+            ("loop volume", self.loop_volume.get::<gallon>()),
+            ("reservoir volume", self.reservoir_volume.get::<gallon>()),
+            (
+                "return line volume",
+                self.return_line_volume.get::<gallon>(),
+            ),
+            (
+                "accumulator fluid volume",
+                self.accumulator_fluid_volume.get::<gallon>(),
+            ),
+            (
+                "accumulator gas volume",
+                self.accumulator_gas_volume.get::<gallon>(),
+            ),
+            (
+                "accumulator gas pressure",
+                self.accumulator_gas_pressure.get::<psi>(),
+            ),
+            ("flow", self.flow.get::<gallon_per_second>()),
+            (
+                "PTU delivered flow",
+                self.ptu_delivered_flow.get::<gallon_per_second>(),
+            ),
+        ];
+
+        quantities
+            .iter()
+            .find(|(_, value)| !value.is_finite())
+            .map(|(name, _)| *name)
+    }
+}
+
+/// Keeps NaN and infinite values out of the state of a loop.
+///
+/// A single bad step, e.g. a division by a zero time step, would otherwise
+/// poison the whole loop and every system reading it from then on. When an
+/// update leaves any state variable not finite, the guard logs the offending
+/// component and quantity and the loop goes back to its state before that
+/// update.
+///
+/// The guard is always on in debug builds. Release builds skip the checks
+/// unless it is enabled.
+pub struct FiniteGuard {
+    enabled: bool,
+    resets: usize,
+}
+impl FiniteGuard {
+    pub fn new() -> Self {
+        FiniteGuard {
+            enabled: cfg!(debug_assertions),
+            resets: 0,
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The state to carry on with: the updated state when all of it is finite,
+    /// the previous state otherwise.
+    pub fn check(
+        &mut self,
+        component: &dyn fmt::Debug,
+        previous: LoopState,
+        updated: LoopState,
+    ) -> LoopState {
+        match updated.non_finite_quantity() {
+            Some(quantity) => {
+                self.resets += 1;
+                log::error!(
+                    target: "hydraulic",
+                    "Hydraulic {:?}: {} is not finite after update, state reset to the previous step",
+                    component,
+                    quantity
+                );
+
+                previous
+            }
+            None => updated,
+        }
+    }
+
+    /// How many times the state was reset since the guard was created.
+    pub fn resets(&self) -> usize {
+        self.resets
+    }
+}
+impl Default for FiniteGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod finite_guard_tests {
+    use super::*;
+
+    #[test]
+    fn finite_state_is_kept() {
+        let mut guard = FiniteGuard::new();
+        let updated = LoopState {
+            pressure: Pressure::new::<psi>(3000.),
+            ..state()
+        };
+
+        assert_eq!(guard.check(&"green loop", state(), updated), updated);
+        assert_eq!(guard.resets(), 0);
+    }
+
+    #[test]
+    fn non_finite_state_goes_back_to_previous_state() {
+        let mut guard = FiniteGuard::new();
+        let updated = LoopState {
+            flow: VolumeRate::new::<gallon_per_second>(f64::INFINITY),
+            ..state()
+        };
+
+        assert_eq!(guard.check(&"green loop", state(), updated), state());
+        assert_eq!(guard.resets(), 1);
+    }
+
+    #[test]
+    fn names_the_offending_quantity() {
+        let updated = LoopState {
+            reservoir_volume: Volume::new::<gallon>(f64::NAN),
+            ..state()
+        };
+
+        assert_eq!(state().non_finite_quantity(), None);
+        assert_eq!(updated.non_finite_quantity(), Some("reservoir volume"));
+    }
+
+    #[test]
+    fn guard_is_on_in_debug_builds() {
+        assert_eq!(FiniteGuard::new().is_enabled(), cfg!(debug_assertions));
+    }
+
+    fn state() -> LoopState {
+        LoopState {
+            pressure: Pressure::new::<psi>(14.7),
+            loop_volume: Volume::new::<gallon>(1.),
+            reservoir_volume: Volume::new::<gallon>(3.),
+            return_line_volume: Volume::new::<gallon>(0.),
+            accumulator_fluid_volume: Volume::new::<gallon>(0.),
+            accumulator_gas_volume: Volume::new::<gallon>(0.264),
+            accumulator_gas_pressure: Pressure::new::<psi>(1885.),
+            flow: VolumeRate::new::<gallon_per_second>(0.),
+            ptu_delivered_flow: VolumeRate::new::<gallon_per_second>(0.),
+        }
+    }
+}
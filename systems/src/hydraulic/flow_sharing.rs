@@ -0,0 +1,309 @@
+use super::ActuatorType;
+use uom::si::{f64::*, pressure::psi, volume_rate::gallon_per_second};
+
+/// Consumers behind the priority valve lose their supply first when the loop
+/// can't deliver all the flow demanded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConsumerPriority {
+    /// Primary flight controls, brakes and the emergency generator.
+    Primary,
+    /// Heavy load consumers such as landing gear, flaps and slats.
+    Secondary,
+}
+impl From<ActuatorType> for ConsumerPriority {
+    fn from(consumer: ActuatorType) -> Self {
+        match consumer {
+            ActuatorType::Aileron
+            | ActuatorType::BrakesNormal
+            | ActuatorType::BrakesAlternate
+            | ActuatorType::BrakesParking
+            | ActuatorType::Elevator
+            | ActuatorType::EmergencyGenerator
+            | ActuatorType::Rudder
+            | ActuatorType::Spoiler
+            | ActuatorType::Stabilizer
+            | ActuatorType::YawDamper => ConsumerPriority::Primary,
+            ActuatorType::CargoDoor
+            | ActuatorType::EngReverser
+            | ActuatorType::Flaps
+            | ActuatorType::LandingGearNose
+            | ActuatorType::LandingGearMain
+            | ActuatorType::LandingGearDoorNose
+            | ActuatorType::LandingGearDoorMain
+            | ActuatorType::NoseWheelSteering
+            | ActuatorType::Slat => ConsumerPriority::Secondary,
+        }
+    }
+}
+
+/// The flow a consumer needs from its loop to move at full rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlowDemand {
+    consumer: ActuatorType,
+    flow: VolumeRate,
+}
+impl FlowDemand {
+    pub fn new(consumer: ActuatorType, flow: VolumeRate) -> Self {
+        FlowDemand { consumer, flow }
+    }
+
+    pub fn consumer(&self) -> ActuatorType {
+        self.consumer
+    }
+
+    pub fn flow(&self) -> VolumeRate {
+        self.flow
+    }
+
+    pub fn priority(&self) -> ConsumerPriority {
+        self.consumer.into()
+    }
+}
+
+/// How the flow available in a loop is shared between its consumers.
+/// Primary consumers are served first, secondary consumers share what remains,
+/// each of them slowing down by the same ratio. The priority valve throttles
+/// secondary consumers as the loop pressure sags and shuts them off below its
+/// closing pressure.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlowSharing {
+    demand_ratio: f64,
+    primary_ratio: f64,
+    secondary_ratio: f64,
+    served_flow: VolumeRate,
+}
+impl FlowSharing {
+    // Below this loop pressure the priority valve shuts off secondary consumers.
+    const PRIORITY_VALVE_CLOSING_PRESSURE_PSI: f64 = 1840.;
+    const PRIORITY_VALVE_FULLY_OPEN_PRESSURE_PSI: f64 = 2200.;
+    const NOMINAL_PRESSURE_PSI: f64 = 3000.;
+
+    /// Shares the available flow between the given demands at nominal pressure,
+    /// consumers drawing less of it at the given loop pressure.
+    pub fn new(
+        available_flow: VolumeRate,
+        primary_demand: VolumeRate,
+        secondary_demand: VolumeRate,
+        loop_pressure: Pressure,
+    ) -> Self {
+        let demand_ratio = FlowSharing::demand_ratio(loop_pressure);
+        let primary_demand = primary_demand * demand_ratio;
+        let secondary_demand = secondary_demand * demand_ratio;
+        let available_flow = available_flow.max(VolumeRate::new::<gallon_per_second>(0.));
+        let primary_ratio = FlowSharing::served_ratio_of(available_flow, primary_demand);
+        let primary_served_flow = primary_demand * primary_ratio;
+
+        let priority_valve_opening = FlowSharing::priority_valve_opening(loop_pressure);
+        let secondary_ratio = priority_valve_opening
+            * FlowSharing::served_ratio_of(
+                available_flow - primary_served_flow,
+                secondary_demand * priority_valve_opening,
+            );
+
+        FlowSharing {
+            demand_ratio,
+            primary_ratio,
+            secondary_ratio,
+            served_flow: primary_served_flow + secondary_demand * secondary_ratio,
+        }
+    }
+
+    /// No consumer is served.
+    pub fn none() -> Self {
+        FlowSharing {
+            demand_ratio: 0.,
+            primary_ratio: 0.,
+            secondary_ratio: 0.,
+            served_flow: VolumeRate::new::<gallon_per_second>(0.),
+        }
+    }
+
+    pub fn is_priority_valve_open(loop_pressure: Pressure) -> bool {
+        loop_pressure >= Pressure::new::<psi>(FlowSharing::PRIORITY_VALVE_CLOSING_PRESSURE_PSI)
+    }
+
+    /// Ratio of the secondary consumers demand the priority valve lets through,
+    /// from 0 at its closing pressure to 1 once fully open.
+    pub fn priority_valve_opening(loop_pressure: Pressure) -> f64 {
+        ((loop_pressure.get::<psi>() - FlowSharing::PRIORITY_VALVE_CLOSING_PRESSURE_PSI)
+            / (FlowSharing::PRIORITY_VALVE_FULLY_OPEN_PRESSURE_PSI
+                - FlowSharing::PRIORITY_VALVE_CLOSING_PRESSURE_PSI))
+            .clamp(0., 1.)
+    }
+
+    /// Servo valves pass less flow as the loop pressure drops: ratio of their
+    /// demand at nominal pressure consumers draw at the given pressure.
+    pub fn demand_ratio(loop_pressure: Pressure) -> f64 {
+        (loop_pressure.get::<psi>() / FlowSharing::NOMINAL_PRESSURE_PSI)
+            .max(0.)
+            .sqrt()
+            .min(1.)
+    }
+
+    /// Total flow consumers with the given demands at nominal pressure draw
+    /// at the given loop pressure, when the pumps can supply it.
+    pub fn demanded_flow_at(
+        primary_demand: VolumeRate,
+        secondary_demand: VolumeRate,
+        loop_pressure: Pressure,
+    ) -> VolumeRate {
+        (primary_demand + secondary_demand * FlowSharing::priority_valve_opening(loop_pressure))
+            * FlowSharing::demand_ratio(loop_pressure)
+    }
+
+    fn served_ratio_of(available_flow: VolumeRate, demand: VolumeRate) -> f64 {
+        if demand > VolumeRate::new::<gallon_per_second>(0.) {
+            (available_flow / demand).value.clamp(0., 1.)
+        } else {
+            1.
+        }
+    }
+
+    /// Ratio of the flow they draw at the loop pressure the consumers of the given
+    /// priority receive.
+    pub fn served_ratio(&self, priority: ConsumerPriority) -> f64 {
+        match priority {
+            ConsumerPriority::Primary => self.primary_ratio,
+            ConsumerPriority::Secondary => self.secondary_ratio,
+        }
+    }
+
+    pub fn served_flow_for(&self, demand: &FlowDemand) -> VolumeRate {
+        self.served_flow_of(demand.priority(), demand.flow())
+    }
+
+    /// The flow served to consumers of the given priority demanding the given flow in total.
+    pub fn served_flow_of(&self, priority: ConsumerPriority, demand: VolumeRate) -> VolumeRate {
+        demand * self.demand_ratio * self.served_ratio(priority)
+    }
+
+    /// Total flow delivered to all consumers.
+    pub fn served_flow(&self) -> VolumeRate {
+        self.served_flow
+    }
+}
+
+#[cfg(test)]
+mod flow_sharing_tests {
+    use super::*;
+    use uom::si::volume_rate::gallon_per_minute;
+
+    #[test]
+    fn all_consumers_are_served_when_flow_is_sufficient() {
+        let sharing = sharing(20., 5., 10., 3000.);
+
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Primary), 1.);
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Secondary), 1.);
+        assert_flow(sharing.served_flow(), 15.);
+    }
+
+    #[test]
+    fn secondary_consumers_slow_down_when_flow_is_insufficient() {
+        let sharing = sharing(10., 5., 10., 3000.);
+
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Primary), 1.);
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Secondary), 0.5);
+        assert_flow(sharing.served_flow(), 10.);
+    }
+
+    #[test]
+    fn secondary_consumers_slow_down_proportionally() {
+        let sharing = sharing(10., 5., 10., 3000.);
+        let flaps = FlowDemand::new(ActuatorType::Flaps, gpm(4.));
+        let gear = FlowDemand::new(ActuatorType::LandingGearMain, gpm(6.));
+
+        assert_flow(sharing.served_flow_for(&flaps), 2.);
+        assert_flow(sharing.served_flow_for(&gear), 3.);
+    }
+
+    #[test]
+    fn primary_consumers_share_flow_when_it_cannot_serve_them_all() {
+        let sharing = sharing(4., 8., 10., 3000.);
+
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Primary), 0.5);
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Secondary), 0.);
+    }
+
+    #[test]
+    fn priority_valve_shuts_off_secondary_consumers_at_low_pressure() {
+        let sharing = sharing(20., 5., 10., 1500.);
+
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Primary), 1.);
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Secondary), 0.);
+    }
+
+    #[test]
+    fn priority_valve_throttles_secondary_consumers_when_pressure_sags() {
+        let sharing = sharing(20., 5., 10., 2020.);
+        let flaps = FlowDemand::new(ActuatorType::Flaps, gpm(10.));
+
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Primary), 1.);
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Secondary), 0.5);
+        assert_flow(
+            sharing.served_flow_for(&flaps),
+            10. * FlowSharing::demand_ratio(Pressure::new::<psi>(2020.)) * 0.5,
+        );
+    }
+
+    #[test]
+    fn consumers_draw_less_flow_at_low_pressure() {
+        assert_ratio(FlowSharing::demand_ratio(Pressure::new::<psi>(3000.)), 1.);
+        assert_ratio(FlowSharing::demand_ratio(Pressure::new::<psi>(750.)), 0.5);
+        assert_ratio(FlowSharing::demand_ratio(Pressure::new::<psi>(0.)), 0.);
+    }
+
+    #[test]
+    fn consumers_are_served_what_they_draw_at_loop_pressure() {
+        let sharing = sharing(20., 5., 10., 750.);
+        let aileron = FlowDemand::new(ActuatorType::Aileron, gpm(5.));
+
+        assert_ratio(sharing.served_ratio(ConsumerPriority::Primary), 1.);
+        assert_flow(sharing.served_flow_for(&aileron), 2.5);
+        assert_flow(sharing.served_flow(), 2.5);
+    }
+
+    #[test]
+    fn demanded_flow_includes_secondary_consumers_only_through_priority_valve() {
+        assert_flow(
+            FlowSharing::demanded_flow_at(gpm(5.), gpm(10.), Pressure::new::<psi>(3000.)),
+            15.,
+        );
+        assert_flow(
+            FlowSharing::demanded_flow_at(gpm(5.), gpm(10.), Pressure::new::<psi>(1200.)),
+            5. * 0.4_f64.sqrt(),
+        );
+    }
+
+    #[test]
+    fn flight_controls_have_priority_over_gear() {
+        assert_eq!(
+            ConsumerPriority::from(ActuatorType::Aileron),
+            ConsumerPriority::Primary
+        );
+        assert_eq!(
+            ConsumerPriority::from(ActuatorType::LandingGearMain),
+            ConsumerPriority::Secondary
+        );
+    }
+
+    fn sharing(available: f64, primary: f64, secondary: f64, pressure: f64) -> FlowSharing {
+        FlowSharing::new(
+            gpm(available),
+            gpm(primary),
+            gpm(secondary),
+            Pressure::new::<psi>(pressure),
+        )
+    }
+
+    fn assert_ratio(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9);
+    }
+
+    fn assert_flow(actual: VolumeRate, expected_gpm: f64) {
+        assert!((actual.get::<gallon_per_minute>() - expected_gpm).abs() < 1e-9);
+    }
+
+    fn gpm(flow: f64) -> VolumeRate {
+        VolumeRate::new::<gallon_per_minute>(flow)
+    }
+}
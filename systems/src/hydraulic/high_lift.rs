@@ -0,0 +1,285 @@
+use super::HydraulicMotor;
+use std::time::Duration;
+use uom::si::{
+    angular_velocity::revolution_per_minute, f64::*, pressure::psi, ratio::ratio,
+    volume::cubic_inch, volume_rate::gallon_per_minute,
+};
+
+/// A hydraulically applied brake locking a wing end of a high lift transmission.
+///
+/// Once applied, the brake stays on until it is reset on ground by maintenance.
+pub struct WingTipBrake {
+    applied: bool,
+}
+impl WingTipBrake {
+    const MIN_APPLICATION_PRESSURE_PSI: f64 = 1000.;
+
+    pub fn new() -> Self {
+        WingTipBrake { applied: false }
+    }
+
+    /// Applies the brake when commanded with enough pressure to do so.
+    pub fn update(&mut self, commanded: bool, pressure: Pressure) {
+        if commanded && pressure >= Pressure::new::<psi>(WingTipBrake::MIN_APPLICATION_PRESSURE_PSI)
+        {
+            self.applied = true;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.applied = false;
+    }
+
+    pub fn is_applied(&self) -> bool {
+        self.applied
+    }
+}
+impl Default for WingTipBrake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The transmission of the flaps or the slats: a power control unit with two
+/// hydraulic motors, each supplied by its own loop, drives the surfaces of both
+/// wings through a shaft. The motors add their speeds, so the surfaces move at
+/// half speed with a single motor.
+///
+/// Position pick-off units at both wing tips compare the positions of the left
+/// and right surfaces and the speed of the shaft. An asymmetry, such as a broken
+/// shaft leaving a wing behind, or an overspeed applies the wing tip brakes:
+/// the surfaces are then frozen until the brakes are reset.
+pub struct HighLiftTransmission {
+    motors: [HydraulicMotor; 2],
+    // Left and right surface positions, as ratios of the full travel.
+    positions: [f64; 2],
+    disconnected: [bool; 2],
+    wing_tip_brakes: [WingTipBrake; 2],
+    fault_detected: bool,
+}
+impl HighLiftTransmission {
+    const MOTOR_DISPLACEMENT_CUBIC_INCH: f64 = 0.2;
+    const MOTOR_VOLUMETRIC_EFFICIENCY: f64 = 0.95;
+    const MOTOR_MECHANICAL_EFFICIENCY: f64 = 0.9;
+    const RATED_MOTOR_FLOW_GPM: f64 = 4.;
+    const RATED_PRESSURE_PSI: f64 = 3000.;
+    // Both motors at rated flow move the surfaces over their full travel in about 30 seconds.
+    const FULL_TRAVEL_SHAFT_REVOLUTIONS: f64 = 2200.;
+    const ASYMMETRY_THRESHOLD: f64 = 0.02;
+    const OVERSPEED_RATIO: f64 = 1.2;
+
+    pub fn new() -> Self {
+        HighLiftTransmission {
+            motors: [HighLiftTransmission::motor(), HighLiftTransmission::motor()],
+            positions: [0., 0.],
+            disconnected: [false, false],
+            wing_tip_brakes: [WingTipBrake::new(), WingTipBrake::new()],
+            fault_detected: false,
+        }
+    }
+
+    fn motor() -> HydraulicMotor {
+        HydraulicMotor::new(
+            Volume::new::<cubic_inch>(HighLiftTransmission::MOTOR_DISPLACEMENT_CUBIC_INCH),
+            Ratio::new::<ratio>(HighLiftTransmission::MOTOR_VOLUMETRIC_EFFICIENCY),
+            Ratio::new::<ratio>(HighLiftTransmission::MOTOR_MECHANICAL_EFFICIENCY),
+        )
+    }
+
+    /// The flow the valve block of a motor meters from the given supply pressure.
+    pub fn rated_motor_flow(pressure: Pressure) -> VolumeRate {
+        let pressure_ratio =
+            (pressure.get::<psi>() / HighLiftTransmission::RATED_PRESSURE_PSI).max(0.);
+
+        VolumeRate::new::<gallon_per_minute>(
+            HighLiftTransmission::RATED_MOTOR_FLOW_GPM * pressure_ratio.sqrt(),
+        )
+    }
+
+    /// Updates the transmission with the flows through both motors, the supply
+    /// pressure, which also applies the wing tip brakes, and the commanded surface position.
+    pub fn update(
+        &mut self,
+        delta_time: &Duration,
+        motor_flows: [VolumeRate; 2],
+        pressure: Pressure,
+        command: Ratio,
+    ) {
+        let frozen = self.is_locked() || self.fault_detected;
+        for (motor, flow) in self.motors.iter_mut().zip(motor_flows.iter()) {
+            let flow = if frozen {
+                VolumeRate::new::<gallon_per_minute>(0.)
+            } else {
+                *flow
+            };
+            motor.update(flow, pressure);
+        }
+
+        let max_travel = self.shaft_speed().get::<revolution_per_minute>() / 60.
+            * delta_time.as_secs_f64()
+            / HighLiftTransmission::FULL_TRAVEL_SHAFT_REVOLUTIONS;
+        for (position, disconnected) in self.positions.iter_mut().zip(self.disconnected.iter()) {
+            if !*disconnected {
+                let travel = command.get::<ratio>().max(0.).min(1.) - *position;
+                *position += travel.max(-max_travel).min(max_travel);
+            }
+        }
+
+        self.fault_detected = self.fault_detected || self.has_asymmetry() || self.has_overspeed();
+        for brake in self.wing_tip_brakes.iter_mut() {
+            brake.update(self.fault_detected, pressure);
+        }
+    }
+
+    // The shaft turns at the average speed of the motors, through a differential gearbox.
+    fn shaft_speed(&self) -> AngularVelocity {
+        (self.motors[0].speed() + self.motors[1].speed()) / 2.
+    }
+
+    fn has_asymmetry(&self) -> bool {
+        (self.positions[0] - self.positions[1]).abs() > HighLiftTransmission::ASYMMETRY_THRESHOLD
+    }
+
+    fn has_overspeed(&self) -> bool {
+        let rated_motor = HighLiftTransmission::motor();
+        let rated_speed = rated_motor.speed_for(HighLiftTransmission::rated_motor_flow(
+            Pressure::new::<psi>(HighLiftTransmission::RATED_PRESSURE_PSI),
+        ));
+
+        self.shaft_speed() > rated_speed * HighLiftTransmission::OVERSPEED_RATIO
+    }
+
+    /// Breaks the shaft to the surfaces of the given wing (1 for left, 2 for right),
+    /// which then stop following the transmission.
+    pub fn disconnect(&mut self, wing: usize) {
+        self.disconnected[wing - 1] = true;
+    }
+
+    /// Releases the wing tip brakes, as done by maintenance on ground.
+    pub fn reset_wing_tip_brakes(&mut self) {
+        self.fault_detected = false;
+        for brake in self.wing_tip_brakes.iter_mut() {
+            brake.reset();
+        }
+    }
+
+    /// The position of the surfaces of the given wing (1 for left, 2 for right).
+    pub fn position(&self, wing: usize) -> Ratio {
+        Ratio::new::<ratio>(self.positions[wing - 1])
+    }
+
+    /// Indicates if the wing tip brakes lock the transmission.
+    pub fn is_locked(&self) -> bool {
+        self.wing_tip_brakes.iter().all(|brake| brake.is_applied())
+    }
+}
+impl Default for HighLiftTransmission {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod high_lift_tests {
+    use super::*;
+
+    #[test]
+    fn surfaces_reach_the_command() {
+        let mut transmission = HighLiftTransmission::new();
+        run(&mut transmission, rated_flows(), 0.5, 60);
+
+        assert!(is_about(transmission.position(1), 0.5));
+        assert!(is_about(transmission.position(2), 0.5));
+        assert!(!transmission.is_locked());
+    }
+
+    #[test]
+    fn single_motor_moves_the_surfaces_at_half_speed() {
+        let mut both_motors = HighLiftTransmission::new();
+        run(&mut both_motors, rated_flows(), 1., 10);
+        let mut single_motor = HighLiftTransmission::new();
+        run(&mut single_motor, [rated_flows()[0], gpm(0.)], 1., 10);
+
+        assert!(is_about(
+            single_motor.position(1) * 2.,
+            both_motors.position(1).get::<ratio>()
+        ));
+    }
+
+    #[test]
+    fn disconnected_wing_locks_the_transmission() {
+        let mut transmission = HighLiftTransmission::new();
+        transmission.disconnect(2);
+        run(&mut transmission, rated_flows(), 1., 60);
+
+        assert!(transmission.is_locked());
+        assert!(transmission.position(1) < Ratio::new::<ratio>(0.1));
+    }
+
+    #[test]
+    fn overspeed_locks_the_transmission() {
+        let mut transmission = HighLiftTransmission::new();
+        run(&mut transmission, [gpm(6.), gpm(6.)], 1., 1);
+
+        assert!(transmission.is_locked());
+    }
+
+    #[test]
+    fn locked_surfaces_stay_frozen_until_reset() {
+        let mut transmission = HighLiftTransmission::new();
+        run(&mut transmission, [gpm(6.), gpm(6.)], 1., 1);
+        let frozen_position = transmission.position(1);
+        run(&mut transmission, rated_flows(), 1., 10);
+        assert_eq!(transmission.position(1), frozen_position);
+
+        transmission.reset_wing_tip_brakes();
+        run(&mut transmission, rated_flows(), 1., 10);
+        assert!(transmission.position(1) > frozen_position);
+    }
+
+    #[test]
+    fn wing_tip_brakes_need_pressure_to_apply() {
+        let mut transmission = HighLiftTransmission::new();
+        transmission.disconnect(2);
+        for _ in 0..600 {
+            transmission.update(
+                &Duration::from_millis(100),
+                rated_flows(),
+                Pressure::new::<psi>(0.),
+                Ratio::new::<ratio>(1.),
+            );
+        }
+
+        assert!(!transmission.is_locked());
+        assert!(transmission.position(1) < Ratio::new::<ratio>(0.1));
+    }
+
+    fn run(
+        transmission: &mut HighLiftTransmission,
+        flows: [VolumeRate; 2],
+        command: f64,
+        seconds: u64,
+    ) {
+        for _ in 0..seconds * 10 {
+            transmission.update(
+                &Duration::from_millis(100),
+                flows,
+                Pressure::new::<psi>(3000.),
+                Ratio::new::<ratio>(command),
+            );
+        }
+    }
+
+    fn rated_flows() -> [VolumeRate; 2] {
+        let flow = HighLiftTransmission::rated_motor_flow(Pressure::new::<psi>(3000.));
+        [flow, flow]
+    }
+
+    fn gpm(flow: f64) -> VolumeRate {
+        VolumeRate::new::<gallon_per_minute>(flow)
+    }
+
+    fn is_about(position: Ratio, expected: f64) -> bool {
+        (position.get::<ratio>() - expected).abs() < 1e-6
+    }
+}
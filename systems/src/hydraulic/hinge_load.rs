@@ -0,0 +1,144 @@
+use crate::simulator::UpdateContext;
+use uom::si::{angle::degree, f64::*, force::newton, ratio::ratio, velocity::meter_per_second};
+
+/// Aerodynamic load on a control surface, pushing it back to its trailing position.
+///
+/// The load grows with dynamic pressure and with the angle between the surface
+/// and the airflow. On ailerons wing flex, which follows the load factor, adds
+/// to the load.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HingeLoad {
+    newton_per_pascal_per_degree: f64,
+    wing_number: Option<usize>,
+}
+impl HingeLoad {
+    const SEA_LEVEL_AIR_DENSITY_KG_PER_CUBIC_METER: f64 = 1.225;
+    const AILERON_NEWTON_PER_PASCAL_PER_DEGREE: f64 = 0.12;
+    const ELEVATOR_NEWTON_PER_PASCAL_PER_DEGREE: f64 = 0.15;
+
+    pub fn new(newton_per_pascal_per_degree: f64, wing_number: Option<usize>) -> Self {
+        HingeLoad {
+            newton_per_pascal_per_degree,
+            wing_number,
+        }
+    }
+
+    /// The load on the aileron of the given wing (1 for left, 2 for right).
+    pub fn aileron(wing_number: usize) -> Self {
+        HingeLoad::new(
+            HingeLoad::AILERON_NEWTON_PER_PASCAL_PER_DEGREE,
+            Some(wing_number),
+        )
+    }
+
+    pub fn elevator() -> Self {
+        HingeLoad::new(HingeLoad::ELEVATOR_NEWTON_PER_PASCAL_PER_DEGREE, None)
+    }
+
+    /// The load for the given surface deflection, positive when pushing the
+    /// surface towards negative angles.
+    pub fn load(&self, context: &UpdateContext, deflection: Angle) -> Force {
+        // Indicated airspeed relates to dynamic pressure through sea level air density.
+        let airspeed = context.indicated_airspeed.get::<meter_per_second>();
+        let dynamic_pressure =
+            0.5 * HingeLoad::SEA_LEVEL_AIR_DENSITY_KG_PER_CUBIC_METER * airspeed * airspeed;
+        let flow_angle = deflection + context.angle_of_attack();
+
+        Force::new::<newton>(
+            self.newton_per_pascal_per_degree
+                * dynamic_pressure
+                * flow_angle.get::<degree>()
+                * self.wing_flex_factor(context),
+        )
+    }
+
+    fn wing_flex_factor(&self, context: &UpdateContext) -> f64 {
+        self.wing_number.map_or(1., |number| {
+            1. + context.wing_flex[number - 1].get::<ratio>().abs()
+        })
+    }
+}
+
+#[cfg(test)]
+mod hinge_load_tests {
+    use super::*;
+    use crate::simulator::test_helpers::context_with;
+    use uom::si::velocity::{foot_per_second, knot};
+
+    #[test]
+    fn no_load_without_airspeed() {
+        let context = context_with()
+            .indicated_airspeed(Velocity::new::<knot>(0.))
+            .build();
+
+        assert_eq!(
+            HingeLoad::elevator().load(&context, Angle::new::<degree>(10.)),
+            Force::new::<newton>(0.)
+        );
+    }
+
+    #[test]
+    fn load_increases_with_airspeed() {
+        let slow = context_with()
+            .indicated_airspeed(Velocity::new::<knot>(150.))
+            .build();
+        let fast = context_with()
+            .indicated_airspeed(Velocity::new::<knot>(300.))
+            .build();
+        let deflection = Angle::new::<degree>(10.);
+
+        assert!(
+            HingeLoad::elevator().load(&fast, deflection)
+                > HingeLoad::elevator().load(&slow, deflection)
+        );
+    }
+
+    #[test]
+    fn load_pushes_back_to_trailing_position() {
+        let context = context_with().build();
+
+        assert!(
+            HingeLoad::elevator().load(&context, Angle::new::<degree>(10.))
+                > Force::new::<newton>(0.)
+        );
+        assert!(
+            HingeLoad::elevator().load(&context, Angle::new::<degree>(-10.))
+                < Force::new::<newton>(0.)
+        );
+    }
+
+    #[test]
+    fn angle_of_attack_adds_to_load() {
+        let level = context_with().build();
+        let climbing = context_with()
+            .body_velocity(
+                Velocity::new::<foot_per_second>(400.),
+                Velocity::new::<foot_per_second>(-30.),
+            )
+            .build();
+        let deflection = Angle::new::<degree>(5.);
+
+        assert!(
+            HingeLoad::elevator().load(&climbing, deflection)
+                > HingeLoad::elevator().load(&level, deflection)
+        );
+    }
+
+    #[test]
+    fn wing_flex_increases_aileron_load_on_its_wing_only() {
+        let level = context_with().build();
+        let left_wing_flexed = context_with()
+            .wing_flex([Ratio::new::<ratio>(0.5), Ratio::new::<ratio>(0.)])
+            .build();
+        let deflection = Angle::new::<degree>(10.);
+
+        assert!(
+            HingeLoad::aileron(1).load(&left_wing_flexed, deflection)
+                > HingeLoad::aileron(1).load(&level, deflection)
+        );
+        assert_eq!(
+            HingeLoad::aileron(2).load(&left_wing_flexed, deflection),
+            HingeLoad::aileron(2).load(&level, deflection)
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::f64::consts::PI;
+use uom::si::{
+    angular_velocity::revolution_per_minute, f64::*, power::watt, pressure::pascal, ratio::ratio,
+    torque::newton_meter, volume::cubic_inch, volume::cubic_meter,
+    volume_rate::cubic_meter_per_second,
+};
+
+/// A fixed displacement hydraulic motor turning a rotary load, such as the
+/// emergency generator, the flap and slat power control units or a door drive.
+///
+/// The flow through the motor sets its speed, the pressure difference across
+/// it its torque. Internal leakage lowers the speed reached with a given flow,
+/// friction the torque reached with a given pressure difference.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HydraulicMotor {
+    displacement: Volume,
+    volumetric_efficiency: Ratio,
+    mechanical_efficiency: Ratio,
+    speed: AngularVelocity,
+    torque: Torque,
+}
+impl HydraulicMotor {
+    const EMERGENCY_GENERATOR_DISPLACEMENT_CUBIC_INCH: f64 = 0.125;
+    const EMERGENCY_GENERATOR_VOLUMETRIC_EFFICIENCY: f64 = 0.95;
+    const EMERGENCY_GENERATOR_MECHANICAL_EFFICIENCY: f64 = 0.9;
+
+    /// A motor of the given displacement per revolution.
+    pub fn new(
+        displacement: Volume,
+        volumetric_efficiency: Ratio,
+        mechanical_efficiency: Ratio,
+    ) -> Self {
+        HydraulicMotor {
+            displacement,
+            volumetric_efficiency,
+            mechanical_efficiency,
+            speed: AngularVelocity::new::<revolution_per_minute>(0.),
+            torque: Torque::new::<newton_meter>(0.),
+        }
+    }
+
+    /// The constant speed motor driving the emergency generator, which turns at
+    /// about 12000 rpm on the full flow of the ram air turbine.
+    pub fn emergency_generator() -> Self {
+        HydraulicMotor::new(
+            Volume::new::<cubic_inch>(HydraulicMotor::EMERGENCY_GENERATOR_DISPLACEMENT_CUBIC_INCH),
+            Ratio::new::<ratio>(HydraulicMotor::EMERGENCY_GENERATOR_VOLUMETRIC_EFFICIENCY),
+            Ratio::new::<ratio>(HydraulicMotor::EMERGENCY_GENERATOR_MECHANICAL_EFFICIENCY),
+        )
+    }
+
+    /// Updates the motor with the flow going through it and the pressure
+    /// difference between its inlet and its outlet.
+    pub fn update(&mut self, flow: VolumeRate, pressure_difference: Pressure) {
+        self.speed = self.speed_for(flow);
+        self.torque = self.torque_for(pressure_difference);
+    }
+
+    pub fn speed_for(&self, flow: VolumeRate) -> AngularVelocity {
+        let revolutions_per_second = flow.get::<cubic_meter_per_second>()
+            * self.volumetric_efficiency.get::<ratio>()
+            / self.displacement.get::<cubic_meter>();
+
+        AngularVelocity::new::<revolution_per_minute>(revolutions_per_second * 60.)
+    }
+
+    /// The flow the motor takes to turn at the given speed.
+    pub fn flow_for(&self, speed: AngularVelocity) -> VolumeRate {
+        VolumeRate::new::<cubic_meter_per_second>(
+            speed.get::<revolution_per_minute>() / 60. * self.displacement.get::<cubic_meter>()
+                / self.volumetric_efficiency.get::<ratio>(),
+        )
+    }
+
+    pub fn torque_for(&self, pressure_difference: Pressure) -> Torque {
+        Torque::new::<newton_meter>(
+            pressure_difference.get::<pascal>() * self.displacement.get::<cubic_meter>()
+                / (2. * PI)
+                * self.mechanical_efficiency.get::<ratio>(),
+        )
+    }
+
+    pub fn speed(&self) -> AngularVelocity {
+        self.speed
+    }
+
+    pub fn torque(&self) -> Torque {
+        self.torque
+    }
+
+    /// The mechanical power delivered to the load.
+    pub fn shaft_power(&self) -> Power {
+        Power::new::<watt>(
+            self.torque.get::<newton_meter>() * self.speed.get::<revolution_per_minute>() * 2. * PI
+                / 60.,
+        )
+    }
+}
+
+#[cfg(test)]
+mod hydraulic_motor_tests {
+    use super::*;
+    use uom::si::{pressure::psi, volume_rate::gallon_per_minute};
+
+    #[test]
+    fn does_not_turn_without_flow() {
+        let mut motor = HydraulicMotor::emergency_generator();
+        motor.update(gpm(0.), pressure(3000.));
+
+        assert_eq!(
+            motor.speed(),
+            AngularVelocity::new::<revolution_per_minute>(0.)
+        );
+        assert_eq!(motor.shaft_power(), Power::new::<watt>(0.));
+    }
+
+    #[test]
+    fn emergency_generator_motor_turns_at_about_12000_rpm_on_rat_flow() {
+        let mut motor = HydraulicMotor::emergency_generator();
+        motor.update(gpm(6.6), pressure(3000.));
+
+        let rpm = motor.speed().get::<revolution_per_minute>();
+        assert!(rpm > 11000. && rpm < 12500.);
+    }
+
+    #[test]
+    fn flow_for_speed_is_the_inverse_of_speed_for_flow() {
+        let motor = HydraulicMotor::emergency_generator();
+        let flow = motor.flow_for(motor.speed_for(gpm(4.)));
+
+        assert!((flow.get::<gallon_per_minute>() - 4.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn torque_grows_with_pressure_difference() {
+        let motor = HydraulicMotor::emergency_generator();
+
+        let torque_ratio = motor.torque_for(pressure(3000.)).get::<newton_meter>()
+            / motor.torque_for(pressure(1500.)).get::<newton_meter>();
+        assert!((torque_ratio - 2.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn shaft_power_is_hydraulic_power_less_losses() {
+        let mut motor = HydraulicMotor::emergency_generator();
+        motor.update(gpm(6.6), pressure(3000.));
+
+        let hydraulic_power =
+            pressure(3000.).get::<pascal>() * gpm(6.6).get::<cubic_meter_per_second>();
+        let efficiency = motor.shaft_power().get::<watt>() / hydraulic_power;
+        assert!((efficiency - 0.95 * 0.9).abs() < 1e-9);
+    }
+
+    fn gpm(flow: f64) -> VolumeRate {
+        VolumeRate::new::<gallon_per_minute>(flow)
+    }
+
+    fn pressure(pressure: f64) -> Pressure {
+        Pressure::new::<psi>(pressure)
+    }
+}
@@ -0,0 +1,250 @@
+use super::{ActuatorCommand, LinearActuator};
+use std::time::Duration;
+use uom::si::{
+    area::square_inch, f64::*, force::newton, length::inch, pressure::psi, ratio::ratio,
+    volume_rate::gallon_per_second,
+};
+
+/// A mechanical lock holding a landing gear or a door at the end of its travel.
+///
+/// The lock engages by itself when the locked part reaches it. It is released
+/// by a small unlocking actuator, which needs a pressure pulse of a minimum
+/// pressure and duration: with degraded pressure the lock doesn't release.
+pub struct HydraulicLock {
+    release_pressure: Pressure,
+    release_pulse: Duration,
+    pulse_duration: Duration,
+    locked: bool,
+}
+impl HydraulicLock {
+    pub fn new(release_pressure: Pressure, release_pulse: Duration, locked: bool) -> Self {
+        HydraulicLock {
+            release_pressure,
+            release_pulse,
+            pulse_duration: Duration::from_secs(0),
+            locked,
+        }
+    }
+
+    /// Updates the lock with the pressure on its unlocking actuator and whether
+    /// the part it holds is at the lock.
+    pub fn update(&mut self, delta_time: &Duration, unlock_pressure: Pressure, at_lock: bool) {
+        let releasing = unlock_pressure >= self.release_pressure;
+        self.pulse_duration = if releasing {
+            self.pulse_duration + *delta_time
+        } else {
+            Duration::from_secs(0)
+        };
+
+        if self.locked {
+            self.locked = self.pulse_duration < self.release_pulse;
+        } else {
+            self.locked = at_lock && !releasing;
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+/// A landing gear leg retracted and extended by its actuator, held up by its
+/// uplock and down by its downlock.
+///
+/// Selecting the gear down pressurises the uplock release actuator and the
+/// extension side of the gear actuator, selecting it up pressurises the
+/// downlock release actuator and the retraction side. The gear only moves once
+/// the lock holding it is released.
+pub struct LandingGearLeg {
+    actuator: LinearActuator,
+    uplock: HydraulicLock,
+    downlock: HydraulicLock,
+}
+impl LandingGearLeg {
+    const LOCK_RELEASE_PRESSURE_PSI: f64 = 1500.;
+    const LOCK_RELEASE_PULSE_MILLIS: u64 = 300;
+
+    /// A gear leg down and locked, as on a parked aircraft.
+    pub fn new(actuator: LinearActuator) -> Self {
+        LandingGearLeg {
+            actuator: actuator.at_position(Ratio::new::<ratio>(1.)),
+            uplock: LandingGearLeg::lock(false),
+            downlock: LandingGearLeg::lock(true),
+        }
+    }
+
+    pub fn nose() -> Self {
+        LandingGearLeg::new(LinearActuator::new(
+            Area::new::<square_inch>(3.),
+            Length::new::<inch>(16.),
+            VolumeRate::new::<gallon_per_second>(0.25),
+            Force::new::<newton>(5000.),
+        ))
+    }
+
+    pub fn main() -> Self {
+        LandingGearLeg::new(LinearActuator::new(
+            Area::new::<square_inch>(6.),
+            Length::new::<inch>(20.),
+            VolumeRate::new::<gallon_per_second>(0.5),
+            Force::new::<newton>(15000.),
+        ))
+    }
+
+    fn lock(locked: bool) -> HydraulicLock {
+        HydraulicLock::new(
+            Pressure::new::<psi>(LandingGearLeg::LOCK_RELEASE_PRESSURE_PSI),
+            Duration::from_millis(LandingGearLeg::LOCK_RELEASE_PULSE_MILLIS),
+            locked,
+        )
+    }
+
+    pub fn update(&mut self, delta_time: &Duration, pressure: Pressure, down_selected: bool) {
+        let no_pressure = Pressure::new::<psi>(0.);
+        let (uplock_release_pressure, downlock_release_pressure) = if down_selected {
+            (pressure, no_pressure)
+        } else {
+            (no_pressure, pressure)
+        };
+        self.uplock.update(
+            delta_time,
+            uplock_release_pressure,
+            self.actuator.is_fully_retracted(),
+        );
+        self.downlock.update(
+            delta_time,
+            downlock_release_pressure,
+            self.actuator.is_fully_extended(),
+        );
+
+        if !self.uplock.is_locked() && !self.downlock.is_locked() {
+            let command = if down_selected {
+                ActuatorCommand::Extend
+            } else {
+                ActuatorCommand::Retract
+            };
+            self.actuator.update(delta_time, pressure, command);
+        }
+    }
+
+    /// Ratio of the travel, from 0 when up to 1 when down.
+    pub fn position(&self) -> Ratio {
+        self.actuator.position()
+    }
+
+    pub fn is_uplocked(&self) -> bool {
+        self.uplock.is_locked()
+    }
+
+    pub fn is_downlocked(&self) -> bool {
+        self.downlock.is_locked()
+    }
+
+    pub fn is_in_transit(&self) -> bool {
+        !self.is_uplocked() && !self.is_downlocked()
+    }
+}
+
+#[cfg(test)]
+mod landing_gear_tests {
+    use super::*;
+
+    #[test]
+    fn lock_releases_after_a_pressure_pulse() {
+        let mut lock = lock();
+        lock.update(&Duration::from_millis(200), psi(3000.), true);
+        assert!(lock.is_locked());
+
+        lock.update(&Duration::from_millis(200), psi(3000.), true);
+        assert!(!lock.is_locked());
+    }
+
+    #[test]
+    fn lock_does_not_release_with_degraded_pressure() {
+        let mut lock = lock();
+        lock.update(&Duration::from_secs(10), psi(1000.), true);
+
+        assert!(lock.is_locked());
+    }
+
+    #[test]
+    fn interrupted_pulse_does_not_release_the_lock() {
+        let mut lock = lock();
+        lock.update(&Duration::from_millis(200), psi(3000.), true);
+        lock.update(&Duration::from_millis(100), psi(0.), true);
+        lock.update(&Duration::from_millis(200), psi(3000.), true);
+
+        assert!(lock.is_locked());
+    }
+
+    #[test]
+    fn lock_engages_when_reached_without_release_pressure() {
+        let mut lock = HydraulicLock::new(psi(1500.), Duration::from_millis(300), false);
+        lock.update(&Duration::from_millis(100), psi(0.), false);
+        assert!(!lock.is_locked());
+
+        lock.update(&Duration::from_millis(100), psi(0.), true);
+        assert!(lock.is_locked());
+    }
+
+    #[test]
+    fn gear_starts_down_and_locked() {
+        let gear = LandingGearLeg::main();
+
+        assert!(gear.is_downlocked());
+        assert!(!gear.is_uplocked());
+        assert_eq!(gear.position(), Ratio::new::<ratio>(1.));
+    }
+
+    #[test]
+    fn gear_retracts_and_uplocks() {
+        let mut gear = LandingGearLeg::main();
+        run(&mut gear, 3000., false, 20);
+
+        assert!(gear.is_uplocked());
+        assert!(!gear.is_downlocked());
+        assert_eq!(gear.position(), Ratio::new::<ratio>(0.));
+    }
+
+    #[test]
+    fn gear_is_in_transit_while_moving() {
+        let mut gear = LandingGearLeg::main();
+        run(&mut gear, 3000., false, 1);
+
+        assert!(gear.is_in_transit());
+    }
+
+    #[test]
+    fn gear_extends_and_downlocks() {
+        let mut gear = LandingGearLeg::nose();
+        run(&mut gear, 3000., false, 20);
+        run(&mut gear, 3000., true, 20);
+
+        assert!(gear.is_downlocked());
+        assert!(!gear.is_uplocked());
+    }
+
+    #[test]
+    fn uplocked_gear_stays_up_with_degraded_pressure() {
+        let mut gear = LandingGearLeg::main();
+        run(&mut gear, 3000., false, 20);
+        run(&mut gear, 1000., true, 20);
+
+        assert!(gear.is_uplocked());
+        assert_eq!(gear.position(), Ratio::new::<ratio>(0.));
+    }
+
+    fn lock() -> HydraulicLock {
+        HydraulicLock::new(psi(1500.), Duration::from_millis(300), true)
+    }
+
+    fn run(gear: &mut LandingGearLeg, pressure: f64, down_selected: bool, seconds: u64) {
+        for _ in 0..seconds * 10 {
+            gear.update(&Duration::from_millis(100), psi(pressure), down_selected);
+        }
+    }
+
+    fn psi(pressure: f64) -> Pressure {
+        Pressure::new::<psi>(pressure)
+    }
+}
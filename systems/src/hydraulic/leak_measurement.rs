@@ -0,0 +1,132 @@
+use std::time::Duration;
+use uom::si::{f64::*, pressure::psi};
+
+/// Measures the pressure decay of a loop during the leak measurement ground test.
+///
+/// The loop is pressurised by its pump, its leak measurement valves are closed
+/// to isolate the primary flight controls and the pump is stopped. How fast
+/// the loop then loses pressure tells how much the rest of the loop leaks.
+pub struct LeakMeasurement {
+    in_progress: bool,
+    start_pressure: Pressure,
+    latest_pressure: Pressure,
+    elapsed: Duration,
+}
+impl LeakMeasurement {
+    /// Shorter measurements mostly show the pressure ripple of the stopping pump.
+    const MIN_DURATION: Duration = Duration::from_secs(1);
+
+    pub fn new() -> Self {
+        LeakMeasurement {
+            in_progress: false,
+            start_pressure: Pressure::new::<psi>(0.),
+            latest_pressure: Pressure::new::<psi>(0.),
+            elapsed: Duration::from_secs(0),
+        }
+    }
+
+    /// Starts a new measurement from the given loop pressure, discarding the previous results.
+    pub fn start(&mut self, pressure: Pressure) {
+        self.in_progress = true;
+        self.start_pressure = pressure;
+        self.latest_pressure = pressure;
+        self.elapsed = Duration::from_secs(0);
+    }
+
+    /// Ends the measurement. Its results stay available until the next one starts.
+    pub fn stop(&mut self) {
+        self.in_progress = false;
+    }
+
+    pub fn update(&mut self, delta_time: &Duration, pressure: Pressure) {
+        if self.in_progress {
+            self.latest_pressure = pressure;
+            self.elapsed += *delta_time;
+        }
+    }
+
+    pub fn is_in_progress(&self) -> bool {
+        self.in_progress
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn pressure_drop(&self) -> Pressure {
+        self.start_pressure - self.latest_pressure
+    }
+
+    /// The average pressure lost per second since the measurement started,
+    /// once it lasted long enough to be meaningful.
+    pub fn decay_rate(&self) -> Option<Pressure> {
+        if self.elapsed < LeakMeasurement::MIN_DURATION {
+            None
+        } else {
+            Some(self.pressure_drop() / self.elapsed.as_secs_f64())
+        }
+    }
+}
+impl Default for LeakMeasurement {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod leak_measurement_tests {
+    use super::*;
+
+    #[test]
+    fn has_no_decay_rate_before_measuring() {
+        assert_eq!(LeakMeasurement::new().decay_rate(), None);
+    }
+
+    #[test]
+    fn has_no_decay_rate_during_the_first_second() {
+        let mut measurement = LeakMeasurement::new();
+        measurement.start(Pressure::new::<psi>(3000.));
+        measurement.update(&Duration::from_millis(500), Pressure::new::<psi>(2900.));
+
+        assert_eq!(measurement.decay_rate(), None);
+    }
+
+    #[test]
+    fn decay_rate_is_the_average_pressure_loss_per_second() {
+        let mut measurement = LeakMeasurement::new();
+        measurement.start(Pressure::new::<psi>(3000.));
+        measurement.update(&Duration::from_secs(5), Pressure::new::<psi>(2800.));
+        measurement.update(&Duration::from_secs(5), Pressure::new::<psi>(2500.));
+
+        assert_eq!(measurement.elapsed(), Duration::from_secs(10));
+        assert!(is_about(measurement.pressure_drop(), 500.));
+        assert!(is_about(measurement.decay_rate().unwrap(), 50.));
+    }
+
+    #[test]
+    fn results_are_kept_after_stopping() {
+        let mut measurement = LeakMeasurement::new();
+        measurement.start(Pressure::new::<psi>(3000.));
+        measurement.update(&Duration::from_secs(10), Pressure::new::<psi>(2900.));
+        measurement.stop();
+        measurement.update(&Duration::from_secs(10), Pressure::new::<psi>(0.));
+
+        assert!(!measurement.is_in_progress());
+        assert!(is_about(measurement.decay_rate().unwrap(), 10.));
+    }
+
+    #[test]
+    fn starting_again_discards_previous_results() {
+        let mut measurement = LeakMeasurement::new();
+        measurement.start(Pressure::new::<psi>(3000.));
+        measurement.update(&Duration::from_secs(10), Pressure::new::<psi>(2900.));
+        measurement.start(Pressure::new::<psi>(2900.));
+
+        assert_eq!(measurement.elapsed(), Duration::from_secs(0));
+        assert_eq!(measurement.decay_rate(), None);
+    }
+
+    fn is_about(pressure: Pressure, expected_psi: f64) -> bool {
+        (pressure.get::<psi>() - expected_psi).abs() < 0.001
+    }
+}
@@ -0,0 +1,236 @@
+use super::ActuatorRate;
+use std::time::Duration;
+use uom::si::{
+    f64::*, force::newton, pressure::psi, ratio::ratio, time::second,
+    volume_rate::gallon_per_second,
+};
+
+/// The position of the selector valve controlling an actuator.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ActuatorCommand {
+    Extend,
+    Retract,
+    /// Both sides of the piston are closed off, trapping the fluid in the actuator.
+    Neutral,
+}
+
+/// A hydraulic jack moving a load such as a landing gear, a door or a flap
+/// transmission over its stroke.
+///
+/// When the actuator isn't driven, the fluid trapped in it holds the load.
+/// The load pressurises the trapped fluid, which leaks past the piston seal,
+/// so a load pulled by gravity slowly drifts: doors sag and flaps creep on a
+/// parked aircraft. The same internal leakage adds to the loop static leaks
+/// while the actuator is pressurised.
+pub struct LinearActuator {
+    piston_area: Area,
+    stroke: Length,
+    max_flow: VolumeRate,
+    actuator_rate: ActuatorRate,
+    gravity_load: Force,
+    internal_leak_at_3000_psi: VolumeRate,
+    position: f64,
+    flow: VolumeRate,
+}
+impl LinearActuator {
+    const NOMINAL_PRESSURE_PSI: f64 = 3000.;
+
+    /// An actuator whose load is pulled by gravity towards extension for
+    /// positive gravity loads, and towards retraction for negative ones.
+    pub fn new(
+        piston_area: Area,
+        stroke: Length,
+        max_flow: VolumeRate,
+        gravity_load: Force,
+    ) -> Self {
+        LinearActuator {
+            piston_area,
+            stroke,
+            max_flow,
+            actuator_rate: ActuatorRate::new(
+                Pressure::new::<psi>(LinearActuator::NOMINAL_PRESSURE_PSI) * piston_area,
+            ),
+            gravity_load,
+            internal_leak_at_3000_psi: VolumeRate::new::<gallon_per_second>(0.),
+            position: 0.,
+            flow: VolumeRate::new::<gallon_per_second>(0.),
+        }
+    }
+
+    /// Leakage past the piston seal at 3000 psi across the piston.
+    pub fn with_internal_leakage(mut self, internal_leak_at_3000_psi: VolumeRate) -> Self {
+        self.internal_leak_at_3000_psi = internal_leak_at_3000_psi;
+        self
+    }
+
+    /// Places the actuator at the given ratio of its stroke.
+    pub fn at_position(mut self, position: Ratio) -> Self {
+        self.position = position.get::<ratio>().max(0.).min(1.);
+        self
+    }
+
+    pub fn update(&mut self, delta_time: &Duration, pressure: Pressure, command: ActuatorCommand) {
+        let direction = match command {
+            ActuatorCommand::Extend => 1.,
+            ActuatorCommand::Retract => -1.,
+            ActuatorCommand::Neutral => 0.,
+        };
+        // Gravity opposes moving against it and aids moving along with it.
+        let opposing_load = -self.gravity_load * direction;
+        let rate_ratio = if direction != 0. {
+            self.actuator_rate.rate_ratio(pressure, opposing_load)
+        } else {
+            0.
+        };
+
+        let (velocity, leak) = if rate_ratio > 0. {
+            (
+                self.max_flow / self.piston_area * rate_ratio * direction,
+                self.internal_leak_at(pressure),
+            )
+        } else {
+            let leak = self.internal_leak_at(self.gravity_load.abs() / self.piston_area);
+            (
+                leak / self.piston_area * self.gravity_load.get::<newton>().signum(),
+                VolumeRate::new::<gallon_per_second>(0.),
+            )
+        };
+
+        let travel = velocity * Time::new::<second>(delta_time.as_secs_f64()) / self.stroke;
+        self.position = (self.position + travel.get::<ratio>()).max(0.).min(1.);
+        self.flow = if rate_ratio > 0. {
+            self.max_flow * rate_ratio + leak
+        } else {
+            VolumeRate::new::<gallon_per_second>(0.)
+        };
+    }
+
+    fn internal_leak_at(&self, pressure_difference: Pressure) -> VolumeRate {
+        self.internal_leak_at_3000_psi
+            * (pressure_difference / Pressure::new::<psi>(LinearActuator::NOMINAL_PRESSURE_PSI))
+                .get::<ratio>()
+                .max(0.)
+    }
+
+    /// Ratio of the stroke, from 0 when fully retracted to 1 when fully extended.
+    pub fn position(&self) -> Ratio {
+        Ratio::new::<ratio>(self.position)
+    }
+
+    pub fn is_fully_extended(&self) -> bool {
+        self.position >= 1.
+    }
+
+    pub fn is_fully_retracted(&self) -> bool {
+        self.position <= 0.
+    }
+
+    /// The flow the actuator drew from its loop during its last update.
+    pub fn flow(&self) -> VolumeRate {
+        self.flow
+    }
+
+    pub fn internal_leak_at_3000_psi(&self) -> VolumeRate {
+        self.internal_leak_at_3000_psi
+    }
+}
+
+#[cfg(test)]
+mod linear_actuator_tests {
+    use super::*;
+    use uom::si::{area::square_inch, length::inch};
+
+    #[test]
+    fn pressurised_actuator_retracts_against_gravity() {
+        let mut actuator = door().at_position(Ratio::new::<ratio>(1.));
+        run(&mut actuator, 3000., ActuatorCommand::Retract, 10);
+
+        assert!(actuator.is_fully_retracted());
+    }
+
+    #[test]
+    fn pressurised_moving_actuator_draws_flow() {
+        let mut actuator = door();
+        actuator.update(
+            &Duration::from_millis(100),
+            Pressure::new::<psi>(3000.),
+            ActuatorCommand::Extend,
+        );
+
+        assert!(actuator.flow() > VolumeRate::new::<gallon_per_second>(0.));
+    }
+
+    #[test]
+    fn actuator_without_leakage_holds_its_load_in_neutral() {
+        let mut actuator = door().at_position(Ratio::new::<ratio>(0.5));
+        run(&mut actuator, 0., ActuatorCommand::Neutral, 3600);
+
+        assert_eq!(actuator.position(), Ratio::new::<ratio>(0.5));
+        assert_eq!(actuator.flow(), VolumeRate::new::<gallon_per_second>(0.));
+    }
+
+    #[test]
+    fn leaking_actuator_slowly_drifts_with_gravity() {
+        let mut actuator = leaking_door().at_position(Ratio::new::<ratio>(0.5));
+        run(&mut actuator, 0., ActuatorCommand::Neutral, 60);
+        let position_after_a_minute = actuator.position();
+        run(&mut actuator, 0., ActuatorCommand::Neutral, 8 * 3600);
+
+        assert!(position_after_a_minute > Ratio::new::<ratio>(0.5));
+        assert!(position_after_a_minute < Ratio::new::<ratio>(0.51));
+        assert!(actuator.position() > position_after_a_minute);
+    }
+
+    #[test]
+    fn leaking_actuator_drifts_towards_retraction_under_negative_gravity_load() {
+        let mut actuator = LinearActuator::new(
+            Area::new::<square_inch>(2.),
+            Length::new::<inch>(10.),
+            VolumeRate::new::<gallon_per_second>(0.1),
+            Force::new::<newton>(-2000.),
+        )
+        .with_internal_leakage(VolumeRate::new::<gallon_per_second>(0.001))
+        .at_position(Ratio::new::<ratio>(0.5));
+        run(&mut actuator, 0., ActuatorCommand::Neutral, 3600);
+
+        assert!(actuator.position() < Ratio::new::<ratio>(0.5));
+    }
+
+    #[test]
+    fn unloaded_leaking_actuator_does_not_drift() {
+        let mut actuator = LinearActuator::new(
+            Area::new::<square_inch>(2.),
+            Length::new::<inch>(10.),
+            VolumeRate::new::<gallon_per_second>(0.1),
+            Force::new::<newton>(0.),
+        )
+        .with_internal_leakage(VolumeRate::new::<gallon_per_second>(0.001))
+        .at_position(Ratio::new::<ratio>(0.5));
+        run(&mut actuator, 0., ActuatorCommand::Neutral, 3600);
+
+        assert_eq!(actuator.position(), Ratio::new::<ratio>(0.5));
+    }
+
+    fn door() -> LinearActuator {
+        LinearActuator::new(
+            Area::new::<square_inch>(2.),
+            Length::new::<inch>(10.),
+            VolumeRate::new::<gallon_per_second>(0.1),
+            Force::new::<newton>(2000.),
+        )
+    }
+
+    fn leaking_door() -> LinearActuator {
+        door().with_internal_leakage(VolumeRate::new::<gallon_per_second>(0.0001))
+    }
+
+    fn run(actuator: &mut LinearActuator, pressure: f64, command: ActuatorCommand, seconds: u64) {
+        for _ in 0..seconds {
+            actuator.update(
+                &Duration::from_secs(1),
+                Pressure::new::<psi>(pressure),
+                command,
+            );
+        }
+    }
+}
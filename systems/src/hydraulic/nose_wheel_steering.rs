@@ -0,0 +1,163 @@
+use crate::shared::Hysteresis;
+use uom::si::{angle::degree, f64::*, pressure::psi};
+
+/// The green loop nose wheel steering actuator, as seen by the ground handling code.
+///
+/// When a tug is attached or the tow pin is inserted the steering selector valve
+/// depressurises the actuator and the steering commands are disconnected, so the
+/// tug can turn the nose wheel up to its towing limit. Once towing ends steering
+/// re-engages only when the green loop pressure is high enough to hold the wheel.
+pub struct NoseWheelSteering {
+    pressure_switch: Hysteresis,
+    tow_engaged: bool,
+    available: bool,
+    actuator_pressure: Pressure,
+}
+impl NoseWheelSteering {
+    const ENGAGE_PRESSURE_PSI: f64 = 1500.;
+    const DISENGAGE_PRESSURE_PSI: f64 = 1000.;
+    const STEERING_ANGLE_LIMIT_DEGREES: f64 = 75.;
+    const TOWING_ANGLE_LIMIT_DEGREES: f64 = 95.;
+
+    pub fn new() -> Self {
+        NoseWheelSteering {
+            pressure_switch: Hysteresis::new(
+                NoseWheelSteering::DISENGAGE_PRESSURE_PSI,
+                NoseWheelSteering::ENGAGE_PRESSURE_PSI,
+            ),
+            tow_engaged: false,
+            available: false,
+            actuator_pressure: Pressure::new::<psi>(0.),
+        }
+    }
+
+    /// `controlled` tells whether the BSCU can control the steering.
+    pub fn update(&mut self, green_pressure: Pressure, tow_engaged: bool, controlled: bool) {
+        let pressurised = self.pressure_switch.update(green_pressure.get::<psi>());
+
+        self.tow_engaged = tow_engaged;
+        self.actuator_pressure = if tow_engaged {
+            Pressure::new::<psi>(0.)
+        } else {
+            green_pressure
+        };
+        self.available = !tow_engaged && pressurised && controlled;
+    }
+
+    /// Steering commands move the nose wheel.
+    pub fn is_available(&self) -> bool {
+        self.available
+    }
+
+    pub fn is_tow_engaged(&self) -> bool {
+        self.tow_engaged
+    }
+
+    pub fn actuator_pressure(&self) -> Pressure {
+        self.actuator_pressure
+    }
+
+    /// Maximum nose wheel angle either side of neutral: the steering limit when
+    /// available, the towing limit when towed and none otherwise.
+    pub fn angle_limit(&self) -> Angle {
+        if self.tow_engaged {
+            Angle::new::<degree>(NoseWheelSteering::TOWING_ANGLE_LIMIT_DEGREES)
+        } else if self.available {
+            Angle::new::<degree>(NoseWheelSteering::STEERING_ANGLE_LIMIT_DEGREES)
+        } else {
+            Angle::new::<degree>(0.)
+        }
+    }
+
+    /// The nose wheel angle resulting from a steering command, which is ignored
+    /// while steering isn't available.
+    pub fn commanded_angle(&self, command: Angle) -> Option<Angle> {
+        if self.available {
+            let limit = self.angle_limit();
+            Some(command.max(-limit).min(limit))
+        } else {
+            None
+        }
+    }
+}
+impl Default for NoseWheelSteering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod nose_wheel_steering_tests {
+    use super::*;
+
+    #[test]
+    fn is_available_with_green_pressure() {
+        let mut steering = NoseWheelSteering::new();
+        steering.update(Pressure::new::<psi>(3000.), false, true);
+
+        assert!(steering.is_available());
+        assert_eq!(steering.angle_limit(), Angle::new::<degree>(75.));
+        assert_eq!(steering.actuator_pressure(), Pressure::new::<psi>(3000.));
+    }
+
+    #[test]
+    fn towing_depressurises_actuator_and_disconnects_commands() {
+        let mut steering = NoseWheelSteering::new();
+        steering.update(Pressure::new::<psi>(3000.), true, true);
+
+        assert!(!steering.is_available());
+        assert_eq!(steering.actuator_pressure(), Pressure::new::<psi>(0.));
+        assert_eq!(steering.commanded_angle(Angle::new::<degree>(20.)), None);
+        assert_eq!(steering.angle_limit(), Angle::new::<degree>(95.));
+    }
+
+    #[test]
+    fn re_engages_after_towing_only_above_pressure_threshold() {
+        let mut steering = NoseWheelSteering::new();
+        steering.update(Pressure::new::<psi>(0.), true, true);
+        steering.update(Pressure::new::<psi>(1200.), false, true);
+
+        assert!(!steering.is_available());
+        assert_eq!(steering.angle_limit(), Angle::new::<degree>(0.));
+
+        steering.update(Pressure::new::<psi>(1500.), false, true);
+
+        assert!(steering.is_available());
+    }
+
+    #[test]
+    fn stays_available_while_pressure_drops_above_disengage_threshold() {
+        let mut steering = NoseWheelSteering::new();
+        steering.update(Pressure::new::<psi>(3000.), false, true);
+        steering.update(Pressure::new::<psi>(1200.), false, true);
+
+        assert!(steering.is_available());
+
+        steering.update(Pressure::new::<psi>(900.), false, true);
+
+        assert!(!steering.is_available());
+    }
+
+    #[test]
+    fn is_not_available_without_bscu_control() {
+        let mut steering = NoseWheelSteering::new();
+        steering.update(Pressure::new::<psi>(3000.), false, false);
+
+        assert!(!steering.is_available());
+    }
+
+    #[test]
+    fn commands_are_limited_to_steering_angle() {
+        let mut steering = NoseWheelSteering::new();
+        steering.update(Pressure::new::<psi>(3000.), false, true);
+
+        assert_eq!(
+            steering.commanded_angle(Angle::new::<degree>(-90.)),
+            Some(Angle::new::<degree>(-75.))
+        );
+        assert_eq!(
+            steering.commanded_angle(Angle::new::<degree>(10.)),
+            Some(Angle::new::<degree>(10.))
+        );
+    }
+}
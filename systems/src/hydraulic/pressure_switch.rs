@@ -0,0 +1,160 @@
+use std::time::Duration;
+use uom::si::{f64::*, pressure::psi};
+
+use crate::shared::Hysteresis;
+
+/// The pressure switch at the delivery port of a pump, driving its LO PRESS
+/// indication on the overhead panel and the ECAM.
+///
+/// The switch opens below its low threshold and closes again above its high
+/// threshold. A pump commanded on is given some time to build pressure before
+/// it is indicated at low pressure: past this timeout, the pump has a pressure
+/// not built fault until it is commanded off.
+pub struct PumpPressureSwitch {
+    switch: Hysteresis,
+    build_timeout: Duration,
+    commanded_on: bool,
+    pressure_built: bool,
+    time_without_pressure: Duration,
+}
+impl PumpPressureSwitch {
+    const LOW_PRESSURE_PSI: f64 = 1450.;
+    const HIGH_PRESSURE_PSI: f64 = 1750.;
+    const BUILD_TIMEOUT: Duration = Duration::from_secs(6);
+
+    pub fn new() -> Self {
+        PumpPressureSwitch::with_build_timeout(PumpPressureSwitch::BUILD_TIMEOUT)
+    }
+
+    pub fn with_build_timeout(build_timeout: Duration) -> Self {
+        PumpPressureSwitch {
+            switch: Hysteresis::new(
+                PumpPressureSwitch::LOW_PRESSURE_PSI,
+                PumpPressureSwitch::HIGH_PRESSURE_PSI,
+            ),
+            build_timeout,
+            commanded_on: false,
+            pressure_built: false,
+            time_without_pressure: Duration::from_secs(0),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        delta_time: &Duration,
+        commanded_on: bool,
+        delivery_pressure: Pressure,
+    ) {
+        let pressurised = self.switch.update(delivery_pressure.get::<psi>());
+
+        if !commanded_on {
+            self.pressure_built = false;
+            self.time_without_pressure = Duration::from_secs(0);
+        } else if pressurised {
+            self.pressure_built = true;
+        } else if !self.pressure_built {
+            self.time_without_pressure += *delta_time;
+        }
+        self.commanded_on = commanded_on;
+    }
+
+    /// The switch reads the delivery pressure above its threshold.
+    pub fn is_pressurised(&self) -> bool {
+        self.switch.output()
+    }
+
+    /// The pump was commanded on and is still building pressure.
+    pub fn is_building_pressure(&self) -> bool {
+        self.commanded_on && !self.pressure_built && self.time_without_pressure < self.build_timeout
+    }
+
+    pub fn has_pressure_not_built_fault(&self) -> bool {
+        self.commanded_on
+            && !self.pressure_built
+            && self.time_without_pressure >= self.build_timeout
+    }
+
+    /// The LO PRESS indication: the switch reads low pressure, unless the pump
+    /// was just commanded on and is still given time to build it.
+    pub fn has_low_pressure(&self) -> bool {
+        !self.is_pressurised() && !self.is_building_pressure()
+    }
+}
+impl Default for PumpPressureSwitch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod pump_pressure_switch_tests {
+    use super::*;
+
+    #[test]
+    fn stopped_pump_has_low_pressure() {
+        let mut switch = PumpPressureSwitch::new();
+        switch.update(&Duration::from_secs(1), false, Pressure::new::<psi>(0.));
+
+        assert!(switch.has_low_pressure());
+        assert!(!switch.has_pressure_not_built_fault());
+    }
+
+    #[test]
+    fn pump_building_pressure_has_no_low_pressure_indication() {
+        let mut switch = PumpPressureSwitch::new();
+        switch.update(&Duration::from_secs(2), true, Pressure::new::<psi>(500.));
+
+        assert!(switch.is_building_pressure());
+        assert!(!switch.has_low_pressure());
+    }
+
+    #[test]
+    fn pressure_not_built_within_timeout_is_a_fault() {
+        let mut switch = PumpPressureSwitch::new();
+        for _ in 0..7 {
+            switch.update(&Duration::from_secs(1), true, Pressure::new::<psi>(500.));
+        }
+
+        assert!(switch.has_pressure_not_built_fault());
+        assert!(switch.has_low_pressure());
+    }
+
+    #[test]
+    fn fault_clears_when_commanded_off() {
+        let mut switch = PumpPressureSwitch::new();
+        for _ in 0..7 {
+            switch.update(&Duration::from_secs(1), true, Pressure::new::<psi>(500.));
+        }
+        switch.update(&Duration::from_secs(1), false, Pressure::new::<psi>(500.));
+
+        assert!(!switch.has_pressure_not_built_fault());
+    }
+
+    #[test]
+    fn switch_has_hysteresis() {
+        let mut switch = PumpPressureSwitch::new();
+        switch.update(&Duration::from_secs(1), true, Pressure::new::<psi>(1600.));
+
+        assert!(!switch.is_pressurised());
+
+        switch.update(&Duration::from_secs(1), true, Pressure::new::<psi>(3000.));
+        switch.update(&Duration::from_secs(1), true, Pressure::new::<psi>(1600.));
+
+        assert!(switch.is_pressurised());
+        assert!(!switch.has_low_pressure());
+    }
+
+    #[test]
+    fn pressure_lost_after_being_built_is_low_pressure_without_timeout() {
+        let mut switch = PumpPressureSwitch::new();
+        switch.update(&Duration::from_secs(1), true, Pressure::new::<psi>(3000.));
+        switch.update(
+            &Duration::from_millis(100),
+            true,
+            Pressure::new::<psi>(1000.),
+        );
+
+        assert!(switch.has_low_pressure());
+        assert!(!switch.has_pressure_not_built_fault());
+    }
+}
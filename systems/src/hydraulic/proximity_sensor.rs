@@ -0,0 +1,187 @@
+use uom::si::{f64::*, ratio::ratio};
+
+/// A discrete position of a landing gear or a gear door, as told by its
+/// proximity sensors. A closed door is uplocked, an open one downlocked.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SensedPosition {
+    Uplocked,
+    Downlocked,
+    InTransit,
+}
+
+/// The end of the travel a proximity sensor is mounted at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SensorLocation {
+    Up,
+    Down,
+}
+
+/// A proximity sensor detecting when its target, carried by a gear or a door,
+/// comes close to the end of the travel it is mounted at.
+///
+/// A failed sensor is stuck near or far whatever the position of its target.
+pub struct ProximitySensor {
+    location: SensorLocation,
+    near: bool,
+    failed_near: Option<bool>,
+}
+impl ProximitySensor {
+    // Ratio of the travel within which the target is detected.
+    const DETECTION_RANGE: f64 = 0.01;
+
+    pub fn new(location: SensorLocation) -> Self {
+        ProximitySensor {
+            location,
+            near: false,
+            failed_near: None,
+        }
+    }
+
+    /// Updates the sensor with the position of its target, from 0 when up to 1 when down.
+    pub fn update(&mut self, position: Ratio) {
+        let distance = match self.location {
+            SensorLocation::Up => position,
+            SensorLocation::Down => Ratio::new::<ratio>(1.) - position,
+        };
+
+        self.near = distance.get::<ratio>() <= ProximitySensor::DETECTION_RANGE;
+    }
+
+    /// Fails the sensor, stuck near or far from its target.
+    pub fn fail(&mut self, stuck_near: bool) {
+        self.failed_near = Some(stuck_near);
+    }
+
+    pub fn is_near(&self) -> bool {
+        self.failed_near.unwrap_or(self.near)
+    }
+}
+
+/// The up and down proximity sensors of a gear or a door, read by each of the two
+/// landing gear control and interface units (LGCIU).
+///
+/// Each unit has its own set of sensors. Their position is only given when both
+/// units agree on it, a failed sensor makes them disagree.
+pub struct GearPositionSensors {
+    // Up and down sensors of each unit.
+    units: [[ProximitySensor; 2]; 2],
+}
+impl GearPositionSensors {
+    pub fn new() -> Self {
+        GearPositionSensors {
+            units: [GearPositionSensors::unit(), GearPositionSensors::unit()],
+        }
+    }
+
+    fn unit() -> [ProximitySensor; 2] {
+        [
+            ProximitySensor::new(SensorLocation::Up),
+            ProximitySensor::new(SensorLocation::Down),
+        ]
+    }
+
+    /// Updates the sensors with the position of the gear or door, from 0 when up to 1 when down.
+    pub fn update(&mut self, position: Ratio) {
+        for sensor in self.units.iter_mut().flatten() {
+            sensor.update(position);
+        }
+    }
+
+    /// Fails the sensor of the given unit (1 or 2) at the given location.
+    pub fn fail_sensor(&mut self, unit: usize, location: SensorLocation, stuck_near: bool) {
+        let index = match location {
+            SensorLocation::Up => 0,
+            SensorLocation::Down => 1,
+        };
+
+        self.units[unit - 1][index].fail(stuck_near);
+    }
+
+    /// The position seen by the given unit (1 or 2), if its sensors aren't both near.
+    pub fn unit_position(&self, unit: usize) -> Option<SensedPosition> {
+        let [up, down] = &self.units[unit - 1];
+        match (up.is_near(), down.is_near()) {
+            (true, true) => None,
+            (true, false) => Some(SensedPosition::Uplocked),
+            (false, true) => Some(SensedPosition::Downlocked),
+            (false, false) => Some(SensedPosition::InTransit),
+        }
+    }
+
+    /// The position both units agree on, if any.
+    pub fn position(&self) -> Option<SensedPosition> {
+        match (self.unit_position(1), self.unit_position(2)) {
+            (Some(first), Some(second)) if first == second => Some(first),
+            _ => None,
+        }
+    }
+
+    pub fn has_disagreement(&self) -> bool {
+        self.position().is_none()
+    }
+}
+impl Default for GearPositionSensors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod proximity_sensor_tests {
+    use super::*;
+
+    #[test]
+    fn sensor_detects_its_target_at_the_end_of_travel() {
+        let mut sensor = ProximitySensor::new(SensorLocation::Down);
+        sensor.update(Ratio::new::<ratio>(0.5));
+        assert!(!sensor.is_near());
+
+        sensor.update(Ratio::new::<ratio>(1.));
+        assert!(sensor.is_near());
+    }
+
+    #[test]
+    fn failed_sensor_is_stuck() {
+        let mut sensor = ProximitySensor::new(SensorLocation::Up);
+        sensor.fail(false);
+        sensor.update(Ratio::new::<ratio>(0.));
+
+        assert!(!sensor.is_near());
+    }
+
+    #[test]
+    fn sensors_tell_the_position() {
+        assert_eq!(sensed(0.), Some(SensedPosition::Uplocked));
+        assert_eq!(sensed(0.5), Some(SensedPosition::InTransit));
+        assert_eq!(sensed(1.), Some(SensedPosition::Downlocked));
+    }
+
+    #[test]
+    fn sensor_failure_makes_the_units_disagree() {
+        let mut sensors = GearPositionSensors::new();
+        sensors.fail_sensor(2, SensorLocation::Down, false);
+        sensors.update(Ratio::new::<ratio>(1.));
+
+        assert_eq!(sensors.unit_position(1), Some(SensedPosition::Downlocked));
+        assert_eq!(sensors.unit_position(2), Some(SensedPosition::InTransit));
+        assert!(sensors.has_disagreement());
+        assert_eq!(sensors.position(), None);
+    }
+
+    #[test]
+    fn unit_with_both_sensors_near_has_no_position() {
+        let mut sensors = GearPositionSensors::new();
+        sensors.fail_sensor(1, SensorLocation::Up, true);
+        sensors.update(Ratio::new::<ratio>(1.));
+
+        assert_eq!(sensors.unit_position(1), None);
+        assert!(sensors.has_disagreement());
+    }
+
+    fn sensed(position: f64) -> Option<SensedPosition> {
+        let mut sensors = GearPositionSensors::new();
+        sensors.update(Ratio::new::<ratio>(position));
+
+        sensors.position()
+    }
+}
@@ -0,0 +1,129 @@
+use crate::shared::Hysteresis;
+use std::time::Duration;
+use uom::si::{
+    f64::*, power::watt, pressure::psi, thermodynamic_temperature::degree_celsius,
+    volume_rate::gallon_per_second,
+};
+
+/// Temperature of a pump case, as sensed in its case drain line.
+///
+/// The flow leaking inside the pump from its outlet to its case drain is
+/// throttled through the whole outlet pressure, which turns its hydraulic power
+/// into heat. The case drain flow carries this heat away to the fluid going
+/// back to the reservoir, so the case settles above the fluid temperature. A
+/// worn pump leaks more and runs hotter: above its overheat threshold the pump
+/// has an overheat fault until it cools down below the reset threshold.
+pub struct PumpCaseTemperature {
+    temperature: ThermodynamicTemperature,
+    overheat: Hysteresis,
+}
+impl PumpCaseTemperature {
+    const HEAT_CAPACITY: f64 = 3_000.; // in joules per kelvin, pump case and fluid inside
+    const COOLING_COEFFICIENT: f64 = 30.; // in watts per kelvin
+    const OVERHEAT_CELSIUS: f64 = 120.;
+    const OVERHEAT_RESET_CELSIUS: f64 = 100.;
+
+    pub fn new(temperature: ThermodynamicTemperature) -> Self {
+        PumpCaseTemperature {
+            temperature,
+            overheat: Hysteresis::new(
+                PumpCaseTemperature::OVERHEAT_RESET_CELSIUS,
+                PumpCaseTemperature::OVERHEAT_CELSIUS,
+            ),
+        }
+    }
+
+    pub fn temperature(&self) -> ThermodynamicTemperature {
+        self.temperature
+    }
+
+    pub fn update(
+        &mut self,
+        delta_time: &Duration,
+        fluid_temperature: ThermodynamicTemperature,
+        case_drain_flow: VolumeRate,
+        outlet_pressure: Pressure,
+    ) {
+        let heating: Power = outlet_pressure.max(Pressure::new::<psi>(0.))
+            * case_drain_flow.max(VolumeRate::new::<gallon_per_second>(0.));
+        let temperature_difference =
+            self.temperature.get::<degree_celsius>() - fluid_temperature.get::<degree_celsius>();
+        let net_power = heating.get::<watt>()
+            - PumpCaseTemperature::COOLING_COEFFICIENT * temperature_difference;
+
+        self.temperature = ThermodynamicTemperature::new::<degree_celsius>(
+            self.temperature.get::<degree_celsius>()
+                + net_power * delta_time.as_secs_f64() / PumpCaseTemperature::HEAT_CAPACITY,
+        );
+        self.overheat
+            .update(self.temperature.get::<degree_celsius>());
+    }
+
+    pub fn is_overheated(&self) -> bool {
+        self.overheat.output()
+    }
+}
+
+#[cfg(test)]
+mod pump_case_temperature_tests {
+    use super::*;
+    use uom::si::volume_rate::gallon_per_minute;
+
+    #[test]
+    fn settles_at_fluid_temperature_without_leakage() {
+        let case = case_after(40., 0., 3000., 600);
+
+        assert!((case.temperature().get::<degree_celsius>() - 40.).abs() < 0.1);
+        assert!(!case.is_overheated());
+    }
+
+    #[test]
+    fn new_pump_runs_slightly_above_fluid_temperature() {
+        let case = case_after(40., 0.25, 3000., 1200);
+
+        assert!(case.temperature().get::<degree_celsius>() > 45.);
+        assert!(!case.is_overheated());
+    }
+
+    #[test]
+    fn high_case_drain_flow_overheats_the_pump() {
+        let case = case_after(60., 1.75, 3000., 1200);
+
+        assert!(case.is_overheated());
+    }
+
+    #[test]
+    fn overheat_clears_once_cooled_down() {
+        let mut case = case_after(60., 1.75, 3000., 1200);
+        for _ in 0..1200 {
+            case.update(
+                &Duration::from_secs(1),
+                ThermodynamicTemperature::new::<degree_celsius>(40.),
+                VolumeRate::new::<gallon_per_minute>(1.75),
+                Pressure::new::<psi>(0.),
+            );
+        }
+
+        assert!(!case.is_overheated());
+    }
+
+    fn case_after(
+        fluid_temperature: f64,
+        case_drain_flow: f64,
+        outlet_pressure: f64,
+        seconds: u64,
+    ) -> PumpCaseTemperature {
+        let mut case =
+            PumpCaseTemperature::new(ThermodynamicTemperature::new::<degree_celsius>(40.));
+        for _ in 0..seconds {
+            case.update(
+                &Duration::from_secs(1),
+                ThermodynamicTemperature::new::<degree_celsius>(fluid_temperature),
+                VolumeRate::new::<gallon_per_minute>(case_drain_flow),
+                Pressure::new::<psi>(outlet_pressure),
+            );
+        }
+
+        case
+    }
+}
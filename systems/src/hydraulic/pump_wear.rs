@@ -0,0 +1,201 @@
+use crate::shared::Hysteresis;
+use std::time::Duration;
+use uom::si::{f64::*, pressure::psi, ratio::ratio, volume_rate::gallon_per_minute};
+
+/// Long term degradation of a pump from seal and bearing wear.
+///
+/// Wear accumulates with operating time and with pressure cycles, which
+/// fatigue the seals, and faster when the fluid is contaminated. A worn pump
+/// fills less of its displacement (lower volumetric efficiency) and leaks more
+/// of its outlet flow back to its case drain, the more so at high pressure.
+///
+/// The wear state can be saved and restored with [`PumpWear::with_state`], so it
+/// can persist from one flight to the next until maintenance clears it.
+pub struct PumpWear {
+    operating_time: Duration,
+    pressure_cycles: u32,
+    wear: f64,
+    contamination: f64,
+    pressure_switch: Hysteresis,
+}
+impl PumpWear {
+    const RATED_LIFE_HOURS: f64 = 20000.;
+    const RATED_PRESSURE_CYCLES: f64 = 200000.;
+    // Fully contaminated fluid wears the pump this many times faster than clean fluid.
+    const CONTAMINATED_FLUID_WEAR_FACTOR: f64 = 5.;
+    // A pressure cycle goes from below the low to above the high pressure.
+    const CYCLE_LOW_PRESSURE_PSI: f64 = 500.;
+    const CYCLE_HIGH_PRESSURE_PSI: f64 = 2500.;
+    const MAX_VOLUMETRIC_EFFICIENCY_LOSS: f64 = 0.15;
+    const MAX_INTERNAL_LEAKAGE_GPM: f64 = 1.5;
+    const RATED_PRESSURE_PSI: f64 = 3000.;
+
+    /// A new or freshly overhauled pump.
+    pub fn new() -> Self {
+        PumpWear::with_state(Duration::from_secs(0), 0, Ratio::new::<ratio>(0.))
+    }
+
+    /// Restores a saved wear state, wear going from 0 for a new pump to 1 for a
+    /// pump at the end of its life.
+    pub fn with_state(operating_time: Duration, pressure_cycles: u32, wear: Ratio) -> Self {
+        PumpWear {
+            operating_time,
+            pressure_cycles,
+            wear: wear.get::<ratio>().max(0.).min(1.),
+            contamination: 0.,
+            pressure_switch: Hysteresis::new(
+                PumpWear::CYCLE_LOW_PRESSURE_PSI,
+                PumpWear::CYCLE_HIGH_PRESSURE_PSI,
+            ),
+        }
+    }
+
+    /// Sets the contamination level of the fluid going through the pump,
+    /// from 0 for clean fluid to 1 for fully contaminated fluid.
+    pub fn set_contamination(&mut self, contamination: Ratio) {
+        self.contamination = contamination.get::<ratio>().max(0.).min(1.);
+    }
+
+    /// Accumulates wear while the pump is running and delivering the given pressure.
+    pub fn update(&mut self, delta_time: &Duration, running: bool, pressure: Pressure) {
+        if !running {
+            return;
+        }
+
+        self.operating_time += *delta_time;
+        let contamination_factor =
+            1. + (PumpWear::CONTAMINATED_FLUID_WEAR_FACTOR - 1.) * self.contamination;
+        let mut wear = delta_time.as_secs_f64() / 3600. / PumpWear::RATED_LIFE_HOURS;
+
+        let was_pressurised = self.pressure_switch.output();
+        if self.pressure_switch.update(pressure.get::<psi>()) && !was_pressurised {
+            self.pressure_cycles += 1;
+            wear += 1. / PumpWear::RATED_PRESSURE_CYCLES;
+        }
+
+        self.wear = (self.wear + wear * contamination_factor).min(1.);
+    }
+
+    /// Maintenance action: the pump is overhauled to as new condition.
+    pub fn perform_maintenance(&mut self) {
+        *self = PumpWear::new();
+    }
+
+    pub fn operating_time(&self) -> Duration {
+        self.operating_time
+    }
+
+    pub fn pressure_cycles(&self) -> u32 {
+        self.pressure_cycles
+    }
+
+    pub fn wear(&self) -> Ratio {
+        Ratio::new::<ratio>(self.wear)
+    }
+
+    /// Ratio of its displacement the pump fills.
+    pub fn volumetric_efficiency(&self) -> Ratio {
+        Ratio::new::<ratio>(1. - PumpWear::MAX_VOLUMETRIC_EFFICIENCY_LOSS * self.wear)
+    }
+
+    /// Flow leaking back to the case drain at the given outlet pressure.
+    pub fn internal_leakage(&self, pressure: Pressure) -> VolumeRate {
+        let pressure_ratio = pressure.get::<psi>().max(0.) / PumpWear::RATED_PRESSURE_PSI;
+        VolumeRate::new::<gallon_per_minute>(
+            PumpWear::MAX_INTERNAL_LEAKAGE_GPM * self.wear * pressure_ratio,
+        )
+    }
+
+    /// The flow a worn pump delivers instead of the given flow of a new one.
+    pub fn delivered_flow(&self, flow: VolumeRate, pressure: Pressure) -> VolumeRate {
+        (flow * self.volumetric_efficiency().get::<ratio>() - self.internal_leakage(pressure))
+            .max(VolumeRate::new::<gallon_per_minute>(0.))
+    }
+}
+impl Default for PumpWear {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod pump_wear_tests {
+    use super::*;
+
+    #[test]
+    fn new_pump_delivers_its_whole_flow() {
+        let wear = PumpWear::new();
+
+        assert_eq!(delivered_gpm(&wear, 3000.), 30.);
+    }
+
+    #[test]
+    fn worn_pump_delivers_less_flow_and_leaks_more_at_high_pressure() {
+        let wear = PumpWear::with_state(Duration::from_secs(0), 0, Ratio::new::<ratio>(1.));
+
+        assert!(delivered_gpm(&wear, 3000.) < delivered_gpm(&wear, 1000.));
+        assert!(delivered_gpm(&wear, 1000.) < 30. * 0.86);
+    }
+
+    #[test]
+    fn accumulates_operating_time_only_while_running() {
+        let mut wear = PumpWear::new();
+        run(&mut wear, false, 3000., 3600);
+        run(&mut wear, true, 3000., 3600);
+
+        assert_eq!(wear.operating_time(), Duration::from_secs(3600));
+        assert!(wear.wear() > Ratio::new::<ratio>(0.));
+    }
+
+    #[test]
+    fn counts_pressure_cycles() {
+        let mut wear = PumpWear::new();
+        for _ in 0..3 {
+            run(&mut wear, true, 3000., 1);
+            run(&mut wear, true, 1000., 1);
+            run(&mut wear, true, 0., 1);
+        }
+
+        assert_eq!(wear.pressure_cycles(), 3);
+    }
+
+    #[test]
+    fn contaminated_fluid_wears_the_pump_faster() {
+        let mut clean = PumpWear::new();
+        run(&mut clean, true, 3000., 3600);
+        let mut contaminated = PumpWear::new();
+        contaminated.set_contamination(Ratio::new::<ratio>(1.));
+        run(&mut contaminated, true, 3000., 3600);
+
+        assert!(contaminated.wear() > clean.wear() * 4.);
+    }
+
+    #[test]
+    fn maintenance_restores_a_new_pump() {
+        let mut wear =
+            PumpWear::with_state(Duration::from_secs(36000), 1000, Ratio::new::<ratio>(0.5));
+        wear.perform_maintenance();
+
+        assert_eq!(wear.operating_time(), Duration::from_secs(0));
+        assert_eq!(wear.pressure_cycles(), 0);
+        assert_eq!(wear.volumetric_efficiency(), Ratio::new::<ratio>(1.));
+    }
+
+    fn delivered_gpm(wear: &PumpWear, pressure: f64) -> f64 {
+        wear.delivered_flow(
+            VolumeRate::new::<gallon_per_minute>(30.),
+            Pressure::new::<psi>(pressure),
+        )
+        .get::<gallon_per_minute>()
+    }
+
+    fn run(wear: &mut PumpWear, running: bool, pressure: f64, seconds: u64) {
+        for _ in 0..seconds {
+            wear.update(
+                &Duration::from_secs(1),
+                running,
+                Pressure::new::<psi>(pressure),
+            );
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::simulator::TimeSource;
+use std::{error::Error, fmt, time::Duration};
+use uom::si::{f64::*, pressure::psi, ratio::ratio};
+
+/// The ram air turbine powering the blue loop in emergency. Once deployed it stays
+/// latched out until it is restowed on the ground.
+///
+/// Its deployment spring swings it out of the fuselage in a few seconds: the turbine
+/// only drives its pump once fully deployed.
+pub struct RamAirTurbine {
+    deployed: bool,
+    // From 0 when stowed to 1 when fully deployed.
+    position: f64,
+}
+impl RamAirTurbine {
+    // The stow actuator is driven by a hydraulic ground cart connected to the blue loop.
+    const MIN_STOW_PRESSURE_PSI: f64 = 2500.;
+    const DEPLOYMENT_TIME: Duration = Duration::from_secs(4);
+
+    pub fn new() -> RamAirTurbine {
+        RamAirTurbine {
+            deployed: false,
+            position: 0.,
+        }
+    }
+
+    pub fn deploy(&mut self) {
+        self.deployed = true;
+    }
+
+    pub fn update<S: TimeSource>(&mut self, time: &S) {
+        if self.deployed {
+            self.position = (self.position
+                + time.delta().as_secs_f64() / RamAirTurbine::DEPLOYMENT_TIME.as_secs_f64())
+            .min(1.);
+        }
+    }
+
+    /// Whether deployment was commanded, the turbine being in transit or fully deployed.
+    pub fn is_deployed(&self) -> bool {
+        self.deployed
+    }
+
+    pub fn is_fully_deployed(&self) -> bool {
+        self.position >= 1.
+    }
+
+    pub fn is_in_transit(&self) -> bool {
+        self.deployed && !self.is_fully_deployed()
+    }
+
+    pub fn deployment_progress(&self) -> Ratio {
+        Ratio::new::<ratio>(self.position)
+    }
+
+    /// Time left until the turbine is fully deployed, zero once deployed or when
+    /// deployment isn't commanded.
+    pub fn time_to_deploy(&self) -> Duration {
+        if self.is_in_transit() {
+            RamAirTurbine::DEPLOYMENT_TIME.mul_f64(1. - self.position)
+        } else {
+            Duration::from_secs(0)
+        }
+    }
+
+    /// Restows the turbine as a maintenance action, resetting the deployment latch.
+    /// Blue pressure from the turbine's own pump doesn't count, only what the ground
+    /// cart delivers.
+    pub fn stow(
+        &mut self,
+        on_ground: bool,
+        ground_cart_pressure: Pressure,
+    ) -> Result<(), RatStowError> {
+        if !on_ground {
+            return Err(RatStowError::NotOnGround);
+        }
+        if ground_cart_pressure < Pressure::new::<psi>(RamAirTurbine::MIN_STOW_PRESSURE_PSI) {
+            return Err(RatStowError::InsufficientPressure(ground_cart_pressure));
+        }
+
+        self.deployed = false;
+        self.position = 0.;
+        Ok(())
+    }
+}
+impl Default for RamAirTurbine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RatStowError {
+    NotOnGround,
+    InsufficientPressure(Pressure),
+}
+impl fmt::Display for RatStowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RatStowError::NotOnGround => write!(f, "The RAT can only be stowed on the ground"),
+            RatStowError::InsufficientPressure(pressure) => write!(
+                f,
+                "The RAT stow actuator needs {} psi from a ground cart, only {:.0} psi available",
+                RamAirTurbine::MIN_STOW_PRESSURE_PSI,
+                pressure.get::<psi>()
+            ),
+        }
+    }
+}
+impl Error for RatStowError {}
+
+#[cfg(test)]
+mod ram_air_turbine_tests {
+    use super::*;
+    use uom::si::ratio::percent;
+
+    #[test]
+    fn starts_stowed() {
+        assert!(!RamAirTurbine::new().is_deployed());
+    }
+
+    #[test]
+    fn stays_deployed_once_deployed() {
+        let mut rat = RamAirTurbine::new();
+        rat.deploy();
+
+        assert!(rat.is_deployed());
+    }
+
+    #[test]
+    fn is_in_transit_right_after_deployment() {
+        let mut rat = RamAirTurbine::new();
+        rat.deploy();
+        rat.update(&Duration::from_secs(1));
+
+        assert!(rat.is_in_transit());
+        assert!(!rat.is_fully_deployed());
+        assert!((rat.deployment_progress().get::<percent>() - 25.).abs() < 1e-9);
+        assert_eq!(rat.time_to_deploy(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn is_fully_deployed_after_deployment_time() {
+        let mut rat = RamAirTurbine::new();
+        rat.deploy();
+        rat.update(&Duration::from_secs(5));
+
+        assert!(!rat.is_in_transit());
+        assert!(rat.is_fully_deployed());
+        assert_eq!(rat.deployment_progress().get::<percent>(), 100.);
+        assert_eq!(rat.time_to_deploy(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn stowed_rat_does_not_move() {
+        let mut rat = RamAirTurbine::new();
+        rat.update(&Duration::from_secs(5));
+
+        assert!(!rat.is_in_transit());
+        assert_eq!(rat.deployment_progress().get::<percent>(), 0.);
+        assert_eq!(rat.time_to_deploy(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn cannot_be_stowed_in_flight() {
+        let mut rat = deployed_rat();
+
+        assert_eq!(
+            rat.stow(false, Pressure::new::<psi>(3000.)),
+            Err(RatStowError::NotOnGround)
+        );
+        assert!(rat.is_deployed());
+    }
+
+    #[test]
+    fn cannot_be_stowed_without_ground_cart_pressure() {
+        let mut rat = deployed_rat();
+
+        assert!(rat.stow(true, Pressure::new::<psi>(0.)).is_err());
+        assert!(rat.is_deployed());
+    }
+
+    #[test]
+    fn can_be_stowed_on_ground_with_ground_cart_pressure() {
+        let mut rat = deployed_rat();
+
+        assert_eq!(rat.stow(true, Pressure::new::<psi>(3000.)), Ok(()));
+        assert!(!rat.is_deployed());
+        assert_eq!(rat.deployment_progress().get::<percent>(), 0.);
+    }
+
+    fn deployed_rat() -> RamAirTurbine {
+        let mut rat = RamAirTurbine::new();
+        rat.deploy();
+        rat.update(&Duration::from_secs(5));
+
+        rat
+    }
+}
@@ -0,0 +1,191 @@
+use std::time::Duration;
+use uom::si::{f64::*, power::kilowatt, velocity::knot};
+
+use crate::shared::LowPassFilter;
+
+/// The blade pitch governor of the ram air turbine driving the RAT pump (Eaton PV3-115).
+///
+/// At low airspeed the blades windmill at fine pitch and the turbine speed grows
+/// with airspeed. Once the turbine reaches its normal speed the governor coarsens
+/// the blade pitch to hold it, letting the speed droop up as airspeed increases
+/// until the maximum overspeed.
+///
+/// The power the airflow gives the turbine grows with the cube of airspeed. When
+/// the load of the pump and of the emergency generator exceeds it, the turbine
+/// slows down and the pump delivers less flow.
+pub struct RatGovernor {
+    speed: LowPassFilter,
+    load: Power,
+    governing: bool,
+}
+impl RatGovernor {
+    pub const NORMAL_RPM: f64 = 6600.;
+    pub const MAX_OVERSPEED_RPM: f64 = 8250.;
+    // Airspeed at which the unloaded turbine reaches its normal speed at fine pitch.
+    const GOVERNING_SPEED_KNOTS: f64 = 140.;
+    // Speed increase per knot above the governing speed, reaching the maximum
+    // overspeed at 260 knots.
+    const GOVERNOR_DROOP_RPM_PER_KNOT: f64 = 13.75;
+    // Power the airflow gives the turbine at the governing speed.
+    const GOVERNING_SPEED_POWER_KW: f64 = 15.;
+    const SPEED_TIME_CONSTANT: Duration = Duration::from_millis(500);
+
+    pub fn new() -> Self {
+        RatGovernor {
+            speed: LowPassFilter::new(RatGovernor::SPEED_TIME_CONSTANT),
+            load: Power::new::<kilowatt>(0.),
+            governing: false,
+        }
+    }
+
+    /// Updates the turbine speed for the airspeed and the shaft power drawn from
+    /// it, returning the speed in RPM.
+    pub fn update(
+        &mut self,
+        delta_time: &Duration,
+        indicated_airspeed: Velocity,
+        load: Power,
+    ) -> f64 {
+        self.load = load;
+        let target = RatGovernor::unloaded_rpm(indicated_airspeed)
+            * RatGovernor::load_ratio(indicated_airspeed, load);
+        self.governing = target >= RatGovernor::NORMAL_RPM;
+
+        self.speed.update(delta_time, target)
+    }
+
+    pub fn rpm(&self) -> f64 {
+        self.speed.output()
+    }
+
+    /// How quickly the turbine speed follows the airspeed and the load.
+    pub fn set_speed_time_constant(&mut self, time_constant: Duration) {
+        self.speed.set_time_constant(time_constant);
+    }
+
+    /// Whether the governor coarsened the blade pitch to hold the turbine speed.
+    pub fn is_governing(&self) -> bool {
+        self.governing
+    }
+
+    /// The airspeed below which the turbine can't carry its current load and
+    /// slows down.
+    pub fn stall_speed(&self) -> Velocity {
+        Velocity::new::<knot>(
+            RatGovernor::GOVERNING_SPEED_KNOTS
+                * (self.load.get::<kilowatt>() / RatGovernor::GOVERNING_SPEED_POWER_KW).cbrt(),
+        )
+    }
+
+    fn unloaded_rpm(indicated_airspeed: Velocity) -> f64 {
+        let airspeed = indicated_airspeed.get::<knot>().max(0.);
+        if airspeed < RatGovernor::GOVERNING_SPEED_KNOTS {
+            RatGovernor::NORMAL_RPM * airspeed / RatGovernor::GOVERNING_SPEED_KNOTS
+        } else {
+            (RatGovernor::NORMAL_RPM
+                + (airspeed - RatGovernor::GOVERNING_SPEED_KNOTS)
+                    * RatGovernor::GOVERNOR_DROOP_RPM_PER_KNOT)
+                .min(RatGovernor::MAX_OVERSPEED_RPM)
+        }
+    }
+
+    // The load absorbs a power growing with the turbine speed: the speed drops in
+    // the ratio of the available power to the load.
+    fn load_ratio(indicated_airspeed: Velocity, load: Power) -> f64 {
+        let available = RatGovernor::available_power(indicated_airspeed);
+        if load > available {
+            (available / load).value
+        } else {
+            1.
+        }
+    }
+
+    fn available_power(indicated_airspeed: Velocity) -> Power {
+        let airspeed_ratio =
+            indicated_airspeed.get::<knot>().max(0.) / RatGovernor::GOVERNING_SPEED_KNOTS;
+
+        Power::new::<kilowatt>(RatGovernor::GOVERNING_SPEED_POWER_KW * airspeed_ratio.powi(3))
+    }
+}
+impl Default for RatGovernor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod rat_governor_tests {
+    use super::*;
+
+    #[test]
+    fn windmills_below_governing_speed() {
+        let governor = governor_after(70., 0.);
+
+        assert!((governor.rpm() - RatGovernor::NORMAL_RPM / 2.).abs() < 1.);
+        assert!(!governor.is_governing());
+    }
+
+    #[test]
+    fn governs_to_normal_speed() {
+        let governor = governor_after(140., 0.);
+
+        assert!((governor.rpm() - RatGovernor::NORMAL_RPM).abs() < 1.);
+        assert!(governor.is_governing());
+    }
+
+    #[test]
+    fn never_exceeds_max_overspeed() {
+        let governor = governor_after(350., 0.);
+
+        assert!((governor.rpm() - RatGovernor::MAX_OVERSPEED_RPM).abs() < 1.);
+    }
+
+    #[test]
+    fn slows_down_when_loaded_beyond_available_power() {
+        let unloaded = governor_after(140., 5.);
+        let loaded = governor_after(140., 30.);
+
+        assert!((unloaded.rpm() - RatGovernor::NORMAL_RPM).abs() < 1.);
+        assert!((loaded.rpm() - RatGovernor::NORMAL_RPM / 2.).abs() < 1.);
+    }
+
+    #[test]
+    fn stall_speed_increases_with_load() {
+        let pump_only = governor_after(250., 2.);
+        let pump_and_generator = governor_after(250., 8.);
+
+        assert!(pump_only.stall_speed() < pump_and_generator.stall_speed());
+        assert!(
+            (governor_after(250., 15.).stall_speed().get::<knot>()
+                - RatGovernor::GOVERNING_SPEED_KNOTS)
+                .abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn speed_lags_airspeed_changes() {
+        let mut governor = RatGovernor::new();
+        governor.update(
+            &Duration::from_millis(100),
+            Velocity::new::<knot>(140.),
+            Power::new::<kilowatt>(0.),
+        );
+
+        assert!(governor.rpm() > 0.);
+        assert!(governor.rpm() < RatGovernor::NORMAL_RPM);
+    }
+
+    fn governor_after(indicated_airspeed: f64, load: f64) -> RatGovernor {
+        let mut governor = RatGovernor::new();
+        for _ in 0..100 {
+            governor.update(
+                &Duration::from_millis(100),
+                Velocity::new::<knot>(indicated_airspeed),
+                Power::new::<kilowatt>(load),
+            );
+        }
+
+        governor
+    }
+}
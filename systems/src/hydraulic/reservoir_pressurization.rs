@@ -0,0 +1,168 @@
+use super::interpolation;
+use crate::{
+    shared::LowPassFilter,
+    simulator::{ambient_pressure, UpdateContext},
+};
+use std::time::Duration;
+use uom::si::{f64::*, length::foot, pressure::psi};
+
+/// Air pressurisation of a reservoir by bleed air, keeping the pump inlets
+/// above the pressure at which pumps cavitate.
+///
+/// Without bleed air a check valve traps the reservoir air pressure, which then
+/// slowly leaks away. At high altitude the reservoir then ends up near ambient
+/// pressure, and pumps can't fill completely any more: their flow degrades.
+pub struct ReservoirPressurization {
+    bleed_air_available: bool,
+    // Pressure above ambient.
+    gauge_pressure: LowPassFilter,
+    ambient_pressure: Pressure,
+}
+impl ReservoirPressurization {
+    const NOMINAL_GAUGE_PRESSURE_PSI: f64 = 50.;
+    const PRESSURISATION_TIME_CONSTANT: Duration = Duration::from_secs(2);
+    const LEAK_TIME_CONSTANT: Duration = Duration::from_secs(600);
+    const LOW_GAUGE_PRESSURE_PSI: f64 = 22.;
+    // Ratio of their displacement pumps fill for a given absolute inlet pressure.
+    const INLET_PRESSURE_BREAKPOINTS_PSI: [f64; 6] = [0., 2., 4., 6., 8., 10.];
+    const INLET_FILL_RATIO: [f64; 6] = [0., 0.3, 0.6, 0.85, 0.95, 1.];
+
+    /// A reservoir pressurised at its nominal pressure with bleed air available.
+    pub fn new() -> Self {
+        ReservoirPressurization {
+            bleed_air_available: true,
+            gauge_pressure: LowPassFilter::new_with_init_value(
+                ReservoirPressurization::PRESSURISATION_TIME_CONSTANT,
+                ReservoirPressurization::NOMINAL_GAUGE_PRESSURE_PSI,
+            ),
+            ambient_pressure: ambient_pressure(Length::new::<foot>(0.)),
+        }
+    }
+
+    pub fn set_bleed_air_available(&mut self, available: bool) {
+        self.bleed_air_available = available;
+    }
+
+    pub fn is_bleed_air_available(&self) -> bool {
+        self.bleed_air_available
+    }
+
+    pub fn update(&mut self, delta_time: &Duration, context: &UpdateContext) {
+        self.ambient_pressure = context.ambient_pressure;
+
+        let (time_constant, target) = if self.bleed_air_available {
+            (
+                ReservoirPressurization::PRESSURISATION_TIME_CONSTANT,
+                ReservoirPressurization::NOMINAL_GAUGE_PRESSURE_PSI,
+            )
+        } else {
+            (ReservoirPressurization::LEAK_TIME_CONSTANT, 0.)
+        };
+        self.gauge_pressure.set_time_constant(time_constant);
+        self.gauge_pressure.update(delta_time, target);
+    }
+
+    /// Absolute air pressure in the reservoir, which is the pump inlet pressure.
+    pub fn pressure(&self) -> Pressure {
+        self.ambient_pressure + Pressure::new::<psi>(self.gauge_pressure.output())
+    }
+
+    /// The reservoir air pressure is below what the low air pressure switch detects.
+    pub fn is_pressure_low(&self) -> bool {
+        self.gauge_pressure.output() < ReservoirPressurization::LOW_GAUGE_PRESSURE_PSI
+    }
+
+    /// Ratio of their displacement pumps fill at the current inlet pressure,
+    /// 1 when they don't cavitate.
+    pub fn pump_inlet_fill_ratio(&self) -> f64 {
+        interpolation(
+            &ReservoirPressurization::INLET_PRESSURE_BREAKPOINTS_PSI,
+            &ReservoirPressurization::INLET_FILL_RATIO,
+            self.pressure().get::<psi>(),
+        )
+    }
+}
+impl Default for ReservoirPressurization {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod reservoir_pressurization_tests {
+    use super::*;
+    use crate::simulator::test_helpers::context_with;
+
+    #[test]
+    fn bleed_air_keeps_reservoir_pressurised_at_altitude() {
+        let mut reservoir = ReservoirPressurization::new();
+        run(&mut reservoir, 39000., 3600);
+
+        assert!(reservoir.pressure() > Pressure::new::<psi>(50.));
+        assert_eq!(reservoir.pump_inlet_fill_ratio(), 1.);
+    }
+
+    #[test]
+    fn pressure_leaks_slowly_without_bleed_air() {
+        let mut reservoir = ReservoirPressurization::new();
+        reservoir.set_bleed_air_available(false);
+        run(&mut reservoir, 39000., 60);
+
+        assert!(reservoir.pressure() > Pressure::new::<psi>(40.));
+    }
+
+    #[test]
+    fn low_pressure_is_detected_once_pressure_has_leaked() {
+        let mut reservoir = ReservoirPressurization::new();
+        reservoir.set_bleed_air_available(false);
+        run(&mut reservoir, 0., 60);
+
+        assert!(!reservoir.is_pressure_low());
+
+        run(&mut reservoir, 0., 3600);
+
+        assert!(reservoir.is_pressure_low());
+    }
+
+    #[test]
+    fn pumps_cavitate_at_altitude_once_pressure_has_leaked() {
+        let mut reservoir = ReservoirPressurization::new();
+        reservoir.set_bleed_air_available(false);
+        run(&mut reservoir, 39000., 3600);
+
+        assert!(reservoir.pump_inlet_fill_ratio() < 0.6);
+        assert!(reservoir.pump_inlet_fill_ratio() > 0.);
+    }
+
+    #[test]
+    fn pumps_do_not_cavitate_at_low_altitude_without_bleed_air() {
+        let mut reservoir = ReservoirPressurization::new();
+        reservoir.set_bleed_air_available(false);
+        run(&mut reservoir, 0., 3600);
+
+        assert_eq!(reservoir.pump_inlet_fill_ratio(), 1.);
+    }
+
+    #[test]
+    fn bleed_air_restores_pressure() {
+        let mut reservoir = ReservoirPressurization::new();
+        reservoir.set_bleed_air_available(false);
+        run(&mut reservoir, 39000., 3600);
+
+        reservoir.set_bleed_air_available(true);
+        run(&mut reservoir, 39000., 10);
+
+        assert_eq!(reservoir.pump_inlet_fill_ratio(), 1.);
+    }
+
+    fn run(reservoir: &mut ReservoirPressurization, altitude_feet: f64, seconds: u64) {
+        let delta = Duration::from_secs(1);
+        let context = context_with()
+            .delta(delta)
+            .indicated_altitude(Length::new::<foot>(altitude_feet))
+            .build();
+        for _ in 0..seconds {
+            reservoir.update(&delta, &context);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use super::HydFluid;
+use std::f64::consts::PI;
+use uom::si::{
+    f64::*, length::meter, mass_density::kilogram_per_cubic_meter, pressure::pascal, ratio::ratio,
+    volume_rate::cubic_meter_per_second,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RestrictionKind {
+    Orifice,
+    Line { length: Length },
+}
+
+/// A restriction of the flow between two points of a loop: a sharp edged orifice,
+/// such as a valve passage, or a length of line.
+///
+/// The pressure drop depends on whether the flow is laminar or turbulent, told by
+/// its Reynolds number computed with the viscosity and density of the loop fluid.
+/// Laminar flow drops pressure proportionally to the flow, turbulent flow with
+/// its square. A partially closed valve is a restrictor with a reduced opening.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HydraulicRestrictor {
+    kind: RestrictionKind,
+    diameter: Length,
+    opening: Ratio,
+}
+impl HydraulicRestrictor {
+    const ORIFICE_DISCHARGE_COEFFICIENT: f64 = 0.7;
+    // Below this Reynolds number the flow through an orifice is laminar.
+    const ORIFICE_CRITICAL_REYNOLDS_NUMBER: f64 = 12.;
+    // Flow in a line is laminar below the first and turbulent above the second.
+    const LINE_LAMINAR_REYNOLDS_NUMBER: f64 = 2000.;
+    const LINE_TURBULENT_REYNOLDS_NUMBER: f64 = 4000.;
+    // A fully closed valve doesn't let any flow through, it isn't a restrictor.
+    const MIN_OPENING: f64 = 0.01;
+
+    pub fn orifice(diameter: Length) -> Self {
+        HydraulicRestrictor {
+            kind: RestrictionKind::Orifice,
+            diameter,
+            opening: Ratio::new::<ratio>(1.),
+        }
+    }
+
+    pub fn line(diameter: Length, length: Length) -> Self {
+        HydraulicRestrictor {
+            kind: RestrictionKind::Line { length },
+            diameter,
+            opening: Ratio::new::<ratio>(1.),
+        }
+    }
+
+    /// Ratio of the full flow area left open, down to one percent.
+    pub fn set_opening(&mut self, opening: Ratio) {
+        self.opening = opening
+            .max(Ratio::new::<ratio>(HydraulicRestrictor::MIN_OPENING))
+            .min(Ratio::new::<ratio>(1.));
+    }
+
+    pub fn opening(&self) -> Ratio {
+        self.opening
+    }
+
+    pub fn reynolds_number(&self, flow: VolumeRate, fluid: &HydFluid) -> f64 {
+        self.velocity(flow).abs() * self.effective_diameter() / fluid.get_kinematic_viscosity()
+    }
+
+    /// The pressure drop across the restrictor, with the sign of the flow.
+    pub fn pressure_drop(&self, flow: VolumeRate, fluid: &HydFluid) -> Pressure {
+        let drop = match self.kind {
+            RestrictionKind::Orifice => self.orifice_drop(flow, fluid),
+            RestrictionKind::Line { length } => self.line_drop(flow, length, fluid),
+        };
+
+        Pressure::new::<pascal>(drop.copysign(flow.get::<cubic_meter_per_second>()))
+    }
+
+    // Below the critical Reynolds number the drop continues linearly from the
+    // turbulent drop at the critical velocity.
+    fn orifice_drop(&self, flow: VolumeRate, fluid: &HydFluid) -> f64 {
+        let reynolds_number = self.reynolds_number(flow, fluid);
+        if reynolds_number >= HydraulicRestrictor::ORIFICE_CRITICAL_REYNOLDS_NUMBER {
+            HydraulicRestrictor::turbulent_orifice_drop(self.velocity(flow), fluid)
+        } else {
+            let critical_velocity = HydraulicRestrictor::ORIFICE_CRITICAL_REYNOLDS_NUMBER
+                * fluid.get_kinematic_viscosity()
+                / self.effective_diameter();
+
+            HydraulicRestrictor::turbulent_orifice_drop(critical_velocity, fluid) * reynolds_number
+                / HydraulicRestrictor::ORIFICE_CRITICAL_REYNOLDS_NUMBER
+        }
+    }
+
+    fn turbulent_orifice_drop(velocity: f64, fluid: &HydFluid) -> f64 {
+        let velocity = velocity / HydraulicRestrictor::ORIFICE_DISCHARGE_COEFFICIENT;
+
+        fluid.get_density().get::<kilogram_per_cubic_meter>() / 2. * velocity * velocity
+    }
+
+    // Darcy-Weisbach, with the friction factor of laminar flow or the Blasius one of
+    // turbulent flow, blended in between.
+    fn line_drop(&self, flow: VolumeRate, length: Length, fluid: &HydFluid) -> f64 {
+        let velocity = self.velocity(flow);
+        let friction_factor =
+            HydraulicRestrictor::friction_factor(self.reynolds_number(flow, fluid));
+
+        friction_factor * length.get::<meter>() / self.effective_diameter()
+            * fluid.get_density().get::<kilogram_per_cubic_meter>()
+            / 2.
+            * velocity
+            * velocity
+    }
+
+    fn friction_factor(reynolds_number: f64) -> f64 {
+        let laminar = |re: f64| 64. / re;
+        let turbulent = |re: f64| 0.316 * re.powf(-0.25);
+
+        if reynolds_number <= 0. {
+            0.
+        } else if reynolds_number <= HydraulicRestrictor::LINE_LAMINAR_REYNOLDS_NUMBER {
+            laminar(reynolds_number)
+        } else if reynolds_number >= HydraulicRestrictor::LINE_TURBULENT_REYNOLDS_NUMBER {
+            turbulent(reynolds_number)
+        } else {
+            let transition = (reynolds_number - HydraulicRestrictor::LINE_LAMINAR_REYNOLDS_NUMBER)
+                / (HydraulicRestrictor::LINE_TURBULENT_REYNOLDS_NUMBER
+                    - HydraulicRestrictor::LINE_LAMINAR_REYNOLDS_NUMBER);
+
+            laminar(HydraulicRestrictor::LINE_LAMINAR_REYNOLDS_NUMBER) * (1. - transition)
+                + turbulent(HydraulicRestrictor::LINE_TURBULENT_REYNOLDS_NUMBER) * transition
+        }
+    }
+
+    fn velocity(&self, flow: VolumeRate) -> f64 {
+        flow.get::<cubic_meter_per_second>() / self.area()
+    }
+
+    fn area(&self) -> f64 {
+        PI * self.diameter.get::<meter>().powi(2) / 4. * self.opening.get::<ratio>()
+    }
+
+    // The diameter of a circular passage with the area left open.
+    fn effective_diameter(&self) -> f64 {
+        self.diameter.get::<meter>() * self.opening.get::<ratio>().sqrt()
+    }
+}
+
+#[cfg(test)]
+mod restrictor_tests {
+    use super::*;
+    use uom::si::{length::millimeter, pressure::psi, volume_rate::gallon_per_minute};
+
+    #[test]
+    fn no_flow_no_pressure_drop() {
+        assert_eq!(
+            orifice().pressure_drop(gpm(0.), &fluid()),
+            Pressure::new::<pascal>(0.)
+        );
+        assert_eq!(
+            line().pressure_drop(gpm(0.), &fluid()),
+            Pressure::new::<pascal>(0.)
+        );
+    }
+
+    #[test]
+    fn turbulent_orifice_drop_grows_with_the_square_of_the_flow() {
+        let drop = orifice().pressure_drop(gpm(5.), &fluid());
+        let doubled_flow_drop = orifice().pressure_drop(gpm(10.), &fluid());
+
+        assert!(orifice().reynolds_number(gpm(5.), &fluid()) > 1000.);
+        assert!(((doubled_flow_drop / drop).get::<ratio>() - 4.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn laminar_line_drop_grows_with_the_flow() {
+        let drop = line().pressure_drop(gpm(0.1), &fluid());
+        let doubled_flow_drop = line().pressure_drop(gpm(0.2), &fluid());
+
+        assert!(line().reynolds_number(gpm(0.2), &fluid()) < 2000.);
+        assert!(((doubled_flow_drop / drop).get::<ratio>() - 2.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn line_drop_is_continuous_through_the_transition() {
+        let mut previous_drop = Pressure::new::<pascal>(0.);
+        for flow in 1..1000 {
+            let drop = line().pressure_drop(gpm(flow as f64 * 0.01), &fluid());
+
+            assert!(drop >= previous_drop);
+            assert!(drop - previous_drop < Pressure::new::<psi>(2.));
+            previous_drop = drop;
+        }
+    }
+
+    #[test]
+    fn drop_has_the_sign_of_the_flow() {
+        assert_eq!(
+            orifice().pressure_drop(gpm(-5.), &fluid()),
+            -orifice().pressure_drop(gpm(5.), &fluid())
+        );
+    }
+
+    #[test]
+    fn closing_a_valve_increases_its_drop() {
+        let mut valve = orifice();
+        let open_drop = valve.pressure_drop(gpm(5.), &fluid());
+        valve.set_opening(Ratio::new::<ratio>(0.5));
+
+        assert!(valve.pressure_drop(gpm(5.), &fluid()) > open_drop * 3.);
+    }
+
+    #[test]
+    fn opening_is_never_fully_closed() {
+        let mut valve = orifice();
+        valve.set_opening(Ratio::new::<ratio>(0.));
+
+        assert_eq!(valve.opening(), Ratio::new::<ratio>(0.01));
+        assert!(valve
+            .pressure_drop(gpm(5.), &fluid())
+            .get::<psi>()
+            .is_finite());
+    }
+
+    fn orifice() -> HydraulicRestrictor {
+        HydraulicRestrictor::orifice(Length::new::<millimeter>(5.))
+    }
+
+    fn line() -> HydraulicRestrictor {
+        HydraulicRestrictor::line(Length::new::<millimeter>(7.5), Length::new::<meter>(10.))
+    }
+
+    fn fluid() -> HydFluid {
+        HydFluid::new(Pressure::new::<pascal>(1450000000.))
+    }
+
+    fn gpm(flow: f64) -> VolumeRate {
+        VolumeRate::new::<gallon_per_minute>(flow)
+    }
+}
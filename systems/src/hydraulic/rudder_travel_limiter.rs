@@ -0,0 +1,153 @@
+use super::{interpolation, ActuatorRate};
+use crate::simulator::UpdateContext;
+use std::time::Duration;
+use uom::si::{angle::degree, f64::*, force::newton, velocity::knot};
+
+/// Limits the rudder travel as airspeed increases. The limiting actuator is
+/// powered by the green and yellow loops. Without pressure it freezes at the
+/// last limit.
+pub struct RudderTravelLimiter {
+    limit: Angle,
+    actuator_rate: ActuatorRate,
+}
+impl RudderTravelLimiter {
+    const SPEED_BREAKPOINTS_KNOTS: [f64; 8] = [0., 160., 200., 250., 300., 350., 380., 500.];
+    const LIMIT_DEGREES: [f64; 8] = [30., 30., 19.6, 11.5, 7.2, 4.6, 3.4, 3.4];
+    const MAX_RATE_DEGREES_PER_SECOND: f64 = 1.5;
+    const STALL_LOAD_NEWTON: f64 = 5000.;
+    // Friction of the limiting mechanism, residual pressure can't overcome it.
+    const FRICTION_LOAD_NEWTON: f64 = 200.;
+
+    pub fn new() -> Self {
+        RudderTravelLimiter {
+            limit: Angle::new::<degree>(RudderTravelLimiter::LIMIT_DEGREES[0]),
+            actuator_rate: ActuatorRate::new(Force::new::<newton>(
+                RudderTravelLimiter::STALL_LOAD_NEWTON,
+            )),
+        }
+    }
+
+    /// The limit commanded for the given airspeed.
+    pub fn commanded_limit(indicated_airspeed: Velocity) -> Angle {
+        Angle::new::<degree>(interpolation(
+            &RudderTravelLimiter::SPEED_BREAKPOINTS_KNOTS,
+            &RudderTravelLimiter::LIMIT_DEGREES,
+            indicated_airspeed.get::<knot>(),
+        ))
+    }
+
+    pub fn update(
+        &mut self,
+        delta_time: &Duration,
+        context: &UpdateContext,
+        green_pressure: Pressure,
+        yellow_pressure: Pressure,
+    ) {
+        let rate_ratio = self.actuator_rate.rate_ratio(
+            green_pressure.max(yellow_pressure),
+            Force::new::<newton>(RudderTravelLimiter::FRICTION_LOAD_NEWTON),
+        );
+        let max_step = Angle::new::<degree>(
+            RudderTravelLimiter::MAX_RATE_DEGREES_PER_SECOND
+                * rate_ratio
+                * delta_time.as_secs_f64(),
+        );
+        let commanded = RudderTravelLimiter::commanded_limit(context.indicated_airspeed);
+
+        self.limit += (commanded - self.limit).max(-max_step).min(max_step);
+    }
+
+    /// Maximum rudder deflection either side of neutral.
+    pub fn limit(&self) -> Angle {
+        self.limit
+    }
+}
+impl Default for RudderTravelLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod rudder_travel_limiter_tests {
+    use super::*;
+    use crate::simulator::test_helpers::context_with;
+    use uom::si::pressure::psi;
+
+    #[test]
+    fn full_travel_at_low_speed() {
+        assert_eq!(
+            RudderTravelLimiter::commanded_limit(Velocity::new::<knot>(140.)),
+            Angle::new::<degree>(30.)
+        );
+    }
+
+    #[test]
+    fn travel_is_limited_at_high_speed() {
+        assert_eq!(
+            RudderTravelLimiter::commanded_limit(Velocity::new::<knot>(400.)),
+            Angle::new::<degree>(3.4)
+        );
+    }
+
+    #[test]
+    fn pressurised_limiter_follows_commanded_limit() {
+        let mut limiter = RudderTravelLimiter::new();
+        run(&mut limiter, 300., 3000., 0., 60);
+
+        assert_limit(&limiter, 7.2);
+    }
+
+    #[test]
+    fn limiter_moves_at_limited_rate() {
+        let mut limiter = RudderTravelLimiter::new();
+        run(&mut limiter, 300., 3000., 0., 1);
+
+        assert!(limiter.limit() < Angle::new::<degree>(30.));
+        assert!(limiter.limit() > Angle::new::<degree>(7.2));
+    }
+
+    #[test]
+    fn either_loop_powers_the_limiter() {
+        let mut limiter = RudderTravelLimiter::new();
+        run(&mut limiter, 300., 0., 3000., 60);
+
+        assert_limit(&limiter, 7.2);
+    }
+
+    #[test]
+    fn limiter_freezes_at_last_limit_without_pressure() {
+        let mut limiter = RudderTravelLimiter::new();
+        run(&mut limiter, 300., 3000., 0., 60);
+
+        run(&mut limiter, 140., 14.7, 14.7, 60);
+
+        assert_limit(&limiter, 7.2);
+    }
+
+    fn assert_limit(limiter: &RudderTravelLimiter, degrees: f64) {
+        assert!((limiter.limit().get::<degree>() - degrees).abs() < 1e-9);
+    }
+
+    fn run(
+        limiter: &mut RudderTravelLimiter,
+        indicated_airspeed: f64,
+        green_pressure: f64,
+        yellow_pressure: f64,
+        seconds: u64,
+    ) {
+        let delta = Duration::from_millis(100);
+        let context = context_with()
+            .delta(delta)
+            .indicated_airspeed(Velocity::new::<knot>(indicated_airspeed))
+            .build();
+        for _ in 0..seconds * 10 {
+            limiter.update(
+                &delta,
+                &context,
+                Pressure::new::<psi>(green_pressure),
+                Pressure::new::<psi>(yellow_pressure),
+            );
+        }
+    }
+}
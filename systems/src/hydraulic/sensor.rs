@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+/// A digital sensor as seen by the aircraft displays: the measured value is
+/// sampled at a fixed rate, disturbed by noise and quantized to the sensor
+/// resolution. Values are in the unit of the resolution.
+pub struct DigitalSensor {
+    resolution: f64,
+    sample_period: Duration,
+    noise_amplitude: f64,
+    time_since_sample: Duration,
+    output: f64,
+    noise_state: u64,
+}
+impl DigitalSensor {
+    const ECAM_SAMPLE_PERIOD_MILLIS: u64 = 500;
+    const ECAM_PRESSURE_RESOLUTION_PSI: f64 = 50.;
+    const ECAM_PRESSURE_NOISE_PSI: f64 = 15.;
+    const ECAM_QUANTITY_RESOLUTION_GALLON: f64 = 0.05;
+    const ECAM_QUANTITY_NOISE_GALLON: f64 = 0.01;
+
+    pub fn new(
+        resolution: f64,
+        sample_period: Duration,
+        noise_amplitude: f64,
+        noise_seed: u64,
+    ) -> Self {
+        DigitalSensor {
+            resolution,
+            sample_period,
+            noise_amplitude,
+            time_since_sample: sample_period,
+            output: 0.,
+            // Xorshift can't leave the zero state.
+            noise_state: noise_seed.max(1),
+        }
+    }
+
+    /// A loop pressure sensor in psi, as displayed on the ECAM.
+    pub fn ecam_pressure(noise_seed: u64) -> Self {
+        DigitalSensor::new(
+            DigitalSensor::ECAM_PRESSURE_RESOLUTION_PSI,
+            Duration::from_millis(DigitalSensor::ECAM_SAMPLE_PERIOD_MILLIS),
+            DigitalSensor::ECAM_PRESSURE_NOISE_PSI,
+            noise_seed,
+        )
+    }
+
+    /// A reservoir quantity sensor in gallons, as displayed on the ECAM.
+    pub fn ecam_quantity(noise_seed: u64) -> Self {
+        DigitalSensor::new(
+            DigitalSensor::ECAM_QUANTITY_RESOLUTION_GALLON,
+            Duration::from_millis(DigitalSensor::ECAM_SAMPLE_PERIOD_MILLIS),
+            DigitalSensor::ECAM_QUANTITY_NOISE_GALLON,
+            noise_seed,
+        )
+    }
+
+    pub fn update(&mut self, delta_time: &Duration, measured: f64) {
+        self.time_since_sample += *delta_time;
+        if self.time_since_sample >= self.sample_period {
+            self.time_since_sample = Duration::from_secs(0);
+            let noisy = measured + self.noise_amplitude * self.next_noise();
+            self.output = (noisy / self.resolution).round() * self.resolution;
+        }
+    }
+
+    /// Noise uniformly distributed between -1 and 1.
+    fn next_noise(&mut self) -> f64 {
+        self.noise_state ^= self.noise_state << 13;
+        self.noise_state ^= self.noise_state >> 7;
+        self.noise_state ^= self.noise_state << 17;
+
+        (self.noise_state as f64 / u64::MAX as f64) * 2. - 1.
+    }
+
+    pub fn output(&self) -> f64 {
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod digital_sensor_tests {
+    use super::*;
+
+    #[test]
+    fn output_is_quantized_to_resolution() {
+        let mut sensor = DigitalSensor::ecam_pressure(1);
+        sensor.update(&Duration::from_millis(100), 2987.);
+
+        assert_eq!(sensor.output() % 50., 0.);
+    }
+
+    #[test]
+    fn output_stays_close_to_measured_value() {
+        let mut sensor = DigitalSensor::ecam_pressure(1);
+        for _ in 0..100 {
+            sensor.update(&Duration::from_millis(500), 3000.);
+
+            assert!((sensor.output() - 3000.).abs() <= 50.);
+        }
+    }
+
+    #[test]
+    fn output_is_held_between_samples() {
+        let mut sensor = DigitalSensor::ecam_pressure(1);
+        sensor.update(&Duration::from_millis(100), 3000.);
+
+        sensor.update(&Duration::from_millis(100), 0.);
+
+        assert!(sensor.output() > 2900.);
+    }
+
+    #[test]
+    fn output_follows_measured_value_at_next_sample() {
+        let mut sensor = DigitalSensor::ecam_pressure(1);
+        sensor.update(&Duration::from_millis(100), 3000.);
+
+        sensor.update(&Duration::from_millis(500), 0.);
+
+        assert!(sensor.output().abs() <= 50.);
+    }
+
+    #[test]
+    fn noise_makes_output_vary_around_a_resolution_step() {
+        let mut sensor = DigitalSensor::ecam_pressure(1);
+        let mut outputs = Vec::new();
+        for _ in 0..20 {
+            sensor.update(&Duration::from_millis(500), 3025.);
+            outputs.push(sensor.output());
+        }
+
+        assert!(outputs.contains(&3000.));
+        assert!(outputs.contains(&3050.));
+    }
+
+    #[test]
+    fn sensors_with_the_same_seed_give_the_same_output() {
+        let mut first = DigitalSensor::ecam_quantity(42);
+        let mut second = DigitalSensor::ecam_quantity(42);
+        for _ in 0..20 {
+            first.update(&Duration::from_millis(500), 3.8);
+            second.update(&Duration::from_millis(500), 3.8);
+
+            assert_eq!(first.output(), second.output());
+        }
+    }
+}
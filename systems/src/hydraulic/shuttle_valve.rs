@@ -0,0 +1,99 @@
+use uom::si::{f64::*, pressure::psi};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShuttleValvePort {
+    Normal,
+    Alternate,
+}
+
+/// A shuttle valve connects its outlet to either its normal or its alternate inlet.
+/// The shuttle is pushed over to the alternate inlet once the alternate pressure
+/// exceeds the normal pressure by the selection hysteresis, and is returned by its
+/// spring to the normal inlet as soon as the normal pressure is the highest again.
+/// The hysteresis keeps the shuttle from chattering between two close pressures.
+pub struct ShuttleValve {
+    hysteresis: Pressure,
+    selected: ShuttleValvePort,
+    outlet_pressure: Pressure,
+}
+impl ShuttleValve {
+    pub fn new(hysteresis: Pressure) -> Self {
+        ShuttleValve {
+            hysteresis,
+            selected: ShuttleValvePort::Normal,
+            outlet_pressure: Pressure::new::<psi>(0.),
+        }
+    }
+
+    pub fn update(&mut self, normal_pressure: Pressure, alternate_pressure: Pressure) {
+        self.selected = match self.selected {
+            ShuttleValvePort::Normal if alternate_pressure > normal_pressure + self.hysteresis => {
+                ShuttleValvePort::Alternate
+            }
+            ShuttleValvePort::Alternate if normal_pressure > alternate_pressure => {
+                ShuttleValvePort::Normal
+            }
+            selected => selected,
+        };
+
+        self.outlet_pressure = match self.selected {
+            ShuttleValvePort::Normal => normal_pressure,
+            ShuttleValvePort::Alternate => alternate_pressure,
+        };
+    }
+
+    pub fn selected(&self) -> ShuttleValvePort {
+        self.selected
+    }
+
+    pub fn outlet_pressure(&self) -> Pressure {
+        self.outlet_pressure
+    }
+}
+
+#[cfg(test)]
+mod shuttle_valve_tests {
+    use super::*;
+
+    #[test]
+    fn starts_on_normal_inlet() {
+        assert_eq!(valve().selected(), ShuttleValvePort::Normal);
+    }
+
+    #[test]
+    fn stays_on_normal_inlet_within_hysteresis() {
+        let mut valve = valve();
+        valve.update(psi(2950.), psi(3000.));
+
+        assert_eq!(valve.selected(), ShuttleValvePort::Normal);
+        assert_eq!(valve.outlet_pressure(), psi(2950.));
+    }
+
+    #[test]
+    fn moves_to_alternate_inlet_beyond_hysteresis() {
+        let mut valve = valve();
+        valve.update(psi(500.), psi(3000.));
+
+        assert_eq!(valve.selected(), ShuttleValvePort::Alternate);
+        assert_eq!(valve.outlet_pressure(), psi(3000.));
+    }
+
+    #[test]
+    fn returns_to_normal_inlet_once_normal_pressure_is_highest() {
+        let mut valve = valve();
+        valve.update(psi(500.), psi(3000.));
+        valve.update(psi(2990.), psi(3000.));
+        assert_eq!(valve.selected(), ShuttleValvePort::Alternate);
+
+        valve.update(psi(3010.), psi(3000.));
+        assert_eq!(valve.selected(), ShuttleValvePort::Normal);
+    }
+
+    fn valve() -> ShuttleValve {
+        ShuttleValve::new(psi(100.))
+    }
+
+    fn psi(pressure: f64) -> Pressure {
+        Pressure::new::<psi>(pressure)
+    }
+}
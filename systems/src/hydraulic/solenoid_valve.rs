@@ -0,0 +1,193 @@
+use crate::{
+    electrical::{ElectricalBusType, PowerConsumption},
+    simulator::{
+        SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor, UpdateContext,
+    },
+};
+use std::time::Duration;
+use uom::si::{f64::*, ratio::ratio};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolenoidValvePosition {
+    Open,
+    Closed,
+}
+impl SolenoidValvePosition {
+    fn opening(&self) -> f64 {
+        match self {
+            SolenoidValvePosition::Open => 1.,
+            SolenoidValvePosition::Closed => 0.,
+        }
+    }
+
+    fn other(&self) -> Self {
+        match self {
+            SolenoidValvePosition::Open => SolenoidValvePosition::Closed,
+            SolenoidValvePosition::Closed => SolenoidValvePosition::Open,
+        }
+    }
+}
+
+/// A valve moved by a solenoid. The valve is energised when it is commanded
+/// and its electrical bus is powered, and otherwise returned by its spring to
+/// its de-energised position. It takes its travel time to move from one
+/// position to the other.
+pub struct SolenoidValve {
+    power_consumption: PowerConsumption,
+    de_energised_position: SolenoidValvePosition,
+    travel_time: Duration,
+    commanded: bool,
+    opening: f64,
+}
+impl SolenoidValve {
+    pub fn new(
+        powered_by: ElectricalBusType,
+        de_energised_position: SolenoidValvePosition,
+        travel_time: Duration,
+    ) -> Self {
+        SolenoidValve {
+            power_consumption: PowerConsumption::from_single(powered_by),
+            de_energised_position,
+            travel_time,
+            commanded: false,
+            opening: de_energised_position.opening(),
+        }
+    }
+
+    /// Commands the solenoid to be energised or not.
+    pub fn command(&mut self, energise: bool) {
+        self.commanded = energise;
+    }
+
+    pub fn update(&mut self, context: &UpdateContext) {
+        let target = self.target_position().opening();
+        let max_travel = if self.travel_time > Duration::from_secs(0) {
+            context.delta.as_secs_f64() / self.travel_time.as_secs_f64()
+        } else {
+            1.
+        };
+
+        self.opening = if (target - self.opening).abs() <= max_travel {
+            target
+        } else {
+            self.opening + max_travel.copysign(target - self.opening)
+        };
+    }
+
+    fn target_position(&self) -> SolenoidValvePosition {
+        if self.is_energised() {
+            self.de_energised_position.other()
+        } else {
+            self.de_energised_position
+        }
+    }
+
+    pub fn is_energised(&self) -> bool {
+        self.commanded && self.power_consumption.is_powered()
+    }
+
+    /// Ratio of the valve travel towards its open position.
+    pub fn opening(&self) -> Ratio {
+        Ratio::new::<ratio>(self.opening)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.opening >= 1.
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.opening <= 0.
+    }
+
+    /// The valve is in transit while it moves between its open and closed positions.
+    pub fn is_in_transit(&self) -> bool {
+        !self.is_open() && !self.is_closed()
+    }
+}
+impl SimulatorElementVisitable for SolenoidValve {
+    fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+        self.power_consumption.accept(visitor);
+        visitor.visit(&mut Box::new(self));
+    }
+}
+impl SimulatorElement for SolenoidValve {}
+
+#[cfg(test)]
+mod solenoid_valve_tests {
+    use super::*;
+    use crate::{
+        electrical::{Current, ElectricPowerSource, ElectricalBus, PowerSupply, Powerable},
+        simulator::test_helpers::context_with,
+    };
+
+    #[test]
+    fn starts_in_its_de_energised_position() {
+        assert!(normally_open_valve().is_open());
+        assert!(!normally_open_valve().is_energised());
+    }
+
+    #[test]
+    fn commanded_and_powered_valve_moves_to_its_energised_position() {
+        let mut valve = normally_open_valve();
+        valve.command(true);
+        run(&mut valve, true, Duration::from_secs(1));
+
+        assert!(valve.is_energised());
+        assert!(valve.is_closed());
+    }
+
+    #[test]
+    fn commanded_valve_stays_in_its_de_energised_position_without_power() {
+        let mut valve = normally_open_valve();
+        valve.command(true);
+        run(&mut valve, false, Duration::from_secs(1));
+
+        assert!(!valve.is_energised());
+        assert!(valve.is_open());
+    }
+
+    #[test]
+    fn valve_is_in_transit_during_its_travel_time() {
+        let mut valve = normally_open_valve();
+        valve.command(true);
+        run(&mut valve, true, Duration::from_millis(200));
+
+        assert!(valve.is_in_transit());
+        assert!((valve.opening().get::<ratio>() - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn valve_returns_to_its_de_energised_position_on_power_loss() {
+        let mut valve = normally_open_valve();
+        valve.command(true);
+        run(&mut valve, true, Duration::from_secs(1));
+        run(&mut valve, false, Duration::from_secs(1));
+
+        assert!(valve.is_open());
+    }
+
+    fn normally_open_valve() -> SolenoidValve {
+        SolenoidValve::new(
+            ElectricalBusType::DirectCurrentEssential,
+            SolenoidValvePosition::Open,
+            Duration::from_millis(400),
+        )
+    }
+
+    fn run(valve: &mut SolenoidValve, powered: bool, duration: Duration) {
+        let mut bus = ElectricalBus::new(ElectricalBusType::DirectCurrentEssential);
+        bus.set_input(if powered {
+            Current::some(ElectricPowerSource::Batteries)
+        } else {
+            Current::none()
+        });
+        let mut supply = PowerSupply::new();
+        supply.add(&bus);
+        valve.power_consumption.supply_power(&supply);
+
+        let delta = Duration::from_millis(50);
+        for _ in 0..duration.as_millis() / delta.as_millis() {
+            valve.update(&context_with().delta(delta).build());
+        }
+    }
+}
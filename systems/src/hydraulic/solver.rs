@@ -0,0 +1,255 @@
+use super::{PressureSource, PtuSide};
+use uom::si::{f64::*, volume::gallon};
+
+/// The volume a flow source can exchange with a loop during a step. The source
+/// delivers at least `min` whatever the loop needs, and at most `max`. A negative
+/// `min` is a source able to take fluid from the loop, as a charging accumulator.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlowBounds {
+    pub min: Volume,
+    pub max: Volume,
+}
+impl FlowBounds {
+    pub fn new(min: Volume, max: Volume) -> Self {
+        FlowBounds { min, max }
+    }
+
+    pub fn none() -> Self {
+        FlowBounds::new(Volume::new::<gallon>(0.), Volume::new::<gallon>(0.))
+    }
+
+    fn add(self, min: Volume, max: Volume) -> Self {
+        FlowBounds::new(self.min + min, self.max + max)
+    }
+}
+
+/// What the PTU sides connected to a loop exchange with it during a step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PtuExchange {
+    /// Flow the driven side can deliver to the loop.
+    pub delivery: FlowBounds,
+    /// Fluid the driving side takes from the loop and sends back to the reservoir.
+    pub consumed_volume: Volume,
+}
+
+/// Sums the volumes the pumps connected to a loop can deliver during a step.
+pub fn compute_pump_flows<'a, I>(pumps: I) -> FlowBounds
+where
+    I: IntoIterator<Item = &'a dyn PressureSource>,
+{
+    pumps.into_iter().fold(FlowBounds::none(), |bounds, pump| {
+        bounds.add(pump.get_delta_vol_min(), pump.get_delta_vol_max())
+    })
+}
+
+/// Sums what the PTU sides connected to a loop deliver to and take from it.
+pub fn apply_ptu<'a, I>(sides: I) -> PtuExchange
+where
+    I: IntoIterator<Item = &'a PtuSide>,
+{
+    sides.into_iter().fold(
+        PtuExchange {
+            delivery: FlowBounds::none(),
+            consumed_volume: Volume::new::<gallon>(0.),
+        },
+        |exchange, side| PtuExchange {
+            delivery: exchange
+                .delivery
+                .add(side.get_delta_vol_min(), side.get_delta_vol_max()),
+            consumed_volume: exchange.consumed_volume + side.get_consumed_volume(),
+        },
+    )
+}
+
+/// The volume an accumulator can exchange with its loop during a step: it can
+/// discharge when the loop is below its gas pressure and charge when above, at
+/// the given flow and as long as it holds fluid to discharge.
+pub fn accumulator_bounds(
+    gas_pressure: Pressure,
+    loop_pressure: Pressure,
+    fluid_volume: Volume,
+    flow_volume: Volume,
+) -> FlowBounds {
+    if gas_pressure > loop_pressure {
+        FlowBounds::new(Volume::new::<gallon>(0.), fluid_volume.min(flow_volume))
+    } else {
+        FlowBounds::new(-flow_volume, Volume::new::<gallon>(0.))
+    }
+}
+
+/// The volume the loop takes from its sources to reach its target pressure,
+/// limited by their bounds and the fluid left in the reservoir. Sources also
+/// try to charge the accumulator.
+pub fn regulate_to_target(
+    volume_needed: Volume,
+    sources: FlowBounds,
+    accumulator: FlowBounds,
+    reservoir_volume: Volume,
+) -> Volume {
+    reservoir_volume.min(
+        sources
+            .min
+            .max(sources.max.min(volume_needed - accumulator.min)),
+    )
+}
+
+/// The volume the accumulator gives to the loop, negative when charging: it
+/// makes up for what the sources couldn't deliver, or stores their surplus.
+pub fn apply_accumulator(
+    volume_needed: Volume,
+    sources_volume: Volume,
+    accumulator: FlowBounds,
+) -> Volume {
+    (volume_needed - sources_volume)
+        .max(accumulator.min)
+        .min(accumulator.max)
+}
+
+/// The part of the sources volume delivered by the PTU, which only supplies
+/// what the pumps can't.
+pub fn ptu_share(sources_volume: Volume, pumps_max: Volume, ptu_max: Volume) -> Volume {
+    (sources_volume - pumps_max)
+        .max(Volume::new::<gallon>(0.))
+        .min(ptu_max)
+}
+
+#[cfg(test)]
+mod solver_tests {
+    use super::*;
+    use uom::si::pressure::psi;
+
+    struct Source(f64, f64);
+    impl PressureSource for Source {
+        fn get_delta_vol_max(&self) -> Volume {
+            gallons(self.1)
+        }
+
+        fn get_delta_vol_min(&self) -> Volume {
+            gallons(self.0)
+        }
+    }
+
+    #[test]
+    fn pump_flows_add_up() {
+        let pumps = [Source(0., 0.125), Source(0.0625, 0.25)];
+        let bounds = compute_pump_flows(pumps.iter().map(|pump| pump as &dyn PressureSource));
+
+        assert_eq!(bounds, FlowBounds::new(gallons(0.0625), gallons(0.375)));
+    }
+
+    #[test]
+    fn no_pump_delivers_nothing() {
+        assert_eq!(compute_pump_flows(None), FlowBounds::none());
+    }
+
+    #[test]
+    fn ptu_without_sides_exchanges_nothing() {
+        let exchange = apply_ptu(None);
+
+        assert_eq!(exchange.delivery, FlowBounds::none());
+        assert_eq!(exchange.consumed_volume, gallons(0.));
+    }
+
+    #[test]
+    fn accumulator_discharges_below_its_gas_pressure() {
+        let bounds = accumulator_bounds(
+            pressure(3000.),
+            pressure(2000.),
+            gallons(0.0625),
+            gallons(0.125),
+        );
+
+        assert_eq!(bounds, FlowBounds::new(gallons(0.), gallons(0.0625)));
+    }
+
+    #[test]
+    fn accumulator_charges_above_its_gas_pressure() {
+        let bounds = accumulator_bounds(
+            pressure(2000.),
+            pressure(3000.),
+            gallons(0.0625),
+            gallons(0.125),
+        );
+
+        assert_eq!(bounds, FlowBounds::new(gallons(-0.125), gallons(0.)));
+    }
+
+    #[test]
+    fn regulation_takes_what_is_needed_within_source_bounds() {
+        let sources = FlowBounds::new(gallons(0.), gallons(0.5));
+
+        assert_eq!(
+            regulate_to_target(gallons(0.25), sources, FlowBounds::none(), gallons(3.)),
+            gallons(0.25)
+        );
+        assert_eq!(
+            regulate_to_target(gallons(0.75), sources, FlowBounds::none(), gallons(3.)),
+            gallons(0.5)
+        );
+        assert_eq!(
+            regulate_to_target(gallons(-0.25), sources, FlowBounds::none(), gallons(3.)),
+            gallons(0.)
+        );
+    }
+
+    #[test]
+    fn regulation_is_limited_by_reservoir_fluid() {
+        let sources = FlowBounds::new(gallons(0.), gallons(0.5));
+
+        assert_eq!(
+            regulate_to_target(gallons(0.375), sources, FlowBounds::none(), gallons(0.125)),
+            gallons(0.125)
+        );
+    }
+
+    #[test]
+    fn sources_charge_the_accumulator_with_their_surplus() {
+        let sources = FlowBounds::new(gallons(0.), gallons(0.5));
+        let accumulator = FlowBounds::new(gallons(-0.125), gallons(0.));
+        let sources_volume = regulate_to_target(gallons(0.25), sources, accumulator, gallons(3.));
+
+        assert_eq!(sources_volume, gallons(0.375));
+        assert_eq!(
+            apply_accumulator(gallons(0.25), sources_volume, accumulator),
+            gallons(-0.125)
+        );
+    }
+
+    #[test]
+    fn accumulator_makes_up_for_sources_deficit() {
+        let accumulator = FlowBounds::new(gallons(0.), gallons(0.125));
+
+        assert_eq!(
+            apply_accumulator(gallons(0.25), gallons(0.1875), accumulator),
+            gallons(0.0625)
+        );
+        assert_eq!(
+            apply_accumulator(gallons(0.25), gallons(0.), accumulator),
+            gallons(0.125)
+        );
+    }
+
+    #[test]
+    fn ptu_only_supplies_what_pumps_cannot() {
+        assert_eq!(
+            ptu_share(gallons(0.125), gallons(0.25), gallons(0.375)),
+            gallons(0.)
+        );
+        assert_eq!(
+            ptu_share(gallons(0.375), gallons(0.25), gallons(0.375)),
+            gallons(0.125)
+        );
+        assert_eq!(
+            ptu_share(gallons(0.75), gallons(0.25), gallons(0.375)),
+            gallons(0.375)
+        );
+    }
+
+    fn gallons(volume: f64) -> Volume {
+        Volume::new::<gallon>(volume)
+    }
+
+    fn pressure(pressure: f64) -> Pressure {
+        Pressure::new::<psi>(pressure)
+    }
+}
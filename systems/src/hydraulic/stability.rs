@@ -0,0 +1,153 @@
+use uom::si::{f64::*, pressure::psi};
+
+/// Monitors the pressure change of a loop at each step to detect numerical oscillation:
+/// the pressure repeatedly overshooting in alternating directions by a significant amount.
+/// Physical pressure changes don't alternate direction at every step.
+pub struct StabilityWatchdog {
+    previous_delta: Pressure,
+    sign_changes: usize,
+}
+impl StabilityWatchdog {
+    // Pressure changes below this magnitude are considered noise.
+    const MIN_DELTA_PSI: f64 = 50.;
+    const OSCILLATION_SIGN_CHANGES: usize = 4;
+
+    pub fn new() -> StabilityWatchdog {
+        StabilityWatchdog {
+            previous_delta: Pressure::new::<psi>(0.),
+            sign_changes: 0,
+        }
+    }
+
+    /// Records the pressure change of the last step.
+    /// Returns true when the loop just started oscillating.
+    pub fn update(&mut self, delta: Pressure) -> bool {
+        let was_oscillating = self.is_oscillating();
+        let min_delta = Pressure::new::<psi>(StabilityWatchdog::MIN_DELTA_PSI);
+
+        if delta.abs() > min_delta
+            && self.previous_delta.abs() > min_delta
+            && delta.value.signum() != self.previous_delta.value.signum()
+        {
+            self.sign_changes += 1;
+        } else {
+            self.sign_changes = 0;
+        }
+        self.previous_delta = delta;
+
+        !was_oscillating && self.is_oscillating()
+    }
+
+    pub fn is_oscillating(&self) -> bool {
+        self.sign_changes >= StabilityWatchdog::OSCILLATION_SIGN_CHANGES
+    }
+
+    pub fn sign_changes(&self) -> usize {
+        self.sign_changes
+    }
+
+    /// While oscillating, limits the given pressure change to half of the previous
+    /// change's magnitude, so the oscillation is damped out.
+    pub fn limit(&self, delta: Pressure) -> Pressure {
+        if self.is_oscillating() {
+            let max_delta = self.previous_delta.abs() / 2.;
+            delta.max(-max_delta).min(max_delta)
+        } else {
+            delta
+        }
+    }
+}
+impl Default for StabilityWatchdog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod stability_watchdog_tests {
+    use super::*;
+
+    #[test]
+    fn monotonic_pressure_rise_is_stable() {
+        let mut watchdog = StabilityWatchdog::new();
+        for _ in 0..20 {
+            watchdog.update(Pressure::new::<psi>(200.));
+        }
+
+        assert!(!watchdog.is_oscillating());
+    }
+
+    #[test]
+    fn small_alternating_changes_are_noise() {
+        let mut watchdog = StabilityWatchdog::new();
+        alternate(&mut watchdog, 10., 20);
+
+        assert!(!watchdog.is_oscillating());
+    }
+
+    #[test]
+    fn detects_large_alternating_changes() {
+        let mut watchdog = StabilityWatchdog::new();
+        alternate(&mut watchdog, 500., 10);
+
+        assert!(watchdog.is_oscillating());
+    }
+
+    #[test]
+    fn reports_start_of_oscillation_once() {
+        let mut watchdog = StabilityWatchdog::new();
+        let mut starts = 0;
+        let mut delta = 500.;
+        for _ in 0..20 {
+            if watchdog.update(Pressure::new::<psi>(delta)) {
+                starts += 1;
+            }
+            delta = -delta;
+        }
+
+        assert_eq!(starts, 1);
+    }
+
+    #[test]
+    fn recovers_when_changes_stop_alternating() {
+        let mut watchdog = StabilityWatchdog::new();
+        alternate(&mut watchdog, 500., 10);
+        watchdog.update(Pressure::new::<psi>(0.));
+
+        assert!(!watchdog.is_oscillating());
+        assert_eq!(watchdog.sign_changes(), 0);
+    }
+
+    #[test]
+    fn limits_changes_while_oscillating() {
+        let mut watchdog = StabilityWatchdog::new();
+        alternate(&mut watchdog, 500., 10);
+
+        assert_eq!(
+            watchdog.limit(Pressure::new::<psi>(800.)),
+            Pressure::new::<psi>(250.)
+        );
+        assert_eq!(
+            watchdog.limit(Pressure::new::<psi>(-800.)),
+            Pressure::new::<psi>(-250.)
+        );
+    }
+
+    #[test]
+    fn does_not_limit_stable_changes() {
+        let watchdog = StabilityWatchdog::new();
+
+        assert_eq!(
+            watchdog.limit(Pressure::new::<psi>(800.)),
+            Pressure::new::<psi>(800.)
+        );
+    }
+
+    fn alternate(watchdog: &mut StabilityWatchdog, magnitude: f64, steps: usize) {
+        let mut delta = magnitude;
+        for _ in 0..steps {
+            watchdog.update(Pressure::new::<psi>(delta));
+            delta = -delta;
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use super::ActuatorType;
+use uom::si::{f64::*, volume_rate::gallon_per_second};
+
+/// The part of a loop a static leak is located in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LeakZone {
+    /// Pumps outlets, filters, valves and lines of the high pressure manifold.
+    HighPressureManifold,
+    /// All actuators of a kind connected to the loop.
+    ActuatorBank(ActuatorType),
+}
+impl LeakZone {
+    /// The primary flight controls are supplied through the leak measurement valves.
+    pub fn is_behind_leak_measurement_valve(&self) -> bool {
+        matches!(
+            self,
+            LeakZone::ActuatorBank(ActuatorType::Aileron)
+                | LeakZone::ActuatorBank(ActuatorType::Elevator)
+                | LeakZone::ActuatorBank(ActuatorType::Rudder)
+                | LeakZone::ActuatorBank(ActuatorType::Spoiler)
+        )
+    }
+}
+
+/// Leakage of a zone of a loop, proportional to the loop pressure above ambient.
+///
+/// Internal leakage through seals and spool valves flows back to the reservoir.
+/// External leakage, from a failed seal or a cracked line, is lost overboard and
+/// makes the reservoir quantity drop over time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StaticLeak {
+    zone: LeakZone,
+    internal_flow_at_3000_psi: VolumeRate,
+    external_flow_at_3000_psi: VolumeRate,
+}
+impl StaticLeak {
+    pub fn new(zone: LeakZone, internal_flow_at_3000_psi: VolumeRate) -> Self {
+        StaticLeak {
+            zone,
+            internal_flow_at_3000_psi,
+            external_flow_at_3000_psi: VolumeRate::new::<gallon_per_second>(0.),
+        }
+    }
+
+    pub fn zone(&self) -> LeakZone {
+        self.zone
+    }
+
+    pub fn internal_flow_at_3000_psi(&self) -> VolumeRate {
+        self.internal_flow_at_3000_psi
+    }
+
+    pub fn set_internal_flow_at_3000_psi(&mut self, flow: VolumeRate) {
+        self.internal_flow_at_3000_psi = flow;
+    }
+
+    pub fn external_flow_at_3000_psi(&self) -> VolumeRate {
+        self.external_flow_at_3000_psi
+    }
+
+    /// Failure injection: a zero flow repairs the leak.
+    pub fn set_external_flow_at_3000_psi(&mut self, flow: VolumeRate) {
+        self.external_flow_at_3000_psi = flow;
+    }
+
+    pub fn is_leaking_overboard(&self) -> bool {
+        self.external_flow_at_3000_psi > VolumeRate::new::<gallon_per_second>(0.)
+    }
+}
+
+#[cfg(test)]
+mod static_leak_tests {
+    use super::*;
+
+    #[test]
+    fn new_leak_only_leaks_internally() {
+        let leak = StaticLeak::new(
+            LeakZone::HighPressureManifold,
+            VolumeRate::new::<gallon_per_second>(0.04),
+        );
+
+        assert!(!leak.is_leaking_overboard());
+        assert_eq!(
+            leak.external_flow_at_3000_psi(),
+            VolumeRate::new::<gallon_per_second>(0.)
+        );
+    }
+
+    #[test]
+    fn leaks_overboard_until_repaired() {
+        let mut leak = StaticLeak::new(
+            LeakZone::ActuatorBank(ActuatorType::Spoiler),
+            VolumeRate::new::<gallon_per_second>(0.005),
+        );
+        leak.set_external_flow_at_3000_psi(VolumeRate::new::<gallon_per_second>(0.1));
+
+        assert!(leak.is_leaking_overboard());
+
+        leak.set_external_flow_at_3000_psi(VolumeRate::new::<gallon_per_second>(0.));
+
+        assert!(!leak.is_leaking_overboard());
+    }
+
+    #[test]
+    fn only_flight_control_banks_are_behind_leak_measurement_valves() {
+        assert!(LeakZone::ActuatorBank(ActuatorType::Rudder).is_behind_leak_measurement_valve());
+        assert!(!LeakZone::ActuatorBank(ActuatorType::CargoDoor).is_behind_leak_measurement_valve());
+        assert!(!LeakZone::HighPressureManifold.is_behind_leak_measurement_valve());
+    }
+}
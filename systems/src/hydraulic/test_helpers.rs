@@ -0,0 +1,64 @@
+//! Fixtures building the hydraulic elements of the A320 the same way for all
+//! tests, also for those of the crates depending on this one through the
+//! `test-support` feature.
+use super::{ElectricPump, EngineDrivenPump, HydLoop, LoopId};
+use crate::engine::Engine;
+use uom::si::f64::*;
+
+/// The A320 loop of the given colour. Panics for a loop the A320 doesn't have.
+pub fn hydraulic_loop(loop_color: LoopId) -> HydLoop {
+    match loop_color {
+        LoopId::YELLOW => HydLoop::a320_yellow(),
+        LoopId::GREEN => HydLoop::a320_green(),
+        LoopId::BLUE => HydLoop::a320_blue(),
+        _ => panic!("The A320 has no {} hydraulic loop.", loop_color),
+    }
+}
+
+pub fn electric_pump() -> ElectricPump {
+    ElectricPump::new()
+}
+
+pub fn engine_driven_pump() -> EngineDrivenPump {
+    EngineDrivenPump::new()
+}
+
+/// The first engine, turning at the given N2.
+pub fn engine(n2: Ratio) -> Engine {
+    numbered_engine(1, n2)
+}
+
+/// The engine of the given number, turning at the given N2.
+pub fn numbered_engine(number: usize, n2: Ratio) -> Engine {
+    let mut engine = Engine::new(number);
+    engine.n2 = n2;
+
+    engine
+}
+
+#[cfg(test)]
+mod test_helpers_tests {
+    use super::*;
+    use uom::si::ratio::percent;
+
+    #[test]
+    fn hydraulic_loop_has_the_given_colour() {
+        for color in [LoopId::BLUE, LoopId::GREEN, LoopId::YELLOW].iter() {
+            assert_eq!(hydraulic_loop(*color).get_color(), *color);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn hydraulic_loop_rejects_a_loop_the_a320_does_not_have() {
+        hydraulic_loop(LoopId::new(3));
+    }
+
+    #[test]
+    fn engine_turns_at_the_given_n2() {
+        assert_eq!(
+            engine(Ratio::new::<percent>(60.)).n2,
+            Ratio::new::<percent>(60.)
+        );
+    }
+}
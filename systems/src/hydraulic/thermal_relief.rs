@@ -0,0 +1,172 @@
+use uom::si::{f64::*, pressure::psi, thermodynamic_temperature::kelvin, volume::gallon};
+
+/// A relief valve protecting a closed section against the pressure rise of its
+/// trapped fluid. It cracks open at its cracking pressure and stays open until
+/// the pressure is back down to its reseat pressure.
+pub struct ThermalReliefValve {
+    cracking_pressure: Pressure,
+    reseat_pressure: Pressure,
+    open: bool,
+}
+impl ThermalReliefValve {
+    pub fn new(cracking_pressure: Pressure, reseat_pressure: Pressure) -> Self {
+        ThermalReliefValve {
+            cracking_pressure,
+            reseat_pressure,
+            open: false,
+        }
+    }
+
+    pub fn update(&mut self, pressure: Pressure) {
+        self.open = if self.open {
+            pressure > self.reseat_pressure
+        } else {
+            pressure > self.cracking_pressure
+        };
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn reseat_pressure(&self) -> Pressure {
+        self.reseat_pressure
+    }
+}
+
+/// A section of line closed off at both ends, such as the actuators behind
+/// closed leak measurement valves. Its fluid can't flow away when it warms up,
+/// so its expansion raises the section pressure until the thermal relief valve
+/// vents the excess fluid to the return.
+pub struct TrappedSection {
+    volume: Volume,
+    bulk_modulus: Pressure,
+    pressure: Pressure,
+    temperature: ThermodynamicTemperature,
+    relief_valve: ThermalReliefValve,
+}
+impl TrappedSection {
+    // Volumetric thermal expansion of the fluid per kelvin.
+    const FLUID_EXPANSION_COEFFICIENT: f64 = 0.00075;
+    const RELIEF_VALVE_CRACKING_PRESSURE_PSI: f64 = 3700.;
+    const RELIEF_VALVE_RESEAT_PRESSURE_PSI: f64 = 3500.;
+
+    pub fn new(volume: Volume, bulk_modulus: Pressure) -> Self {
+        TrappedSection {
+            volume,
+            bulk_modulus,
+            pressure: Pressure::new::<psi>(0.),
+            temperature: ThermodynamicTemperature::new::<kelvin>(0.),
+            relief_valve: ThermalReliefValve::new(
+                Pressure::new::<psi>(TrappedSection::RELIEF_VALVE_CRACKING_PRESSURE_PSI),
+                Pressure::new::<psi>(TrappedSection::RELIEF_VALVE_RESEAT_PRESSURE_PSI),
+            ),
+        }
+    }
+
+    /// Closes the section off with the given fluid pressure and temperature in it.
+    pub fn trap(&mut self, pressure: Pressure, temperature: ThermodynamicTemperature) {
+        self.pressure = pressure;
+        self.temperature = temperature;
+    }
+
+    /// Updates the section pressure for the given fluid temperature.
+    /// Returns the volume vented to the return by the thermal relief valve.
+    pub fn update(&mut self, temperature: ThermodynamicTemperature) -> Volume {
+        let temperature_rise = temperature.get::<kelvin>() - self.temperature.get::<kelvin>();
+        self.temperature = temperature;
+
+        self.pressure = (self.pressure
+            + self.bulk_modulus * TrappedSection::FLUID_EXPANSION_COEFFICIENT * temperature_rise)
+            .max(Pressure::new::<psi>(0.));
+
+        self.relief_valve.update(self.pressure);
+        if self.relief_valve.is_open() {
+            let reseat_pressure = self.relief_valve.reseat_pressure();
+            let vented = self.volume * ((self.pressure - reseat_pressure) / self.bulk_modulus);
+            self.pressure = reseat_pressure;
+
+            vented
+        } else {
+            Volume::new::<gallon>(0.)
+        }
+    }
+
+    pub fn pressure(&self) -> Pressure {
+        self.pressure
+    }
+
+    pub fn is_relief_valve_open(&self) -> bool {
+        self.relief_valve.is_open()
+    }
+}
+
+#[cfg(test)]
+mod thermal_relief_tests {
+    use super::*;
+    use uom::si::{pressure::pascal, thermodynamic_temperature::degree_celsius};
+
+    #[test]
+    fn relief_valve_opens_at_cracking_pressure_and_reseats_below() {
+        let mut valve = ThermalReliefValve::new(psi(3700.), psi(3500.));
+        valve.update(psi(3600.));
+        assert!(!valve.is_open());
+
+        valve.update(psi(3750.));
+        assert!(valve.is_open());
+
+        valve.update(psi(3600.));
+        assert!(valve.is_open());
+
+        valve.update(psi(3450.));
+        assert!(!valve.is_open());
+    }
+
+    #[test]
+    fn warming_raises_the_trapped_pressure() {
+        let mut section = section();
+        section.trap(psi(3000.), celsius(20.));
+
+        assert_eq!(section.update(celsius(22.)), Volume::new::<gallon>(0.));
+        assert!(section.pressure() > psi(3200.));
+    }
+
+    #[test]
+    fn cooling_lowers_the_trapped_pressure_down_to_zero() {
+        let mut section = section();
+        section.trap(psi(3000.), celsius(40.));
+        section.update(celsius(0.));
+
+        assert_eq!(section.pressure(), psi(0.));
+    }
+
+    #[test]
+    fn relief_valve_keeps_heated_section_from_running_away() {
+        let mut section = section();
+        section.trap(psi(3000.), celsius(20.));
+
+        let mut vented = Volume::new::<gallon>(0.);
+        for temperature in 20..70 {
+            vented += section.update(celsius(temperature as f64));
+            assert!(section.pressure() <= psi(3700.));
+        }
+
+        assert!(section.is_relief_valve_open());
+        assert!(vented > Volume::new::<gallon>(0.));
+    }
+
+    fn section() -> TrappedSection {
+        TrappedSection::new(
+            Volume::new::<gallon>(1.),
+            Pressure::new::<pascal>(1450000000.),
+        )
+    }
+
+    fn psi(pressure: f64) -> Pressure {
+        Pressure::new::<psi>(pressure)
+    }
+
+    fn celsius(temperature: f64) -> ThermodynamicTemperature {
+        ThermodynamicTemperature::new::<degree_celsius>(temperature)
+    }
+}
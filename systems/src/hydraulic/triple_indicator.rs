@@ -0,0 +1,153 @@
+use crate::shared::LowPassFilter;
+use std::time::Duration;
+use uom::si::{f64::*, pressure::psi};
+
+/// The needle of a pressure gauge, which lags behind the pressure it measures
+/// and stops at the end of its scale.
+pub struct GaugeNeedle {
+    reading: LowPassFilter,
+    full_scale: Pressure,
+}
+impl GaugeNeedle {
+    const TIME_CONSTANT: Duration = Duration::from_millis(500);
+
+    pub fn new(full_scale: Pressure) -> Self {
+        GaugeNeedle {
+            reading: LowPassFilter::new(GaugeNeedle::TIME_CONSTANT),
+            full_scale,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: &Duration, pressure: Pressure) {
+        let pressure = pressure
+            .get::<psi>()
+            .max(0.)
+            .min(self.full_scale.get::<psi>());
+        self.reading.update(delta_time, pressure);
+    }
+
+    pub fn reading(&self) -> Pressure {
+        Pressure::new::<psi>(self.reading.output())
+    }
+
+    pub fn set_time_constant(&mut self, time_constant: Duration) {
+        self.reading.set_time_constant(time_constant);
+    }
+}
+
+/// The brake and accumulator pressure indicator on the centre instrument panel,
+/// showing the yellow brake accumulator pressure and the left and right
+/// alternate brake pressures on three needles.
+pub struct TripleIndicator {
+    accumulator: GaugeNeedle,
+    left_brake: GaugeNeedle,
+    right_brake: GaugeNeedle,
+}
+impl TripleIndicator {
+    const ACCUMULATOR_FULL_SCALE_PSI: f64 = 4000.;
+    const BRAKE_FULL_SCALE_PSI: f64 = 3000.;
+
+    pub fn new() -> Self {
+        TripleIndicator {
+            accumulator: GaugeNeedle::new(Pressure::new::<psi>(
+                TripleIndicator::ACCUMULATOR_FULL_SCALE_PSI,
+            )),
+            left_brake: GaugeNeedle::new(Pressure::new::<psi>(
+                TripleIndicator::BRAKE_FULL_SCALE_PSI,
+            )),
+            right_brake: GaugeNeedle::new(Pressure::new::<psi>(
+                TripleIndicator::BRAKE_FULL_SCALE_PSI,
+            )),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        delta_time: &Duration,
+        accumulator_pressure: Pressure,
+        left_brake_pressure: Pressure,
+        right_brake_pressure: Pressure,
+    ) {
+        self.accumulator.update(delta_time, accumulator_pressure);
+        self.left_brake.update(delta_time, left_brake_pressure);
+        self.right_brake.update(delta_time, right_brake_pressure);
+    }
+
+    pub fn accumulator_pressure(&self) -> Pressure {
+        self.accumulator.reading()
+    }
+
+    /// How quickly all three needles follow the pressures they show.
+    pub fn set_needle_time_constant(&mut self, time_constant: Duration) {
+        self.accumulator.set_time_constant(time_constant);
+        self.left_brake.set_time_constant(time_constant);
+        self.right_brake.set_time_constant(time_constant);
+    }
+
+    pub fn left_brake_pressure(&self) -> Pressure {
+        self.left_brake.reading()
+    }
+
+    pub fn right_brake_pressure(&self) -> Pressure {
+        self.right_brake.reading()
+    }
+}
+impl Default for TripleIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod triple_indicator_tests {
+    use super::*;
+
+    #[test]
+    fn needles_lag_behind_a_pressure_step() {
+        let mut indicator = TripleIndicator::new();
+        run(&mut indicator, 3000., 1000., 1000., 100);
+
+        assert!(indicator.accumulator_pressure() > Pressure::new::<psi>(0.));
+        assert!(indicator.accumulator_pressure() < Pressure::new::<psi>(1000.));
+    }
+
+    #[test]
+    fn needles_settle_on_the_measured_pressure() {
+        let mut indicator = TripleIndicator::new();
+        run(&mut indicator, 3000., 1000., 500., 5000);
+
+        assert!(is_about(indicator.accumulator_pressure(), 3000.));
+        assert!(is_about(indicator.left_brake_pressure(), 1000.));
+        assert!(is_about(indicator.right_brake_pressure(), 500.));
+    }
+
+    #[test]
+    fn needles_stop_at_the_end_of_the_scale() {
+        let mut indicator = TripleIndicator::new();
+        run(&mut indicator, 5000., 3500., 3500., 10000);
+
+        assert!(is_about(indicator.accumulator_pressure(), 4000.));
+        assert!(is_about(indicator.left_brake_pressure(), 3000.));
+    }
+
+    fn run(
+        indicator: &mut TripleIndicator,
+        accumulator_psi: f64,
+        left_psi: f64,
+        right_psi: f64,
+        milliseconds: u64,
+    ) {
+        for _ in 0..milliseconds / 10 {
+            indicator.update(
+                &Duration::from_millis(10),
+                Pressure::new::<psi>(accumulator_psi),
+                Pressure::new::<psi>(left_psi),
+                Pressure::new::<psi>(right_psi),
+            );
+        }
+    }
+
+    fn is_about(pressure: Pressure, expected_psi: f64) -> bool {
+        (pressure.get::<psi>() - expected_psi).abs() < 1.
+    }
+}
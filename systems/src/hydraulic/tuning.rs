@@ -0,0 +1,205 @@
+use std::{
+    error::Error,
+    fmt,
+    str::FromStr,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+/// A hydraulic constant which can be changed while the simulation runs.
+/// Table entries are addressed by their index, e.g. `electric_pump.displacement.6`.
+/// Filter time constants are in seconds and PTU thresholds in psi.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TuningParameter {
+    ElectricPumpDisplacement(usize),
+    EngineDrivenPumpDisplacement(usize),
+    AccumulatorFlow(usize),
+    GaugeNeedleTimeConstant,
+    RatSpeedTimeConstant,
+    PtuBarkingDecayTime,
+    PtuBreakoutPressure,
+    PtuHoldingPressure,
+    PtuMinDrivingPressure,
+}
+impl TuningParameter {
+    const TABLE_LENGTH: usize = 9;
+}
+impl FromStr for TuningParameter {
+    type Err = TuningError;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        match key.trim() {
+            "filter.gauge_needle" => return Ok(TuningParameter::GaugeNeedleTimeConstant),
+            "filter.rat_speed" => return Ok(TuningParameter::RatSpeedTimeConstant),
+            "filter.ptu_barking_decay" => return Ok(TuningParameter::PtuBarkingDecayTime),
+            "ptu.breakout_pressure" => return Ok(TuningParameter::PtuBreakoutPressure),
+            "ptu.holding_pressure" => return Ok(TuningParameter::PtuHoldingPressure),
+            "ptu.min_driving_pressure" => return Ok(TuningParameter::PtuMinDrivingPressure),
+            _ => {}
+        }
+
+        let unknown = || TuningError::UnknownKey(key.to_owned());
+        let (table, index) = key.trim().rsplit_once('.').ok_or_else(unknown)?;
+        let index: usize = index.parse().map_err(|_| unknown())?;
+        if index >= TuningParameter::TABLE_LENGTH {
+            return Err(unknown());
+        }
+
+        match table {
+            "electric_pump.displacement" => Ok(TuningParameter::ElectricPumpDisplacement(index)),
+            "engine_driven_pump.displacement" => {
+                Ok(TuningParameter::EngineDrivenPumpDisplacement(index))
+            }
+            "accumulator.flow" => Ok(TuningParameter::AccumulatorFlow(index)),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+/// Creates a channel over which tuning values are sent to a running hydraulic system.
+pub fn tuning_channel() -> (TuningSender, TuningReceiver) {
+    let (sender, receiver) = channel();
+
+    (TuningSender { sender }, TuningReceiver { receiver })
+}
+
+#[derive(Clone)]
+pub struct TuningSender {
+    sender: Sender<(TuningParameter, f64)>,
+}
+impl TuningSender {
+    pub fn set(&self, key: &str, value: f64) -> Result<(), TuningError> {
+        let parameter = key.parse()?;
+        if !(value.is_finite() && value >= 0.) {
+            return Err(TuningError::InvalidValue(key.to_owned()));
+        }
+
+        self.sender
+            .send((parameter, value))
+            .map_err(|_| TuningError::Disconnected)
+    }
+
+    /// Sets a value from a `key = value` line.
+    pub fn set_line(&self, line: &str) -> Result<(), TuningError> {
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| TuningError::UnknownKey(line.to_owned()))?;
+        let value = value
+            .trim()
+            .parse()
+            .map_err(|_| TuningError::InvalidValue(key.trim().to_owned()))?;
+
+        self.set(key, value)
+    }
+}
+
+pub struct TuningReceiver {
+    receiver: Receiver<(TuningParameter, f64)>,
+}
+impl TuningReceiver {
+    /// Returns the values received since the last call, oldest first.
+    pub fn receive(&self) -> Vec<(TuningParameter, f64)> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TuningError {
+    UnknownKey(String),
+    InvalidValue(String),
+    Disconnected,
+}
+impl fmt::Display for TuningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TuningError::UnknownKey(key) => write!(f, "Unknown tuning parameter: {}", key),
+            TuningError::InvalidValue(key) => write!(f, "Invalid value for {}", key),
+            TuningError::Disconnected => write!(f, "The hydraulic system is no longer running"),
+        }
+    }
+}
+impl Error for TuningError {}
+
+#[cfg(test)]
+mod tuning_tests {
+    use super::*;
+
+    #[test]
+    fn parses_table_entries() {
+        assert_eq!(
+            "electric_pump.displacement.6".parse(),
+            Ok(TuningParameter::ElectricPumpDisplacement(6))
+        );
+        assert_eq!(
+            "engine_driven_pump.displacement.0".parse(),
+            Ok(TuningParameter::EngineDrivenPumpDisplacement(0))
+        );
+        assert_eq!(
+            "accumulator.flow.8".parse(),
+            Ok(TuningParameter::AccumulatorFlow(8))
+        );
+    }
+
+    #[test]
+    fn parses_filter_and_ptu_keys() {
+        assert_eq!(
+            "filter.gauge_needle".parse(),
+            Ok(TuningParameter::GaugeNeedleTimeConstant)
+        );
+        assert_eq!(
+            " filter.ptu_barking_decay".parse(),
+            Ok(TuningParameter::PtuBarkingDecayTime)
+        );
+        assert_eq!(
+            "ptu.breakout_pressure".parse(),
+            Ok(TuningParameter::PtuBreakoutPressure)
+        );
+        assert_eq!(
+            "ptu.min_driving_pressure".parse(),
+            Ok(TuningParameter::PtuMinDrivingPressure)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!("ptu.gain.1".parse::<TuningParameter>().is_err());
+        assert!("accumulator.flow".parse::<TuningParameter>().is_err());
+        assert!("accumulator.flow.9".parse::<TuningParameter>().is_err());
+    }
+
+    #[test]
+    fn receives_values_in_order_they_were_sent() {
+        let (sender, receiver) = tuning_channel();
+        sender.set("accumulator.flow.1", 0.1).unwrap();
+        sender.set_line("accumulator.flow.1 = 0.2").unwrap();
+
+        assert_eq!(
+            receiver.receive(),
+            vec![
+                (TuningParameter::AccumulatorFlow(1), 0.1),
+                (TuningParameter::AccumulatorFlow(1), 0.2)
+            ]
+        );
+        assert_eq!(receiver.receive(), vec![]);
+    }
+
+    #[test]
+    fn rejects_negative_values() {
+        let (sender, _receiver) = tuning_channel();
+
+        assert_eq!(
+            sender.set("accumulator.flow.1", -1.),
+            Err(TuningError::InvalidValue("accumulator.flow.1".to_owned()))
+        );
+    }
+
+    #[test]
+    fn reports_when_receiver_is_gone() {
+        let (sender, receiver) = tuning_channel();
+        drop(receiver);
+
+        assert_eq!(
+            sender.set("accumulator.flow.1", 0.1),
+            Err(TuningError::Disconnected)
+        );
+    }
+}
@@ -1,11 +1,31 @@
 mod a320;
-pub use a320::A320;
+pub use a320::{
+    A320Hydraulic, A320HydraulicOutput, A320HydraulicOverheadPanel, A320HydraulicWarnings,
+    HydraulicCaution, A320,
+};
 
 mod apu;
 mod electrical;
+//The benchmarks and integration tests build on the engine and hydraulic internals. Without them,
+//the parts only they use are unused
+#[cfg(not(feature = "test-support"))]
+#[allow(dead_code, unused_imports)]
 mod engine;
+#[cfg(feature = "test-support")]
+pub mod engine;
+#[cfg(not(feature = "test-support"))]
+#[allow(dead_code, unused_imports)]
 mod hydraulic;
+#[cfg(feature = "test-support")]
+pub mod hydraulic;
+pub use hydraulic::{HydraulicConfig, HydraulicConfigError, PtuState};
+#[cfg(feature = "dev-tuning")]
+pub use hydraulic::{tuning_channel, TuningError, TuningParameter, TuningReceiver, TuningSender};
 mod overhead;
+pub use overhead::PushButtonLights;
 mod pneumatic;
 mod shared;
+pub use shared::{
+    Arinc429ParityError, Arinc429Word, Hysteresis, LowPassFilter, RateLimiter, SignStatus,
+};
 pub mod simulator;
@@ -163,21 +163,101 @@ impl AutoOffPushButton {
     }
 }
 
+/// The lights of a push button as seen in the cockpit.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PushButtonLights {
+    pub fault: bool,
+    pub off: bool,
+}
+
+/// An AUTO/OFF push button with FAULT and OFF lights. The OFF light shows the
+/// button is released, the FAULT light shows the fault set by the system and
+/// both can be on together. The annunciator light test turns both on.
+pub struct AutoOffFaultPushButton {
+    state: AutoOffPushButtonState,
+    fault: bool,
+}
+impl AutoOffFaultPushButton {
+    pub fn new_auto() -> AutoOffFaultPushButton {
+        AutoOffFaultPushButton {
+            state: AutoOffPushButtonState::Auto,
+            fault: false,
+        }
+    }
+
+    pub fn new_off() -> AutoOffFaultPushButton {
+        AutoOffFaultPushButton {
+            state: AutoOffPushButtonState::Off,
+            fault: false,
+        }
+    }
+
+    pub fn push_off(&mut self) {
+        self.state = AutoOffPushButtonState::Off;
+    }
+
+    pub fn push_auto(&mut self) {
+        self.state = AutoOffPushButtonState::Auto;
+    }
+
+    pub fn is_auto(&self) -> bool {
+        self.state == AutoOffPushButtonState::Auto
+    }
+
+    pub fn is_off(&self) -> bool {
+        self.state == AutoOffPushButtonState::Off
+    }
+
+    pub fn set_auto(&mut self, value: bool) {
+        self.state = if value {
+            AutoOffPushButtonState::Auto
+        } else {
+            AutoOffPushButtonState::Off
+        };
+    }
+
+    pub fn set_fault(&mut self, fault: bool) {
+        self.fault = fault;
+    }
+
+    pub fn has_fault(&self) -> bool {
+        self.fault
+    }
+
+    pub fn lights(&self, light_test: bool) -> PushButtonLights {
+        PushButtonLights {
+            fault: self.fault || light_test,
+            off: self.is_off() || light_test,
+        }
+    }
+}
+
 pub struct FirePushButton {
     released: bool,
+    released_input: bool,
 }
 impl FirePushButton {
     pub fn new() -> Self {
-        FirePushButton { released: false }
+        FirePushButton {
+            released: false,
+            released_input: false,
+        }
     }
 
+    /// Latches the button as released when the input changes to released. An input
+    /// which stays released doesn't latch the button again after a reset.
     pub fn set(&mut self, released: bool) {
-        self.released = self.released || released;
+        self.released = self.released || (released && !self.released_input);
+        self.released_input = released;
     }
 
     pub fn is_released(&self) -> bool {
         self.released
     }
+
+    pub fn reset(&mut self) {
+        self.released = false;
+    }
 }
 
 #[cfg(test)]
@@ -225,6 +305,58 @@ mod auto_off_push_button_tests {
     }
 }
 
+#[cfg(test)]
+mod auto_off_fault_push_button_tests {
+    use super::{AutoOffFaultPushButton, PushButtonLights};
+
+    #[test]
+    fn new_auto_push_button_shows_no_light() {
+        let pb = AutoOffFaultPushButton::new_auto();
+
+        assert_eq!(pb.lights(false), PushButtonLights::default());
+    }
+
+    #[test]
+    fn released_push_button_shows_off_light() {
+        let pb = AutoOffFaultPushButton::new_off();
+
+        assert_eq!(
+            pb.lights(false),
+            PushButtonLights {
+                fault: false,
+                off: true
+            }
+        );
+    }
+
+    #[test]
+    fn fault_and_off_lights_can_be_on_together() {
+        let mut pb = AutoOffFaultPushButton::new_off();
+        pb.set_fault(true);
+
+        assert_eq!(
+            pb.lights(false),
+            PushButtonLights {
+                fault: true,
+                off: true
+            }
+        );
+    }
+
+    #[test]
+    fn light_test_turns_all_lights_on() {
+        let pb = AutoOffFaultPushButton::new_auto();
+
+        assert_eq!(
+            pb.lights(true),
+            PushButtonLights {
+                fault: true,
+                off: true
+            }
+        );
+    }
+}
+
 #[cfg(test)]
 mod fire_push_button_tests {
     use super::FirePushButton;
@@ -252,4 +384,25 @@ mod fire_push_button_tests {
 
         assert_eq!(pb.is_released(), true);
     }
+
+    #[test]
+    fn reset_button_is_not_released_again_while_input_stays_released() {
+        let mut pb = FirePushButton::new();
+        pb.set(true);
+        pb.reset();
+        pb.set(true);
+
+        assert!(!pb.is_released());
+    }
+
+    #[test]
+    fn reset_button_is_released_again_by_a_new_release() {
+        let mut pb = FirePushButton::new();
+        pb.set(true);
+        pb.reset();
+        pb.set(false);
+        pb.set(true);
+
+        assert!(pb.is_released());
+    }
 }
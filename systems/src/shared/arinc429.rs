@@ -0,0 +1,203 @@
+/// The sign/status matrix (bits 30 and 31) of an ARINC 429 word, telling
+/// receivers whether the data can be used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SignStatus {
+    FailureWarning,
+    NoComputedData,
+    FunctionalTest,
+    NormalOperation,
+}
+impl SignStatus {
+    fn bits(self) -> u32 {
+        match self {
+            SignStatus::FailureWarning => 0b00,
+            SignStatus::NoComputedData => 0b01,
+            SignStatus::FunctionalTest => 0b10,
+            SignStatus::NormalOperation => 0b11,
+        }
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        match bits & 0b11 {
+            0b00 => SignStatus::FailureWarning,
+            0b01 => SignStatus::NoComputedData,
+            0b10 => SignStatus::FunctionalTest,
+            _ => SignStatus::NormalOperation,
+        }
+    }
+}
+
+/// A 32 bit ARINC 429 word.
+///
+/// Bits are numbered from 1 as in the specification: the label takes bits 1 to 8,
+/// the source/destination identifier bits 9 and 10, data bits 11 to 29, the sign/status
+/// matrix bits 30 and 31 and bit 32 makes the parity of the word odd.
+/// Binary (BNR) data is a two's complement number of the given resolution in bits 11 to 29.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Arinc429Word {
+    raw: u32,
+}
+impl Arinc429Word {
+    const SDI_SHIFT: u32 = 8;
+    const DATA_SHIFT: u32 = 10;
+    const DATA_BITS: u32 = 19;
+    const SSM_SHIFT: u32 = 29;
+    const PARITY_BIT: u32 = 1 << 31;
+
+    /// Encodes a binary value, rounded to the resolution and saturated to the
+    /// range of the data field.
+    pub fn new_bnr(label: u8, value: f64, resolution: f64, ssm: SignStatus) -> Self {
+        let max = (1_i64 << (Arinc429Word::DATA_BITS - 1)) - 1;
+        let min = -(1_i64 << (Arinc429Word::DATA_BITS - 1));
+        let counts = ((value / resolution).round() as i64).max(min).min(max);
+        let data = (counts as u32) & Arinc429Word::data_mask();
+
+        Arinc429Word::from_fields(label, 0, data, ssm)
+    }
+
+    /// Encodes discrete states: bit 0 of `discretes` goes to word bit 11.
+    pub fn new_discrete(label: u8, discretes: u32, ssm: SignStatus) -> Self {
+        Arinc429Word::from_fields(label, 0, discretes & Arinc429Word::data_mask(), ssm)
+    }
+
+    /// Sets the source/destination identifier, from 0 to 3.
+    pub fn with_sdi(self, sdi: u8) -> Self {
+        Arinc429Word::from_fields(self.label(), sdi, self.data(), self.ssm())
+    }
+
+    /// Decodes a word received on the bus, failing when its parity is wrong.
+    pub fn from_raw(raw: u32) -> Result<Self, Arinc429ParityError> {
+        if raw.count_ones() % 2 == 1 {
+            Ok(Arinc429Word { raw })
+        } else {
+            Err(Arinc429ParityError { raw })
+        }
+    }
+
+    pub fn raw(&self) -> u32 {
+        self.raw
+    }
+
+    pub fn label(&self) -> u8 {
+        (self.raw & 0xff) as u8
+    }
+
+    pub fn sdi(&self) -> u8 {
+        ((self.raw >> Arinc429Word::SDI_SHIFT) & 0b11) as u8
+    }
+
+    pub fn ssm(&self) -> SignStatus {
+        SignStatus::from_bits(self.raw >> Arinc429Word::SSM_SHIFT)
+    }
+
+    /// The binary value of the word for the given resolution.
+    pub fn bnr_value(&self, resolution: f64) -> f64 {
+        // Sign extend the data field.
+        let unused_bits = 32 - Arinc429Word::DATA_BITS;
+        let counts = ((self.data() << unused_bits) as i32) >> unused_bits;
+
+        counts as f64 * resolution
+    }
+
+    /// The discrete at the given position, 0 being word bit 11.
+    pub fn discrete(&self, position: u32) -> bool {
+        position < Arinc429Word::DATA_BITS && (self.data() >> position) & 1 == 1
+    }
+
+    fn data(&self) -> u32 {
+        (self.raw >> Arinc429Word::DATA_SHIFT) & Arinc429Word::data_mask()
+    }
+
+    fn data_mask() -> u32 {
+        (1 << Arinc429Word::DATA_BITS) - 1
+    }
+
+    fn from_fields(label: u8, sdi: u8, data: u32, ssm: SignStatus) -> Self {
+        let raw = label as u32
+            | ((sdi as u32 & 0b11) << Arinc429Word::SDI_SHIFT)
+            | (data << Arinc429Word::DATA_SHIFT)
+            | (ssm.bits() << Arinc429Word::SSM_SHIFT);
+
+        if raw.count_ones() % 2 == 0 {
+            Arinc429Word {
+                raw: raw | Arinc429Word::PARITY_BIT,
+            }
+        } else {
+            Arinc429Word { raw }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Arinc429ParityError {
+    pub raw: u32,
+}
+
+#[cfg(test)]
+mod arinc429_word_tests {
+    use super::*;
+
+    #[test]
+    fn bnr_value_round_trips() {
+        let word = Arinc429Word::new_bnr(0o160, 3000., 1., SignStatus::NormalOperation);
+
+        assert_eq!(word.bnr_value(1.), 3000.);
+    }
+
+    #[test]
+    fn negative_bnr_value_round_trips() {
+        let word = Arinc429Word::new_bnr(0o160, -12.5, 0.5, SignStatus::NormalOperation);
+
+        assert_eq!(word.bnr_value(0.5), -12.5);
+    }
+
+    #[test]
+    fn bnr_value_is_rounded_to_resolution() {
+        let word = Arinc429Word::new_bnr(0o163, 3.78, 0.05, SignStatus::NormalOperation);
+
+        assert!((word.bnr_value(0.05) - 3.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bnr_value_saturates_to_data_field_range() {
+        let word = Arinc429Word::new_bnr(0o160, 1e9, 1., SignStatus::NormalOperation);
+
+        assert_eq!(word.bnr_value(1.), 262143.);
+    }
+
+    #[test]
+    fn label_sdi_and_ssm_are_kept() {
+        let word = Arinc429Word::new_bnr(0o161, 1500., 1., SignStatus::NoComputedData).with_sdi(2);
+
+        assert_eq!(word.label(), 0o161);
+        assert_eq!(word.sdi(), 2);
+        assert_eq!(word.ssm(), SignStatus::NoComputedData);
+        assert_eq!(word.bnr_value(1.), 1500.);
+    }
+
+    #[test]
+    fn words_have_odd_parity() {
+        for value in 0..64 {
+            let word = Arinc429Word::new_discrete(0o166, value, SignStatus::NormalOperation);
+
+            assert_eq!(word.raw().count_ones() % 2, 1);
+        }
+    }
+
+    #[test]
+    fn discretes_round_trip() {
+        let word = Arinc429Word::new_discrete(0o166, 0b101, SignStatus::NormalOperation);
+
+        assert!(word.discrete(0));
+        assert!(!word.discrete(1));
+        assert!(word.discrete(2));
+    }
+
+    #[test]
+    fn received_word_with_wrong_parity_is_rejected() {
+        let word = Arinc429Word::new_bnr(0o160, 3000., 1., SignStatus::NormalOperation);
+
+        assert_eq!(Arinc429Word::from_raw(word.raw()), Ok(word));
+        assert!(Arinc429Word::from_raw(word.raw() ^ 1 << 12).is_err());
+    }
+}
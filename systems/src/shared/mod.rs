@@ -1,10 +1,16 @@
-use crate::simulator::UpdateContext;
+use crate::simulator::TimeSource;
 use std::time::Duration;
 use uom::si::f64::*;
 
+mod arinc429;
+pub use arinc429::*;
+
 mod random;
 pub use random::*;
 
+mod signal;
+pub use signal::*;
+
 /// The delay logic gate delays the true result of a given expression by the given amount of time.
 /// False results are output immediately.
 pub struct DelayedTrueLogicGate {
@@ -21,10 +27,10 @@ impl DelayedTrueLogicGate {
         }
     }
 
-    pub fn update(&mut self, context: &UpdateContext, expression_result: bool) {
+    pub fn update<S: TimeSource>(&mut self, time: &S, expression_result: bool) {
         // We do not include the delta representing the moment before the expression_result became true.
         if self.expression_result && expression_result {
-            self.true_duration += context.delta;
+            self.true_duration += time.delta();
         } else {
             self.true_duration = Duration::from_millis(0);
         }
@@ -41,6 +47,40 @@ impl DelayedTrueLogicGate {
     }
 }
 
+/// The delay logic gate delays the false result of a given expression by the given amount of time.
+/// True results are output immediately.
+pub struct DelayedFalseLogicGate {
+    delay: Duration,
+    expression_result: bool,
+    false_duration: Duration,
+}
+impl DelayedFalseLogicGate {
+    pub fn new(delay: Duration) -> DelayedFalseLogicGate {
+        DelayedFalseLogicGate {
+            delay,
+            expression_result: false,
+            // The expression has been false for longer than the delay,
+            // so a newly created gate starts with a false output.
+            false_duration: delay,
+        }
+    }
+
+    pub fn update<S: TimeSource>(&mut self, time: &S, expression_result: bool) {
+        // We do not include the delta representing the moment before the expression_result became false.
+        if !self.expression_result && !expression_result {
+            self.false_duration += time.delta();
+        } else {
+            self.false_duration = Duration::from_millis(0);
+        }
+
+        self.expression_result = expression_result;
+    }
+
+    pub fn output(&self) -> bool {
+        self.expression_result || self.false_duration < self.delay
+    }
+}
+
 /// Provides a way to return a different value from a collection of values
 /// which is randomly selected once per the given duration.
 pub struct TimedRandom<T> {
@@ -59,8 +99,8 @@ impl<T: Copy + Default> TimedRandom<T> {
         }
     }
 
-    pub fn update(&mut self, context: &UpdateContext) {
-        self.passed_time += context.delta;
+    pub fn update<S: TimeSource>(&mut self, time: &S) {
+        self.passed_time += time.delta();
         if self.passed_time >= self.recalculate_every {
             self.passed_time = Duration::from_secs(0);
 
@@ -171,6 +211,138 @@ mod delayed_true_logic_gate_tests {
     }
 }
 
+#[cfg(test)]
+mod delayed_false_logic_gate_tests {
+    use crate::simulator::test_helpers::context_with;
+
+    use super::*;
+
+    #[test]
+    fn starts_with_false_output() {
+        let gate = delay_logic_gate(Duration::from_millis(100));
+
+        assert_eq!(gate.output(), false);
+    }
+
+    #[test]
+    fn when_the_expression_is_false_returns_false() {
+        let mut gate = delay_logic_gate(Duration::from_millis(100));
+        gate.update(
+            &context_with().delta(Duration::from_millis(0)).build(),
+            false,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(1_000)).build(),
+            false,
+        );
+
+        assert_eq!(gate.output(), false);
+    }
+
+    #[test]
+    fn when_the_expression_is_true_returns_true() {
+        let mut gate = delay_logic_gate(Duration::from_millis(100));
+        gate.update(
+            &context_with().delta(Duration::from_millis(0)).build(),
+            true,
+        );
+
+        assert_eq!(gate.output(), true);
+    }
+
+    #[test]
+    fn when_the_expression_becomes_false_and_delay_hasnt_passed_returns_true() {
+        let mut gate = delay_logic_gate(Duration::from_millis(10_000));
+        gate.update(
+            &context_with().delta(Duration::from_millis(0)).build(),
+            true,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(1_000)).build(),
+            false,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(1_000)).build(),
+            false,
+        );
+
+        assert_eq!(gate.output(), true);
+    }
+
+    #[test]
+    fn when_the_expression_becomes_false_and_delay_has_passed_returns_false() {
+        let mut gate = delay_logic_gate(Duration::from_millis(100));
+        gate.update(
+            &context_with().delta(Duration::from_millis(0)).build(),
+            true,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(0)).build(),
+            false,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(1_000)).build(),
+            false,
+        );
+
+        assert_eq!(gate.output(), false);
+    }
+
+    #[test]
+    fn when_the_expression_becomes_true_again_before_delay_has_passed_restarts_the_delay() {
+        let mut gate = delay_logic_gate(Duration::from_millis(1_000));
+        gate.update(
+            &context_with().delta(Duration::from_millis(0)).build(),
+            true,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(0)).build(),
+            false,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(800)).build(),
+            false,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(100)).build(),
+            true,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(100)).build(),
+            false,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(800)).build(),
+            false,
+        );
+
+        assert_eq!(gate.output(), true);
+    }
+
+    #[test]
+    fn does_not_include_delta_at_the_moment_of_expression_becoming_false() {
+        let mut gate = delay_logic_gate(Duration::from_millis(1_000));
+        gate.update(
+            &context_with().delta(Duration::from_millis(0)).build(),
+            true,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(900)).build(),
+            false,
+        );
+        gate.update(
+            &context_with().delta(Duration::from_millis(200)).build(),
+            false,
+        );
+
+        assert_eq!(gate.output(), true);
+    }
+
+    fn delay_logic_gate(delay: Duration) -> DelayedFalseLogicGate {
+        DelayedFalseLogicGate::new(delay)
+    }
+}
+
 #[cfg(test)]
 mod timed_random_tests {
     use crate::simulator::test_helpers::context_with;
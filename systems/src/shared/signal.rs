@@ -0,0 +1,228 @@
+use crate::simulator::TimeSource;
+use std::time::Duration;
+
+/// First order low-pass filter. The output reaches 63% of a step change of
+/// the input after one time constant, whatever the update rate.
+pub struct LowPassFilter {
+    time_constant: Duration,
+    output: f64,
+}
+impl LowPassFilter {
+    pub fn new(time_constant: Duration) -> Self {
+        LowPassFilter::new_with_init_value(time_constant, 0.)
+    }
+
+    pub fn new_with_init_value(time_constant: Duration, init_value: f64) -> Self {
+        LowPassFilter {
+            time_constant,
+            output: init_value,
+        }
+    }
+
+    pub fn update<S: TimeSource>(&mut self, time: &S, input: f64) -> f64 {
+        let delta_time = time.delta().as_secs_f64();
+        let alpha = delta_time / (self.time_constant.as_secs_f64() + delta_time);
+        // A zero time constant with no elapsed time has nothing to filter.
+        if alpha.is_finite() {
+            self.output += alpha * (input - self.output);
+        }
+
+        self.output
+    }
+
+    /// Changes how fast the output follows the input from now on.
+    pub fn set_time_constant(&mut self, time_constant: Duration) {
+        self.time_constant = time_constant;
+    }
+
+    /// Sets the output without filtering, e.g. when warm starting a component.
+    pub fn reset(&mut self, value: f64) {
+        self.output = value;
+    }
+
+    pub fn output(&self) -> f64 {
+        self.output
+    }
+}
+
+/// Limits how fast the output follows the input, with separate rates per
+/// second for rising and falling inputs.
+pub struct RateLimiter {
+    rising_rate: f64,
+    falling_rate: f64,
+    output: f64,
+}
+impl RateLimiter {
+    pub fn new(rising_rate: f64, falling_rate: f64) -> Self {
+        RateLimiter::new_with_init_value(rising_rate, falling_rate, 0.)
+    }
+
+    pub fn new_with_init_value(rising_rate: f64, falling_rate: f64, init_value: f64) -> Self {
+        RateLimiter {
+            rising_rate,
+            falling_rate,
+            output: init_value,
+        }
+    }
+
+    pub fn update<S: TimeSource>(&mut self, time: &S, input: f64) -> f64 {
+        let delta_time = time.delta().as_secs_f64();
+        self.output += (input - self.output)
+            .max(-self.falling_rate * delta_time)
+            .min(self.rising_rate * delta_time);
+
+        self.output
+    }
+
+    pub fn reset(&mut self, value: f64) {
+        self.output = value;
+    }
+
+    pub fn output(&self) -> f64 {
+        self.output
+    }
+}
+
+/// Switches on when the input reaches the high threshold and back off when
+/// it falls to the low threshold, like a pressure switch.
+pub struct Hysteresis {
+    low_threshold: f64,
+    high_threshold: f64,
+    output: bool,
+}
+impl Hysteresis {
+    pub fn new(low_threshold: f64, high_threshold: f64) -> Self {
+        Hysteresis {
+            low_threshold,
+            high_threshold,
+            output: false,
+        }
+    }
+
+    pub fn update(&mut self, input: f64) -> bool {
+        if input >= self.high_threshold {
+            self.output = true;
+        } else if input <= self.low_threshold {
+            self.output = false;
+        }
+
+        self.output
+    }
+
+    pub fn output(&self) -> bool {
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod low_pass_filter_tests {
+    use super::*;
+
+    #[test]
+    fn reaches_63_percent_of_a_step_after_one_time_constant() {
+        let mut filter = LowPassFilter::new(Duration::from_secs(1));
+        for _ in 0..100 {
+            filter.update(&Duration::from_millis(10), 1.);
+        }
+
+        assert!((filter.output() - 0.63).abs() < 0.01);
+    }
+
+    #[test]
+    fn settles_on_input() {
+        let mut filter = LowPassFilter::new(Duration::from_millis(500));
+        for _ in 0..100 {
+            filter.update(&Duration::from_millis(100), 3000.);
+        }
+
+        assert!((filter.output() - 3000.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn output_does_not_depend_on_time_step() {
+        let mut fine_steps = LowPassFilter::new(Duration::from_secs(2));
+        for _ in 0..40 {
+            fine_steps.update(&Duration::from_millis(50), 1.);
+        }
+        let mut coarse_steps = LowPassFilter::new(Duration::from_secs(2));
+        for _ in 0..20 {
+            coarse_steps.update(&Duration::from_millis(100), 1.);
+        }
+
+        assert!((fine_steps.output() - coarse_steps.output()).abs() < 0.01);
+    }
+
+    #[test]
+    fn zero_time_constant_follows_input() {
+        let mut filter = LowPassFilter::new(Duration::from_secs(0));
+        filter.update(&Duration::from_millis(100), 5.);
+
+        assert_eq!(filter.output(), 5.);
+    }
+
+    #[test]
+    fn no_elapsed_time_keeps_output() {
+        let mut filter = LowPassFilter::new_with_init_value(Duration::from_secs(1), 2.);
+        filter.update(&Duration::from_secs(0), 5.);
+
+        assert_eq!(filter.output(), 2.);
+    }
+}
+
+#[cfg(test)]
+mod rate_limiter_tests {
+    use super::*;
+
+    #[test]
+    fn rises_at_rising_rate() {
+        let mut limiter = RateLimiter::new(10., 100.);
+        limiter.update(&Duration::from_secs(1), 50.);
+
+        assert_eq!(limiter.output(), 10.);
+    }
+
+    #[test]
+    fn falls_at_falling_rate() {
+        let mut limiter = RateLimiter::new_with_init_value(10., 100., 500.);
+        limiter.update(&Duration::from_secs(1), 0.);
+
+        assert_eq!(limiter.output(), 400.);
+    }
+
+    #[test]
+    fn does_not_overshoot_input() {
+        let mut limiter = RateLimiter::new(10., 10.);
+        limiter.update(&Duration::from_secs(1), 5.);
+
+        assert_eq!(limiter.output(), 5.);
+    }
+}
+
+#[cfg(test)]
+mod hysteresis_tests {
+    use super::*;
+
+    #[test]
+    fn switches_on_at_high_threshold() {
+        let mut hysteresis = Hysteresis::new(16., 17.);
+
+        assert!(!hysteresis.update(16.5));
+        assert!(hysteresis.update(17.));
+    }
+
+    #[test]
+    fn stays_on_between_thresholds() {
+        let mut hysteresis = Hysteresis::new(16., 17.);
+        hysteresis.update(17.);
+
+        assert!(hysteresis.update(16.5));
+    }
+
+    #[test]
+    fn switches_off_at_low_threshold() {
+        let mut hysteresis = Hysteresis::new(16., 17.);
+        hysteresis.update(17.);
+
+        assert!(!hysteresis.update(16.));
+    }
+}
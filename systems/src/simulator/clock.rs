@@ -0,0 +1,107 @@
+use super::UpdateContext;
+use std::time::Duration;
+
+/// Gives timers the time elapsed since their previous update.
+///
+/// Timers take any time source, so they can be updated from the frame context
+/// as well as from a plain duration.
+pub trait TimeSource {
+    fn delta(&self) -> Duration;
+}
+impl TimeSource for UpdateContext {
+    fn delta(&self) -> Duration {
+        self.delta
+    }
+}
+impl TimeSource for Duration {
+    fn delta(&self) -> Duration {
+        *self
+    }
+}
+
+/// Advances the simulation step by step.
+///
+/// In the simulator the frame time comes from the simulator itself. Scenario
+/// tests use a clock instead, to run deterministically or to fast-forward
+/// through long delays in a few large steps.
+pub trait Clock {
+    /// Advances the clock by one step, returning the duration of that step.
+    fn tick(&mut self) -> Duration;
+
+    /// The time elapsed over all the steps so far.
+    fn elapsed(&self) -> Duration;
+
+    /// Advances the clock by one step, returning the given context for a frame
+    /// of that step.
+    fn next_context(&mut self, context: &UpdateContext) -> UpdateContext {
+        context.clone().with_delta(self.tick())
+    }
+}
+
+/// A clock advancing by the same step every tick.
+pub struct FixedStepClock {
+    step: Duration,
+    elapsed: Duration,
+}
+impl FixedStepClock {
+    pub fn new(step: Duration) -> Self {
+        FixedStepClock {
+            step,
+            elapsed: Duration::from_secs(0),
+        }
+    }
+
+    /// A clock running the given number of times faster than real time, where
+    /// each real time step advances the simulation by that many steps at once.
+    pub fn accelerated(step: Duration, rate: u32) -> Self {
+        FixedStepClock::new(step * rate)
+    }
+
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+}
+impl Clock for FixedStepClock {
+    fn tick(&mut self) -> Duration {
+        self.elapsed += self.step;
+
+        self.step
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+#[cfg(test)]
+mod clock_tests {
+    use super::*;
+    use crate::simulator::test_helpers::context;
+
+    #[test]
+    fn fixed_step_clock_accumulates_its_steps() {
+        let mut clock = FixedStepClock::new(Duration::from_millis(50));
+        for _ in 0..20 {
+            assert_eq!(clock.tick(), Duration::from_millis(50));
+        }
+
+        assert_eq!(clock.elapsed(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn accelerated_clock_takes_larger_steps() {
+        let mut clock = FixedStepClock::accelerated(Duration::from_millis(50), 20);
+        clock.tick();
+
+        assert_eq!(clock.step(), Duration::from_secs(1));
+        assert_eq!(clock.elapsed(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn next_context_has_the_step_as_delta() {
+        let mut clock = FixedStepClock::new(Duration::from_millis(250));
+        let context = clock.next_context(&context());
+
+        assert_eq!(context.delta(), Duration::from_millis(250));
+    }
+}
@@ -1,17 +1,33 @@
 //! Provides all the necessary types for integrating the
 //! crate into a Microsoft Flight Simulator aircraft.
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use uom::si::f64::*;
 
+mod clock;
+pub use clock::{Clock, FixedStepClock, TimeSource};
+
+mod recording;
+pub use recording::{RecordingError, RecordingReadWriter, ReplayReadWriter};
+
 mod update_context;
-#[cfg(test)]
+#[cfg(any(test, feature = "test-support"))]
 pub use update_context::test_helpers;
-pub use update_context::UpdateContext;
+pub use update_context::{ambient_pressure, UpdateContext};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod xplane;
+#[cfg(not(target_arch = "wasm32"))]
+pub use xplane::XPlaneReadWriter;
 
 use crate::electrical::{PowerConsumptionState, PowerSupply};
+use crate::hydraulic::PtuState;
+use crate::overhead::PushButtonLights;
 
 /// Trait for reading data from and writing data to the simulator.
 pub trait SimulatorReadWriter {
+    /// Tells the read writer a tick of the given duration starts, before reading.
+    fn start_tick(&self, _delta: Duration) {}
     /// Reads data from the simulator into a model representing that state.
     fn read(&self) -> SimulatorReadState;
     /// Writes data from a model into the simulator.
@@ -39,6 +55,7 @@ impl<T: Aircraft, U: SimulatorReadWriter> Simulation<T, U> {
     }
 
     pub fn tick(&mut self, delta: Duration) {
+        self.simulator_read_writer.start_tick(delta);
         let state = self.simulator_read_writer.read();
         let mut visitor = SimulatorToModelVisitor::new(&state);
         self.aircraft.accept(&mut Box::new(&mut visitor));
@@ -134,48 +151,106 @@ pub trait SimulatorElementVisitor {
 
 /// The data which is read from the simulator and can
 /// be passed into the aircraft system simulation.
-#[derive(Default)]
+/// Fields missing when deserializing take their default value, so recordings
+/// outlive changes to the state.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulatorReadState {
+    pub ambient_pressure: Pressure,
     pub ambient_temperature: ThermodynamicTemperature,
+    pub annunciator_light_test: bool,
     pub apu: SimulatorApuReadState,
     pub electrical: SimulatorElectricalReadState,
     pub fire: SimulatorFireReadState,
+    pub ground_speed: Velocity,
+    pub hydraulic: SimulatorHydraulicReadState,
     pub indicated_airspeed: Velocity,
     pub indicated_altitude: Length,
+    pub landing_gear: SimulatorLandingGearReadState,
     pub left_inner_tank_fuel_quantity: Mass,
+    pub longitudinal_velocity: Velocity,
+    pub on_ground: bool,
     pub pneumatic: SimulatorPneumaticReadState,
     pub unlimited_fuel: bool,
+    pub vertical_velocity: Velocity,
+    pub wing_flex: [Ratio; 2],
     pub engine_n2: [Ratio; 2],
+    pub engine_master_on: [bool; 2],
+    // The FWC flight phase, from 1 to 10.
+    pub flight_phase: u8,
 }
 impl SimulatorReadState {
     /// Creates a context based on the data that was read from the simulator.
     pub fn to_context(&self, delta_time: Duration) -> UpdateContext {
         UpdateContext {
+            ambient_pressure: self.ambient_pressure,
             ambient_temperature: self.ambient_temperature,
             indicated_airspeed: self.indicated_airspeed,
             indicated_altitude: self.indicated_altitude,
+            ground_speed: self.ground_speed,
+            longitudinal_velocity: self.longitudinal_velocity,
+            vertical_velocity: self.vertical_velocity,
+            wing_flex: self.wing_flex,
             delta: delta_time,
         }
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulatorApuReadState {
     pub master_sw_pb_on: bool,
     pub start_pb_on: bool,
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulatorPneumaticReadState {
     pub apu_bleed_pb_on: bool,
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulatorFireReadState {
     pub apu_fire_button_released: bool,
+    pub engine_fire_button_released: [bool; 2],
+    pub engine_fire_button_pushed_in: [bool; 2],
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulatorHydraulicReadState {
+    pub anti_skid_and_nws_switch_on: bool,
+    pub autobrake_lo_pb_pressed: bool,
+    pub autobrake_med_pb_pressed: bool,
+    pub autobrake_max_pb_pressed: bool,
+    pub blue_electric_pump_pb_auto: bool,
+    pub blue_ground_cart_pressure: Pressure,
+    pub blue_pump_override_pb_on: bool,
+    pub brake_fans_running: bool,
+    pub cargo_door_selector_operated: [bool; 2],
+    pub dump_state_requested: bool,
+    pub engine_pump_pb_auto: [bool; 2],
+    pub ground_spoilers_deployed: bool,
+    pub left_brake_pedal_position: Ratio,
+    pub right_brake_pedal_position: Ratio,
+    pub nose_wheel_steering_tow_pin_inserted: bool,
+    pub parking_brake_applied: bool,
+    pub ptu_pb_auto: bool,
+    pub pushback_tug_attached: bool,
+    pub rat_stow_requested: bool,
+    pub yellow_electric_pump_pb_on: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulatorLandingGearReadState {
+    pub gear_lever_up: bool,
+    pub wheel_rpm: [AngularVelocity; 4],
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulatorElectricalReadState {
     pub ac_ess_feed_pb_normal: bool,
     pub apu_generator_pb_on: bool,
@@ -195,6 +270,7 @@ pub struct SimulatorElectricalReadState {
 pub struct SimulatorWriteState {
     pub apu: SimulatorApuWriteState,
     pub electrical: SimulatorElectricalWriteState,
+    pub hydraulic: SimulatorHydraulicWriteState,
     pub pneumatic: SimulatorPneumaticWriteState,
 }
 
@@ -273,6 +349,63 @@ pub struct SimulatorCurrentPotentialElectricalWriteState {
     pub potential_within_normal_range: bool,
 }
 
+#[derive(Default)]
+pub struct SimulatorHydraulicWriteState {
+    pub aileron_positions: [Angle; 2],
+    pub autobrake_lo_armed: bool,
+    pub autobrake_med_armed: bool,
+    pub autobrake_max_armed: bool,
+    pub autobrake_decel: bool,
+    pub blue_pressure: Pressure,
+    pub blue_reservoir_volume: Volume,
+    pub brake_accumulator_pressure: Pressure,
+    pub brake_temperatures: [ThermodynamicTemperature; 4],
+    pub blue_electric_pump_low_pressure_caution: bool,
+    pub blue_electric_pump_pb_lights: PushButtonLights,
+    pub blue_electric_pump_power: Power,
+    pub bscu_channel_fault: [bool; 2],
+    pub elevator_positions: [Angle; 2],
+    pub engine_driven_pump_shaft_power: [Power; 2],
+    pub engine_pump_low_pressure_caution: [bool; 2],
+    pub engine_pump_overheat_caution: [bool; 2],
+    pub engine_pump_pb_lights: [PushButtonLights; 2],
+    // Nose, left main and right main gears.
+    pub gear_downlocked: [bool; 3],
+    pub gear_position_disagree: [bool; 3],
+    pub gear_uplocked: [bool; 3],
+    pub green_pressure: Pressure,
+    pub green_reservoir_volume: Volume,
+    pub left_alternate_brake_pressure: Pressure,
+    pub left_normal_brake_pressure: Pressure,
+    pub left_spoiler_positions: [Angle; 5],
+    pub nose_wheel_steering_angle_limit: Angle,
+    pub nose_wheel_steering_available: bool,
+    pub ptu_barking_intensity: f64,
+    pub ptu_flow: VolumeRate,
+    pub ptu_pb_lights: PushButtonLights,
+    pub ptu_self_test_in_progress: bool,
+    pub ptu_shaft_speed: AngularVelocity,
+    pub ptu_state: PtuState,
+    pub rat_deployment_progress: Ratio,
+    pub rat_in_transit: bool,
+    pub rat_time_to_deploy: Duration,
+    pub right_alternate_brake_pressure: Pressure,
+    pub right_normal_brake_pressure: Pressure,
+    pub right_spoiler_positions: [Angle; 5],
+    pub rudder_travel_limit: Angle,
+    // One bit per spoiler, left spoilers 1 to 5 then right spoilers 1 to 5.
+    pub spoiler_availability: u16,
+    // Green, blue and yellow.
+    pub system_low_pressure_caution: [bool; 3],
+    // Needle readings of the brake and accumulator pressure indicator.
+    pub triple_indicator_accumulator_pressure: Pressure,
+    pub triple_indicator_left_brake_pressure: Pressure,
+    pub triple_indicator_right_brake_pressure: Pressure,
+    pub yellow_electric_pump_power: Power,
+    pub yellow_pressure: Pressure,
+    pub yellow_reservoir_volume: Volume,
+}
+
 #[derive(Default)]
 pub struct SimulatorPneumaticWriteState {
     pub apu_bleed_pb_fault: bool,
@@ -0,0 +1,345 @@
+use super::{SimulatorReadState, SimulatorReadWriter, SimulatorWriteState};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+/// The state read from the simulator on one tick, at the simulation time at
+/// the end of that tick.
+#[derive(Serialize, Deserialize)]
+struct RecordedRead<S> {
+    time: f64,
+    state: S,
+}
+
+/// Records everything read from the simulator, one JSON line per tick, so a
+/// [`ReplayReadWriter`] can feed it back to the aircraft outside the simulator.
+///
+/// Failing to record never fails the simulation: the error is logged and the
+/// read state passed on as usual.
+pub struct RecordingReadWriter<T: SimulatorReadWriter, W: Write = BufWriter<File>> {
+    read_writer: T,
+    output: RefCell<W>,
+    time: Cell<Duration>,
+}
+impl<T: SimulatorReadWriter> RecordingReadWriter<T> {
+    /// Records to the file at the given path, replacing any previous recording.
+    pub fn to_file<P: AsRef<Path>>(read_writer: T, path: P) -> io::Result<Self> {
+        Ok(RecordingReadWriter::new(
+            read_writer,
+            BufWriter::new(File::create(path)?),
+        ))
+    }
+}
+impl<T: SimulatorReadWriter, W: Write> RecordingReadWriter<T, W> {
+    pub fn new(read_writer: T, output: W) -> Self {
+        RecordingReadWriter {
+            read_writer,
+            output: RefCell::new(output),
+            time: Cell::new(Duration::from_secs(0)),
+        }
+    }
+
+    fn record(&self, state: &SimulatorReadState) -> Result<(), RecordingError> {
+        let read = RecordedRead {
+            time: self.time.get().as_secs_f64(),
+            state,
+        };
+        let mut output = self.output.borrow_mut();
+        serde_json::to_writer(&mut *output, &read).map_err(RecordingError::Format)?;
+        writeln!(output).map_err(RecordingError::Io)?;
+        // Bugs worth a recording often end the simulation, keep what was recorded so far.
+        output.flush().map_err(RecordingError::Io)
+    }
+}
+impl<T: SimulatorReadWriter, W: Write> SimulatorReadWriter for RecordingReadWriter<T, W> {
+    fn start_tick(&self, delta: Duration) {
+        self.time.set(self.time.get() + delta);
+        self.read_writer.start_tick(delta);
+    }
+
+    fn read(&self) -> SimulatorReadState {
+        let state = self.read_writer.read();
+        if let Err(error) = self.record(&state) {
+            log::warn!("{}", error);
+        }
+
+        state
+    }
+
+    fn write(&self, state: &SimulatorWriteState) {
+        self.read_writer.write(state);
+    }
+}
+
+/// Feeds a recording made by a [`RecordingReadWriter`] back to the aircraft,
+/// one recorded tick per read. Writes go nowhere.
+///
+/// Running the simulation with the recorded tick durations reproduces the
+/// recorded session:
+///
+/// ```no_run
+/// # use airbus_systems::{simulator::{ReplayReadWriter, Simulation}, A320};
+/// let replay = ReplayReadWriter::from_file("recording.jsonl").unwrap();
+/// let deltas = replay.deltas();
+/// let mut simulation = Simulation::new(A320::new(), replay);
+/// for delta in deltas {
+///     simulation.tick(delta);
+/// }
+/// ```
+pub struct ReplayReadWriter {
+    reads: RefCell<VecDeque<RecordedRead<SimulatorReadState>>>,
+    deltas: Vec<Duration>,
+}
+impl ReplayReadWriter {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, RecordingError> {
+        ReplayReadWriter::from_reader(BufReader::new(
+            File::open(path).map_err(RecordingError::Io)?,
+        ))
+    }
+
+    pub fn from_reader<R: BufRead>(input: R) -> Result<Self, RecordingError> {
+        let mut reads = VecDeque::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line.map_err(RecordingError::Io)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let read: RecordedRead<SimulatorReadState> =
+                serde_json::from_str(&line).map_err(|error| RecordingError::Parse {
+                    line: index + 1,
+                    error,
+                })?;
+            reads.push_back(read);
+        }
+
+        let mut previous_time = 0.;
+        let deltas = reads
+            .iter()
+            .map(|read| {
+                let delta = Duration::from_secs_f64((read.time - previous_time).max(0.));
+                previous_time = read.time;
+
+                delta
+            })
+            .collect();
+
+        Ok(ReplayReadWriter {
+            reads: RefCell::new(reads),
+            deltas,
+        })
+    }
+
+    /// The duration of each recorded tick, in order.
+    pub fn deltas(&self) -> Vec<Duration> {
+        self.deltas.clone()
+    }
+
+    /// The number of recorded ticks not read yet.
+    pub fn remaining(&self) -> usize {
+        self.reads.borrow().len()
+    }
+}
+impl SimulatorReadWriter for ReplayReadWriter {
+    /// The next recorded state, or the default state once all were read.
+    fn read(&self) -> SimulatorReadState {
+        self.reads
+            .borrow_mut()
+            .pop_front()
+            .map(|read| read.state)
+            .unwrap_or_default()
+    }
+
+    fn write(&self, _state: &SimulatorWriteState) {}
+}
+
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    Parse {
+        line: usize,
+        error: serde_json::Error,
+    },
+}
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(error) => write!(f, "Cannot access the recording: {}", error),
+            RecordingError::Format(error) => write!(f, "Cannot record the read state: {}", error),
+            RecordingError::Parse { line, error } => {
+                write!(f, "Invalid recording at line {}: {}", line, error)
+            }
+        }
+    }
+}
+impl Error for RecordingError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RecordingError::Io(error) => Some(error),
+            RecordingError::Format(error) => Some(error),
+            RecordingError::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod recording_tests {
+    use super::*;
+    use crate::simulator::{
+        Aircraft, Simulation, SimulatorElement, SimulatorElementVisitable, SimulatorElementVisitor,
+        UpdateContext,
+    };
+    use std::{env, fs, process};
+    use uom::si::{f64::*, pressure::psi, ratio::percent};
+
+    #[test]
+    fn records_each_read_with_its_time() {
+        let mut output = Vec::new();
+        {
+            let recording = RecordingReadWriter::new(TestReadWriter::new(), &mut output);
+            for _ in 0..3 {
+                recording.start_tick(Duration::from_millis(100));
+                recording.read();
+            }
+        }
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert!((lines[2]["time"].as_f64().unwrap() - 0.3).abs() < 1e-9);
+        assert_eq!(lines[2]["state"]["flight_phase"], 3);
+    }
+
+    #[test]
+    fn passes_reads_and_writes_through() {
+        let recording = RecordingReadWriter::new(TestReadWriter::new(), io::sink());
+        recording.write(&SimulatorWriteState::default());
+
+        assert_eq!(recording.read().flight_phase, 1);
+        assert_eq!(recording.read_writer.writes.get(), 1);
+    }
+
+    #[test]
+    fn replays_the_recorded_reads_in_order() {
+        let mut output = Vec::new();
+        {
+            let recording = RecordingReadWriter::new(TestReadWriter::new(), &mut output);
+            for delta in [50, 100, 20].iter() {
+                recording.start_tick(Duration::from_millis(*delta));
+                recording.read();
+            }
+        }
+
+        let replay = ReplayReadWriter::from_reader(&output[..]).unwrap();
+        let deltas: Vec<u128> = replay
+            .deltas()
+            .iter()
+            .map(|delta| delta.as_millis())
+            .collect();
+        assert_eq!(deltas, vec![50, 100, 20]);
+        for phase in 1..=3 {
+            let state = replay.read();
+            assert_eq!(state.flight_phase, phase);
+            assert_eq!(
+                state.engine_n2[0],
+                Ratio::new::<percent>(10. * phase as f64)
+            );
+            assert_eq!(state.ambient_pressure, Pressure::new::<psi>(14.7));
+        }
+        assert_eq!(replay.remaining(), 0);
+        assert_eq!(replay.read().flight_phase, 0);
+    }
+
+    #[test]
+    fn replay_reports_the_invalid_line() {
+        let result = ReplayReadWriter::from_reader(&b"{\"time\":0.1,\"state\":{}}\nnot json\n"[..]);
+
+        assert!(matches!(result, Err(RecordingError::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn simulation_recorded_to_a_file_replays_with_the_same_ticks() {
+        let path =
+            env::temp_dir().join(format!("airbus_systems_recording_{}.jsonl", process::id()));
+        {
+            let recording = RecordingReadWriter::to_file(TestReadWriter::new(), &path).unwrap();
+            let mut simulation = Simulation::new(TestAircraft::default(), recording);
+            for _ in 0..4 {
+                simulation.tick(Duration::from_millis(250));
+            }
+        }
+
+        let replay = ReplayReadWriter::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(replay.deltas(), vec![Duration::from_millis(250); 4]);
+
+        let mut simulation = Simulation::new(TestAircraft::default(), replay);
+        simulation.tick(Duration::from_millis(250));
+        assert_eq!(simulation.aircraft.flight_phase, 1);
+    }
+
+    #[test]
+    fn missing_recording_is_an_io_error() {
+        let result = ReplayReadWriter::from_file("this/recording/does/not/exist.jsonl");
+
+        assert!(matches!(result, Err(RecordingError::Io(_))));
+    }
+
+    struct TestReadWriter {
+        reads: Cell<u8>,
+        writes: Cell<usize>,
+    }
+    impl TestReadWriter {
+        fn new() -> Self {
+            TestReadWriter {
+                reads: Cell::new(0),
+                writes: Cell::new(0),
+            }
+        }
+    }
+    impl SimulatorReadWriter for TestReadWriter {
+        fn read(&self) -> SimulatorReadState {
+            self.reads.set(self.reads.get() + 1);
+            let mut state = SimulatorReadState::default();
+            state.flight_phase = self.reads.get();
+            state.engine_n2[0] = Ratio::new::<percent>(10. * self.reads.get() as f64);
+            state.ambient_pressure = Pressure::new::<psi>(14.7);
+
+            state
+        }
+
+        fn write(&self, _state: &SimulatorWriteState) {
+            self.writes.set(self.writes.get() + 1);
+        }
+    }
+
+    #[derive(Default)]
+    struct TestAircraft {
+        flight_phase: u8,
+    }
+    impl Aircraft for TestAircraft {
+        fn update(&mut self, _context: &UpdateContext) {}
+    }
+    impl SimulatorElementVisitable for TestAircraft {
+        fn accept(&mut self, visitor: &mut Box<&mut dyn SimulatorElementVisitor>) {
+            visitor.visit(&mut Box::new(self));
+        }
+    }
+    impl SimulatorElement for TestAircraft {
+        fn read(&mut self, state: &SimulatorReadState) {
+            self.flight_phase = state.flight_phase;
+        }
+    }
+}
@@ -1,14 +1,37 @@
 use std::time::Duration;
-use uom::si::f64::*;
+use uom::si::{
+    angle::radian, f64::*, length::foot, pressure::psi, ratio::ratio, velocity::foot_per_second,
+};
+
+/// Static air pressure of the standard atmosphere at the given altitude.
+pub fn ambient_pressure(altitude: Length) -> Pressure {
+    const SEA_LEVEL_PSI: f64 = 14.696;
+    const LAPSE_FACTOR_PER_FOOT: f64 = 6.8756e-6;
+    const EXPONENT: f64 = 5.2559;
+
+    let altitude_feet = altitude.get::<foot>().min(1. / LAPSE_FACTOR_PER_FOOT);
+    Pressure::new::<psi>(
+        SEA_LEVEL_PSI * (1. - LAPSE_FACTOR_PER_FOOT * altitude_feet).powf(EXPONENT),
+    )
+}
 
 /// Provides data unowned by any system in the aircraft system simulation
 /// for the purpose of handling an update frame.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct UpdateContext {
     pub delta: Duration,
     pub indicated_airspeed: Velocity,
     pub indicated_altitude: Length,
     pub ambient_temperature: ThermodynamicTemperature,
+    /// Static air pressure around the aircraft (AMBIENT PRESSURE).
+    pub ambient_pressure: Pressure,
+    pub ground_speed: Velocity,
+    /// Velocity along the aircraft's longitudinal axis (VELOCITY BODY Z).
+    pub longitudinal_velocity: Velocity,
+    /// Velocity along the aircraft's vertical axis (VELOCITY BODY Y).
+    pub vertical_velocity: Velocity,
+    /// Left and right wing flex, as a ratio of the maximum flex (WING FLEX PCT).
+    pub wing_flex: [Ratio; 2],
 }
 impl UpdateContext {
     pub fn new(
@@ -16,21 +39,118 @@ impl UpdateContext {
         indicated_airspeed: Velocity,
         indicated_altitude: Length,
         ambient_temperature: ThermodynamicTemperature,
+        ground_speed: Velocity,
     ) -> UpdateContext {
         UpdateContext {
             delta,
             indicated_airspeed,
             indicated_altitude,
             ambient_temperature,
+            ambient_pressure: ambient_pressure(indicated_altitude),
+            ground_speed,
+            longitudinal_velocity: Velocity::new::<foot_per_second>(0.),
+            vertical_velocity: Velocity::new::<foot_per_second>(0.),
+            wing_flex: [Ratio::new::<ratio>(0.), Ratio::new::<ratio>(0.)],
+        }
+    }
+
+    /// Replaces the time elapsed since the previous frame.
+    pub fn with_delta(mut self, delta: Duration) -> UpdateContext {
+        self.delta = delta;
+        self
+    }
+
+    pub fn with_body_velocity(
+        mut self,
+        longitudinal_velocity: Velocity,
+        vertical_velocity: Velocity,
+    ) -> UpdateContext {
+        self.longitudinal_velocity = longitudinal_velocity;
+        self.vertical_velocity = vertical_velocity;
+        self
+    }
+
+    /// Replaces the standard atmosphere pressure at the indicated altitude.
+    pub fn with_ambient_pressure(mut self, ambient_pressure: Pressure) -> UpdateContext {
+        self.ambient_pressure = ambient_pressure;
+        self
+    }
+
+    pub fn with_wing_flex(mut self, wing_flex: [Ratio; 2]) -> UpdateContext {
+        self.wing_flex = wing_flex;
+        self
+    }
+
+    /// Angle between the aircraft's longitudinal axis and the airflow.
+    /// Without forward velocity there is no airflow to speak of.
+    pub fn angle_of_attack(&self) -> Angle {
+        if self.longitudinal_velocity > Velocity::new::<foot_per_second>(0.) {
+            Angle::new::<radian>(
+                (-self.vertical_velocity.get::<foot_per_second>())
+                    .atan2(self.longitudinal_velocity.get::<foot_per_second>()),
+            )
+        } else {
+            Angle::new::<radian>(0.)
         }
     }
 }
 
 #[cfg(test)]
+mod update_context_tests {
+    use super::*;
+    use uom::si::velocity::knot;
+
+    #[test]
+    fn ambient_pressure_follows_standard_atmosphere() {
+        assert!((ambient_pressure(Length::new::<foot>(0.)).get::<psi>() - 14.7).abs() < 0.01);
+        assert!((ambient_pressure(Length::new::<foot>(39000.)).get::<psi>() - 2.87).abs() < 0.05);
+    }
+
+    #[test]
+    fn ambient_pressure_defaults_to_standard_atmosphere_at_indicated_altitude() {
+        let context = test_helpers::context_with()
+            .indicated_altitude(Length::new::<foot>(39000.))
+            .build();
+
+        assert_eq!(
+            context.ambient_pressure,
+            ambient_pressure(Length::new::<foot>(39000.))
+        );
+    }
+
+    #[test]
+    fn builder_shorthands_set_the_context_in_common_units() {
+        let context = test_helpers::UpdateContextBuilder::new()
+            .delta_ms(100)
+            .airspeed_kt(140.)
+            .altitude_ft(3000.)
+            .build();
+
+        assert_eq!(context.delta, Duration::from_millis(100));
+        assert_eq!(context.indicated_airspeed, Velocity::new::<knot>(140.));
+        assert_eq!(context.indicated_altitude, Length::new::<foot>(3000.));
+    }
+
+    #[test]
+    fn on_ground_stops_the_aircraft_at_sea_level() {
+        let context = test_helpers::context_with().on_ground(true).build();
+
+        assert_eq!(
+            context.indicated_airspeed,
+            Velocity::new::<foot_per_second>(0.)
+        );
+        assert_eq!(context.ground_speed, Velocity::new::<foot_per_second>(0.));
+        assert_eq!(context.indicated_altitude, Length::new::<foot>(0.));
+    }
+}
+
+/// Builds update contexts with sensible defaults for tests, also for those of
+/// the crates depending on this one through the `test-support` feature.
+#[cfg(any(test, feature = "test-support"))]
 pub mod test_helpers {
     use super::*;
 
-    use uom::si::{length::foot, thermodynamic_temperature::degree_celsius, velocity::knot};
+    use uom::si::{thermodynamic_temperature::degree_celsius, velocity::knot};
 
     pub fn context_with() -> UpdateContextBuilder {
         UpdateContextBuilder::new()
@@ -45,24 +165,66 @@ pub mod test_helpers {
         indicated_airspeed: Velocity,
         indicated_altitude: Length,
         ambient_temperature: ThermodynamicTemperature,
+        ambient_pressure: Option<Pressure>,
+        ground_speed: Velocity,
+        longitudinal_velocity: Velocity,
+        vertical_velocity: Velocity,
+        wing_flex: [Ratio; 2],
     }
     impl UpdateContextBuilder {
-        fn new() -> UpdateContextBuilder {
+        pub fn new() -> UpdateContextBuilder {
             UpdateContextBuilder {
                 delta: Duration::from_secs(1),
                 indicated_airspeed: Velocity::new::<knot>(250.),
                 indicated_altitude: Length::new::<foot>(5000.),
                 ambient_temperature: ThermodynamicTemperature::new::<degree_celsius>(0.),
+                ambient_pressure: None,
+                ground_speed: Velocity::new::<knot>(0.),
+                longitudinal_velocity: Velocity::new::<foot_per_second>(0.),
+                vertical_velocity: Velocity::new::<foot_per_second>(0.),
+                wing_flex: [Ratio::new::<ratio>(0.), Ratio::new::<ratio>(0.)],
             }
         }
 
         pub fn build(&self) -> UpdateContext {
-            UpdateContext::new(
+            let context = UpdateContext::new(
                 self.delta,
                 self.indicated_airspeed,
                 self.indicated_altitude,
                 self.ambient_temperature,
+                self.ground_speed,
             )
+            .with_body_velocity(self.longitudinal_velocity, self.vertical_velocity)
+            .with_wing_flex(self.wing_flex);
+
+            match self.ambient_pressure {
+                Some(ambient_pressure) => context.with_ambient_pressure(ambient_pressure),
+                None => context,
+            }
+        }
+
+        pub fn delta_ms(self, milliseconds: u64) -> UpdateContextBuilder {
+            self.delta(Duration::from_millis(milliseconds))
+        }
+
+        pub fn airspeed_kt(self, knots: f64) -> UpdateContextBuilder {
+            self.indicated_airspeed(Velocity::new::<knot>(knots))
+        }
+
+        pub fn altitude_ft(self, feet: f64) -> UpdateContextBuilder {
+            self.indicated_altitude(Length::new::<foot>(feet))
+        }
+
+        /// Stops the aircraft at sea level when on the ground. The context doesn't
+        /// carry the on ground state itself, elements read it from the simulator.
+        pub fn on_ground(self, on_ground: bool) -> UpdateContextBuilder {
+            if on_ground {
+                self.airspeed_kt(0.)
+                    .altitude_ft(0.)
+                    .ground_speed(Velocity::new::<knot>(0.))
+            } else {
+                self
+            }
         }
 
         pub fn and(self) -> UpdateContextBuilder {
@@ -91,5 +253,36 @@ pub mod test_helpers {
             self.ambient_temperature = ambient_temperature;
             self
         }
+
+        /// Overrides the standard atmosphere pressure at the indicated altitude.
+        pub fn ambient_pressure(mut self, ambient_pressure: Pressure) -> UpdateContextBuilder {
+            self.ambient_pressure = Some(ambient_pressure);
+            self
+        }
+
+        pub fn ground_speed(mut self, ground_speed: Velocity) -> UpdateContextBuilder {
+            self.ground_speed = ground_speed;
+            self
+        }
+
+        pub fn body_velocity(
+            mut self,
+            longitudinal_velocity: Velocity,
+            vertical_velocity: Velocity,
+        ) -> UpdateContextBuilder {
+            self.longitudinal_velocity = longitudinal_velocity;
+            self.vertical_velocity = vertical_velocity;
+            self
+        }
+
+        pub fn wing_flex(mut self, wing_flex: [Ratio; 2]) -> UpdateContextBuilder {
+            self.wing_flex = wing_flex;
+            self
+        }
+    }
+    impl Default for UpdateContextBuilder {
+        fn default() -> Self {
+            Self::new()
+        }
     }
 }
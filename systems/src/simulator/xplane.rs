@@ -0,0 +1,607 @@
+use super::{
+    from_bool, SimulatorApuReadState, SimulatorElectricalReadState, SimulatorFireReadState,
+    SimulatorHydraulicReadState, SimulatorLandingGearReadState, SimulatorPneumaticReadState,
+    SimulatorReadState, SimulatorReadWriter, SimulatorWriteState,
+};
+use crate::hydraulic::PtuState;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    convert::TryInto,
+    io,
+    net::{SocketAddr, UdpSocket},
+};
+use uom::si::{
+    angle::degree, angular_velocity::radian_per_second, f64::*, length::foot,
+    pressure::inch_of_mercury, pressure::psi, ratio::percent, ratio::ratio,
+    thermodynamic_temperature::degree_celsius, velocity::foot_per_minute, velocity::knot,
+    velocity::meter_per_second, volume::gallon, volume_rate::gallon_per_minute,
+};
+
+const RREF_PATH_LENGTH: usize = 400;
+const DREF_PATH_LENGTH: usize = 500;
+const MAX_PACKET_LENGTH: usize = 1500;
+
+/// The datarefs read from X-Plane, with the value to use until X-Plane sends one.
+///
+/// X-Plane has no datarefs for the A320 overhead panel. Those are custom
+/// datarefs under `a32nx/`, which the aircraft publishes the way the MSFS
+/// aircraft publishes its `A32NX_` local variables. Their defaults set the
+/// panel up for a normal flight, so the systems work without them.
+const READ_DATAREFS: &[(&str, f64)] = &[
+    ("sim/weather/barometer_current_inhg", 29.92),
+    ("sim/weather/temperature_ambient_c", 15.),
+    ("sim/cockpit/warnings/annunciator_test_pressed", 0.),
+    ("sim/flightmodel/position/indicated_airspeed", 0.),
+    ("sim/cockpit2/gauges/indicators/altitude_ft_pilot", 0.),
+    ("sim/flightmodel/position/groundspeed", 0.),
+    ("sim/flightmodel/position/vh_ind_fpm", 0.),
+    ("sim/flightmodel/failures/onground_any", 1.),
+    ("sim/flightmodel/engine/ENGN_N2_[0]", 0.),
+    ("sim/flightmodel/engine/ENGN_N2_[1]", 0.),
+    ("sim/cockpit2/engine/actuators/mixture_ratio[0]", 0.),
+    ("sim/cockpit2/engine/actuators/mixture_ratio[1]", 0.),
+    ("sim/cockpit2/controls/parking_brake_ratio", 1.),
+    ("sim/cockpit2/controls/left_brake_ratio", 0.),
+    ("sim/cockpit2/controls/right_brake_ratio", 0.),
+    ("sim/cockpit2/controls/speedbrake_ratio", 0.),
+    ("sim/cockpit2/controls/gear_handle_down", 1.),
+    ("sim/flightmodel2/gear/tire_rotation_speed_rad_sec[1]", 0.),
+    ("sim/flightmodel2/gear/tire_rotation_speed_rad_sec[2]", 0.),
+    ("a32nx/electrical/ac_ess_feed_pb_normal", 1.),
+    ("a32nx/electrical/apu_generator_pb_on", 1.),
+    ("a32nx/electrical/battery_1_pb_auto", 1.),
+    ("a32nx/electrical/battery_2_pb_auto", 1.),
+    ("a32nx/electrical/bus_tie_pb_auto", 1.),
+    ("a32nx/electrical/commercial_pb_on", 1.),
+    ("a32nx/electrical/galy_and_cab_pb_auto", 1.),
+    ("a32nx/electrical/generator_1_pb_on", 1.),
+    ("a32nx/electrical/generator_2_pb_on", 1.),
+    ("a32nx/hydraulic/anti_skid_and_nws_switch_on", 1.),
+    ("a32nx/hydraulic/blue_electric_pump_pb_auto", 1.),
+    ("a32nx/hydraulic/engine_1_pump_pb_auto", 1.),
+    ("a32nx/hydraulic/engine_2_pump_pb_auto", 1.),
+    ("a32nx/hydraulic/ptu_pb_auto", 1.),
+    ("a32nx/hydraulic/yellow_electric_pump_pb_on", 0.),
+    ("a32nx/hydraulic/fwd_cargo_door_selector_operated", 0.),
+    ("a32nx/hydraulic/aft_cargo_door_selector_operated", 0.),
+];
+
+/// Reads from and writes to X-Plane over UDP, so the aircraft systems can run
+/// against X-Plane instead of Microsoft Flight Simulator.
+///
+/// On connecting, it asks X-Plane to send the datarefs it reads at a fixed
+/// frequency (RREF). Reading takes the latest values X-Plane sent. Writing sets
+/// the `a32nx/` datarefs of the aircraft (DREF).
+pub struct XPlaneReadWriter {
+    socket: UdpSocket,
+    xplane: SocketAddr,
+    values: RefCell<HashMap<&'static str, f64>>,
+}
+impl XPlaneReadWriter {
+    /// The port X-Plane receives UDP commands on by default.
+    pub const DEFAULT_PORT: u16 = 49000;
+
+    /// Subscribes to the read datarefs of the X-Plane instance at the given
+    /// address, to be sent the given number of times per second.
+    pub fn connect(xplane: SocketAddr, frequency: i32) -> io::Result<Self> {
+        let socket = UdpSocket::bind(if xplane.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        })?;
+        socket.set_nonblocking(true)?;
+
+        let read_writer = XPlaneReadWriter {
+            socket,
+            xplane,
+            values: RefCell::new(READ_DATAREFS.iter().cloned().collect()),
+        };
+        read_writer.subscribe(frequency)?;
+
+        Ok(read_writer)
+    }
+
+    fn subscribe(&self, frequency: i32) -> io::Result<()> {
+        for (index, (path, _)) in READ_DATAREFS.iter().enumerate() {
+            self.socket
+                .send_to(&rref_request(frequency, index as i32, path), self.xplane)?;
+        }
+
+        Ok(())
+    }
+
+    /// Takes in the values from all the packets X-Plane sent since the previous read.
+    fn receive(&self) {
+        let mut buffer = [0; MAX_PACKET_LENGTH];
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((length, from)) if from == self.xplane => {
+                    let mut values = self.values.borrow_mut();
+                    for (index, value) in decode_rref(&buffer[..length]) {
+                        if let Some((path, _)) = READ_DATAREFS.get(index as usize) {
+                            values.insert(path, value as f64);
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                Err(error) => {
+                    log::warn!("Couldn't receive datarefs from X-Plane: {}", error);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn value(&self, path: &str) -> f64 {
+        self.values.borrow()[path]
+    }
+
+    fn is_set(&self, path: &str) -> bool {
+        self.value(path) > 0.5
+    }
+}
+impl SimulatorReadWriter for XPlaneReadWriter {
+    fn read(&self) -> SimulatorReadState {
+        self.receive();
+
+        // X-Plane has no body axis velocity dataref, the ground speed stands in for it.
+        let ground_speed =
+            Velocity::new::<meter_per_second>(self.value("sim/flightmodel/position/groundspeed"));
+        // X-Plane gives a single wheel speed for each main gear.
+        let wheel_speeds = [
+            AngularVelocity::new::<radian_per_second>(
+                self.value("sim/flightmodel2/gear/tire_rotation_speed_rad_sec[1]"),
+            ),
+            AngularVelocity::new::<radian_per_second>(
+                self.value("sim/flightmodel2/gear/tire_rotation_speed_rad_sec[2]"),
+            ),
+        ];
+
+        SimulatorReadState {
+            ambient_pressure: Pressure::new::<inch_of_mercury>(
+                self.value("sim/weather/barometer_current_inhg"),
+            ),
+            ambient_temperature: ThermodynamicTemperature::new::<degree_celsius>(
+                self.value("sim/weather/temperature_ambient_c"),
+            ),
+            annunciator_light_test: self.is_set("sim/cockpit/warnings/annunciator_test_pressed"),
+            apu: SimulatorApuReadState::default(),
+            electrical: SimulatorElectricalReadState {
+                ac_ess_feed_pb_normal: self.is_set("a32nx/electrical/ac_ess_feed_pb_normal"),
+                apu_generator_pb_on: self.is_set("a32nx/electrical/apu_generator_pb_on"),
+                battery_pb_auto: [
+                    self.is_set("a32nx/electrical/battery_1_pb_auto"),
+                    self.is_set("a32nx/electrical/battery_2_pb_auto"),
+                ],
+                bus_tie_pb_auto: self.is_set("a32nx/electrical/bus_tie_pb_auto"),
+                commercial_pb_on: self.is_set("a32nx/electrical/commercial_pb_on"),
+                galy_and_cab_pb_auto: self.is_set("a32nx/electrical/galy_and_cab_pb_auto"),
+                engine_generator_pb_on: [
+                    self.is_set("a32nx/electrical/generator_1_pb_on"),
+                    self.is_set("a32nx/electrical/generator_2_pb_on"),
+                ],
+                ..SimulatorElectricalReadState::default()
+            },
+            fire: SimulatorFireReadState::default(),
+            ground_speed,
+            hydraulic: SimulatorHydraulicReadState {
+                anti_skid_and_nws_switch_on: self
+                    .is_set("a32nx/hydraulic/anti_skid_and_nws_switch_on"),
+                blue_electric_pump_pb_auto: self
+                    .is_set("a32nx/hydraulic/blue_electric_pump_pb_auto"),
+                cargo_door_selector_operated: [
+                    self.is_set("a32nx/hydraulic/fwd_cargo_door_selector_operated"),
+                    self.is_set("a32nx/hydraulic/aft_cargo_door_selector_operated"),
+                ],
+                engine_pump_pb_auto: [
+                    self.is_set("a32nx/hydraulic/engine_1_pump_pb_auto"),
+                    self.is_set("a32nx/hydraulic/engine_2_pump_pb_auto"),
+                ],
+                ground_spoilers_deployed: self.value("sim/cockpit2/controls/speedbrake_ratio")
+                    > 0.9,
+                left_brake_pedal_position: Ratio::new::<ratio>(
+                    self.value("sim/cockpit2/controls/left_brake_ratio"),
+                ),
+                right_brake_pedal_position: Ratio::new::<ratio>(
+                    self.value("sim/cockpit2/controls/right_brake_ratio"),
+                ),
+                parking_brake_applied: self.is_set("sim/cockpit2/controls/parking_brake_ratio"),
+                ptu_pb_auto: self.is_set("a32nx/hydraulic/ptu_pb_auto"),
+                yellow_electric_pump_pb_on: self
+                    .is_set("a32nx/hydraulic/yellow_electric_pump_pb_on"),
+                ..SimulatorHydraulicReadState::default()
+            },
+            indicated_airspeed: Velocity::new::<knot>(
+                self.value("sim/flightmodel/position/indicated_airspeed"),
+            ),
+            indicated_altitude: Length::new::<foot>(
+                self.value("sim/cockpit2/gauges/indicators/altitude_ft_pilot"),
+            ),
+            landing_gear: SimulatorLandingGearReadState {
+                gear_lever_up: !self.is_set("sim/cockpit2/controls/gear_handle_down"),
+                wheel_rpm: [
+                    wheel_speeds[0],
+                    wheel_speeds[0],
+                    wheel_speeds[1],
+                    wheel_speeds[1],
+                ],
+            },
+            longitudinal_velocity: ground_speed,
+            on_ground: self.is_set("sim/flightmodel/failures/onground_any"),
+            pneumatic: SimulatorPneumaticReadState::default(),
+            vertical_velocity: Velocity::new::<foot_per_minute>(
+                self.value("sim/flightmodel/position/vh_ind_fpm"),
+            ),
+            engine_n2: [
+                Ratio::new::<percent>(self.value("sim/flightmodel/engine/ENGN_N2_[0]")),
+                Ratio::new::<percent>(self.value("sim/flightmodel/engine/ENGN_N2_[1]")),
+            ],
+            // The fuel cutoff levers of airliners in X-Plane are their mixture levers.
+            engine_master_on: [
+                self.is_set("sim/cockpit2/engine/actuators/mixture_ratio[0]"),
+                self.is_set("sim/cockpit2/engine/actuators/mixture_ratio[1]"),
+            ],
+            ..SimulatorReadState::default()
+        }
+    }
+
+    fn write(&self, state: &SimulatorWriteState) {
+        for (path, value) in hydraulic_datarefs(state) {
+            if let Err(error) = self.socket.send_to(&dref_request(path, value), self.xplane) {
+                log::warn!("Couldn't write {} to X-Plane: {}", path, error);
+            }
+        }
+    }
+}
+impl Drop for XPlaneReadWriter {
+    fn drop(&mut self) {
+        // A frequency of zero stops X-Plane sending the dataref.
+        let _ = self.subscribe(0);
+    }
+}
+
+fn hydraulic_datarefs(state: &SimulatorWriteState) -> Vec<(&'static str, f64)> {
+    let hydraulic = &state.hydraulic;
+    let mut datarefs = vec![
+        (
+            "a32nx/hydraulic/green_pressure",
+            hydraulic.green_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/blue_pressure",
+            hydraulic.blue_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/yellow_pressure",
+            hydraulic.yellow_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/green_reservoir_volume",
+            hydraulic.green_reservoir_volume.get::<gallon>(),
+        ),
+        (
+            "a32nx/hydraulic/blue_reservoir_volume",
+            hydraulic.blue_reservoir_volume.get::<gallon>(),
+        ),
+        (
+            "a32nx/hydraulic/yellow_reservoir_volume",
+            hydraulic.yellow_reservoir_volume.get::<gallon>(),
+        ),
+        (
+            "a32nx/hydraulic/green_system_low_pressure_caution",
+            from_bool(hydraulic.system_low_pressure_caution[0]),
+        ),
+        (
+            "a32nx/hydraulic/blue_system_low_pressure_caution",
+            from_bool(hydraulic.system_low_pressure_caution[1]),
+        ),
+        (
+            "a32nx/hydraulic/yellow_system_low_pressure_caution",
+            from_bool(hydraulic.system_low_pressure_caution[2]),
+        ),
+        (
+            "a32nx/hydraulic/ptu_direction",
+            match hydraulic.ptu_state {
+                PtuState::GreenToYellow => 1.,
+                PtuState::YellowToGreen => -1.,
+                _ => 0.,
+            },
+        ),
+        (
+            "a32nx/hydraulic/ptu_flow",
+            hydraulic.ptu_flow.get::<gallon_per_minute>(),
+        ),
+        (
+            "a32nx/hydraulic/rat_deployment_progress",
+            hydraulic.rat_deployment_progress.get::<percent>(),
+        ),
+        (
+            "a32nx/hydraulic/brake_accumulator_pressure",
+            hydraulic.brake_accumulator_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/left_normal_brake_pressure",
+            hydraulic.left_normal_brake_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/right_normal_brake_pressure",
+            hydraulic.right_normal_brake_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/left_alternate_brake_pressure",
+            hydraulic.left_alternate_brake_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/right_alternate_brake_pressure",
+            hydraulic.right_alternate_brake_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/nose_wheel_steering_available",
+            from_bool(hydraulic.nose_wheel_steering_available),
+        ),
+        (
+            "a32nx/hydraulic/nose_gear_downlocked",
+            from_bool(hydraulic.gear_downlocked[0]),
+        ),
+        (
+            "a32nx/hydraulic/nose_gear_uplocked",
+            from_bool(hydraulic.gear_uplocked[0]),
+        ),
+        (
+            "a32nx/hydraulic/left_gear_downlocked",
+            from_bool(hydraulic.gear_downlocked[1]),
+        ),
+        (
+            "a32nx/hydraulic/left_gear_uplocked",
+            from_bool(hydraulic.gear_uplocked[1]),
+        ),
+        (
+            "a32nx/hydraulic/right_gear_downlocked",
+            from_bool(hydraulic.gear_downlocked[2]),
+        ),
+        (
+            "a32nx/hydraulic/right_gear_uplocked",
+            from_bool(hydraulic.gear_uplocked[2]),
+        ),
+        (
+            "a32nx/lgciu/nose_gear_disagree",
+            from_bool(hydraulic.gear_position_disagree[0]),
+        ),
+        (
+            "a32nx/lgciu/left_gear_disagree",
+            from_bool(hydraulic.gear_position_disagree[1]),
+        ),
+        (
+            "a32nx/lgciu/right_gear_disagree",
+            from_bool(hydraulic.gear_position_disagree[2]),
+        ),
+        (
+            "a32nx/hydraulic/left_aileron_position",
+            hydraulic.aileron_positions[0].get::<degree>(),
+        ),
+        (
+            "a32nx/hydraulic/right_aileron_position",
+            hydraulic.aileron_positions[1].get::<degree>(),
+        ),
+        (
+            "a32nx/hydraulic/left_elevator_position",
+            hydraulic.elevator_positions[0].get::<degree>(),
+        ),
+        (
+            "a32nx/hydraulic/right_elevator_position",
+            hydraulic.elevator_positions[1].get::<degree>(),
+        ),
+        (
+            "a32nx/hydraulic/spoiler_availability",
+            hydraulic.spoiler_availability as f64,
+        ),
+        (
+            "a32nx/hydraulic/triple_indicator_accumulator_pressure",
+            hydraulic.triple_indicator_accumulator_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/triple_indicator_left_brake_pressure",
+            hydraulic.triple_indicator_left_brake_pressure.get::<psi>(),
+        ),
+        (
+            "a32nx/hydraulic/triple_indicator_right_brake_pressure",
+            hydraulic.triple_indicator_right_brake_pressure.get::<psi>(),
+        ),
+    ];
+
+    const LEFT_SPOILERS: [&str; 5] = [
+        "a32nx/hydraulic/left_spoiler_1_position",
+        "a32nx/hydraulic/left_spoiler_2_position",
+        "a32nx/hydraulic/left_spoiler_3_position",
+        "a32nx/hydraulic/left_spoiler_4_position",
+        "a32nx/hydraulic/left_spoiler_5_position",
+    ];
+    const RIGHT_SPOILERS: [&str; 5] = [
+        "a32nx/hydraulic/right_spoiler_1_position",
+        "a32nx/hydraulic/right_spoiler_2_position",
+        "a32nx/hydraulic/right_spoiler_3_position",
+        "a32nx/hydraulic/right_spoiler_4_position",
+        "a32nx/hydraulic/right_spoiler_5_position",
+    ];
+    for (path, position) in LEFT_SPOILERS
+        .iter()
+        .zip(hydraulic.left_spoiler_positions.iter())
+    {
+        datarefs.push((path, position.get::<degree>()));
+    }
+    for (path, position) in RIGHT_SPOILERS
+        .iter()
+        .zip(hydraulic.right_spoiler_positions.iter())
+    {
+        datarefs.push((path, position.get::<degree>()));
+    }
+
+    datarefs
+}
+
+/// Asks X-Plane to send the dataref at the given frequency, tagged with the given index.
+fn rref_request(frequency: i32, index: i32, path: &str) -> Vec<u8> {
+    let mut request = b"RREF\0".to_vec();
+    request.extend_from_slice(&frequency.to_le_bytes());
+    request.extend_from_slice(&index.to_le_bytes());
+    request.extend(padded_path(path, RREF_PATH_LENGTH));
+
+    request
+}
+
+/// Sets the dataref to the given value.
+fn dref_request(path: &str, value: f64) -> Vec<u8> {
+    let mut request = b"DREF\0".to_vec();
+    request.extend_from_slice(&(value as f32).to_le_bytes());
+    request.extend(padded_path(path, DREF_PATH_LENGTH));
+
+    request
+}
+
+fn padded_path(path: &str, length: usize) -> Vec<u8> {
+    let mut padded: Vec<u8> = path.bytes().take(length - 1).collect();
+    padded.resize(length, 0);
+
+    padded
+}
+
+/// The index and value pairs in an RREF packet sent by X-Plane. Anything else
+/// decodes to no values.
+fn decode_rref(packet: &[u8]) -> Vec<(i32, f32)> {
+    if packet.len() < 5 || &packet[..4] != b"RREF" {
+        return Vec::new();
+    }
+
+    packet[5..]
+        .chunks_exact(8)
+        .map(|pair| {
+            (
+                i32::from_le_bytes(pair[..4].try_into().unwrap()),
+                f32::from_le_bytes(pair[4..].try_into().unwrap()),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod xplane_tests {
+    use super::*;
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn rref_request_has_the_layout_x_plane_expects() {
+        let request = rref_request(20, 3, "sim/flightmodel/engine/ENGN_N2_[0]");
+
+        assert_eq!(request.len(), 5 + 4 + 4 + RREF_PATH_LENGTH);
+        assert_eq!(&request[..5], b"RREF\0");
+        assert_eq!(request[5..9], 20i32.to_le_bytes());
+        assert_eq!(request[9..13], 3i32.to_le_bytes());
+        assert!(request[13..].starts_with(b"sim/flightmodel/engine/ENGN_N2_[0]\0"));
+    }
+
+    #[test]
+    fn dref_request_has_the_layout_x_plane_expects() {
+        let request = dref_request("a32nx/hydraulic/green_pressure", 3000.);
+
+        assert_eq!(request.len(), 5 + 4 + DREF_PATH_LENGTH);
+        assert_eq!(&request[..5], b"DREF\0");
+        assert_eq!(request[5..9], 3000f32.to_le_bytes());
+        assert!(request[9..].starts_with(b"a32nx/hydraulic/green_pressure\0"));
+    }
+
+    #[test]
+    fn decodes_all_values_of_an_rref_packet() {
+        assert_eq!(
+            decode_rref(&rref_packet(&[(0, 29.92), (8, 80.)])),
+            vec![(0, 29.92), (8, 80.)]
+        );
+    }
+
+    #[test]
+    fn ignores_other_packets() {
+        assert!(decode_rref(b"DATA\0").is_empty());
+        assert!(decode_rref(b"RRE").is_empty());
+    }
+
+    #[test]
+    fn reads_the_values_x_plane_sends() {
+        let xplane = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let read_writer = XPlaneReadWriter::connect(xplane.local_addr().unwrap(), 20).unwrap();
+
+        let (requests, from) = receive_all(&xplane);
+        assert_eq!(requests.len(), READ_DATAREFS.len());
+        assert!(requests
+            .iter()
+            .all(|request| request.starts_with(b"RREF\0")));
+
+        let n2_index = index_of("sim/flightmodel/engine/ENGN_N2_[0]");
+        let ptu_index = index_of("a32nx/hydraulic/ptu_pb_auto");
+        xplane
+            .send_to(&rref_packet(&[(n2_index, 80.), (ptu_index, 0.)]), from)
+            .unwrap();
+
+        let mut state = read_writer.read();
+        for _ in 0..100 {
+            if state.engine_n2[0] > Ratio::new::<percent>(0.) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+            state = read_writer.read();
+        }
+
+        assert_eq!(state.engine_n2[0], Ratio::new::<percent>(80.));
+        assert!(!state.hydraulic.ptu_pb_auto);
+        // Datarefs X-Plane didn't send keep their defaults.
+        assert!(state.hydraulic.engine_pump_pb_auto[0]);
+        assert!(state.on_ground);
+    }
+
+    #[test]
+    fn writes_the_hydraulic_state_as_datarefs() {
+        let xplane = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let read_writer = XPlaneReadWriter::connect(xplane.local_addr().unwrap(), 20).unwrap();
+        receive_all(&xplane);
+
+        let mut state = SimulatorWriteState::default();
+        state.hydraulic.green_pressure = Pressure::new::<psi>(3000.);
+        read_writer.write(&state);
+
+        let (requests, _) = receive_all(&xplane);
+        assert_eq!(requests.len(), hydraulic_datarefs(&state).len());
+        assert!(requests.contains(&dref_request("a32nx/hydraulic/green_pressure", 3000.)));
+    }
+
+    fn index_of(path: &str) -> i32 {
+        READ_DATAREFS
+            .iter()
+            .position(|(read, _)| *read == path)
+            .unwrap() as i32
+    }
+
+    fn rref_packet(values: &[(i32, f32)]) -> Vec<u8> {
+        let mut packet = b"RREF,".to_vec();
+        for (index, value) in values {
+            packet.extend_from_slice(&index.to_le_bytes());
+            packet.extend_from_slice(&value.to_le_bytes());
+        }
+
+        packet
+    }
+
+    fn receive_all(socket: &UdpSocket) -> (Vec<Vec<u8>>, SocketAddr) {
+        socket
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        let mut packets = Vec::new();
+        let mut from = None;
+        let mut buffer = [0; MAX_PACKET_LENGTH];
+        while let Ok((length, sender)) = socket.recv_from(&mut buffer) {
+            packets.push(buffer[..length].to_vec());
+            from = Some(sender);
+        }
+
+        (packets, from.unwrap())
+    }
+}
@@ -0,0 +1,366 @@
+use airbus_systems::{
+    engine::Engine,
+    hydraulic::{
+        test_helpers::{engine, engine_driven_pump, hydraulic_loop},
+        ActuatorType, ControlSurface, EngineDrivenPump, FlowDemand, HydLoop, HydraulicConsumer,
+        LoopId,
+    },
+    simulator::test_helpers::context_with,
+};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+use uom::si::{
+    angle::degree, f64::*, pressure::psi, ratio::percent,
+    thermodynamic_temperature::degree_celsius, time::second, velocity::knot, volume::gallon,
+    volume_rate::gallon_per_minute,
+};
+
+// Acceptance bounds of the actuators sharing the green loop. The main gear
+// actuators keep the volume of their rods when retracted; the flaps power
+// control unit is a hydraulic motor returning all the fluid it is served.
+const MAIN_GEAR_STROKE_VOLUME_GALLON: f64 = 2.;
+const MAIN_GEAR_HELD_VOLUME_GALLON: f64 = 0.3;
+const MAIN_GEAR_FLOW_GPM: f64 = 30.;
+const FLAPS_STROKE_VOLUME_GALLON: f64 = 2.4;
+const FLAPS_FLOW_GPM: f64 = 14.6;
+const SPOILER_FLOW_GPM: f64 = 3.;
+
+// The engine driven pump delivers more than the gear alone needs: the loop stays
+// at nominal pressure and the gear travels in its nominal time.
+const NOMINAL_PRESSURE_PSI: f64 = 2900.;
+const GEAR_MAX_TRAVEL_TIME_SECS: f64 = 4.5;
+// Gear and flaps together need more than the pump delivers, but the loop stays
+// above where the priority valve starts throttling secondary consumers.
+const GEAR_AND_FLAPS_MIN_PRESSURE_PSI: f64 = 2200.;
+// Once the actuators stop, the loop is back to nominal pressure within this time.
+const MAX_RECOVERY_TIME_SECS: f64 = 1.;
+// With gear, flaps and spoilers all moving, primary consumers are still served.
+const ALL_MOVING_MIN_PRESSURE_PSI: f64 = 1840.;
+const SPOILER_MAX_TRAVEL_TIME_SECS: f64 = 2.;
+// Fluid left out of the reservoir after a full gear cycle.
+const MAX_GEAR_CYCLE_FLUID_LOSS_GALLON: f64 = 0.02;
+
+const TIME_STEP: Duration = Duration::from_millis(100);
+
+/// An actuator moving between its two ends, at a rate set by the flow it is
+/// served. Moving towards its far end, it keeps its held volume out of the loop
+/// and gives it back when moving back.
+struct LinearActuator {
+    consumer: ActuatorType,
+    stroke_volume: Volume,
+    held_volume: Volume,
+    full_rate_flow: VolumeRate,
+    position: f64,
+    commanded: f64,
+    served_flow: VolumeRate,
+    moved_volume: Volume,
+}
+impl LinearActuator {
+    fn new(
+        consumer: ActuatorType,
+        stroke_volume: f64,
+        held_volume: f64,
+        full_rate_flow: f64,
+    ) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(LinearActuator {
+            consumer,
+            stroke_volume: Volume::new::<gallon>(stroke_volume),
+            held_volume: Volume::new::<gallon>(held_volume),
+            full_rate_flow: VolumeRate::new::<gallon_per_minute>(full_rate_flow),
+            position: 0.,
+            commanded: 0.,
+            served_flow: VolumeRate::new::<gallon_per_minute>(0.),
+            moved_volume: Volume::new::<gallon>(0.),
+        }))
+    }
+
+    fn direction(&self) -> f64 {
+        (self.commanded - self.position).signum() * self.is_moving() as u8 as f64
+    }
+
+    fn is_moving(&self) -> bool {
+        (self.commanded - self.position).abs() > 1e-9
+    }
+
+    fn update(&mut self) {
+        let volume = self.served_flow * Time::new::<second>(TIME_STEP.as_secs_f64());
+        self.moved_volume += volume;
+        self.position =
+            (self.position + self.direction() * (volume / self.stroke_volume).value).clamp(0., 1.);
+    }
+}
+
+struct SharedActuator(Rc<RefCell<LinearActuator>>);
+impl HydraulicConsumer for SharedActuator {
+    fn demanded_flow(&self) -> FlowDemand {
+        let actuator = self.0.borrow();
+        // Only what is left of the stroke, so the actuator stops at its end.
+        let remaining = actuator.stroke_volume * (actuator.commanded - actuator.position).abs()
+            / Time::new::<second>(TIME_STEP.as_secs_f64());
+
+        FlowDemand::new(actuator.consumer, actuator.full_rate_flow.min(remaining))
+    }
+
+    fn returned_flow(&self) -> VolumeRate {
+        let actuator = self.0.borrow();
+        let held_ratio = (actuator.held_volume / actuator.stroke_volume).value;
+
+        actuator.served_flow * (1. - actuator.direction() * held_ratio)
+    }
+
+    fn set_supply_pressure(&mut self, _: Pressure, served_flow: VolumeRate) {
+        self.0.borrow_mut().served_flow = served_flow;
+    }
+}
+
+/// The green loop powered by engine 1, with its main gear, flaps and the
+/// spoilers 1 and 5 of both wings.
+struct GreenLoopRig {
+    green_loop: HydLoop,
+    engine_driven_pump: EngineDrivenPump,
+    engine: Engine,
+    main_gear: Rc<RefCell<LinearActuator>>,
+    flaps: Rc<RefCell<LinearActuator>>,
+    spoilers: [ControlSurface; 4],
+    spoiler_command: Angle,
+    time: Duration,
+}
+impl GreenLoopRig {
+    fn new() -> Self {
+        let main_gear = LinearActuator::new(
+            ActuatorType::LandingGearMain,
+            MAIN_GEAR_STROKE_VOLUME_GALLON,
+            MAIN_GEAR_HELD_VOLUME_GALLON,
+            MAIN_GEAR_FLOW_GPM,
+        );
+        let flaps = LinearActuator::new(
+            ActuatorType::Flaps,
+            FLAPS_STROKE_VOLUME_GALLON,
+            0.,
+            FLAPS_FLOW_GPM,
+        );
+        let mut green_loop = hydraulic_loop(LoopId::GREEN);
+        green_loop.register_consumer(Box::new(SharedActuator(main_gear.clone())));
+        green_loop.register_consumer(Box::new(SharedActuator(flaps.clone())));
+
+        let mut rig = GreenLoopRig {
+            green_loop,
+            engine_driven_pump: engine_driven_pump(),
+            engine: engine(Ratio::new::<percent>(80.)),
+            main_gear,
+            flaps,
+            spoilers: [
+                ControlSurface::spoiler(),
+                ControlSurface::spoiler(),
+                ControlSurface::spoiler(),
+                ControlSurface::spoiler(),
+            ],
+            spoiler_command: Angle::new::<degree>(0.),
+            time: Duration::from_secs(0),
+        };
+        rig.run_until(Duration::from_secs(20), |_| false);
+        rig.time = Duration::from_secs(0);
+
+        rig
+    }
+
+    fn command_gear(&mut self, position: f64) {
+        self.main_gear.borrow_mut().commanded = position;
+    }
+
+    fn command_flaps(&mut self, position: f64) {
+        self.flaps.borrow_mut().commanded = position;
+    }
+
+    /// Stops the gear and flaps where they are.
+    fn hold_actuators(&mut self) {
+        for actuator in [&self.main_gear, &self.flaps].iter() {
+            let mut actuator = actuator.borrow_mut();
+            actuator.commanded = actuator.position;
+        }
+    }
+
+    fn command_spoilers(&mut self, angle: f64) {
+        self.spoiler_command = Angle::new::<degree>(angle);
+    }
+
+    fn spoilers_moving(&self) -> bool {
+        self.spoilers.iter().any(|spoiler| {
+            (spoiler.position() - self.spoiler_command).abs() > Angle::new::<degree>(0.1)
+        })
+    }
+
+    fn pressure(&self) -> Pressure {
+        self.green_loop.get_pressure()
+    }
+
+    fn step(&mut self) {
+        let context = context_with()
+            .delta(TIME_STEP)
+            .airspeed_kt(160.)
+            .altitude_ft(3000.)
+            .ambient_temperature(ThermodynamicTemperature::new::<degree_celsius>(15.))
+            .ground_speed(Velocity::new::<knot>(160.))
+            .build();
+        let pressure = self.green_loop.get_pressure();
+        for spoiler in self.spoilers.iter_mut() {
+            if (spoiler.position() - self.spoiler_command).abs() > Angle::new::<degree>(0.1) {
+                self.green_loop.request_flow(&FlowDemand::new(
+                    ActuatorType::Spoiler,
+                    VolumeRate::new::<gallon_per_minute>(SPOILER_FLOW_GPM),
+                ));
+            }
+            spoiler.update(&TIME_STEP, &context, pressure, self.spoiler_command);
+        }
+
+        self.engine_driven_pump
+            .update(&TIME_STEP, &context, &self.green_loop, &self.engine);
+        self.green_loop.update(
+            &TIME_STEP,
+            &context,
+            None,
+            Some(&self.engine_driven_pump),
+            None,
+            None,
+        );
+        self.main_gear.borrow_mut().update();
+        self.flaps.borrow_mut().update();
+        self.time += TIME_STEP;
+    }
+
+    /// Runs until the condition is met or the time limit is reached, returning
+    /// the lowest pressure seen.
+    fn run_until<F>(&mut self, limit: Duration, condition: F) -> Pressure
+    where
+        F: Fn(&GreenLoopRig) -> bool,
+    {
+        let mut min_pressure = self.pressure();
+        let start = self.time;
+        while self.time - start < limit && !condition(self) {
+            self.step();
+            min_pressure = min_pressure.min(self.pressure());
+        }
+
+        min_pressure
+    }
+
+    fn gear_stopped(&self) -> bool {
+        !self.main_gear.borrow().is_moving()
+    }
+
+    fn all_stopped(&self) -> bool {
+        self.gear_stopped() && !self.flaps.borrow().is_moving() && !self.spoilers_moving()
+    }
+}
+
+#[test]
+fn engine_driven_pump_keeps_up_with_gear_alone() {
+    let mut rig = GreenLoopRig::new();
+    assert!(rig.pressure() > Pressure::new::<psi>(NOMINAL_PRESSURE_PSI));
+
+    rig.command_gear(1.);
+    let min_pressure = rig.run_until(Duration::from_secs(30), GreenLoopRig::gear_stopped);
+
+    assert!(rig.gear_stopped());
+    assert!(rig.time.as_secs_f64() <= GEAR_MAX_TRAVEL_TIME_SECS);
+    assert!(min_pressure > Pressure::new::<psi>(NOMINAL_PRESSURE_PSI));
+}
+
+#[test]
+fn gear_and_flaps_dip_green_pressure_within_bounds() {
+    let mut rig = GreenLoopRig::new();
+    rig.command_gear(1.);
+    rig.command_flaps(1.);
+
+    let min_pressure = rig.run_until(Duration::from_secs(30), GreenLoopRig::gear_stopped);
+
+    assert!(rig.gear_stopped());
+    assert!(min_pressure < Pressure::new::<psi>(NOMINAL_PRESSURE_PSI));
+    assert!(min_pressure > Pressure::new::<psi>(GEAR_AND_FLAPS_MIN_PRESSURE_PSI));
+}
+
+#[test]
+fn pressure_recovers_once_actuators_stop() {
+    let mut rig = GreenLoopRig::new();
+    rig.command_gear(1.);
+    rig.command_flaps(1.);
+    rig.run_until(Duration::from_secs(2), |_| false);
+    let dipped = rig.pressure();
+    rig.hold_actuators();
+
+    let stop_time = rig.time;
+    rig.run_until(Duration::from_secs(10), |rig| {
+        rig.pressure() > Pressure::new::<psi>(NOMINAL_PRESSURE_PSI)
+    });
+
+    assert!(dipped < Pressure::new::<psi>(NOMINAL_PRESSURE_PSI));
+    assert!((rig.time - stop_time).as_secs_f64() <= MAX_RECOVERY_TIME_SECS);
+}
+
+#[test]
+fn spoilers_keep_moving_while_gear_and_flaps_load_the_loop() {
+    let mut rig = GreenLoopRig::new();
+    rig.command_gear(1.);
+    rig.command_flaps(1.);
+    rig.command_spoilers(40.);
+
+    let min_pressure = rig.run_until(Duration::from_secs(10), |rig| !rig.spoilers_moving());
+
+    assert!(!rig.spoilers_moving());
+    assert!(rig.time.as_secs_f64() <= SPOILER_MAX_TRAVEL_TIME_SECS);
+    assert!(min_pressure > Pressure::new::<psi>(ALL_MOVING_MIN_PRESSURE_PSI));
+
+    // Gear and flaps go on to the end of their travel with the spoilers held.
+    rig.run_until(Duration::from_secs(60), GreenLoopRig::all_stopped);
+    assert!(rig.all_stopped());
+}
+
+#[test]
+fn flaps_are_slower_when_moving_with_the_gear() {
+    let mut flaps_alone = GreenLoopRig::new();
+    flaps_alone.command_flaps(1.);
+    flaps_alone.run_until(Duration::from_secs(60), |rig| {
+        !rig.flaps.borrow().is_moving()
+    });
+
+    let mut flaps_with_gear = GreenLoopRig::new();
+    flaps_with_gear.command_gear(1.);
+    flaps_with_gear.command_flaps(1.);
+    flaps_with_gear.run_until(Duration::from_secs(60), |rig| {
+        !rig.flaps.borrow().is_moving()
+    });
+
+    assert!(!flaps_with_gear.flaps.borrow().is_moving());
+    assert!(flaps_with_gear.time >= flaps_alone.time);
+}
+
+#[test]
+fn gear_cycle_moves_its_stroke_volume_and_gives_back_held_fluid() {
+    let mut rig = GreenLoopRig::new();
+    let initial_reservoir = rig.green_loop.get_reservoir_volume();
+    let initial_total = rig.green_loop.get_total_fluid_volume();
+
+    rig.command_gear(1.);
+    rig.run_until(Duration::from_secs(30), GreenLoopRig::gear_stopped);
+    rig.run_until(Duration::from_secs(5), |_| false);
+
+    let held = initial_reservoir - rig.green_loop.get_reservoir_volume();
+    assert!((held.get::<gallon>() - MAIN_GEAR_HELD_VOLUME_GALLON).abs() < 0.05);
+
+    rig.command_gear(0.);
+    rig.run_until(Duration::from_secs(30), GreenLoopRig::gear_stopped);
+    rig.run_until(Duration::from_secs(5), |_| false);
+
+    let moved = rig.main_gear.borrow().moved_volume.get::<gallon>();
+    assert!((moved - 2. * MAIN_GEAR_STROKE_VOLUME_GALLON).abs() < 0.01);
+    assert!(
+        (initial_reservoir - rig.green_loop.get_reservoir_volume()).get::<gallon>()
+            < MAX_GEAR_CYCLE_FLUID_LOSS_GALLON
+    );
+    // Held fluid is accounted for as it moves out of and back into the loop.
+    assert!(
+        (rig.green_loop.get_total_fluid_volume() - initial_total)
+            .get::<gallon>()
+            .abs()
+            < MAX_GEAR_CYCLE_FLUID_LOSS_GALLON
+    );
+}
@@ -0,0 +1,140 @@
+use airbus_systems::{
+    hydraulic::{test_helpers::numbered_engine, ActuatorType, LoopId, PtuState},
+    simulator::test_helpers::context_with,
+    A320Hydraulic,
+};
+use uom::si::{f64::*, ratio::percent, velocity::knot};
+
+// The loops supplying each consumer of the A320. A consumer stays available as
+// long as one of its loops is.
+const SUPPLYING_LOOPS: [(ActuatorType, &[LoopId]); 12] = [
+    (ActuatorType::Aileron, &[LoopId::BLUE, LoopId::GREEN]),
+    (
+        ActuatorType::Elevator,
+        &[LoopId::BLUE, LoopId::GREEN, LoopId::YELLOW],
+    ),
+    (
+        ActuatorType::Rudder,
+        &[LoopId::BLUE, LoopId::GREEN, LoopId::YELLOW],
+    ),
+    (ActuatorType::Stabilizer, &[LoopId::GREEN, LoopId::YELLOW]),
+    (ActuatorType::Flaps, &[LoopId::GREEN, LoopId::YELLOW]),
+    (ActuatorType::Slat, &[LoopId::BLUE, LoopId::GREEN]),
+    (ActuatorType::LandingGearMain, &[LoopId::GREEN]),
+    (ActuatorType::NoseWheelSteering, &[LoopId::GREEN]),
+    (ActuatorType::BrakesNormal, &[LoopId::GREEN]),
+    (ActuatorType::BrakesAlternate, &[LoopId::YELLOW]),
+    (ActuatorType::CargoDoor, &[LoopId::YELLOW]),
+    (ActuatorType::EmergencyGenerator, &[LoopId::BLUE]),
+];
+
+// Spoilers 1 to 5 of each wing, from root to tip.
+const SPOILER_LOOPS: [LoopId; 5] = [
+    LoopId::GREEN,
+    LoopId::YELLOW,
+    LoopId::BLUE,
+    LoopId::YELLOW,
+    LoopId::GREEN,
+];
+
+/// An A320 in cruise with both engines running, which loses the given loop,
+/// e.g. after all its fluid leaked away. The PTU can't recover a loop lost this way.
+fn cruise_after_losing(lost_loop: Option<LoopId>) -> (A320Hydraulic, Vec<PtuState>) {
+    let mut hydraulic = A320Hydraulic::new();
+    let engines = [
+        numbered_engine(1, Ratio::new::<percent>(80.)),
+        numbered_engine(2, Ratio::new::<percent>(80.)),
+    ];
+    let context = context_with()
+        .delta_ms(50)
+        .altitude_ft(10000.)
+        .ground_speed(Velocity::new::<knot>(250.))
+        .build();
+    for _ in 0..20 * 20 {
+        hydraulic.update(&context, &engines[0], &engines[1]);
+    }
+
+    if let Some(color) = lost_loop {
+        hydraulic.isolate_loop(color);
+    }
+    let mut ptu_states = Vec::new();
+    for _ in 0..60 * 20 {
+        hydraulic.update(&context, &engines[0], &engines[1]);
+        ptu_states.push(hydraulic.ptu_state());
+    }
+
+    (hydraulic, ptu_states)
+}
+
+fn is_available(hydraulic: &A320Hydraulic, consumer: ActuatorType, loops: &[LoopId]) -> bool {
+    loops
+        .iter()
+        .any(|color| hydraulic.is_consumer_supplied(*color, consumer))
+}
+
+fn assert_matrix_after_losing(lost_loop: Option<LoopId>) {
+    let (hydraulic, _) = cruise_after_losing(lost_loop);
+
+    for (consumer, loops) in SUPPLYING_LOOPS.iter() {
+        let expected = loops.iter().any(|color| Some(*color) != lost_loop);
+        assert_eq!(
+            is_available(&hydraulic, *consumer, loops),
+            expected,
+            "{:?} availability after losing the {:?} loop",
+            consumer,
+            lost_loop
+        );
+    }
+
+    let expected_spoilers = SPOILER_LOOPS
+        .iter()
+        .enumerate()
+        .filter(|(_, color)| Some(**color) != lost_loop)
+        .fold(0, |availability, (number, _)| {
+            availability | (1 << number) | (1 << (number + 5))
+        });
+    assert_eq!(hydraulic.spoiler_availability(), expected_spoilers);
+}
+
+#[test]
+fn all_consumers_are_available_without_failure() {
+    assert_matrix_after_losing(None);
+}
+
+#[test]
+fn green_loss_leaves_blue_and_yellow_consumers() {
+    assert_matrix_after_losing(Some(LoopId::GREEN));
+}
+
+#[test]
+fn blue_loss_leaves_green_and_yellow_consumers() {
+    assert_matrix_after_losing(Some(LoopId::BLUE));
+}
+
+#[test]
+fn yellow_loss_leaves_blue_and_green_consumers() {
+    assert_matrix_after_losing(Some(LoopId::YELLOW));
+}
+
+#[test]
+fn green_loss_isnt_recovered_through_the_ptu() {
+    let (hydraulic, ptu_states) = cruise_after_losing(Some(LoopId::GREEN));
+
+    assert!(ptu_states.iter().all(|state| *state == PtuState::Off));
+    assert!(!hydraulic.is_green_pressurised());
+    // Braking is left to the yellow alternate brakes, steering is lost.
+    assert!(!hydraulic.is_consumer_supplied(LoopId::GREEN, ActuatorType::BrakesNormal));
+    assert!(hydraulic.is_consumer_supplied(LoopId::YELLOW, ActuatorType::BrakesAlternate));
+    assert!(!hydraulic.is_consumer_supplied(LoopId::GREEN, ActuatorType::NoseWheelSteering));
+    assert!(hydraulic.ths_available());
+}
+
+#[test]
+fn yellow_loss_isnt_recovered_through_the_ptu() {
+    let (hydraulic, ptu_states) = cruise_after_losing(Some(LoopId::YELLOW));
+
+    assert!(ptu_states.iter().all(|state| *state == PtuState::Off));
+    assert!(!hydraulic.is_yellow_pressurised());
+    assert!(hydraulic.is_green_pressurised());
+    assert!(hydraulic.ths_available());
+}
@@ -0,0 +1,290 @@
+use airbus_systems::{
+    engine::Engine,
+    hydraulic::{ActuatorType, FlowDemand, HydraulicConsumer, LoopId, PtuState},
+    simulator::{
+        test_helpers::context_with, SimulatorElement, SimulatorElementVisitable,
+        SimulatorElementVisitor, SimulatorReadState,
+    },
+    A320Hydraulic, A320HydraulicOverheadPanel, A320HydraulicWarnings, HydraulicCaution,
+};
+use std::{
+    cell::{Ref, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+use uom::si::{
+    f64::*, power::watt, pressure::psi, thermodynamic_temperature::degree_celsius, time::second,
+    volume::liter, volume_rate::gallon_per_minute,
+};
+
+// Each cargo door opening keeps 0.2 liters of yellow fluid out of the reservoir,
+// given back when the door closes. A door travels in about 15 seconds.
+const DOOR_HELD_VOLUME_LITER: f64 = 0.2;
+const DOOR_STROKE_VOLUME_LITER: f64 = 0.5;
+const DOOR_FLOW_GPM: f64 = 0.5;
+const HELD_VOLUME_TOLERANCE_LITER: f64 = 0.03;
+
+// The FWC flight phase with electrical power only, engines stopped on the ground.
+const ELECTRICAL_POWER_FLIGHT_PHASE: u8 = 1;
+// The A320 hydraulic fixed step, at which registered consumers are updated.
+const HYDRAULIC_TIME_STEP: Duration = Duration::from_millis(100);
+// Highest green pressure still counted as unpressurised by the PTU.
+const GREEN_RESIDUAL_PRESSURE_PSI: f64 = 100.;
+
+/// A cargo door actuator, opening and closing at a rate set by the flow it is
+/// served. While open, it keeps its held volume out of the loop.
+struct CargoDoor {
+    position: f64,
+    commanded: f64,
+    served_flow: VolumeRate,
+}
+impl CargoDoor {
+    fn new() -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(CargoDoor {
+            position: 0.,
+            commanded: 0.,
+            served_flow: VolumeRate::new::<gallon_per_minute>(0.),
+        }))
+    }
+
+    fn stroke_volume() -> Volume {
+        Volume::new::<liter>(DOOR_STROKE_VOLUME_LITER)
+    }
+
+    fn direction(&self) -> f64 {
+        (self.commanded - self.position).signum() * self.is_moving() as u8 as f64
+    }
+
+    fn is_moving(&self) -> bool {
+        (self.commanded - self.position).abs() > 1e-9
+    }
+
+    fn is_open(&self) -> bool {
+        self.position >= 1.
+    }
+
+    fn is_closed(&self) -> bool {
+        self.position <= 0.
+    }
+}
+
+struct SharedCargoDoor(Rc<RefCell<CargoDoor>>);
+impl HydraulicConsumer for SharedCargoDoor {
+    fn demanded_flow(&self) -> FlowDemand {
+        let door = self.0.borrow();
+        // Only what is left of the stroke, so the door stops at its end.
+        let remaining = CargoDoor::stroke_volume() * (door.commanded - door.position).abs()
+            / Time::new::<second>(HYDRAULIC_TIME_STEP.as_secs_f64());
+
+        FlowDemand::new(
+            ActuatorType::CargoDoor,
+            VolumeRate::new::<gallon_per_minute>(DOOR_FLOW_GPM).min(remaining),
+        )
+    }
+
+    fn returned_flow(&self) -> VolumeRate {
+        let door = self.0.borrow();
+        let held_ratio = DOOR_HELD_VOLUME_LITER / DOOR_STROKE_VOLUME_LITER;
+
+        door.served_flow * (1. - door.direction() * held_ratio)
+    }
+
+    fn set_supply_pressure(&mut self, _: Pressure, served_flow: VolumeRate) {
+        let mut door = self.0.borrow_mut();
+        door.served_flow = served_flow;
+        let volume = served_flow * Time::new::<second>(HYDRAULIC_TIME_STEP.as_secs_f64());
+        door.position = (door.position
+            + door.direction() * (volume / CargoDoor::stroke_volume()).value)
+            .clamp(0., 1.);
+    }
+}
+
+struct ReadVisitor<'a>(&'a SimulatorReadState);
+impl SimulatorElementVisitor for ReadVisitor<'_> {
+    fn visit(&mut self, visited: &mut Box<&mut dyn SimulatorElement>) {
+        visited.read(self.0);
+    }
+}
+
+/// An A320 on the ground with its engines stopped, loading cargo through its
+/// forward and aft cargo doors, both powered by the yellow loop.
+struct CargoLoading {
+    hydraulic: A320Hydraulic,
+    warnings: A320HydraulicWarnings,
+    doors: [Rc<RefCell<CargoDoor>>; 2],
+    selectors: [bool; 2],
+    ptu_states: Vec<PtuState>,
+    max_green_pressure: Pressure,
+}
+impl CargoLoading {
+    fn new() -> Self {
+        let mut hydraulic = A320Hydraulic::new();
+        let doors = [CargoDoor::new(), CargoDoor::new()];
+        for door in doors.iter() {
+            hydraulic.register_consumer(LoopId::YELLOW, Box::new(SharedCargoDoor(door.clone())));
+        }
+        let mut warnings = A320HydraulicWarnings::new();
+        warnings.set_flight_phase(ELECTRICAL_POWER_FLIGHT_PHASE);
+
+        CargoLoading {
+            hydraulic,
+            warnings,
+            doors,
+            selectors: [false, false],
+            ptu_states: Vec::new(),
+            max_green_pressure: Pressure::new::<psi>(0.),
+        }
+    }
+
+    /// Holds the selector of the given door (1 forward, 2 aft) to open or close it.
+    fn operate(&mut self, door: usize, open: bool) {
+        self.selectors[door - 1] = true;
+        self.doors[door - 1].borrow_mut().commanded = if open { 1. } else { 0. };
+    }
+
+    fn release(&mut self, door: usize) {
+        self.selectors[door - 1] = false;
+    }
+
+    fn door(&self, door: usize) -> Ref<'_, CargoDoor> {
+        self.doors[door - 1].borrow()
+    }
+
+    fn select_yellow_electric_pump_on(&mut self) {
+        let mut overhead = A320HydraulicOverheadPanel::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.engine_pump_pb_auto = [true, true];
+        state.hydraulic.ptu_pb_auto = true;
+        state.hydraulic.blue_electric_pump_pb_auto = true;
+        state.hydraulic.yellow_electric_pump_pb_on = true;
+        overhead.read(&state);
+        self.hydraulic.update_after_hydraulic_overhead(&overhead);
+    }
+
+    fn run(&mut self, seconds: u64) {
+        let mut state = SimulatorReadState {
+            on_ground: true,
+            ..Default::default()
+        };
+        state.hydraulic.cargo_door_selector_operated = self.selectors;
+        self.hydraulic
+            .accept(&mut Box::new(&mut ReadVisitor(&state)));
+
+        let engines = [Engine::new(1), Engine::new(2)];
+        let context = context_with()
+            .delta_ms(50)
+            .on_ground(true)
+            .and()
+            .ambient_temperature(ThermodynamicTemperature::new::<degree_celsius>(15.))
+            .build();
+        for _ in 0..seconds * 20 {
+            self.hydraulic.update(&context, &engines[0], &engines[1]);
+            self.ptu_states.push(self.hydraulic.ptu_state());
+            self.max_green_pressure = self
+                .max_green_pressure
+                .max(self.hydraulic.loop_pressure(LoopId::GREEN));
+        }
+        self.warnings.update_after_hydraulic(&self.hydraulic);
+    }
+
+    fn yellow_reservoir(&self) -> Volume {
+        self.hydraulic.reservoir_volume(LoopId::YELLOW)
+    }
+
+    fn ptu_transferred(&self) -> bool {
+        self.ptu_states.iter().any(|state| *state != PtuState::Off)
+            || self.max_green_pressure > Pressure::new::<psi>(GREEN_RESIDUAL_PRESSURE_PSI)
+    }
+}
+
+#[test]
+fn yellow_electric_pump_runs_to_open_the_door_without_ptu_transfer() {
+    let mut loading = CargoLoading::new();
+    loading.operate(1, true);
+    loading.run(30);
+
+    assert!(loading.door(1).is_open());
+    assert!(loading.door(2).is_closed());
+    assert!(loading.hydraulic.is_yellow_pressurised());
+    assert!(!loading.hydraulic.is_green_pressurised());
+    assert!(!loading.ptu_transferred());
+}
+
+#[test]
+fn ptu_transfers_to_green_without_cargo_door_operation() {
+    let mut loading = CargoLoading::new();
+    loading.select_yellow_electric_pump_on();
+    loading.run(30);
+
+    assert!(loading.hydraulic.is_yellow_pressurised());
+    assert!(loading.ptu_transferred());
+}
+
+#[test]
+fn each_door_opening_holds_about_two_tenths_of_a_liter() {
+    let mut loading = CargoLoading::new();
+    // The closed door doesn't move: this only pressurises the yellow loop.
+    loading.operate(1, false);
+    loading.run(15);
+    let pressurised_reservoir = loading.yellow_reservoir();
+
+    loading.operate(1, true);
+    loading.run(30);
+    let first_door_held = pressurised_reservoir - loading.yellow_reservoir();
+
+    loading.release(1);
+    loading.operate(2, true);
+    loading.run(30);
+    let both_doors_held = pressurised_reservoir - loading.yellow_reservoir();
+
+    loading.operate(1, false);
+    loading.operate(2, false);
+    loading.run(30);
+    let closed_doors_held = pressurised_reservoir - loading.yellow_reservoir();
+
+    assert!(loading.door(1).is_closed() && loading.door(2).is_closed());
+    assert!(
+        (first_door_held.get::<liter>() - DOOR_HELD_VOLUME_LITER).abs()
+            < HELD_VOLUME_TOLERANCE_LITER
+    );
+    assert!(
+        (both_doors_held.get::<liter>() - 2. * DOOR_HELD_VOLUME_LITER).abs()
+            < HELD_VOLUME_TOLERANCE_LITER
+    );
+    assert!(closed_doors_held.get::<liter>().abs() < HELD_VOLUME_TOLERANCE_LITER);
+    assert!(!loading.hydraulic.has_reservoir_fault(LoopId::YELLOW));
+    assert!(!loading.ptu_transferred());
+}
+
+#[test]
+fn yellow_electric_pump_stops_once_the_door_operation_ended() {
+    let mut loading = CargoLoading::new();
+    loading.operate(1, true);
+    loading.run(30);
+    assert!(loading.hydraulic.yellow_electric_pump_power() > Power::new::<watt>(0.));
+
+    loading.release(1);
+    loading.run(25);
+
+    assert!(loading.door(1).is_open());
+    assert_eq!(
+        loading.hydraulic.yellow_electric_pump_power(),
+        Power::new::<watt>(0.)
+    );
+    assert!(!loading.ptu_transferred());
+}
+
+#[test]
+fn low_pressure_cautions_are_inhibited_during_cargo_loading() {
+    let mut loading = CargoLoading::new();
+    loading.operate(1, true);
+    loading.run(30);
+
+    assert!(!loading
+        .warnings
+        .is_detected(HydraulicCaution::YellowSystemLowPressure));
+    assert!(loading
+        .warnings
+        .is_detected(HydraulicCaution::GreenSystemLowPressure));
+    assert!(loading.warnings.shown_cautions().is_empty());
+}
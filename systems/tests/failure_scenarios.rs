@@ -0,0 +1,201 @@
+use airbus_systems::{
+    engine::Engine,
+    hydraulic::{test_helpers::numbered_engine, ActuatorType, LoopId, PtuState},
+    simulator::{test_helpers::context_with, SimulatorElement, SimulatorReadState},
+    A320Hydraulic, A320HydraulicOverheadPanel, A320HydraulicWarnings, HydraulicCaution,
+};
+use uom::si::{f64::*, pressure::psi, ratio::percent, velocity::knot, volume::gallon};
+
+// The FWC flight phase in cruise, where no hydraulic caution is inhibited.
+const CRUISE_FLIGHT_PHASE: u8 = 6;
+
+/// An A320 hydraulic system in cruise with both engines running, whose
+/// cautions are evaluated as the FWC does after each second of simulation.
+struct Scenario {
+    hydraulic: A320Hydraulic,
+    warnings: A320HydraulicWarnings,
+    engines: [Engine; 2],
+    leaking_loop: Option<LoopId>,
+    ptu_states: Vec<PtuState>,
+}
+impl Scenario {
+    fn new() -> Self {
+        let mut warnings = A320HydraulicWarnings::new();
+        warnings.set_flight_phase(CRUISE_FLIGHT_PHASE);
+
+        let mut scenario = Scenario {
+            hydraulic: A320Hydraulic::new(),
+            warnings,
+            engines: [
+                numbered_engine(1, Ratio::new::<percent>(80.)),
+                numbered_engine(2, Ratio::new::<percent>(80.)),
+            ],
+            leaking_loop: None,
+            ptu_states: Vec::new(),
+        };
+        scenario.run(20);
+
+        scenario
+    }
+
+    fn shut_down_engine(&mut self, number: usize) {
+        self.engines[number - 1] = numbered_engine(number, Ratio::new::<percent>(0.));
+    }
+
+    /// Fluid returning to the reservoir of the loop is lost overboard.
+    fn leak(&mut self, color: LoopId) {
+        self.leaking_loop = Some(color);
+    }
+
+    fn set_blue_electric_pump_push_button_off(&mut self) {
+        let mut overhead = A320HydraulicOverheadPanel::new();
+        let mut state = SimulatorReadState::default();
+        state.hydraulic.engine_pump_pb_auto = [true, true];
+        state.hydraulic.ptu_pb_auto = true;
+        state.hydraulic.blue_electric_pump_pb_auto = false;
+        overhead.read(&state);
+        self.hydraulic.update_after_hydraulic_overhead(&overhead);
+    }
+
+    /// Runs the given number of seconds and returns the pressure history
+    /// of the given loop, one sample per second.
+    fn run_recording(&mut self, seconds: u64, color: LoopId) -> Vec<f64> {
+        let context = context_with()
+            .delta_ms(50)
+            .altitude_ft(10000.)
+            .ground_speed(Velocity::new::<knot>(250.))
+            .build();
+        (0..seconds)
+            .map(|_| {
+                for _ in 0..20 {
+                    self.hydraulic
+                        .update(&context, &self.engines[0], &self.engines[1]);
+                    if let Some(color) = self.leaking_loop {
+                        self.hydraulic
+                            .drain_reservoir(color, Volume::new::<gallon>(1.));
+                    }
+                    self.ptu_states.push(self.hydraulic.ptu_state());
+                }
+                self.warnings.update_after_hydraulic(&self.hydraulic);
+
+                self.hydraulic.loop_pressure(color).get::<psi>()
+            })
+            .collect()
+    }
+
+    fn run(&mut self, seconds: u64) {
+        self.run_recording(seconds, LoopId::GREEN);
+    }
+
+    fn shows(&self, caution: HydraulicCaution) -> bool {
+        self.warnings.is_shown(caution)
+    }
+}
+
+#[test]
+fn all_systems_normal_in_cruise() {
+    let scenario = Scenario::new();
+
+    assert!(scenario.warnings.shown_cautions().is_empty());
+    assert!(scenario.hydraulic.is_green_pressurised());
+    assert!(scenario.hydraulic.is_blue_pressurised());
+    assert!(scenario.hydraulic.is_yellow_pressurised());
+    assert!(scenario.hydraulic.ths_available());
+}
+
+#[test]
+fn green_loop_is_lost_after_engine_1_shutdown_and_leak() {
+    let mut scenario = Scenario::new();
+    scenario.shut_down_engine(1);
+    scenario.leak(LoopId::GREEN);
+    let history = scenario.run_recording(60, LoopId::GREEN);
+
+    assert!(*history.last().unwrap() < 500.);
+    assert!(scenario.shows(HydraulicCaution::GreenSystemLowPressure));
+    assert!(scenario.shows(HydraulicCaution::Engine1PumpLowPressure));
+    assert!(!scenario.shows(HydraulicCaution::YellowSystemLowPressure));
+    assert!(!scenario.shows(HydraulicCaution::BlueSystemLowPressure));
+
+    assert!(!scenario
+        .hydraulic
+        .is_consumer_supplied(LoopId::GREEN, ActuatorType::BrakesNormal));
+    assert!(scenario
+        .hydraulic
+        .is_consumer_supplied(LoopId::YELLOW, ActuatorType::BrakesAlternate));
+    // The THS keeps its yellow hydraulic motor.
+    assert!(scenario.hydraulic.ths_available());
+}
+
+#[test]
+fn ptu_recovers_yellow_loop_after_engine_2_shutdown() {
+    let mut scenario = Scenario::new();
+    scenario.shut_down_engine(2);
+    let history = scenario.run_recording(30, LoopId::YELLOW);
+
+    assert!(scenario.ptu_states.contains(&PtuState::GreenToYellow));
+    assert!(!scenario.ptu_states.contains(&PtuState::YellowToGreen));
+    // The yellow loop pressure never drops to where its priority valve closes.
+    assert!(history.iter().all(|&pressure| pressure > 1840.));
+    assert!(scenario.hydraulic.is_yellow_pressurised());
+    assert!(scenario.shows(HydraulicCaution::Engine2PumpLowPressure));
+    assert!(!scenario.shows(HydraulicCaution::YellowSystemLowPressure));
+    assert!(scenario
+        .hydraulic
+        .is_consumer_supplied(LoopId::YELLOW, ActuatorType::CargoDoor));
+}
+
+#[test]
+fn rat_restores_blue_loop_after_dual_engine_failure() {
+    let mut scenario = Scenario::new();
+    scenario.shut_down_engine(1);
+    scenario.shut_down_engine(2);
+    let history_before_rat = scenario.run_recording(30, LoopId::BLUE);
+
+    assert!(*history_before_rat.last().unwrap() < 500.);
+    assert!(scenario.shows(HydraulicCaution::BlueSystemLowPressure));
+    assert!(scenario.shows(HydraulicCaution::GreenSystemLowPressure));
+    assert!(scenario.shows(HydraulicCaution::YellowSystemLowPressure));
+    assert!(!scenario.hydraulic.ths_available());
+
+    scenario.hydraulic.deploy_rat();
+    let history_with_rat = scenario.run_recording(20, LoopId::BLUE);
+
+    // Pressure comes back once the RAT has swung out.
+    assert!(history_with_rat[0] < 500.);
+    assert!(*history_with_rat.last().unwrap() > 2000.);
+    assert!(!scenario.shows(HydraulicCaution::BlueSystemLowPressure));
+    assert!(scenario.shows(HydraulicCaution::GreenSystemLowPressure));
+    assert!(scenario
+        .hydraulic
+        .is_consumer_supplied(LoopId::BLUE, ActuatorType::EmergencyGenerator));
+    assert!(scenario
+        .hydraulic
+        .emergency_generator_supply(
+            &context_with()
+                .delta_ms(50)
+                .altitude_ft(10000.)
+                .ground_speed(Velocity::new::<knot>(250.))
+                .build()
+        )
+        .can_drive_generator());
+}
+
+#[test]
+fn blue_loop_is_lost_when_its_electric_pump_stops() {
+    let mut scenario = Scenario::new();
+    scenario.set_blue_electric_pump_push_button_off();
+    let history = scenario.run_recording(60, LoopId::BLUE);
+
+    assert!(history[0] > *history.last().unwrap());
+    assert!(*history.last().unwrap() < 500.);
+    assert!(scenario.shows(HydraulicCaution::BlueElectricPumpLowPressure));
+    assert!(scenario.shows(HydraulicCaution::BlueSystemLowPressure));
+    assert!(!scenario.shows(HydraulicCaution::GreenSystemLowPressure));
+    assert!(!scenario.shows(HydraulicCaution::YellowSystemLowPressure));
+    assert!(!scenario
+        .hydraulic
+        .is_consumer_supplied(LoopId::BLUE, ActuatorType::Aileron));
+    assert!(scenario
+        .hydraulic
+        .is_consumer_supplied(LoopId::GREEN, ActuatorType::Aileron));
+}
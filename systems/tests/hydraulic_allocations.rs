@@ -0,0 +1,89 @@
+use airbus_systems::{
+    hydraulic::{
+        test_helpers::{electric_pump, hydraulic_loop, numbered_engine},
+        LoopId,
+    },
+    simulator::test_helpers::context_with,
+    A320Hydraulic,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    time::Duration,
+};
+use uom::si::{f64::*, ratio::percent, thermodynamic_temperature::degree_celsius};
+
+/// Counts the allocations made by the current thread, so tests running
+/// in parallel don't influence each other's count.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = Cell::new(0);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations_during<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATIONS.with(|count| count.get());
+    f();
+
+    ALLOCATIONS.with(|count| count.get()) - before
+}
+
+fn loop_update_allocations(id: LoopId) -> usize {
+    let mut hyd_loop = hydraulic_loop(id);
+    let mut pump = electric_pump();
+    pump.start();
+    let delta = Duration::from_millis(100);
+    let context = context_with()
+        .delta(delta)
+        .ambient_temperature(ThermodynamicTemperature::new::<degree_celsius>(25.))
+        .build();
+
+    allocations_during(|| {
+        for _ in 0..100 {
+            pump.update(&delta, &context, &hyd_loop);
+            hyd_loop.update(&delta, &context, Some(&pump), None, None, None);
+        }
+    })
+}
+
+#[test]
+fn hyd_loop_update_does_not_allocate() {
+    assert_eq!(loop_update_allocations(LoopId::BLUE), 0);
+}
+
+#[test]
+fn yellow_loop_update_does_not_allocate() {
+    assert_eq!(loop_update_allocations(LoopId::YELLOW), 0);
+}
+
+#[test]
+fn a320_hydraulic_update_does_not_allocate() {
+    let mut hydraulic = A320Hydraulic::new();
+    let engine_1 = numbered_engine(1, Ratio::new::<percent>(80.));
+    let engine_2 = numbered_engine(2, Ratio::new::<percent>(80.));
+    let context = context_with()
+        .ambient_temperature(ThermodynamicTemperature::new::<degree_celsius>(25.))
+        .build();
+
+    let allocations = allocations_during(|| {
+        for _ in 0..10 {
+            hydraulic.update(&context, &engine_1, &engine_2);
+        }
+    });
+
+    assert_eq!(allocations, 0);
+}
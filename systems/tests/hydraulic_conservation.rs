@@ -0,0 +1,137 @@
+use airbus_systems::{
+    hydraulic::{
+        test_helpers::{electric_pump, engine, engine_driven_pump, hydraulic_loop},
+        HydLoop, LoopId, Ptu,
+    },
+    simulator::test_helpers::context_with,
+};
+use proptest::prelude::*;
+use std::time::Duration;
+use uom::si::{f64::*, ratio::percent, thermodynamic_temperature::degree_celsius, volume::gallon};
+
+// Loop volumes are in the order of tens of gallons, any difference
+// above this is a bookkeeping error rather than rounding.
+const TOLERANCE_GALLON: f64 = 1e-6;
+
+/// What the crew and the aircraft do during one interval of the sequence.
+#[derive(Clone, Debug)]
+struct Activity {
+    engine_n2: f64,
+    electric_pump_running: bool,
+    ptu_enabled: bool,
+    green_isolated: bool,
+    yellow_isolated: bool,
+    delta_millis: u64,
+    steps: usize,
+}
+
+fn activity() -> impl Strategy<Value = Activity> {
+    (
+        0.0..100.0,
+        any::<bool>(),
+        any::<bool>(),
+        prop::bool::weighted(0.2),
+        prop::bool::weighted(0.2),
+        10u64..500,
+        1usize..20,
+    )
+        .prop_map(
+            |(
+                engine_n2,
+                electric_pump_running,
+                ptu_enabled,
+                green_isolated,
+                yellow_isolated,
+                delta_millis,
+                steps,
+            )| Activity {
+                engine_n2,
+                electric_pump_running,
+                ptu_enabled,
+                green_isolated,
+                yellow_isolated,
+                delta_millis,
+                steps,
+            },
+        )
+}
+
+fn set_isolated(hyd_loop: &mut HydLoop, isolated: bool) {
+    if isolated {
+        hyd_loop.isolate();
+    } else {
+        hyd_loop.reconnect();
+    }
+}
+
+fn assert_conserved(hyd_loop: &HydLoop, initial: Volume) -> Result<(), TestCaseError> {
+    let total = hyd_loop.get_total_fluid_volume();
+    prop_assert!(
+        (total - initial).get::<gallon>().abs() < TOLERANCE_GALLON,
+        "{:?} loop holds {} gal instead of {} gal",
+        hyd_loop.get_color(),
+        total.get::<gallon>(),
+        initial.get::<gallon>()
+    );
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn loops_linked_by_ptu_conserve_fluid(activities in prop::collection::vec(activity(), 1..30)) {
+        let mut green_loop = hydraulic_loop(LoopId::GREEN);
+        let mut yellow_loop = hydraulic_loop(LoopId::YELLOW);
+        let mut engine_driven_pump = engine_driven_pump();
+        let mut electric_pump = electric_pump();
+        let mut ptu = Ptu::new();
+        let mut engine = engine(Ratio::new::<percent>(0.));
+        let green_initial = green_loop.get_total_fluid_volume();
+        let yellow_initial = yellow_loop.get_total_fluid_volume();
+
+        for activity in activities {
+            engine.n2 = Ratio::new::<percent>(activity.engine_n2);
+            if activity.electric_pump_running {
+                electric_pump.start();
+            } else {
+                electric_pump.stop();
+            }
+            ptu.enabling(activity.ptu_enabled);
+            set_isolated(&mut green_loop, activity.green_isolated);
+            set_isolated(&mut yellow_loop, activity.yellow_isolated);
+
+            let delta = Duration::from_millis(activity.delta_millis);
+            let context = context_with()
+                .delta(delta)
+                .ambient_temperature(ThermodynamicTemperature::new::<degree_celsius>(25.))
+                .build();
+            for _ in 0..activity.steps {
+                engine_driven_pump.update(&delta, &context, &green_loop, &engine);
+                electric_pump.update(&delta, &context, &yellow_loop);
+                ptu.update(&delta, &green_loop, &yellow_loop);
+                green_loop.update(&delta, &context, None, Some(&engine_driven_pump), None, Some(&ptu));
+                yellow_loop.update(&delta, &context, Some(&electric_pump), None, None, Some(&ptu));
+
+                assert_conserved(&green_loop, green_initial)?;
+                assert_conserved(&yellow_loop, yellow_initial)?;
+            }
+        }
+    }
+
+    #[test]
+    fn ground_servicing_changes_fluid_by_serviced_amount(
+        filled in 0.0..10.0,
+        drained in 0.0..10.0,
+    ) {
+        let mut hyd_loop = hydraulic_loop(LoopId::YELLOW);
+        let initial = hyd_loop.get_total_fluid_volume();
+
+        hyd_loop.fill_reservoir(Volume::new::<gallon>(filled));
+        let actually_drained = hyd_loop.drain_reservoir(Volume::new::<gallon>(drained));
+
+        assert_conserved(
+            &hyd_loop,
+            initial + Volume::new::<gallon>(filled) - actually_drained,
+        )?;
+    }
+}
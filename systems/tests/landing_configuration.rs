@@ -0,0 +1,114 @@
+use airbus_systems::{
+    engine::Engine,
+    hydraulic::{
+        test_helpers::{engine, engine_driven_pump, hydraulic_loop},
+        ActuatorType, ConsumerPriority, EngineDrivenPump, FlowDemand, FlowSharing, HydLoop, LoopId,
+    },
+    simulator::test_helpers::context_with,
+};
+use std::time::Duration;
+use uom::si::{
+    f64::*, pressure::psi, ratio::percent, thermodynamic_temperature::degree_celsius,
+    volume_rate::gallon_per_minute,
+};
+
+// Nominal flows of the main gear retraction and the flaps on the green loop,
+// together a little above what its engine driven pump delivers.
+const LANDING_GEAR_FLOW_GPM: f64 = 30.;
+const FLAPS_FLOW_GPM: f64 = 14.6;
+
+struct LandingConfiguration {
+    green_loop: HydLoop,
+    engine_driven_pump: EngineDrivenPump,
+    engine: Engine,
+}
+impl LandingConfiguration {
+    fn new() -> Self {
+        let mut configuration = LandingConfiguration {
+            green_loop: hydraulic_loop(LoopId::GREEN),
+            engine_driven_pump: engine_driven_pump(),
+            engine: engine(Ratio::new::<percent>(80.)),
+        };
+        configuration.run(20, false);
+
+        configuration
+    }
+
+    /// Runs the loop, with gear and flaps moving together when `moving` is set.
+    fn run(&mut self, seconds: u64, moving: bool) -> Pressure {
+        let delta = Duration::from_millis(100);
+        let context = context_with()
+            .delta(delta)
+            .airspeed_kt(140.)
+            .altitude_ft(1500.)
+            .ambient_temperature(ThermodynamicTemperature::new::<degree_celsius>(15.))
+            .build();
+        let mut min_pressure = self.green_loop.get_pressure();
+        for _ in 0..seconds * 10 {
+            if moving {
+                self.green_loop.request_flow(&FlowDemand::new(
+                    ActuatorType::LandingGearMain,
+                    VolumeRate::new::<gallon_per_minute>(LANDING_GEAR_FLOW_GPM),
+                ));
+                self.green_loop.request_flow(&FlowDemand::new(
+                    ActuatorType::Flaps,
+                    VolumeRate::new::<gallon_per_minute>(FLAPS_FLOW_GPM),
+                ));
+            }
+            self.engine_driven_pump
+                .update(&delta, &context, &self.green_loop, &self.engine);
+            self.green_loop.update(
+                &delta,
+                &context,
+                None,
+                Some(&self.engine_driven_pump),
+                None,
+                None,
+            );
+            min_pressure = min_pressure.min(self.green_loop.get_pressure());
+        }
+
+        min_pressure
+    }
+}
+
+#[test]
+fn gear_and_flaps_moving_together_drop_green_pressure_to_about_2500_psi() {
+    let mut landing = LandingConfiguration::new();
+    assert!(landing.green_loop.get_pressure() > Pressure::new::<psi>(2900.));
+
+    let min_pressure = landing.run(10, true);
+
+    let pressure = landing.green_loop.get_pressure();
+    assert!(pressure > Pressure::new::<psi>(2300.));
+    assert!(pressure < Pressure::new::<psi>(2700.));
+    assert!(min_pressure > Pressure::new::<psi>(2300.));
+}
+
+#[test]
+fn secondary_consumers_keep_moving_while_pressure_drops() {
+    let mut landing = LandingConfiguration::new();
+
+    landing.run(10, true);
+
+    let pressure = landing.green_loop.get_pressure();
+    assert!(FlowSharing::is_priority_valve_open(pressure));
+    assert_eq!(FlowSharing::priority_valve_opening(pressure), 1.);
+    assert_eq!(
+        landing
+            .green_loop
+            .get_flow_sharing()
+            .served_ratio(ConsumerPriority::Secondary),
+        1.
+    );
+}
+
+#[test]
+fn pressure_recovers_once_gear_and_flaps_stop() {
+    let mut landing = LandingConfiguration::new();
+    landing.run(10, true);
+
+    landing.run(5, false);
+
+    assert!(landing.green_loop.get_pressure() > Pressure::new::<psi>(2900.));
+}